@@ -1,5 +1,5 @@
 use crate::serialization::codec;
-use crate::storage::{BufferPool, PageId, PageType};
+use crate::storage::{BufferPool, FileId, PageId, PageType};
 use std::io::{self, Cursor};
 use std::path::Path;
 
@@ -12,27 +12,30 @@ const MAX_KEYS: usize = 10;
 pub struct BTreePageIndex {
     root_page_id: PageId,
     buffer_pool: BufferPool,
+    file: FileId,
 }
 
 impl BTreePageIndex {
     /// Create a new B+ tree index
     pub fn create(path: impl AsRef<Path>, buffer_pool_size: usize) -> io::Result<Self> {
-        let mut buffer_pool = BufferPool::new(buffer_pool_size, path)?;
+        let mut buffer_pool = BufferPool::new(buffer_pool_size);
+        let file = buffer_pool.open_file(path)?;
 
         // Create root as a leaf page
-        let root_page = buffer_pool.new_page(PageType::BTreeLeaf)?;
+        let root_page = buffer_pool.new_page(file, PageType::BTreeLeaf)?;
         let root_page_id = root_page.page_id();
 
         // Initialize as empty leaf
         let leaf_data = serialize_leaf_node(&[], &[], None)?;
         root_page.add_row(&leaf_data)?;
 
-        buffer_pool.unpin_page(root_page_id, true);
-        buffer_pool.flush_page(root_page_id)?;
+        buffer_pool.unpin_page(file, root_page_id, true);
+        buffer_pool.flush_page(file, root_page_id)?;
 
         Ok(Self {
             root_page_id,
             buffer_pool,
+            file,
         })
     }
 
@@ -42,11 +45,13 @@ impl BTreePageIndex {
         buffer_pool_size: usize,
         root_page_id: PageId,
     ) -> io::Result<Self> {
-        let buffer_pool = BufferPool::new(buffer_pool_size, path)?;
+        let mut buffer_pool = BufferPool::new(buffer_pool_size);
+        let file = buffer_pool.open_file(path)?;
 
         Ok(Self {
             root_page_id,
             buffer_pool,
+            file,
         })
     }
 
@@ -61,14 +66,16 @@ impl BTreePageIndex {
 
         // If root was split, create new root
         if let Some((split_key, new_child_id)) = split_key.zip(new_child_id) {
-            let new_root = self.buffer_pool.new_page(PageType::BTreeInternal)?;
+            let new_root = self
+                .buffer_pool
+                .new_page(self.file, PageType::BTreeInternal)?;
             let new_root_id = new_root.page_id();
 
             let internal_data =
                 serialize_internal_node(&[split_key], &[self.root_page_id, new_child_id])?;
             new_root.add_row(&internal_data)?;
 
-            self.buffer_pool.unpin_page(new_root_id, true);
+            self.buffer_pool.unpin_page(self.file, new_root_id, true);
 
             self.root_page_id = new_root_id;
         }
@@ -83,9 +90,9 @@ impl BTreePageIndex {
         key: i64,
         value: PageId,
     ) -> io::Result<(Option<i64>, Option<PageId>)> {
-        let page = self.buffer_pool.fetch_page(page_id)?;
+        let page = self.buffer_pool.fetch_page(self.file, page_id)?;
         let page_type = page.page_type();
-        self.buffer_pool.unpin_page(page_id, false);
+        self.buffer_pool.unpin_page(self.file, page_id, false);
 
         match page_type {
             PageType::BTreeInternal => self.insert_internal(page_id, key, value),
@@ -106,12 +113,12 @@ impl BTreePageIndex {
     ) -> io::Result<(Option<i64>, Option<PageId>)> {
         // Read current node
         let (keys, mut children) = {
-            let page = self.buffer_pool.fetch_page(page_id)?;
+            let page = self.buffer_pool.fetch_page(self.file, page_id)?;
             let data = page
                 .get_row(0)
                 .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Empty internal node"))?;
             let (keys, children) = deserialize_internal_node(data)?;
-            self.buffer_pool.unpin_page(page_id, false);
+            self.buffer_pool.unpin_page(self.file, page_id, false);
             (keys, children)
         };
 
@@ -156,12 +163,12 @@ impl BTreePageIndex {
     ) -> io::Result<(Option<i64>, Option<PageId>)> {
         // Read current leaf
         let (mut keys, mut values, next) = {
-            let page = self.buffer_pool.fetch_page(page_id)?;
+            let page = self.buffer_pool.fetch_page(self.file, page_id)?;
             let data = page
                 .get_row(0)
                 .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Empty leaf node"))?;
             let (keys, values, next) = deserialize_leaf_node(data)?;
-            self.buffer_pool.unpin_page(page_id, false);
+            self.buffer_pool.unpin_page(self.file, page_id, false);
             (keys, values, next)
         };
 
@@ -206,11 +213,13 @@ impl BTreePageIndex {
         self.write_internal(page_id, &left_data)?;
 
         // Create right node
-        let right_page = self.buffer_pool.new_page(PageType::BTreeInternal)?;
+        let right_page = self
+            .buffer_pool
+            .new_page(self.file, PageType::BTreeInternal)?;
         let right_page_id = right_page.page_id();
         let right_data = serialize_internal_node(&right_keys, &right_children)?;
         right_page.add_row(&right_data)?;
-        self.buffer_pool.unpin_page(right_page_id, true);
+        self.buffer_pool.unpin_page(self.file, right_page_id, true);
 
         Ok((Some(split_key), Some(right_page_id)))
     }
@@ -231,10 +240,10 @@ impl BTreePageIndex {
 
         // Create right leaf first
         let right_data = serialize_leaf_node(&right_keys, &right_values, None)?;
-        let right_page = self.buffer_pool.new_page(PageType::BTreeLeaf)?;
+        let right_page = self.buffer_pool.new_page(self.file, PageType::BTreeLeaf)?;
         let right_page_id = right_page.page_id();
         right_page.add_row(&right_data)?;
-        self.buffer_pool.unpin_page(right_page_id, true);
+        self.buffer_pool.unpin_page(self.file, right_page_id, true);
 
         // Update left leaf to point to right
         self.write_leaf(page_id, &keys, &values, Some(right_page_id))?;
@@ -261,12 +270,12 @@ impl BTreePageIndex {
 
     /// Write node data to page (helper)
     fn write_node_data(&mut self, page_id: PageId, data: &[u8]) -> io::Result<()> {
-        let page = self.buffer_pool.fetch_page(page_id)?;
+        let page = self.buffer_pool.fetch_page(self.file, page_id)?;
 
         // Update the existing row at slot 0 with the new data
         page.update_row(0, data)?;
 
-        self.buffer_pool.unpin_page(page_id, true);
+        self.buffer_pool.unpin_page(self.file, page_id, true);
 
         Ok(())
     }
@@ -278,13 +287,13 @@ impl BTreePageIndex {
 
     /// Search recursively
     fn search_recursive(&mut self, page_id: PageId, key: i64) -> io::Result<Option<PageId>> {
-        let page = self.buffer_pool.fetch_page(page_id)?;
+        let page = self.buffer_pool.fetch_page(self.file, page_id)?;
         let page_type = page.page_type();
         let data = page
             .get_row(0)
             .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Empty node"))?
             .to_vec();
-        self.buffer_pool.unpin_page(page_id, false);
+        self.buffer_pool.unpin_page(self.file, page_id, false);
 
         match page_type {
             PageType::BTreeInternal => {
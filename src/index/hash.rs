@@ -23,6 +23,11 @@ impl<K: Eq + Hash, V> HashIndex<K, V> {
         self.buckets.entry(key).or_default().push(value);
     }
 
+    /// Number of distinct keys in the index.
+    pub fn key_count(&self) -> usize {
+        self.buckets.len()
+    }
+
     /// Return all values for a given key.
     pub fn get(&self, key: &K) -> impl Iterator<Item = &V> {
         self.buckets.get(key).into_iter().flatten()
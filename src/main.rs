@@ -1,4 +1,4 @@
-use db2::sql::{Executor, parse_sql_statements};
+use db2::sql::{Executor, parse_sql_statements_spanned};
 use std::io::{self, Write};
 
 fn main() -> io::Result<()> {
@@ -15,8 +15,13 @@ fn main() -> io::Result<()> {
     println!("  UPDATE <table> SET <col> = <expr>[, ...] [WHERE <pred>]");
     println!("  DELETE FROM <name> [WHERE <pred>]");
     println!("  SELECT <cols|*> FROM <table> [WHERE <pred>] [JOIN ...]");
+    println!("  SHOW TABLES");
+    println!("  SHOW INDEXES [FROM <table>]");
+    println!("  DESCRIBE <table>");
     println!("  .commit - Commit data to disk");
     println!("  .vacuum [table|all] - Vacuum dead row versions");
+    println!("  .txn - Show transaction state and snapshot");
+    println!("  .purge - Drop metadata of tables whose files are missing");
     println!("  .exit - Exit the program");
     println!();
 
@@ -53,6 +58,9 @@ fn main() -> io::Result<()> {
             );
         }
     }
+    for orphan in executor.orphaned_metadata() {
+        eprintln!("Warning: {} (kept; .purge drops it)", orphan);
+    }
     println!();
 
     loop {
@@ -93,18 +101,85 @@ fn main() -> io::Result<()> {
             continue;
         }
 
-        match parse_sql_statements(input) {
-            Ok(stmts) => {
-                for stmt in stmts {
-                    match executor.execute(stmt) {
-                        Ok(result) => println!("{}", result),
-                        Err(e) => eprintln!("Execution error: {}", e),
-                    }
+        if input == ".purge" {
+            match executor.purge_orphaned_metadata() {
+                Ok(purged) => println!("Purged {} metadata entries", purged.len()),
+                Err(e) => eprintln!("Error while purging metadata: {}", e),
+            }
+            continue;
+        }
+
+        if input == ".txn" {
+            print_transaction_status(&executor);
+            continue;
+        }
+
+        // A batch with a parse error is not run at all, but every failing
+        // statement is reported so it can be fixed in one go.
+        let parsed = parse_sql_statements_spanned(input);
+        let numbered = parsed.len() > 1;
+        let mut stmts = Vec::with_capacity(parsed.len());
+        let mut parse_failed = false;
+        for (i, stmt) in parsed.iter().enumerate() {
+            match &stmt.result {
+                Ok(stmt) => stmts.push(stmt.clone()),
+                Err(e) if numbered => {
+                    eprintln!(
+                        "Parse error in statement {} ({}): {}",
+                        i + 1,
+                        stmt.source(input),
+                        e
+                    );
+                    parse_failed = true;
+                }
+                Err(e) => {
+                    eprintln!("Parse error: {}", e);
+                    parse_failed = true;
                 }
             }
-            Err(e) => eprintln!("Parse error: {}", e),
+        }
+        if parse_failed {
+            continue;
+        }
+
+        for (i, stmt) in stmts.into_iter().enumerate() {
+            match executor.execute(stmt) {
+                Ok(result) => println!("{}", result),
+                Err(e) if numbered => eprintln!("Execution error in statement {}: {}", i + 1, e),
+                Err(e) => eprintln!("Execution error: {}", e),
+            }
         }
     }
 
     Ok(())
 }
+
+fn print_transaction_status(executor: &Executor) {
+    println!(
+        "In transaction: {}",
+        if executor.in_transaction() {
+            "yes"
+        } else {
+            "no"
+        }
+    );
+    match executor.current_txn_id() {
+        Some(txn_id) => println!("Transaction id: {}", txn_id),
+        None => println!("Transaction id: (none)"),
+    }
+    match executor.current_txn_state() {
+        Some(state) => println!("State: {:?}", state),
+        None => println!("State: (none)"),
+    }
+    match executor.current_snapshot() {
+        Some(snapshot) => {
+            let mut active: Vec<_> = snapshot.active.into_iter().collect();
+            active.sort_unstable();
+            println!(
+                "Snapshot: xmin={} xmax={} active={:?}",
+                snapshot.xmin, snapshot.xmax, active
+            );
+        }
+        None => println!("Snapshot: (none)"),
+    }
+}
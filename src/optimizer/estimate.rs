@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::sql::ast::{BinaryOp, Expr};
+
+/// Selectivity assumed for an equality predicate when the number of distinct
+/// values in the column is unknown.
+pub const DEFAULT_EQ_SELECTIVITY: f64 = 0.1;
+/// Selectivity assumed for a range predicate (`<`, `<=`, `>`, `>=`).
+pub const RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+/// Selectivity assumed for subquery predicates (`IN`, `EXISTS`).
+pub const SUBQUERY_SELECTIVITY: f64 = 0.5;
+
+/// Statistics about a single table used for cardinality estimation.
+///
+/// These are gathered from bookkeeping the table and its indexes already
+/// maintain, so collecting them never reads row data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableStats {
+    pub row_count: usize,
+    /// Known number of distinct values per column
+    pub distinct_values: HashMap<String, usize>,
+}
+
+impl TableStats {
+    pub fn new(row_count: usize) -> Self {
+        Self {
+            row_count,
+            distinct_values: HashMap::new(),
+        }
+    }
+
+    /// Selectivity of an equality predicate on `column` (≈ 1/ndistinct).
+    pub fn eq_selectivity(&self, column: &str) -> f64 {
+        match self.distinct_values.get(column) {
+            Some(&ndistinct) if ndistinct > 0 => 1.0 / ndistinct as f64,
+            _ => DEFAULT_EQ_SELECTIVITY,
+        }
+    }
+}
+
+/// Estimate the fraction of rows that satisfy `expr`.
+///
+/// Conjuncts are assumed to be independent.
+pub fn estimate_selectivity(expr: &Expr, stats: &TableStats) -> f64 {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOp::And,
+            right,
+        } => estimate_selectivity(left, stats) * estimate_selectivity(right, stats),
+        Expr::BinaryOp { left, op, right } => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(col), _) | (_, Expr::Column(col)) => {
+                predicate_selectivity(&col.column, *op, stats)
+            }
+            _ => predicate_selectivity("", *op, &TableStats::default()),
+        },
+        Expr::InSubquery { .. } | Expr::Exists { .. } => SUBQUERY_SELECTIVITY,
+        Expr::Column(_)
+        | Expr::Literal(_)
+        | Expr::ScalarSubquery(_)
+        | Expr::CurrentTimestamp
+        | Expr::CurrentDate
+        | Expr::JsonExtract { .. } => 1.0,
+    }
+}
+
+/// Selectivity of a single `column <op> value` comparison.
+pub fn predicate_selectivity(column: &str, op: BinaryOp, stats: &TableStats) -> f64 {
+    match op {
+        BinaryOp::Eq | BinaryOp::IsNotDistinctFrom => stats.eq_selectivity(column),
+        BinaryOp::NotEq | BinaryOp::IsDistinctFrom => 1.0 - stats.eq_selectivity(column),
+        BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq | BinaryOp::Like => {
+            RANGE_SELECTIVITY
+        }
+        BinaryOp::And | BinaryOp::Plus | BinaryOp::Minus | BinaryOp::Divide | BinaryOp::Mod => 1.0,
+    }
+}
+
+/// Scale a row count by a selectivity, rounding up so a non-empty input never
+/// estimates to zero rows.
+pub fn apply_selectivity(rows: usize, selectivity: f64) -> usize {
+    if rows == 0 {
+        return 0;
+    }
+    ((rows as f64) * selectivity.clamp(0.0, 1.0))
+        .ceil()
+        .max(1.0) as usize
+}
@@ -0,0 +1,43 @@
+mod tests {
+    use crate::optimizer::estimate::*;
+    use crate::sql::ast::*;
+
+    fn column_cmp(column: &str, op: BinaryOp, value: i128) -> Expr {
+        Expr::binary_op(
+            Expr::Column(ColumnRef::new(None, column)),
+            op,
+            Expr::Literal(Literal::Integer(value)),
+        )
+    }
+
+    #[test]
+    fn equality_uses_distinct_count() {
+        let mut stats = TableStats::new(1000);
+        stats.distinct_values.insert("id".to_string(), 1000);
+
+        let selectivity = estimate_selectivity(&column_cmp("id", BinaryOp::Eq, 7), &stats);
+        assert_eq!(apply_selectivity(stats.row_count, selectivity), 1);
+
+        let unknown = estimate_selectivity(&column_cmp("age", BinaryOp::Eq, 7), &stats);
+        assert_eq!(unknown, DEFAULT_EQ_SELECTIVITY);
+    }
+
+    #[test]
+    fn conjunction_multiplies_selectivities() {
+        let stats = TableStats::new(900);
+        let expr = Expr::binary_op(
+            column_cmp("age", BinaryOp::Gt, 18),
+            BinaryOp::And,
+            column_cmp("age", BinaryOp::Lt, 65),
+        );
+
+        let selectivity = estimate_selectivity(&expr, &stats);
+        assert_eq!(apply_selectivity(stats.row_count, selectivity), 100);
+    }
+
+    #[test]
+    fn apply_selectivity_never_rounds_to_zero() {
+        assert_eq!(apply_selectivity(10, 0.0001), 1);
+        assert_eq!(apply_selectivity(0, 0.5), 0);
+    }
+}
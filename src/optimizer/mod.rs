@@ -1,5 +1,8 @@
+pub mod estimate;
 pub mod planner;
 
+#[cfg(test)]
+mod estimate_test;
 #[cfg(test)]
 mod planner_test;
 
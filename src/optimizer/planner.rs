@@ -2,7 +2,7 @@ use crate::sql::ast::{
     BinaryOp, ColumnRef, Expr, FromClause, IndexType, Literal, SelectColumn, SelectStmt,
 };
 
-use super::rules::extract_indexable_predicates;
+use super::rules::{extract_indexable_predicates, split_join_condition};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IndexMetadata {
@@ -11,6 +11,9 @@ pub struct IndexMetadata {
     pub index_type: IndexType,
 }
 
+/// A predicate an index can serve: column, operator and literal.
+pub type IndexPredicate = (String, BinaryOp, Literal);
+
 /// Physical scan choice for a single table.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ScanPlan {
@@ -18,17 +21,22 @@ pub enum ScanPlan {
     IndexScan {
         index_columns: Vec<String>,
         index_type: IndexType,
-        predicates: Vec<(String, BinaryOp, Literal)>,
+        predicates: Vec<IndexPredicate>,
     },
 }
 
 /// Physical join strategy (nested loop with optional indexed inner).
+///
+/// Merge joins and indexed inner lookups need an equality key; any other ON
+/// condition is planned as a plain nested loop that evaluates `join_filter`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JoinPlan {
     pub outer_table: String,
     pub inner_table: String,
-    pub outer_column: ColumnRef,
-    pub inner_column: ColumnRef,
+    /// Equality join key as (outer column, inner column), if the ON clause has one
+    pub join_key: Option<(ColumnRef, ColumnRef)>,
+    /// ON conjuncts not covered by `join_key`, evaluated on each combined row
+    pub join_filter: Option<Expr>,
     pub strategy: JoinStrategy,
 }
 
@@ -79,9 +87,28 @@ impl Planner {
             FromClause::Join {
                 left_table,
                 right_table,
-                left_column,
-                right_column,
+                condition,
             } => {
+                let (join_key, join_filter) =
+                    split_join_condition(condition, left_table, right_table);
+
+                let Some((left_column, right_column)) = join_key else {
+                    // No equality key: only a nested loop over the full predicate works
+                    return Plan {
+                        from: FromClausePlan::Join(JoinPlan {
+                            outer_table: left_table.clone(),
+                            inner_table: right_table.clone(),
+                            join_key: None,
+                            join_filter,
+                            strategy: JoinStrategy::NestedLoop {
+                                inner_has_index: false,
+                            },
+                        }),
+                        columns,
+                        filter,
+                    };
+                };
+
                 let right_indexed = self.indexed_columns.iter().any(|idx| {
                     idx.table == *right_table && idx.columns.first() == Some(&right_column.column)
                 });
@@ -93,22 +120,22 @@ impl Planner {
                     (
                         left_table.clone(),
                         right_table.clone(),
-                        left_column.clone(),
-                        right_column.clone(),
+                        left_column,
+                        right_column,
                     )
                 } else if left_indexed {
                     (
                         right_table.clone(),
                         left_table.clone(),
-                        right_column.clone(),
-                        left_column.clone(),
+                        right_column,
+                        left_column,
                     )
                 } else {
                     (
                         left_table.clone(),
                         right_table.clone(),
-                        left_column.clone(),
-                        right_column.clone(),
+                        left_column,
+                        right_column,
                     )
                 };
 
@@ -123,8 +150,8 @@ impl Planner {
                 FromClausePlan::Join(JoinPlan {
                     outer_table,
                     inner_table,
-                    outer_column: outer_col,
-                    inner_column: inner_col,
+                    join_key: Some((outer_col, inner_col)),
+                    join_filter,
                     strategy,
                 })
             }
@@ -147,45 +174,51 @@ impl Planner {
             .filter(|idx| idx.table == table)
             .collect();
 
-        let table_preds: Vec<(String, BinaryOp, Literal)> = predicates
+        let table_preds: Vec<IndexPredicate> = predicates
             .into_iter()
             .filter(|(col, _, _)| col.table.as_deref().is_none_or(|t| t == table))
             .map(|(col, op, lit)| (col.column, op, lit))
             .collect();
 
-        let mut best: Option<(&IndexMetadata, Vec<(String, BinaryOp, Literal)>)> = None;
+        let mut best: Option<(&IndexMetadata, Vec<IndexPredicate>, usize)> = None;
 
         for idx in table_indexes {
+            // Every predicate on a covered column is kept so the executor can
+            // intersect them into the tightest range.
             let mut used = Vec::new();
+            let mut covered = 0;
             for col_name in &idx.columns {
-                match table_preds.iter().find(|(c, _, _)| c == col_name) {
-                    Some(pred) => used.push(pred.clone()),
-                    None => break,
+                let column_preds: Vec<_> = table_preds
+                    .iter()
+                    .filter(|(c, op, _)| {
+                        c == col_name && (idx.index_type != IndexType::Hash || *op == BinaryOp::Eq)
+                    })
+                    .cloned()
+                    .collect();
+                if column_preds.is_empty() {
+                    break;
                 }
+                covered += 1;
+                used.extend(column_preds);
             }
 
-            if used.is_empty() {
+            if covered == 0 {
                 continue;
             }
 
-            if idx.index_type == IndexType::Hash {
-                if used.len() != idx.columns.len() {
-                    continue;
-                }
-                if !used.iter().all(|(_, op, _)| *op == BinaryOp::Eq) {
-                    continue;
-                }
+            if idx.index_type == IndexType::Hash && covered != idx.columns.len() {
+                continue;
             }
 
             if best
                 .as_ref()
-                .is_none_or(|(_, b_used)| used.len() > b_used.len())
+                .is_none_or(|(_, _, b_covered)| covered > *b_covered)
             {
-                best = Some((idx, used));
+                best = Some((idx, used, covered));
             }
         }
 
-        if let Some((idx, used)) = best {
+        if let Some((idx, used, _)) = best {
             ScanPlan::IndexScan {
                 index_columns: idx.columns.clone(),
                 index_type: idx.index_type,
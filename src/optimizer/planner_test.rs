@@ -81,8 +81,11 @@ mod tests {
             from: FromClause::Join {
                 left_table: "users".to_string(),
                 right_table: "orders".to_string(),
-                left_column: ColumnRef::new(Some("users".to_string()), "id"),
-                right_column: ColumnRef::new(Some("orders".to_string()), "user_id"),
+                condition: Expr::binary_op(
+                    Expr::Column(ColumnRef::new(Some("users".to_string()), "id")),
+                    BinaryOp::Eq,
+                    Expr::Column(ColumnRef::new(Some("orders".to_string()), "user_id")),
+                ),
             },
             where_clause: None,
             group_by: Vec::new(),
@@ -121,8 +124,11 @@ mod tests {
             from: FromClause::Join {
                 left_table: "users".to_string(),
                 right_table: "orders".to_string(),
-                left_column: ColumnRef::new(Some("users".to_string()), "id"),
-                right_column: ColumnRef::new(Some("orders".to_string()), "user_id"),
+                condition: Expr::binary_op(
+                    Expr::Column(ColumnRef::new(Some("users".to_string()), "id")),
+                    BinaryOp::Eq,
+                    Expr::Column(ColumnRef::new(Some("orders".to_string()), "user_id")),
+                ),
             },
             where_clause: None,
             group_by: Vec::new(),
@@ -148,6 +154,101 @@ mod tests {
         }
     }
 
+    #[test]
+    fn plans_nested_loop_for_non_equi_join() {
+        let planner = Planner::new(vec![IndexMetadata {
+            table: "readings".to_string(),
+            columns: vec!["val".to_string()],
+            index_type: IndexType::BTree,
+        }]);
+
+        let condition = Expr::binary_op(
+            Expr::binary_op(
+                Expr::Column(ColumnRef::new(Some("bands".to_string()), "lo")),
+                BinaryOp::LtEq,
+                Expr::Column(ColumnRef::new(Some("readings".to_string()), "val")),
+            ),
+            BinaryOp::And,
+            Expr::binary_op(
+                Expr::Column(ColumnRef::new(Some("bands".to_string()), "hi")),
+                BinaryOp::GtEq,
+                Expr::Column(ColumnRef::new(Some("readings".to_string()), "val")),
+            ),
+        );
+        let stmt = SelectStmt {
+            columns: SelectColumn::All,
+            from: FromClause::Join {
+                left_table: "bands".to_string(),
+                right_table: "readings".to_string(),
+                condition: condition.clone(),
+            },
+            where_clause: None,
+            group_by: Vec::new(),
+            distinct: false,
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+        };
+
+        let plan = planner.plan_select(&stmt);
+        match plan.from {
+            FromClausePlan::Join(join_plan) => {
+                assert_eq!(join_plan.join_key, None);
+                assert_eq!(join_plan.join_filter, Some(condition));
+                assert_eq!(
+                    join_plan.strategy,
+                    JoinStrategy::NestedLoop {
+                        inner_has_index: false
+                    }
+                );
+            }
+            _ => panic!("Expected join plan"),
+        }
+    }
+
+    #[test]
+    fn splits_equality_key_from_residual_join_condition() {
+        let residual = Expr::binary_op(
+            Expr::Column(ColumnRef::new(Some("orders".to_string()), "amount")),
+            BinaryOp::Gt,
+            Expr::Column(ColumnRef::new(Some("users".to_string()), "min_amount")),
+        );
+        let stmt = SelectStmt {
+            columns: SelectColumn::All,
+            from: FromClause::Join {
+                left_table: "users".to_string(),
+                right_table: "orders".to_string(),
+                condition: Expr::binary_op(
+                    residual.clone(),
+                    BinaryOp::And,
+                    Expr::binary_op(
+                        Expr::Column(ColumnRef::new(Some("orders".to_string()), "user_id")),
+                        BinaryOp::Eq,
+                        Expr::Column(ColumnRef::new(Some("users".to_string()), "id")),
+                    ),
+                ),
+            },
+            where_clause: None,
+            group_by: Vec::new(),
+            distinct: false,
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+        };
+
+        let plan = Planner::new(Vec::new()).plan_select(&stmt);
+        match plan.from {
+            FromClausePlan::Join(join_plan) => {
+                let (outer, inner) = join_plan.join_key.expect("equality key");
+                assert_eq!(outer.column, "id");
+                assert_eq!(inner.column, "user_id");
+                assert_eq!(join_plan.join_filter, Some(residual));
+                assert_eq!(join_plan.strategy, JoinStrategy::MergeJoin);
+            }
+            _ => panic!("Expected join plan"),
+        }
+    }
+
     #[test]
     fn chooses_longer_composite_index_prefix() {
         let planner = Planner::new(vec![IndexMetadata {
@@ -190,4 +291,53 @@ mod tests {
             _ => panic!("Expected single-table plan"),
         }
     }
+
+    #[test]
+    fn keeps_all_predicates_on_index_column() {
+        let planner = Planner::new(vec![IndexMetadata {
+            table: "items".to_string(),
+            columns: vec!["price".to_string()],
+            index_type: IndexType::BTree,
+        }]);
+
+        let stmt = SelectStmt {
+            columns: SelectColumn::All,
+            from: FromClause::Table("items".to_string()),
+            where_clause: Some(Expr::binary_op(
+                Expr::binary_op(
+                    Expr::Column(ColumnRef::new(None, "price")),
+                    BinaryOp::Gt,
+                    Expr::Literal(Literal::Integer(100)),
+                ),
+                BinaryOp::And,
+                Expr::binary_op(
+                    Expr::Literal(Literal::Integer(300)),
+                    BinaryOp::Gt,
+                    Expr::Column(ColumnRef::new(None, "price")),
+                ),
+            )),
+            group_by: Vec::new(),
+            distinct: false,
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+        };
+
+        let plan = planner.plan_select(&stmt);
+        match plan.from {
+            FromClausePlan::Single { scan, .. } => match scan {
+                ScanPlan::IndexScan { predicates, .. } => {
+                    assert_eq!(
+                        predicates,
+                        vec![
+                            ("price".to_string(), BinaryOp::Gt, Literal::Integer(100)),
+                            ("price".to_string(), BinaryOp::Lt, Literal::Integer(300)),
+                        ]
+                    );
+                }
+                _ => panic!("Expected index scan"),
+            },
+            _ => panic!("Expected single-table plan"),
+        }
+    }
 }
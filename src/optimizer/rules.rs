@@ -1,8 +1,84 @@
 use crate::sql::ast::{BinaryOp, ColumnRef, Expr, Literal};
+use crate::sql::pattern;
+
+/// Split a JOIN ON condition into an equi-join key and the remaining predicate.
+///
+/// The key is the first `column = column` conjunct whose columns come from
+/// opposite sides of the join; it is returned as (left column, right column).
+/// Unqualified columns are assumed to be written in left/right order. Every
+/// other conjunct is kept in the residual predicate.
+pub fn split_join_condition(
+    condition: &Expr,
+    left_table: &str,
+    right_table: &str,
+) -> (Option<(ColumnRef, ColumnRef)>, Option<Expr>) {
+    let mut conjuncts = Vec::new();
+    collect_conjuncts(condition, &mut conjuncts);
+
+    let mut key = None;
+    let mut residual: Option<Expr> = None;
+    for conjunct in conjuncts {
+        if key.is_none()
+            && let Some(cols) = as_equi_join_key(conjunct, left_table, right_table)
+        {
+            key = Some(cols);
+            continue;
+        }
+        residual = Some(match residual {
+            Some(expr) => Expr::binary_op(expr, BinaryOp::And, conjunct.clone()),
+            None => conjunct.clone(),
+        });
+    }
+
+    (key, residual)
+}
+
+fn collect_conjuncts<'a>(expr: &'a Expr, out: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOp::And,
+            right,
+        } => {
+            collect_conjuncts(left, out);
+            collect_conjuncts(right, out);
+        }
+        other => out.push(other),
+    }
+}
+
+fn as_equi_join_key(
+    expr: &Expr,
+    left_table: &str,
+    right_table: &str,
+) -> Option<(ColumnRef, ColumnRef)> {
+    let Expr::BinaryOp {
+        left,
+        op: BinaryOp::Eq,
+        right,
+    } = expr
+    else {
+        return None;
+    };
+    let (Expr::Column(a), Expr::Column(b)) = (left.as_ref(), right.as_ref()) else {
+        return None;
+    };
+
+    let belongs = |col: &ColumnRef, table: &str| col.table.as_deref().is_none_or(|t| t == table);
+    if belongs(a, left_table) && belongs(b, right_table) {
+        Some((a.clone(), b.clone()))
+    } else if belongs(b, left_table) && belongs(a, right_table) {
+        Some((b.clone(), a.clone()))
+    } else {
+        None
+    }
+}
 
 /// Extract a simple column-literal predicate if present.
 ///
 /// Returns (column, operator, literal) with operator adjusted for operand order.
+/// `column LIKE 'pattern'` is included only when the pattern starts with
+/// literal text, which bounds a range of the column's values.
 pub fn extract_indexable_predicates(expr: &Expr) -> Vec<(ColumnRef, BinaryOp, Literal)> {
     let mut preds = Vec::new();
     collect_predicates(expr, &mut preds);
@@ -15,20 +91,36 @@ fn collect_predicates(expr: &Expr, out: &mut Vec<(ColumnRef, BinaryOp, Literal)>
             collect_predicates(left, out);
             collect_predicates(right, out);
         }
-        Expr::BinaryOp { left, op, right } => match (left.as_ref(), right.as_ref()) {
-            (Expr::Column(col), Expr::Literal(lit)) => out.push((col.clone(), *op, lit.clone())),
-            (Expr::Literal(lit), Expr::Column(col)) => {
-                let swapped_op = match op {
-                    BinaryOp::Lt => BinaryOp::Gt,
-                    BinaryOp::LtEq => BinaryOp::GtEq,
-                    BinaryOp::Gt => BinaryOp::Lt,
-                    BinaryOp::GtEq => BinaryOp::LtEq,
-                    other => *other,
-                };
-                out.push((col.clone(), swapped_op, lit.clone()));
+        Expr::BinaryOp {
+            left,
+            op: BinaryOp::Like,
+            right,
+        } => {
+            if let (Expr::Column(col), Expr::Literal(Literal::String(p))) =
+                (left.as_ref(), right.as_ref())
+                && !pattern::literal_prefix(p).0.is_empty()
+            {
+                out.push((col.clone(), BinaryOp::Like, Literal::String(p.clone())));
+            }
+        }
+        Expr::BinaryOp { left, op, right } if !op.is_arithmetic() && !op.is_null_safe() => {
+            match (left.as_ref(), right.as_ref()) {
+                (Expr::Column(col), Expr::Literal(lit)) => {
+                    out.push((col.clone(), *op, lit.clone()))
+                }
+                (Expr::Literal(lit), Expr::Column(col)) => {
+                    let swapped_op = match op {
+                        BinaryOp::Lt => BinaryOp::Gt,
+                        BinaryOp::LtEq => BinaryOp::GtEq,
+                        BinaryOp::Gt => BinaryOp::Lt,
+                        BinaryOp::GtEq => BinaryOp::LtEq,
+                        other => *other,
+                    };
+                    out.push((col.clone(), swapped_op, lit.clone()));
+                }
+                _ => {}
             }
-            _ => {}
-        },
+        }
         _ => {}
     }
 }
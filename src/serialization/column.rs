@@ -15,6 +15,7 @@ enum TypeTag {
     Date = 6,
     Timestamp = 7,
     Decimal = 8,
+    Interval = 9,
 }
 
 impl TypeTag {
@@ -29,6 +30,7 @@ impl TypeTag {
             6 => Ok(TypeTag::Date),
             7 => Ok(TypeTag::Timestamp),
             8 => Ok(TypeTag::Decimal),
+            9 => Ok(TypeTag::Interval),
             _ => Err(SerializationError::InvalidTypeTag(value)),
         }
     }
@@ -44,6 +46,7 @@ impl TypeTag {
             Value::Date(_) => TypeTag::Date,
             Value::Timestamp(_) => TypeTag::Timestamp,
             Value::Decimal(_) => TypeTag::Decimal,
+            Value::Interval(_) => TypeTag::Interval,
         }
     }
 }
@@ -174,6 +177,11 @@ impl ColumnSerializer {
                     codec::write_i128(&mut buf, d.value)?;
                     codec::write_u32(&mut buf, d.scale)?;
                 }
+                Value::Interval(interval) => {
+                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_i64(&mut buf, interval.months)?;
+                    codec::write_i64(&mut buf, interval.seconds)?;
+                }
                 Value::Null => unreachable!("nulls handled above"),
             }
         }
@@ -258,6 +266,11 @@ impl ColumnSerializer {
                     let scale = codec::read_u32(&mut cursor)?;
                     Value::Decimal(crate::types::Decimal { value, scale })
                 }
+                TypeTag::Interval => {
+                    let months = codec::read_i64(&mut cursor)?;
+                    let seconds = codec::read_i64(&mut cursor)?;
+                    Value::Interval(crate::types::Interval { months, seconds })
+                }
             };
             values.push(value);
         }
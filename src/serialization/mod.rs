@@ -10,4 +10,6 @@ mod column_test;
 mod row_test;
 
 pub use column::{ColumnSerializer, SerializationError};
-pub use row::{RowMetadata, RowSerializationError, RowSerializer};
+pub use row::{
+    OVERFLOW_POINTER_SIZE, OverflowPointer, RowMetadata, RowSerializationError, RowSerializer,
+};
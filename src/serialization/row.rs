@@ -1,12 +1,21 @@
 use crate::serialization::codec;
-use crate::types::{Date, Decimal, Schema, Timestamp, Value};
+use crate::storage::PageId;
+use crate::types::{DataType, Date, Decimal, Schema, Timestamp, Value};
 use std::io::{self, Cursor};
 
 /// Errors that can occur during row serialization/deserialization
 #[derive(Debug)]
 pub enum RowSerializationError {
     IoError(io::Error),
-    ColumnCountMismatch { expected: usize, found: usize },
+    ColumnCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    TypeMismatch {
+        column: String,
+        expected: DataType,
+        found: String,
+    },
 }
 
 impl From<io::Error> for RowSerializationError {
@@ -26,6 +35,15 @@ impl std::fmt::Display for RowSerializationError {
                     expected, found
                 )
             }
+            RowSerializationError::TypeMismatch {
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Type mismatch for column '{}': expected {}, found {}",
+                column, expected, found
+            ),
         }
     }
 }
@@ -37,7 +55,9 @@ impl std::error::Error for RowSerializationError {}
 /// [8 bytes: xmin (u64)]
 /// [8 bytes: xmax (u64)]
 /// [2 bytes: column_count (u16)]
-/// [for each column: serialized Value]
+/// [for each column: 1 byte marker, then the serialized Value if present]
+///
+/// Markers: 0 = value follows, 1 = NULL, 2 = string stored in overflow pages
 ///
 /// Value serialization:
 ///   Integer: [8 bytes: i64]
@@ -45,9 +65,30 @@ impl std::error::Error for RowSerializationError {}
 ///   Float:    [8 bytes: f64]
 ///   Boolean:  [1 byte: 0 or 1]
 ///   String:  [4 bytes: length (u32)][length bytes: UTF-8 data]
+///   Overflowed string: [4 bytes: length (u32)][4 bytes: first overflow page (u32)]
 /// ```
 pub struct RowSerializer;
 
+/// xmin, xmax and column count
+const ROW_HEADER_SIZE: usize = 8 + 8 + 2;
+
+const VALUE_PRESENT: u8 = 0;
+const VALUE_NULL: u8 = 1;
+const VALUE_OVERFLOW: u8 = 2;
+
+/// Bytes an overflowed string occupies in its row, after the marker
+pub const OVERFLOW_POINTER_SIZE: usize = 4 + 4;
+
+/// Location of a string value stored outside its row in a chain of overflow
+/// pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowPointer {
+    /// Length of the string in bytes
+    pub len: u32,
+    /// First page of the chain
+    pub first_page: PageId,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RowMetadata {
     pub xmin: u64,
@@ -61,6 +102,27 @@ impl Default for RowMetadata {
 }
 
 impl RowSerializer {
+    /// Largest serialized size of a row of `schema`, not counting string
+    /// contents: each string column contributes only its length prefix.
+    pub fn max_fixed_size(schema: &Schema) -> usize {
+        let values: usize = schema
+            .columns()
+            .iter()
+            .map(|column| {
+                // Every value starts with a one-byte null marker
+                1 + match column.data_type() {
+                    DataType::Integer | DataType::Unsigned | DataType::Float => 8,
+                    DataType::Boolean => 1,
+                    DataType::String | DataType::Json => 4,
+                    DataType::Date => 4 + 1 + 1,
+                    DataType::Timestamp => 4 + 5,
+                    DataType::Decimal => 16 + 4,
+                }
+            })
+            .sum();
+        ROW_HEADER_SIZE + values
+    }
+
     /// Serialize a row of values into bytes.
     ///
     /// The row is serialized without type tags, as the schema provides type information.
@@ -83,15 +145,21 @@ impl RowSerializer {
         row: &[Value],
         schema: Option<&Schema>,
         metadata: RowMetadata,
+    ) -> Result<Vec<u8>, RowSerializationError> {
+        Self::serialize_with_overflow(row, schema, metadata, &[])
+    }
+
+    /// Serialize a row, writing `overflow[i]` in place of column `i` where it
+    /// is set. The caller stores those strings in overflow pages.
+    pub fn serialize_with_overflow(
+        row: &[Value],
+        schema: Option<&Schema>,
+        metadata: RowMetadata,
+        overflow: &[Option<OverflowPointer>],
     ) -> Result<Vec<u8>, RowSerializationError> {
         // Validate against schema if provided
         if let Some(schema) = schema {
-            schema
-                .validate_row(row)
-                .map_err(|_| RowSerializationError::ColumnCountMismatch {
-                    expected: schema.column_count(),
-                    found: row.len(),
-                })?;
+            Self::validate_encoding(row, schema)?;
         }
 
         let mut buf = Vec::new();
@@ -104,39 +172,45 @@ impl RowSerializer {
         codec::write_u16(&mut buf, row.len() as u16)?;
 
         // Write each value
-        for value in row {
+        for (i, value) in row.iter().enumerate() {
+            if let Some(pointer) = overflow.get(i).copied().flatten() {
+                codec::write_u8(&mut buf, VALUE_OVERFLOW)?;
+                codec::write_u32(&mut buf, pointer.len)?;
+                codec::write_u32(&mut buf, pointer.first_page)?;
+                continue;
+            }
             match value {
                 Value::Null => {
-                    codec::write_u8(&mut buf, 1)?;
+                    codec::write_u8(&mut buf, VALUE_NULL)?;
                 }
                 Value::Integer(i) => {
-                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
                     codec::write_i64(&mut buf, *i)?;
                 }
                 Value::Unsigned(u) => {
-                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
                     codec::write_u64(&mut buf, *u)?;
                 }
                 Value::Float(fv) => {
-                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
                     codec::write_f64(&mut buf, *fv)?;
                 }
                 Value::Boolean(b) => {
-                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
                     codec::write_u8(&mut buf, *b as u8)?;
                 }
                 Value::String(s) => {
-                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
                     codec::write_string(&mut buf, s)?;
                 }
                 Value::Date(d) => {
-                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
                     codec::write_i32(&mut buf, d.year)?;
                     codec::write_u8(&mut buf, d.month)?;
                     codec::write_u8(&mut buf, d.day)?;
                 }
                 Value::Timestamp(t) => {
-                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
                     codec::write_i32(&mut buf, t.year)?;
                     codec::write_u8(&mut buf, t.month)?;
                     codec::write_u8(&mut buf, t.day)?;
@@ -145,16 +219,64 @@ impl RowSerializer {
                     codec::write_u8(&mut buf, t.second)?;
                 }
                 Value::Decimal(d) => {
-                    codec::write_u8(&mut buf, 0)?;
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
                     codec::write_i128(&mut buf, d.value)?;
                     codec::write_u32(&mut buf, d.scale)?;
                 }
+                // No column type decodes an interval; `validate_encoding`
+                // rejects it whenever a schema is supplied.
+                Value::Interval(interval) => {
+                    codec::write_u8(&mut buf, VALUE_PRESENT)?;
+                    codec::write_i64(&mut buf, interval.months)?;
+                    codec::write_i64(&mut buf, interval.seconds)?;
+                }
             }
         }
 
         Ok(buf)
     }
 
+    /// Check that every value is stored in the exact encoding its column expects.
+    ///
+    /// This is stricter than `Schema::validate_row`, which accepts values that
+    /// can be coerced (e.g. an `Integer` for a FLOAT column). The deserializer
+    /// decodes purely by column type, so such a value would produce a corrupt
+    /// row. Schemas do not carry NOT NULL information, so NULL is accepted for
+    /// every column here; NOT NULL is enforced by the executor's constraints.
+    fn validate_encoding(row: &[Value], schema: &Schema) -> Result<(), RowSerializationError> {
+        if row.len() != schema.column_count() {
+            return Err(RowSerializationError::ColumnCountMismatch {
+                expected: schema.column_count(),
+                found: row.len(),
+            });
+        }
+
+        for (value, column) in row.iter().zip(schema.columns()) {
+            let encoded_correctly = matches!(
+                (column.data_type(), value),
+                (_, Value::Null)
+                    | (DataType::Integer, Value::Integer(_))
+                    | (DataType::Unsigned, Value::Unsigned(_))
+                    | (DataType::Float, Value::Float(_))
+                    | (DataType::Boolean, Value::Boolean(_))
+                    | (DataType::String, Value::String(_))
+                    | (DataType::Date, Value::Date(_))
+                    | (DataType::Timestamp, Value::Timestamp(_))
+                    | (DataType::Decimal, Value::Decimal(_))
+                    | (DataType::Json, Value::String(_))
+            );
+            if !encoded_correctly {
+                return Err(RowSerializationError::TypeMismatch {
+                    column: column.name().to_string(),
+                    expected: column.data_type(),
+                    found: format!("{:?}", value),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Deserialize a row of values from bytes using a schema.
     ///
     /// The schema is required to know the types of each column.
@@ -172,9 +294,25 @@ impl RowSerializer {
         Ok(values)
     }
 
+    /// Deserialize a row that has no values in overflow pages.
     pub fn deserialize_with_metadata(
         bytes: &[u8],
         schema: &Schema,
+    ) -> Result<(RowMetadata, Vec<Value>), RowSerializationError> {
+        Self::deserialize_with_overflow(bytes, schema, |_| {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Row refers to overflow pages",
+            ))
+        })
+    }
+
+    /// Deserialize a row, calling `load` for each string stored in overflow
+    /// pages.
+    pub fn deserialize_with_overflow(
+        bytes: &[u8],
+        schema: &Schema,
+        mut load: impl FnMut(OverflowPointer) -> io::Result<String>,
     ) -> Result<(RowMetadata, Vec<Value>), RowSerializationError> {
         let mut cursor = Cursor::new(bytes);
 
@@ -195,12 +333,22 @@ impl RowSerializer {
         // Deserialize each value according to schema
         let mut values = Vec::with_capacity(column_count);
         for i in 0..column_count {
-            let is_null = codec::read_u8(&mut cursor)? != 0;
-            if is_null {
-                values.push(Value::Null);
-                continue;
-            }
             let column = schema.column(i).expect("column index validated");
+            match codec::read_u8(&mut cursor)? {
+                VALUE_PRESENT => {}
+                VALUE_OVERFLOW
+                    if matches!(column.data_type(), DataType::String | DataType::Json) =>
+                {
+                    let len = codec::read_u32(&mut cursor)?;
+                    let first_page = codec::read_u32(&mut cursor)?;
+                    values.push(Value::String(load(OverflowPointer { len, first_page })?));
+                    continue;
+                }
+                _ => {
+                    values.push(Value::Null);
+                    continue;
+                }
+            }
             let value = match column.data_type() {
                 crate::types::DataType::Integer => {
                     let i = codec::read_i64(&mut cursor)?;
@@ -218,7 +366,7 @@ impl RowSerializer {
                     let b = codec::read_u8(&mut cursor)?;
                     Value::Boolean(b != 0)
                 }
-                crate::types::DataType::String => {
+                crate::types::DataType::String | crate::types::DataType::Json => {
                     let s = codec::read_string(&mut cursor)?;
                     Value::String(s)
                 }
@@ -255,4 +403,23 @@ impl RowSerializer {
 
         Ok((RowMetadata { xmin, xmax }, values))
     }
+
+    /// Overflow chains referenced by a serialized row.
+    pub fn overflow_pointers(
+        bytes: &[u8],
+        schema: &Schema,
+    ) -> Result<Vec<OverflowPointer>, RowSerializationError> {
+        let mut pointers = Vec::new();
+        Self::deserialize_with_overflow(bytes, schema, |pointer| {
+            pointers.push(pointer);
+            Ok(String::new())
+        })?;
+        Ok(pointers)
+    }
+
+    /// Overwrite the MVCC metadata of a serialized row in place.
+    pub fn write_metadata(bytes: &mut [u8], metadata: RowMetadata) {
+        bytes[0..8].copy_from_slice(&metadata.xmin.to_le_bytes());
+        bytes[8..16].copy_from_slice(&metadata.xmax.to_le_bytes());
+    }
 }
@@ -1,5 +1,8 @@
 mod tests {
-    use crate::serialization::{RowSerializationError, RowSerializer, codec};
+    use crate::serialization::{
+        OVERFLOW_POINTER_SIZE, OverflowPointer, RowMetadata, RowSerializationError, RowSerializer,
+        codec,
+    };
     use crate::types::{Column, DataType, Date, Decimal, Schema, Timestamp, Value};
 
     fn create_test_schema() -> Schema {
@@ -195,6 +198,51 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_type_mismatch_on_serialize() {
+        let schema = create_test_schema();
+        let row = vec![
+            Value::String("not a number".to_string()),
+            Value::String("Alice".to_string()),
+            Value::Boolean(true),
+        ];
+
+        let result = RowSerializer::serialize(&row, Some(&schema));
+        match result {
+            Err(RowSerializationError::TypeMismatch {
+                column, expected, ..
+            }) => {
+                assert_eq!(column, "id");
+                assert_eq!(expected, DataType::Integer);
+            }
+            other => panic!("Expected TypeMismatch, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_coercible_value_rejected_on_serialize() {
+        // An Integer is a valid FLOAT per the schema, but would be written as
+        // i64 bytes and misread as f64 on the way back.
+        let schema = Schema::new(vec![Column::new("price", DataType::Float)]);
+        let row = vec![Value::Integer(10)];
+
+        let result = RowSerializer::serialize(&row, Some(&schema));
+        assert!(matches!(
+            result,
+            Err(RowSerializationError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_null_accepted_for_any_column() {
+        let schema = create_test_schema();
+        let row = vec![Value::Null, Value::Null, Value::Null];
+
+        let bytes = RowSerializer::serialize(&row, Some(&schema)).unwrap();
+        let deserialized = RowSerializer::deserialize(&bytes, &schema).unwrap();
+        assert_eq!(row, deserialized);
+    }
+
     #[test]
     fn test_column_count_mismatch_on_deserialize() {
         let schema = create_test_schema();
@@ -267,4 +315,80 @@ mod tests {
 
         assert_eq!(row, deserialized);
     }
+
+    #[test]
+    fn test_max_fixed_size_matches_serialized_row() {
+        let schema = Schema::new(vec![
+            Column::new("i", DataType::Integer),
+            Column::new("u", DataType::Unsigned),
+            Column::new("f", DataType::Float),
+            Column::new("b", DataType::Boolean),
+            Column::new("s", DataType::String),
+            Column::new("d", DataType::Date),
+            Column::new("t", DataType::Timestamp),
+            Column::new("m", DataType::Decimal),
+        ]);
+        let row = vec![
+            Value::Integer(i64::MIN),
+            Value::Unsigned(u64::MAX),
+            Value::Float(1.5),
+            Value::Boolean(true),
+            Value::String(String::new()),
+            Value::Date(Date::parse("2025-01-02").expect("valid date")),
+            Value::Timestamp(Timestamp::parse("2025-01-02 03:04:05").expect("valid ts")),
+            Value::Decimal(Decimal::parse("12.340").expect("valid decimal")),
+        ];
+
+        let bytes = RowSerializer::serialize(&row, Some(&schema)).unwrap();
+        assert_eq!(RowSerializer::max_fixed_size(&schema), bytes.len());
+
+        // String contents come on top of the bound
+        let mut longer = row.clone();
+        longer[4] = Value::String("abc".to_string());
+        let bytes = RowSerializer::serialize(&longer, Some(&schema)).unwrap();
+        assert_eq!(RowSerializer::max_fixed_size(&schema) + 3, bytes.len());
+    }
+
+    #[test]
+    fn test_overflowed_strings_round_trip_through_pointers() {
+        let schema = create_test_schema();
+        let row = vec![
+            Value::Integer(7),
+            Value::String("a long name".to_string()),
+            Value::Boolean(true),
+        ];
+        let pointer = OverflowPointer {
+            len: 11,
+            first_page: 42,
+        };
+        let metadata = RowMetadata { xmin: 3, xmax: 0 };
+
+        let mut bytes = RowSerializer::serialize_with_overflow(
+            &row,
+            Some(&schema),
+            metadata,
+            &[None, Some(pointer), None],
+        )
+        .unwrap();
+        // The pointer replaces the length-prefixed string
+        let inline = RowSerializer::serialize(&row, Some(&schema)).unwrap();
+        assert_eq!(bytes.len(), inline.len() - 4 - 11 + OVERFLOW_POINTER_SIZE);
+        assert_eq!(
+            RowSerializer::overflow_pointers(&bytes, &schema).unwrap(),
+            vec![pointer]
+        );
+
+        // Reading without a loader refuses rather than returning a wrong value
+        assert!(RowSerializer::deserialize_with_metadata(&bytes, &schema).is_err());
+
+        RowSerializer::write_metadata(&mut bytes, RowMetadata { xmin: 3, xmax: 9 });
+        let (read_metadata, values) =
+            RowSerializer::deserialize_with_overflow(&bytes, &schema, |p| {
+                assert_eq!(p, pointer);
+                Ok("a long name".to_string())
+            })
+            .unwrap();
+        assert_eq!(read_metadata, RowMetadata { xmin: 3, xmax: 9 });
+        assert_eq!(values, row);
+    }
 }
@@ -9,6 +9,7 @@ pub enum DataType {
     Date,
     Timestamp,
     Decimal,
+    Json,
 }
 
 impl std::fmt::Display for DataType {
@@ -22,6 +23,7 @@ impl std::fmt::Display for DataType {
             DataType::Date => write!(f, "DATE"),
             DataType::Timestamp => write!(f, "TIMESTAMP"),
             DataType::Decimal => write!(f, "DECIMAL"),
+            DataType::Json => write!(f, "JSON"),
         }
     }
 }
@@ -36,6 +38,11 @@ pub struct ColumnDef {
     pub is_not_null: bool,
     pub check: Option<Expr>,
     pub references: Option<ForeignKeyRef>,
+    /// Value that ALTER TABLE ADD COLUMN writes into existing rows
+    ///
+    /// Only accepted by ADD COLUMN, and only used to backfill: the default is
+    /// not stored, so later INSERTs that omit the column still get NULL.
+    pub default: Option<Literal>,
 }
 
 impl ColumnDef {
@@ -48,6 +55,7 @@ impl ColumnDef {
             is_not_null: false,
             check: None,
             references: None,
+            default: None,
         }
     }
 }
@@ -93,6 +101,7 @@ pub enum Literal {
     Date(String),
     Timestamp(String),
     Decimal(String),
+    Interval(crate::types::Interval),
     Null,
 }
 
@@ -106,6 +115,8 @@ impl PartialEq for Literal {
             (Literal::Date(a), Literal::Date(b)) => a == b,
             (Literal::Timestamp(a), Literal::Timestamp(b)) => a == b,
             (Literal::Decimal(a), Literal::Decimal(b)) => a == b,
+            (Literal::Interval(a), Literal::Interval(b)) => a == b,
+            (Literal::Null, Literal::Null) => true,
             _ => false,
         }
     }
@@ -123,6 +134,7 @@ impl std::fmt::Display for Literal {
             Literal::Date(s) => write!(f, "DATE '{}'", s),
             Literal::Timestamp(s) => write!(f, "TIMESTAMP '{}'", s),
             Literal::Decimal(s) => write!(f, "DECIMAL '{}'", s),
+            Literal::Interval(i) => write!(f, "INTERVAL '{}'", i),
             Literal::Null => write!(f, "NULL"),
         }
     }
@@ -132,14 +144,30 @@ impl std::fmt::Display for Literal {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InsertStmt {
     pub table_name: String,
-    pub values: Vec<Vec<Literal>>,
+    pub source: InsertSource,
+}
+
+/// Rows written by an INSERT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertSource {
+    /// `VALUES (...), (...)`
+    Values(Vec<Vec<Literal>>),
+    /// `SELECT ...`, whose result rows are coerced to the table schema
+    Select(Box<SelectStmt>),
 }
 
 impl InsertStmt {
     pub fn new(table_name: impl Into<String>, values: Vec<Vec<Literal>>) -> Self {
         Self {
             table_name: table_name.into(),
-            values,
+            source: InsertSource::Values(values),
+        }
+    }
+
+    pub fn from_select(table_name: impl Into<String>, select: SelectStmt) -> Self {
+        Self {
+            table_name: table_name.into(),
+            source: InsertSource::Select(Box::new(select)),
         }
     }
 }
@@ -163,13 +191,37 @@ impl ColumnRef {
 /// Binary operator
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinaryOp {
-    Eq,    // =
-    NotEq, // !=
-    Lt,    // <
-    LtEq,  // <=
-    Gt,    // >
-    GtEq,  // >=
-    And,   // AND
+    Eq,                // =
+    NotEq,             // !=
+    Lt,                // <
+    LtEq,              // <=
+    Gt,                // >
+    GtEq,              // >=
+    Like,              // LIKE
+    And,               // AND
+    Plus,              // +
+    Minus,             // -
+    Divide,            // /
+    Mod,               // %
+    IsDistinctFrom,    // IS DISTINCT FROM
+    IsNotDistinctFrom, // IS NOT DISTINCT FROM
+}
+
+impl BinaryOp {
+    /// True for `+`, `-`, `/` and `%`, which produce a value rather than a
+    /// truth value
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Plus | BinaryOp::Minus | BinaryOp::Divide | BinaryOp::Mod
+        )
+    }
+
+    /// True for `IS [NOT] DISTINCT FROM`, which compare NULL like any other
+    /// value instead of failing the comparison
+    pub fn is_null_safe(&self) -> bool {
+        matches!(self, BinaryOp::IsDistinctFrom | BinaryOp::IsNotDistinctFrom)
+    }
 }
 
 /// Expression in SQL
@@ -184,6 +236,20 @@ pub enum Expr {
         expr: Box<Expr>,
         subquery: Box<SelectStmt>,
     },
+    /// [NOT] EXISTS subquery, possibly correlated with the outer row
+    Exists {
+        subquery: Box<SelectStmt>,
+        negated: bool,
+    },
+    /// Parenthesized SELECT yielding a single value, possibly correlated
+    /// with the outer row
+    ScalarSubquery(Box<SelectStmt>),
+    /// CURRENT_TIMESTAMP
+    CurrentTimestamp,
+    /// CURRENT_DATE
+    CurrentDate,
+    /// json_extract(expr, path): the element of a JSON document at `path`
+    JsonExtract { expr: Box<Expr>, path: String },
     /// Binary operation (e.g., col = 5)
     BinaryOp {
         left: Box<Expr>,
@@ -208,6 +274,47 @@ impl Expr {
             subquery: Box::new(subquery),
         }
     }
+
+    pub fn exists(subquery: SelectStmt, negated: bool) -> Self {
+        Expr::Exists {
+            subquery: Box::new(subquery),
+            negated,
+        }
+    }
+
+    pub fn scalar_subquery(subquery: SelectStmt) -> Self {
+        Expr::ScalarSubquery(Box::new(subquery))
+    }
+
+    /// Returns true if evaluating the expression runs a subquery
+    pub fn has_subquery(&self) -> bool {
+        match self {
+            Expr::InSubquery { .. } | Expr::Exists { .. } | Expr::ScalarSubquery(_) => true,
+            Expr::JsonExtract { expr, .. } => expr.has_subquery(),
+            Expr::BinaryOp { left, right, .. } => left.has_subquery() || right.has_subquery(),
+            Expr::Column(_) | Expr::Literal(_) | Expr::CurrentTimestamp | Expr::CurrentDate => {
+                false
+            }
+        }
+    }
+
+    /// Column references in the expression, outside any subquery
+    pub fn column_refs(&self) -> Vec<&ColumnRef> {
+        match self {
+            Expr::Column(col_ref) => vec![col_ref],
+            Expr::InSubquery { expr, .. } | Expr::JsonExtract { expr, .. } => expr.column_refs(),
+            Expr::BinaryOp { left, right, .. } => {
+                let mut refs = left.column_refs();
+                refs.extend(right.column_refs());
+                refs
+            }
+            Expr::Literal(_)
+            | Expr::Exists { .. }
+            | Expr::ScalarSubquery(_)
+            | Expr::CurrentTimestamp
+            | Expr::CurrentDate => Vec::new(),
+        }
+    }
 }
 
 /// Aggregate function
@@ -244,10 +351,14 @@ impl AggregateExpr {
 pub enum SelectItem {
     /// All columns (*)
     All,
+    /// All columns of one table (table.*)
+    QualifiedAll(String),
     /// Specific column
     Column(ColumnRef),
     /// Aggregate expression
     Aggregate(AggregateExpr),
+    /// Computed expression (e.g. a scalar subquery)
+    Expr(Expr),
 }
 
 /// Column selection in SELECT
@@ -266,8 +377,8 @@ pub enum FromClause {
     Join {
         left_table: String,
         right_table: String,
-        left_column: ColumnRef,
-        right_column: ColumnRef,
+        /// ON condition (any predicate over both tables)
+        condition: Expr,
     },
 }
 
@@ -292,8 +403,8 @@ pub struct SelectStmt {
     pub group_by: Vec<ColumnRef>,
     pub distinct: bool,
     pub order_by: Vec<OrderByExpr>,
-    pub limit: Option<usize>,
-    pub offset: Option<usize>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
 }
 
 impl SelectStmt {
@@ -304,8 +415,8 @@ impl SelectStmt {
         group_by: Vec<ColumnRef>,
         distinct: bool,
         order_by: Vec<OrderByExpr>,
-        limit: Option<usize>,
-        offset: Option<usize>,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> Self {
         Self {
             columns,
@@ -360,6 +471,8 @@ pub struct CreateIndexStmt {
     pub columns: Vec<String>,
     pub index_type: IndexType,
     pub is_unique: bool,
+    /// Succeed without changes if a matching index already exists
+    pub if_not_exists: bool,
 }
 
 impl CreateIndexStmt {
@@ -374,6 +487,7 @@ impl CreateIndexStmt {
             columns,
             index_type: IndexType::default(),
             is_unique: false,
+            if_not_exists: false,
         }
     }
 
@@ -389,6 +503,7 @@ impl CreateIndexStmt {
             columns,
             index_type,
             is_unique: false,
+            if_not_exists: false,
         }
     }
 
@@ -405,6 +520,7 @@ impl CreateIndexStmt {
             columns,
             index_type,
             is_unique,
+            if_not_exists: false,
         }
     }
 }
@@ -422,6 +538,7 @@ pub enum AlterTableAction {
     DropColumn(String),
     RenameColumn { from: String, to: String },
     RenameTable { to: String },
+    AlterColumnType { column: String, data_type: DataType },
 }
 
 /// ALTER TABLE statement
@@ -451,10 +568,45 @@ impl DropTableStmt {
 /// DROP INDEX statement
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DropIndexStmt {
-    pub index_name: String,
+    pub target: DropIndexTarget,
+}
+
+/// How a DROP INDEX statement identifies the index to drop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DropIndexTarget {
+    /// DROP INDEX idx_name
+    Name(String),
+    /// DROP INDEX ON table (col, ...)
+    Columns {
+        table_name: String,
+        columns: Vec<String>,
+    },
 }
 
 impl DropIndexStmt {
+    pub fn new(index_name: impl Into<String>) -> Self {
+        Self {
+            target: DropIndexTarget::Name(index_name.into()),
+        }
+    }
+
+    pub fn on_columns(table_name: impl Into<String>, columns: Vec<String>) -> Self {
+        Self {
+            target: DropIndexTarget::Columns {
+                table_name: table_name.into(),
+                columns,
+            },
+        }
+    }
+}
+
+/// REINDEX statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReindexStmt {
+    pub index_name: String,
+}
+
+impl ReindexStmt {
     pub fn new(index_name: impl Into<String>) -> Self {
         Self {
             index_name: index_name.into(),
@@ -520,6 +672,49 @@ impl TransactionStmt {
     }
 }
 
+/// Catalog listed by a SHOW statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShowTarget {
+    /// SHOW TABLES
+    Tables,
+    /// SHOW INDEXES [FROM table]
+    Indexes { table_name: Option<String> },
+}
+
+/// SHOW statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowStmt {
+    pub target: ShowTarget,
+}
+
+impl ShowStmt {
+    pub fn tables() -> Self {
+        Self {
+            target: ShowTarget::Tables,
+        }
+    }
+
+    pub fn indexes(table_name: Option<String>) -> Self {
+        Self {
+            target: ShowTarget::Indexes { table_name },
+        }
+    }
+}
+
+/// DESCRIBE statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescribeStmt {
+    pub table_name: String,
+}
+
+impl DescribeStmt {
+    pub fn new(table_name: impl Into<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+        }
+    }
+}
+
 /// SQL statement
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Statement {
@@ -530,7 +725,10 @@ pub enum Statement {
     Select(SelectStmt),
     CreateIndex(CreateIndexStmt),
     DropIndex(DropIndexStmt),
+    Reindex(ReindexStmt),
     Delete(DeleteStmt),
     Update(UpdateStmt),
     Transaction(TransactionStmt),
+    Show(ShowStmt),
+    Describe(DescribeStmt),
 }
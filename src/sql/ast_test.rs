@@ -1,6 +1,7 @@
 mod tests {
     use crate::sql::{
-        CreateTableStmt, DataType, InsertStmt, Statement, TransactionCommand, TransactionStmt,
+        CreateTableStmt, DataType, InsertSource, InsertStmt, Statement, TransactionCommand,
+        TransactionStmt,
         ast::{ColumnDef, Literal},
     };
 
@@ -70,7 +71,7 @@ mod tests {
             ]],
         );
         assert_eq!(stmt.table_name, "users");
-        assert_eq!(stmt.values.len(), 1);
+        assert!(matches!(stmt.source, InsertSource::Values(ref rows) if rows.len() == 1));
     }
 
     #[test]
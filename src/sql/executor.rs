@@ -1,24 +1,40 @@
 use super::ast::{
     AggregateExpr, AggregateFunc, AggregateTarget, AlterTableAction, AlterTableStmt, BinaryOp,
-    ColumnDef, ColumnRef, CreateIndexStmt, CreateTableStmt, DeleteStmt, DropIndexStmt,
-    DropTableStmt, Expr, IndexType, InsertStmt, Literal, OrderByExpr, SelectColumn, SelectItem,
-    SelectStmt, Statement, TransactionCommand, TransactionStmt, UpdateStmt,
+    ColumnDef, ColumnRef, CreateIndexStmt, CreateTableStmt, DeleteStmt, DescribeStmt,
+    DropIndexStmt, DropIndexTarget, DropTableStmt, Expr, FromClause, IndexType, InsertSource,
+    InsertStmt, Literal, OrderByExpr, SelectColumn, SelectItem, SelectStmt, ShowStmt, ShowTarget,
+    Statement, TransactionCommand, TransactionStmt, UpdateStmt,
 };
 use super::parser::parse_sql;
+use super::pattern;
 use crate::index::{BPlusTree, HashIndex};
+use crate::optimizer::estimate::{
+    TableStats, apply_selectivity, estimate_selectivity, predicate_selectivity,
+};
 use crate::optimizer::planner::{
     FromClausePlan, IndexMetadata, JoinPlan, JoinStrategy, Planner, ScanPlan,
 };
 use crate::serialization::{RowMetadata, RowSerializer};
-use crate::storage::PageError;
-use crate::table::{HeapTable, RowId, TableScan};
-use crate::types::{Column, DataType as DbDataType, Schema, Value};
+use crate::storage::{BufferPool, Compression, MAX_ROW_SIZE, SharedBufferPool};
+use crate::table::sort::SPILL_FILE_PREFIX;
+use crate::table::{ExternalSort, HeapTable, RowId, SortedRows, TableScan};
+use crate::types::{Column, DataType as DbDataType, Json, JsonPath, Schema, Value};
 use crate::wal::{TxnId, WalFile, WalRecord};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
+/// Rows a sort holds in memory before spilling a sorted run to disk.
+const DEFAULT_SORT_RUN_SIZE: usize = 100_000;
+
+/// Buffer pool size of an in-memory database
+const IN_MEMORY_BUFFER_POOL_SIZE: usize = 100;
+
+/// Plan note emitted when rows are paged without a defined order.
+const UNSTABLE_ORDERING_WARNING: &str =
+    "Warning: unstable ordering (LIMIT/OFFSET without ORDER BY)";
+
 /// Execution result
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExecutionResult {
@@ -44,8 +60,12 @@ pub enum ExecutionResult {
         index_type: IndexType,
         is_unique: bool,
     },
+    /// CREATE INDEX IF NOT EXISTS found a matching index and left it alone
+    IndexExists { index_name: String },
     /// Index dropped successfully
     DropIndex { index_name: String },
+    /// Index rebuilt from its table
+    Reindex { index_name: String },
     /// Rows deleted successfully
     Delete { rows_deleted: usize },
     /// Rows updated successfully
@@ -123,9 +143,15 @@ impl std::fmt::Display for ExecutionResult {
                     columns.join(", ")
                 )
             }
+            ExecutionResult::IndexExists { index_name } => {
+                write!(f, "Index '{}' already exists, skipping", index_name)
+            }
             ExecutionResult::DropIndex { index_name } => {
                 write!(f, "Index '{}' dropped successfully", index_name)
             }
+            ExecutionResult::Reindex { index_name } => {
+                write!(f, "Index '{}' rebuilt successfully", index_name)
+            }
             ExecutionResult::Delete { rows_deleted } => {
                 if *rows_deleted == 1 {
                     write!(f, "1 row deleted")
@@ -191,6 +217,14 @@ impl IndexEntry {
         row_ids
     }
 
+    /// Number of distinct keys, when the index can report it without a walk
+    fn distinct_keys(&self) -> Option<usize> {
+        match &self.data {
+            IndexData::BTree(_) => None,
+            IndexData::Hash(index) => Some(index.key_count()),
+        }
+    }
+
     fn lookup_eq(&self, key: &CompositeKey) -> Vec<RowId> {
         match &self.data {
             IndexData::BTree(tree) => tree.range_scan(key, key).map(|(_k, v)| v).collect(),
@@ -199,6 +233,109 @@ impl IndexEntry {
     }
 }
 
+/// Compare rows on ORDER BY keys, given as (column, ascending) pairs
+fn compare_order_keys(
+    a: &[Value],
+    b: &[Value],
+    order_indices: &[(usize, bool)],
+) -> std::cmp::Ordering {
+    for (idx, asc) in order_indices {
+        let ord = a[*idx].cmp(&b[*idx]);
+        if ord != std::cmp::Ordering::Equal {
+            return if *asc { ord } else { ord.reverse() };
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
+/// A row competing for a LIMIT in a top-k sort. Rows tied on every ORDER BY
+/// key order by their input position, as they would in a stable sort.
+struct TopKRow<'a> {
+    row: Vec<Value>,
+    position: usize,
+    order_indices: &'a [(usize, bool)],
+}
+
+impl PartialEq for TopKRow<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for TopKRow<'_> {}
+
+impl PartialOrd for TopKRow<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TopKRow<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        compare_order_keys(&self.row, &other.row, self.order_indices)
+            .then(self.position.cmp(&other.position))
+    }
+}
+
+/// The first `k` rows in ORDER BY order among those pushed so far
+struct TopK<'a> {
+    /// A max-heap of the best rows so far: each new row only has to beat
+    /// the worst of them
+    heap: BinaryHeap<TopKRow<'a>>,
+    k: usize,
+    order_indices: &'a [(usize, bool)],
+    /// Rows pushed so far
+    seen: usize,
+    /// Most rows held at once
+    peak: usize,
+}
+
+impl<'a> TopK<'a> {
+    fn new(order_indices: &'a [(usize, bool)], k: usize) -> Self {
+        Self {
+            // `k` can be far more rows than there are
+            heap: BinaryHeap::new(),
+            k,
+            order_indices,
+            seen: 0,
+            peak: 0,
+        }
+    }
+
+    fn push(&mut self, row: Vec<Value>) {
+        let row = TopKRow {
+            row,
+            position: self.seen,
+            order_indices: self.order_indices,
+        };
+        self.seen += 1;
+        if self.heap.len() < self.k {
+            self.heap.push(row);
+            self.peak = self.peak.max(self.heap.len());
+        } else if let Some(mut worst) = self.heap.peek_mut()
+            && row < *worst
+        {
+            *worst = row;
+        }
+    }
+
+    /// Plan step reporting how many rows the sort had to hold
+    fn describe(&self) -> String {
+        format!(
+            "Top-k sort during scan: held at most {} of {} row(s)",
+            self.peak, self.seen
+        )
+    }
+
+    fn into_sorted_rows(self) -> Vec<Vec<Value>> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|top| top.row)
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct CompositeKey {
     values: Vec<IndexValue>,
@@ -206,8 +343,16 @@ struct CompositeKey {
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum IndexValue {
+    /// Key for NULL column values. It sorts before every other value and is
+    /// never produced by a lookup, so NULL rows are indexed but no equality
+    /// or range predicate matches them.
+    Null,
     Signed(i64),
     Unsigned(u64),
+    Str(String),
+    /// Upper bound above every key, for types with no largest value. Never
+    /// stored.
+    Max,
 }
 
 impl CompositeKey {
@@ -215,9 +360,10 @@ impl CompositeKey {
         Self { values }
     }
 
+    /// Lowest possible key, below every stored key including NULLs
     fn min_values(types: &[DbDataType]) -> Self {
         Self {
-            values: types.iter().map(IndexValue::min_value).collect(),
+            values: vec![IndexValue::Null; types.len()],
         }
     }
 
@@ -226,6 +372,11 @@ impl CompositeKey {
             values: types.iter().map(IndexValue::max_value).collect(),
         }
     }
+
+    /// SQL treats NULLs as distinct, so such keys never violate uniqueness
+    fn has_null(&self) -> bool {
+        self.values.contains(&IndexValue::Null)
+    }
 }
 
 impl IndexValue {
@@ -233,7 +384,8 @@ impl IndexValue {
         match data_type {
             DbDataType::Integer => IndexValue::Signed(i64::MIN),
             DbDataType::Unsigned => IndexValue::Unsigned(0),
-            _ => unreachable!("IndexValue only used for integer types"),
+            DbDataType::String => IndexValue::Str(String::new()),
+            _ => unreachable!("IndexValue only used for indexable types"),
         }
     }
 
@@ -241,7 +393,8 @@ impl IndexValue {
         match data_type {
             DbDataType::Integer => IndexValue::Signed(i64::MAX),
             DbDataType::Unsigned => IndexValue::Unsigned(u64::MAX),
-            _ => unreachable!("IndexValue only used for integer types"),
+            DbDataType::String => IndexValue::Max,
+            _ => unreachable!("IndexValue only used for indexable types"),
         }
     }
 
@@ -249,6 +402,7 @@ impl IndexValue {
         match value {
             Value::Integer(i) => Some(IndexValue::Signed(*i)),
             Value::Unsigned(u) => Some(IndexValue::Unsigned(*u)),
+            Value::String(s) => Some(IndexValue::Str(s.clone())),
             Value::Null => None,
             _ => None,
         }
@@ -269,21 +423,30 @@ impl IndexValue {
             (Literal::Float(fv), DbDataType::Unsigned) if fv.fract() == 0.0 && *fv >= 0.0 => {
                 (*fv as i128).try_into().ok().map(IndexValue::Unsigned)
             }
+            (Literal::String(s), DbDataType::String) => Some(IndexValue::Str(s.clone())),
             _ => None,
         }
     }
 
-    fn saturating_sub_one(&self) -> Self {
+    /// Whether every value has an immediate neighbour on each side, so
+    /// exclusive bounds can be made inclusive
+    fn is_discrete(&self) -> bool {
+        matches!(self, IndexValue::Signed(_) | IndexValue::Unsigned(_))
+    }
+
+    fn checked_sub_one(&self) -> Option<Self> {
         match self {
-            IndexValue::Signed(v) => IndexValue::Signed(v.saturating_sub(1)),
-            IndexValue::Unsigned(v) => IndexValue::Unsigned(v.saturating_sub(1)),
+            IndexValue::Signed(v) => v.checked_sub(1).map(IndexValue::Signed),
+            IndexValue::Unsigned(v) => v.checked_sub(1).map(IndexValue::Unsigned),
+            IndexValue::Null | IndexValue::Str(_) | IndexValue::Max => None,
         }
     }
 
-    fn saturating_add_one(&self) -> Self {
+    fn checked_add_one(&self) -> Option<Self> {
         match self {
-            IndexValue::Signed(v) => IndexValue::Signed(v.saturating_add(1)),
-            IndexValue::Unsigned(v) => IndexValue::Unsigned(v.saturating_add(1)),
+            IndexValue::Signed(v) => v.checked_add(1).map(IndexValue::Signed),
+            IndexValue::Unsigned(v) => v.checked_add(1).map(IndexValue::Unsigned),
+            IndexValue::Null | IndexValue::Str(_) | IndexValue::Max => None,
         }
     }
 }
@@ -302,12 +465,13 @@ impl Ord for CompositeKey {
 
 /// Database executor with catalog
 ///
-/// Manages tables and executes SQL statements.
+/// Manages tables and executes SQL statements. Its tables share a
+/// single-threaded buffer pool, so an executor is not `Send`.
 pub struct Executor {
-    /// Database directory
-    db_path: PathBuf,
-    /// Buffer pool size for each table
-    buffer_pool_size: usize,
+    /// Database directory, or `None` for an in-memory database
+    db_path: Option<PathBuf>,
+    /// Buffer pool every table caches its pages in
+    buffer_pool: SharedBufferPool,
     /// Table catalog (maps table name to HeapTable)
     tables: HashMap<String, HeapTable>,
     /// Index catalog (in-memory B-Tree or hash indexes over integer columns)
@@ -320,16 +484,69 @@ pub struct Executor {
     next_txn_id: TxnId,
     /// Write-ahead log handle.
     wal: WalFile,
+    /// Transactions below this id may have been dropped from the WAL by a
+    /// checkpoint.
+    wal_horizon: TxnId,
     /// In-memory log for undo on rollback.
     txn_log: Vec<WalRecord>,
     /// Active transactions for snapshotting.
     active_txns: HashSet<TxnId>,
     /// Per-transaction snapshots.
     snapshots: HashMap<TxnId, Snapshot>,
+    /// Snapshot pinned by `query_as_of` while a historical read runs.
+    as_of_snapshot: Option<Snapshot>,
     /// Transaction state table (active/committed/aborted).
     txn_states: HashMap<TxnId, TxnState>,
+    /// Position of each finished transaction in commit/rollback order, for
+    /// historical reads.
+    finish_order: HashMap<TxnId, u64>,
+    /// Next position to hand out in `finish_order`.
+    next_finish: u64,
     /// Table constraints (primary/unique/foreign keys).
     constraints: HashMap<String, TableConstraints>,
+    /// Reject LIMIT/OFFSET queries that have no ORDER BY.
+    strict_ordering: bool,
+    /// Reaction to write-write conflicts in UPDATE and DELETE.
+    conflict_policy: ConflictPolicy,
+    /// Rows a merge join's sort holds in memory before spilling a run.
+    sort_run_size: usize,
+    /// Metadata entries for tables whose files were missing at startup.
+    orphaned_metadata: Vec<OrphanedMetadata>,
+}
+
+/// An entry in `indexes.meta` or `constraints.meta` whose table file was
+/// missing when the database was opened.
+///
+/// Orphaned entries are written back unchanged whenever the metadata files
+/// are rewritten, so restoring the table file brings them back, until
+/// [`Executor::purge_orphaned_metadata`] drops them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanedMetadata {
+    /// Metadata file the entry came from
+    pub file: &'static str,
+    /// Table the entry refers to
+    pub table: String,
+    /// The entry as stored in the file
+    pub line: String,
+}
+
+impl std::fmt::Display for OrphanedMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} references missing table '{}': {}",
+            self.file, self.table, self.line
+        )
+    }
+}
+
+/// Cardinality estimate for a SELECT, computed without reading any rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowEstimate {
+    /// Rows the chosen scan (or join) is expected to read
+    pub rows_scanned: usize,
+    /// Rows the query is expected to return
+    pub rows_returned: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -339,6 +556,29 @@ pub struct Snapshot {
     pub active: HashSet<TxnId>,
 }
 
+/// What the running statement may see, captured before a table is borrowed
+/// for scanning
+struct RowVisibility<'a> {
+    snapshot: Option<&'a Snapshot>,
+    /// Historical queries cannot trust the all-visible page flags, which
+    /// describe the present
+    check_every_row: bool,
+    current_txn_id: Option<TxnId>,
+    txn_states: &'a HashMap<TxnId, TxnState>,
+}
+
+impl RowVisibility<'_> {
+    fn is_visible(&self, meta: &RowMetadata) -> bool {
+        Executor::is_visible_for_snapshot(meta, self.snapshot, self.current_txn_id, self.txn_states)
+    }
+
+    /// Whether the row `scan` just returned is visible, skipping the check
+    /// on pages marked all-visible
+    fn visible_in_scan(&self, scan: &TableScan, meta: &RowMetadata) -> bool {
+        (!self.check_every_row && scan.on_all_visible_page()) || self.is_visible(meta)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TxnState {
     Active,
@@ -346,7 +586,54 @@ pub enum TxnState {
     Aborted,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// What UPDATE and DELETE do when a target row was already deleted or
+/// replaced by another transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Roll back the current transaction and fail the statement
+    #[default]
+    Abort,
+    /// Fail only the statement, leaving the transaction open so the caller
+    /// can retry it
+    Retry,
+}
+
+/// A write-write conflict, carried as the payload of the `io::Error` returned
+/// by UPDATE or DELETE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteConflict {
+    /// Row the statement tried to modify
+    pub row_id: RowId,
+    /// Transaction that already deleted or replaced the row
+    pub txn_id: TxnId,
+    /// True when the current transaction was left open (`ConflictPolicy::Retry`)
+    pub retryable: bool,
+}
+
+impl std::fmt::Display for WriteConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Write conflict detected on row {:?} (held by transaction {})",
+            self.row_id, self.txn_id
+        )
+    }
+}
+
+impl std::error::Error for WriteConflict {}
+
+impl From<WriteConflict> for io::Error {
+    fn from(err: WriteConflict) -> Self {
+        let kind = if err.retryable {
+            io::ErrorKind::Interrupted
+        } else {
+            io::ErrorKind::Other
+        };
+        io::Error::new(kind, err)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 struct TableConstraints {
     primary_key: Option<String>,
     unique: HashSet<String>,
@@ -355,6 +642,25 @@ struct TableConstraints {
     checks: Vec<Expr>,
 }
 
+impl TableConstraints {
+    fn rename_column(&mut self, from: &str, to: &str) {
+        if self.primary_key.as_deref() == Some(from) {
+            self.primary_key = Some(to.to_string());
+        }
+        if self.unique.remove(from) {
+            self.unique.insert(to.to_string());
+        }
+        if self.not_null.remove(from) {
+            self.not_null.insert(to.to_string());
+        }
+        for fk in &mut self.foreign_keys {
+            if fk.column == from {
+                fk.column = to.to_string();
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct ForeignKey {
     column: String,
@@ -362,158 +668,833 @@ struct ForeignKey {
     ref_column: String,
 }
 
-impl Executor {
-    /// Create a new executor
-    ///
-    /// # Arguments
-    /// * `db_path` - Directory for database files
-    /// * `buffer_pool_size` - Size of buffer pool for each table
-    pub fn new(db_path: impl AsRef<Path>, buffer_pool_size: usize) -> io::Result<Self> {
-        let db_path = db_path.as_ref().to_path_buf();
+/// A table's constraints, as returned by `Executor::table_constraints`.
+///
+/// Column lists follow the table's column order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConstraintInfo {
+    pub primary_key: Option<String>,
+    /// Columns declared UNIQUE, including the primary key
+    pub unique: Vec<String>,
+    /// Columns declared NOT NULL, including the primary key
+    pub not_null: Vec<String>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+    /// CHECK expressions, in declaration order
+    pub checks: Vec<Expr>,
+}
 
-        // Create database directory if it doesn't exist
-        if !db_path.exists() {
-            std::fs::create_dir_all(&db_path)?;
-        }
+/// A foreign key from `column` to `ref_table.ref_column`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignKeyInfo {
+    pub column: String,
+    pub ref_table: String,
+    pub ref_column: String,
+}
 
-        // Load existing heap tables from disk
-        let mut tables = HashMap::new();
-        for entry in fs::read_dir(&db_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().and_then(|ext| ext.to_str()) == Some("db") {
-                let table = HeapTable::open(&path, buffer_pool_size)?;
-                tables.insert(table.name().to_string(), table);
-            }
-        }
+/// The first statement of a batch that `Executor::validate` rejected or
+/// `Executor::execute_batch` failed on, carried as the payload of the
+/// returned `io::Error`.
+#[derive(Debug)]
+pub struct InvalidStatement {
+    /// Position of the statement in the batch, counting from 0
+    pub index: usize,
+    /// The error executing the statement would have raised
+    pub error: io::Error,
+}
 
-        let wal_path = db_path.join("wal.log");
-        let mut executor = Self {
-            db_path,
-            buffer_pool_size,
-            tables,
-            indexes: Vec::new(),
-            in_transaction: false,
-            current_txn_id: None,
-            next_txn_id: 1,
-            wal: WalFile::new(wal_path),
-            txn_log: Vec::new(),
-            active_txns: HashSet::new(),
-            snapshots: HashMap::new(),
-            txn_states: HashMap::new(),
-            constraints: HashMap::new(),
-        };
+impl std::fmt::Display for InvalidStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Statement {}: {}", self.index, self.error)
+    }
+}
 
-        executor.recover_from_wal()?;
-        executor.load_indexes_from_metadata()?;
-        executor.load_constraints_metadata()?;
+impl std::error::Error for InvalidStatement {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
 
-        Ok(executor)
+impl From<InvalidStatement> for io::Error {
+    fn from(err: InvalidStatement) -> Self {
+        io::Error::new(err.error.kind(), err)
     }
+}
 
-    /// Execute a SQL statement
-    ///
-    /// # Arguments
-    /// * `stmt` - Parsed SQL statement
-    ///
-    /// # Returns
-    /// Execution result
-    ///
-    /// # Errors
-    /// Returns error if execution fails
-    pub fn execute(&mut self, stmt: Statement) -> io::Result<ExecutionResult> {
-        match stmt {
-            Statement::CreateTable(create) => self.execute_create_table(create),
-            Statement::DropTable(drop) => self.execute_drop_table(drop),
-            Statement::AlterTable(alter) => self.execute_alter_table(alter),
-            Statement::Insert(insert) => self.execute_insert(insert),
-            Statement::Select(select) => self.execute_select(select),
-            Statement::CreateIndex(create_index) => self.execute_create_index(create_index),
-            Statement::DropIndex(drop_index) => self.execute_drop_index(drop_index),
-            Statement::Delete(delete) => self.execute_delete(delete),
-            Statement::Update(update) => self.execute_update(update),
-            Statement::Transaction(txn) => self.execute_transaction(txn),
-        }
+/// Column metadata visible to a (sub)query, as used for row evaluation
+type ColumnsMeta = Vec<(Option<String>, String)>;
+
+/// The catalog as a batch being validated would leave it, statement by
+/// statement. Only schemas and constraint definitions are tracked; no rows.
+struct DryRunCatalog {
+    tables: HashMap<String, Schema>,
+    constraints: HashMap<String, TableConstraints>,
+    /// Index names with the table and columns they cover and their type
+    indexes: Vec<(String, IndexKey, IndexType)>,
+    in_transaction: bool,
+}
+
+impl DryRunCatalog {
+    fn schema(&self, table_name: &str) -> io::Result<&Schema> {
+        self.tables.get(table_name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Table '{}' does not exist", table_name),
+            )
+        })
     }
 
-    /// Execute CREATE TABLE statement
-    fn execute_create_table(&mut self, stmt: CreateTableStmt) -> io::Result<ExecutionResult> {
-        // Check if table already exists
+    fn create_table(&mut self, stmt: &CreateTableStmt) -> io::Result<()> {
         if self.tables.contains_key(&stmt.table_name) {
             return Err(io::Error::new(
                 io::ErrorKind::AlreadyExists,
                 format!("Table '{}' already exists", stmt.table_name),
             ));
         }
+        let (schema, constraints) = Executor::table_definition(stmt, |name| self.tables.get(name))?;
+        self.tables.insert(stmt.table_name.clone(), schema);
+        self.constraints
+            .insert(stmt.table_name.clone(), constraints);
+        Ok(())
+    }
 
-        // Convert AST column definitions to database schema
-        let columns: Vec<Column> = stmt
-            .columns
-            .iter()
-            .map(|col| {
-                let db_type = match col.data_type {
-                    super::ast::DataType::Integer => DbDataType::Integer,
-                    super::ast::DataType::Unsigned => DbDataType::Unsigned,
-                    super::ast::DataType::Float => DbDataType::Float,
-                    super::ast::DataType::Boolean => DbDataType::Boolean,
-                    super::ast::DataType::Varchar => DbDataType::String,
-                    super::ast::DataType::Date => DbDataType::Date,
-                    super::ast::DataType::Timestamp => DbDataType::Timestamp,
-                    super::ast::DataType::Decimal => DbDataType::Decimal,
-                };
-                Column::new(&col.name, db_type)
-            })
-            .collect();
-
-        let schema = Schema::new(columns);
-
-        let mut primary_key: Option<String> = None;
-        let mut unique: HashSet<String> = HashSet::new();
-        let mut not_null = HashSet::new();
-        let mut foreign_keys = Vec::new();
-        let mut checks = Vec::new();
+    fn drop_table(&mut self, table_name: &str) -> io::Result<()> {
+        self.schema(table_name)?;
+        self.tables.remove(table_name);
+        self.constraints.remove(table_name);
+        self.indexes.retain(|(_, key, _)| key.table != table_name);
+        Ok(())
+    }
 
-        for col_def in &stmt.columns {
-            if col_def.is_primary_key {
-                if primary_key.is_some() {
+    fn alter_table(&mut self, stmt: &AlterTableStmt) -> io::Result<()> {
+        let table_name = &stmt.table_name;
+        match &stmt.action {
+            AlterTableAction::AddColumn(column_def) => {
+                let schema = self.schema(table_name)?;
+                let (column, _) = Executor::column_to_add(schema, column_def)?;
+                let mut columns = schema.columns().to_vec();
+                columns.push(column);
+                self.tables.insert(table_name.clone(), Schema::new(columns));
+                if column_def.is_not_null {
+                    self.constraints
+                        .entry(table_name.clone())
+                        .or_default()
+                        .not_null
+                        .insert(column_def.name.clone());
+                }
+            }
+            AlterTableAction::DropColumn(column_name) => {
+                let (_, schema) = Executor::schema_without_column(
+                    self.index_keys(),
+                    &self.constraints,
+                    table_name,
+                    self.schema(table_name)?,
+                    column_name,
+                )?;
+                self.tables.insert(table_name.clone(), schema);
+            }
+            AlterTableAction::RenameColumn { from, to } => {
+                if from == to {
+                    return Ok(());
+                }
+                let schema = Executor::schema_with_renamed_column(
+                    &self.constraints,
+                    table_name,
+                    self.schema(table_name)?,
+                    from,
+                    to,
+                )?;
+                self.tables.insert(table_name.clone(), schema);
+                if let Some(constraints) = self.constraints.get_mut(table_name) {
+                    constraints.rename_column(from, to);
+                }
+                for (_, key, _) in &mut self.indexes {
+                    if key.table == *table_name {
+                        for col in &mut key.columns {
+                            if col == from {
+                                *col = to.clone();
+                            }
+                        }
+                    }
+                }
+            }
+            AlterTableAction::RenameTable { to } => {
+                if table_name == to {
+                    return Ok(());
+                }
+                if self.tables.contains_key(to) {
                     return Err(io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        "Only one PRIMARY KEY is supported",
+                        io::ErrorKind::AlreadyExists,
+                        format!("Table '{}' already exists", to),
                     ));
                 }
-                primary_key = Some(col_def.name.clone());
-                unique.insert(col_def.name.clone());
-                not_null.insert(col_def.name.clone());
-            } else if col_def.is_unique {
-                unique.insert(col_def.name.clone());
+                let schema = self.schema(table_name)?.clone();
+                self.tables.remove(table_name);
+                self.tables.insert(to.clone(), schema);
+                Executor::rename_constrained_table(&mut self.constraints, table_name, to);
+                for (_, key, _) in &mut self.indexes {
+                    if key.table == *table_name {
+                        key.table = to.clone();
+                    }
+                }
             }
-            if col_def.is_not_null {
-                not_null.insert(col_def.name.clone());
+            AlterTableAction::AlterColumnType { column, data_type } => {
+                if let Some((_, schema)) = Executor::schema_with_column_type(
+                    self.index_keys(),
+                    &self.constraints,
+                    table_name,
+                    self.schema(table_name)?,
+                    column,
+                    Executor::db_data_type(data_type),
+                    |name| self.tables.get(name),
+                )? {
+                    self.tables.insert(table_name.clone(), schema);
+                }
             }
+        }
+        Ok(())
+    }
 
-            if let Some(ref expr) = col_def.check {
-                checks.push(expr.clone());
-            }
+    fn index_keys(&self) -> impl Iterator<Item = (&str, &IndexKey)> {
+        self.indexes
+            .iter()
+            .map(|(name, key, _)| (name.as_str(), key))
+    }
 
-            if let Some(ref fk) = col_def.references {
-                let referenced = self.tables.get(&fk.table).ok_or_else(|| {
-                    io::Error::new(
-                        io::ErrorKind::NotFound,
-                        format!("Referenced table '{}' does not exist", fk.table),
-                    )
-                })?;
-                let (_, ref_col) =
-                    referenced.schema().find_column(&fk.column).ok_or_else(|| {
-                        io::Error::new(
-                            io::ErrorKind::InvalidInput,
-                            format!(
-                                "Referenced column '{}.{}' does not exist",
-                                fk.table, fk.column
-                            ),
-                        )
-                    })?;
+    fn insert(&self, stmt: &InsertStmt) -> io::Result<()> {
+        let schema = self.schema(&stmt.table_name)?;
+        let constraints = self.constraints.get(&stmt.table_name);
+        match &stmt.source {
+            InsertSource::Values(rows) => {
+                for row_values in rows {
+                    let values = Executor::typed_values_row(schema, row_values)?;
+                    if let Some(constraints) = constraints {
+                        Executor::check_not_null(&stmt.table_name, schema, constraints, &values)?;
+                        Executor::check_row_checks(&stmt.table_name, schema, constraints, &values)?;
+                    }
+                }
+            }
+            InsertSource::Select(select) => {
+                let width = self.select(select, &[])?;
+                Executor::check_insert_select(
+                    &stmt.table_name,
+                    schema,
+                    constraints,
+                    &select.columns,
+                    width,
+                )?;
+            }
+        }
+        Ok(())
+    }
 
-                let (_, column) = schema.find_column(&col_def.name).ok_or_else(|| {
+    fn update(&self, stmt: &UpdateStmt) -> io::Result<()> {
+        let schema = self.schema(&stmt.table_name)?;
+        let assignments = Executor::resolve_assignments(
+            &stmt.table_name,
+            schema,
+            self.constraints.get(&stmt.table_name),
+            &stmt.assignments,
+        )?;
+        let scopes = [Executor::build_column_metadata_for_table(
+            &stmt.table_name,
+            schema,
+        )];
+        for (_, expr) in &assignments {
+            self.expr(expr, &scopes)?;
+        }
+        if let Some(expr) = &stmt.where_clause {
+            self.expr(expr, &scopes)?;
+        }
+        Ok(())
+    }
+
+    fn delete(&self, stmt: &DeleteStmt) -> io::Result<()> {
+        let schema = self.schema(&stmt.table_name)?;
+        if let Some(expr) = &stmt.where_clause {
+            let scopes = [Executor::build_column_metadata_for_table(
+                &stmt.table_name,
+                schema,
+            )];
+            self.expr(expr, &scopes)?;
+        }
+        Ok(())
+    }
+
+    /// Resolve every table and column a SELECT names, returning how many
+    /// columns it produces. `outer` holds the columns of enclosing queries,
+    /// innermost last.
+    fn select(&self, stmt: &SelectStmt, outer: &[ColumnsMeta]) -> io::Result<usize> {
+        let columns_meta = match &stmt.from {
+            FromClause::Table(table_name) => {
+                Executor::build_column_metadata_for_table(table_name, self.schema(table_name)?)
+            }
+            FromClause::Join {
+                left_table,
+                right_table,
+                ..
+            } => Executor::build_join_column_metadata(
+                left_table,
+                self.schema(left_table)?,
+                right_table,
+                self.schema(right_table)?,
+            ),
+        };
+        let width = columns_meta.len();
+        let mut scopes = outer.to_vec();
+        scopes.push(columns_meta);
+
+        if let FromClause::Join { condition, .. } = &stmt.from {
+            self.expr(condition, &scopes)?;
+        }
+        if let Some(expr) = &stmt.where_clause {
+            self.expr(expr, &scopes)?;
+        }
+
+        let width = match &stmt.columns {
+            SelectColumn::All => width,
+            SelectColumn::Items(items) => {
+                let mut count = 0;
+                for item in items {
+                    match item {
+                        SelectItem::All => count += width,
+                        SelectItem::QualifiedAll(table) => {
+                            count +=
+                                Executor::qualified_all_indices(&scopes[scopes.len() - 1], table)?
+                                    .len();
+                        }
+                        SelectItem::Column(col_ref) => {
+                            Self::resolve(col_ref, &scopes)?;
+                            count += 1;
+                        }
+                        SelectItem::Aggregate(agg) => {
+                            if let AggregateTarget::Column(col_ref) = &agg.target {
+                                Self::resolve(col_ref, &scopes)?;
+                            }
+                            count += 1;
+                        }
+                        SelectItem::Expr(expr) => {
+                            self.expr(expr, &scopes)?;
+                            count += 1;
+                        }
+                    }
+                }
+                count
+            }
+        };
+
+        for col_ref in &stmt.group_by {
+            Self::resolve(col_ref, &scopes)?;
+        }
+        for order in &stmt.order_by {
+            Self::resolve(&order.column, &scopes)?;
+        }
+        Ok(width)
+    }
+
+    fn expr(&self, expr: &Expr, scopes: &[ColumnsMeta]) -> io::Result<()> {
+        match expr {
+            Expr::Column(col_ref) => Self::resolve(col_ref, scopes),
+            Expr::Literal(_) | Expr::CurrentTimestamp | Expr::CurrentDate => Ok(()),
+            Expr::BinaryOp { left, right, .. } => {
+                self.expr(left, scopes)?;
+                self.expr(right, scopes)
+            }
+            Expr::InSubquery { expr, subquery } => {
+                self.expr(expr, scopes)?;
+                self.select(subquery, scopes).map(|_| ())
+            }
+            Expr::Exists { subquery, .. } | Expr::ScalarSubquery(subquery) => {
+                self.select(subquery, scopes).map(|_| ())
+            }
+            Expr::JsonExtract { expr, .. } => self.expr(expr, scopes),
+        }
+    }
+
+    /// Resolve `col_ref` in the innermost scope that has a column by that
+    /// name.
+    fn resolve(col_ref: &ColumnRef, scopes: &[ColumnsMeta]) -> io::Result<()> {
+        let innermost = scopes.last().map(Vec::as_slice).unwrap_or_default();
+        let scope = scopes
+            .iter()
+            .rev()
+            .find(|scope| {
+                scope.iter().any(|(table, name)| {
+                    name == &col_ref.column
+                        && col_ref
+                            .table
+                            .as_ref()
+                            .is_none_or(|t| table.as_deref() == Some(t))
+                })
+            })
+            .map(Vec::as_slice)
+            .unwrap_or(innermost);
+        Executor::resolve_column_index(scope, col_ref).map(|_| ())
+    }
+
+    fn create_index(&mut self, stmt: &CreateIndexStmt) -> io::Result<()> {
+        let schema = self.schema(&stmt.table_name)?;
+        let existing = self
+            .indexes
+            .iter()
+            .map(|(name, key, index_type)| (name.as_str(), key, *index_type));
+        if Executor::check_new_index(stmt, existing)?.is_some() {
+            return Ok(());
+        }
+        Executor::index_key_columns(stmt, schema)?;
+        self.indexes.push((
+            stmt.index_name.clone(),
+            IndexKey {
+                table: stmt.table_name.clone(),
+                columns: stmt.columns.clone(),
+            },
+            stmt.index_type,
+        ));
+        Ok(())
+    }
+
+    fn drop_index(&mut self, target: &DropIndexTarget) -> io::Result<()> {
+        let pos = match target {
+            DropIndexTarget::Name(index_name) => self.index_position(index_name)?,
+            DropIndexTarget::Columns {
+                table_name,
+                columns,
+            } => {
+                let matches: Vec<usize> = self
+                    .indexes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, key, _))| key.table == *table_name && key.columns == *columns)
+                    .map(|(pos, _)| pos)
+                    .collect();
+                match matches.as_slice() {
+                    [pos] => *pos,
+                    [] => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("No index on {}({}) exists", table_name, columns.join(", ")),
+                        ));
+                    }
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!(
+                                "Multiple indexes on {}({}); drop one by name",
+                                table_name,
+                                columns.join(", ")
+                            ),
+                        ));
+                    }
+                }
+            }
+        };
+        self.indexes.remove(pos);
+        Ok(())
+    }
+
+    fn index_position(&self, index_name: &str) -> io::Result<usize> {
+        self.indexes
+            .iter()
+            .position(|(name, _, _)| name == index_name)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Index '{}' does not exist", index_name),
+                )
+            })
+    }
+
+    fn transaction(&mut self, command: TransactionCommand) -> io::Result<()> {
+        match command {
+            TransactionCommand::Begin if self.in_transaction => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Transaction already in progress",
+            )),
+            TransactionCommand::Commit if !self.in_transaction => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "No active transaction to commit",
+            )),
+            TransactionCommand::Rollback if !self.in_transaction => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "No active transaction to rollback",
+            )),
+            _ => {
+                self.in_transaction = command == TransactionCommand::Begin;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Executor {
+    /// Create an executor for a database that lives only in memory
+    ///
+    /// Nothing touches the filesystem: tables keep their pages in memory,
+    /// the WAL is disabled (so [`Executor::wal_changes_since`] sees no
+    /// changes), sorts never spill and [`Executor::flush_all`] does nothing.
+    /// Everything is lost when the executor is dropped.
+    pub fn in_memory() -> Self {
+        Self::with_storage(
+            None,
+            BufferPool::shared(IN_MEMORY_BUFFER_POOL_SIZE),
+            HashMap::new(),
+            WalFile::disabled(),
+        )
+    }
+
+    fn with_storage(
+        db_path: Option<PathBuf>,
+        buffer_pool: SharedBufferPool,
+        tables: HashMap<String, HeapTable>,
+        wal: WalFile,
+    ) -> Self {
+        Self {
+            db_path,
+            buffer_pool,
+            tables,
+            indexes: Vec::new(),
+            in_transaction: false,
+            current_txn_id: None,
+            next_txn_id: 1,
+            wal,
+            wal_horizon: 1,
+            txn_log: Vec::new(),
+            active_txns: HashSet::new(),
+            snapshots: HashMap::new(),
+            as_of_snapshot: None,
+            txn_states: HashMap::new(),
+            finish_order: HashMap::new(),
+            next_finish: 0,
+            constraints: HashMap::new(),
+            strict_ordering: false,
+            conflict_policy: ConflictPolicy::default(),
+            sort_run_size: DEFAULT_SORT_RUN_SIZE,
+            orphaned_metadata: Vec::new(),
+        }
+    }
+
+    /// Create a new executor
+    ///
+    /// # Arguments
+    /// * `db_path` - Directory for database files
+    /// * `buffer_pool_size` - Pages cached in memory, shared by all tables
+    pub fn new(db_path: impl AsRef<Path>, buffer_pool_size: usize) -> io::Result<Self> {
+        let db_path = db_path.as_ref().to_path_buf();
+
+        // Create database directory if it doesn't exist
+        if !db_path.exists() {
+            std::fs::create_dir_all(&db_path)?;
+        }
+
+        // Load existing heap tables from disk
+        let buffer_pool = BufferPool::shared(buffer_pool_size);
+        let mut tables = HashMap::new();
+        for entry in fs::read_dir(&db_path)? {
+            let entry = entry?;
+            let path = entry.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("db") => {
+                    let table = HeapTable::open_in_pool(&path, &buffer_pool)?;
+                    tables.insert(table.name().to_string(), table);
+                }
+                // Sort runs left behind by a crash
+                Some("tmp")
+                    if entry
+                        .file_name()
+                        .to_str()
+                        .is_some_and(|name| name.starts_with(SPILL_FILE_PREFIX)) =>
+                {
+                    fs::remove_file(&path)?;
+                }
+                _ => {}
+            }
+        }
+
+        let wal = WalFile::new(db_path.join("wal.log"));
+        let mut executor = Self::with_storage(Some(db_path), buffer_pool, tables, wal);
+
+        executor.recover_from_wal()?;
+        executor.load_wal_horizon()?;
+        executor.load_indexes_from_metadata()?;
+        executor.load_constraints_metadata()?;
+
+        Ok(executor)
+    }
+
+    /// Execute a SQL statement
+    ///
+    /// # Arguments
+    /// * `stmt` - Parsed SQL statement
+    ///
+    /// # Returns
+    /// Execution result
+    ///
+    /// # Errors
+    /// Returns error if execution fails
+    pub fn execute(&mut self, stmt: Statement) -> io::Result<ExecutionResult> {
+        match stmt {
+            Statement::CreateTable(create) => self.execute_create_table(create),
+            Statement::DropTable(drop) => self.execute_drop_table(drop),
+            Statement::AlterTable(alter) => self.execute_alter_table(alter),
+            Statement::Insert(insert) => self.execute_insert(insert),
+            Statement::Select(select) => {
+                self.check_select_ordering(&select)?;
+                self.execute_select(select)
+            }
+            Statement::CreateIndex(create_index) => self.execute_create_index(create_index),
+            Statement::DropIndex(drop_index) => self.execute_drop_index(drop_index),
+            Statement::Reindex(reindex) => {
+                self.reindex(&reindex.index_name)?;
+                Ok(ExecutionResult::Reindex {
+                    index_name: reindex.index_name,
+                })
+            }
+            Statement::Delete(delete) => self.execute_delete(delete),
+            Statement::Update(update) => self.execute_update(update),
+            Statement::Transaction(txn) => self.execute_transaction(txn),
+            Statement::Show(show) => self.execute_show(show),
+            Statement::Describe(describe) => self.execute_describe(describe),
+        }
+    }
+
+    /// Execute a batch of statements as one unit
+    ///
+    /// Outside a transaction the batch runs in an implicit one, committed
+    /// after the last statement and rolled back as a whole when a statement
+    /// fails. Inside a transaction the statements join it, and a failure
+    /// leaves that transaction open for the caller to commit or roll back.
+    /// As with ROLLBACK, schema changes are not undone. The batch cannot
+    /// contain BEGIN, COMMIT or ROLLBACK itself.
+    ///
+    /// # Returns
+    /// The result of each statement
+    ///
+    /// # Errors
+    /// Returns the first failing statement as an [`InvalidStatement`]
+    /// payload; the error kind is that of the underlying failure.
+    pub fn execute_batch(&mut self, stmts: Vec<Statement>) -> io::Result<Vec<ExecutionResult>> {
+        if let Some(index) = stmts
+            .iter()
+            .position(|stmt| matches!(stmt, Statement::Transaction(_)))
+        {
+            return Err(InvalidStatement {
+                index,
+                error: io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Transaction control statements are not allowed in a batch",
+                ),
+            }
+            .into());
+        }
+
+        let implicit = !self.in_transaction;
+        if implicit {
+            self.execute_transaction(TransactionStmt::new(TransactionCommand::Begin))?;
+        }
+
+        let mut results = Vec::with_capacity(stmts.len());
+        for (index, stmt) in stmts.into_iter().enumerate() {
+            match self.execute(stmt) {
+                Ok(result) => results.push(result),
+                Err(error) => {
+                    // A write conflict may already have aborted the transaction
+                    if implicit && self.in_transaction {
+                        self.execute_transaction(TransactionStmt::new(
+                            TransactionCommand::Rollback,
+                        ))?;
+                    }
+                    return Err(InvalidStatement { index, error }.into());
+                }
+            }
+        }
+
+        if implicit {
+            self.execute_transaction(TransactionStmt::new(TransactionCommand::Commit))?;
+        }
+        Ok(results)
+    }
+
+    /// Require an ORDER BY whenever a query uses LIMIT or OFFSET.
+    ///
+    /// Without ORDER BY, paged results follow the physical row order, which
+    /// can change between runs (e.g. after vacuum or page reuse).
+    pub fn set_strict_ordering(&mut self, strict: bool) {
+        self.strict_ordering = strict;
+    }
+
+    /// Choose whether a write-write conflict aborts the current transaction
+    /// or only fails the statement with a retryable error.
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) {
+        self.conflict_policy = policy;
+    }
+
+    /// Set how many rows a merge join's sort holds in memory before it
+    /// spills a sorted run to a temporary file in the database directory.
+    pub fn set_sort_run_size(&mut self, rows: usize) {
+        self.sort_run_size = rows;
+    }
+
+    /// Index and constraint metadata that refers to tables whose files were
+    /// missing when the database was opened
+    pub fn orphaned_metadata(&self) -> &[OrphanedMetadata] {
+        &self.orphaned_metadata
+    }
+
+    /// Remove orphaned metadata from the metadata files, returning the
+    /// entries that were dropped
+    pub fn purge_orphaned_metadata(&mut self) -> io::Result<Vec<OrphanedMetadata>> {
+        let purged = std::mem::take(&mut self.orphaned_metadata);
+        if !purged.is_empty() {
+            self.persist_index_metadata()?;
+            self.persist_constraints_metadata()?;
+        }
+        Ok(purged)
+    }
+
+    /// Drop orphaned metadata for `table` once a table of that name exists
+    /// again, so the old indexes and constraints don't attach to it.
+    fn forget_orphaned_metadata(&mut self, table: &str) -> io::Result<()> {
+        let before = self.orphaned_metadata.len();
+        self.orphaned_metadata
+            .retain(|orphan| orphan.table != table);
+        if self.orphaned_metadata.len() != before {
+            self.persist_index_metadata()?;
+            self.persist_constraints_metadata()?;
+        }
+        Ok(())
+    }
+
+    fn check_select_ordering(&self, stmt: &SelectStmt) -> io::Result<()> {
+        if self.strict_ordering
+            && stmt.order_by.is_empty()
+            && (stmt.limit.is_some() || stmt.offset.is_some())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "LIMIT/OFFSET requires ORDER BY in strict ordering mode",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Execute CREATE TABLE statement
+    fn execute_create_table(&mut self, stmt: CreateTableStmt) -> io::Result<ExecutionResult> {
+        // Check if table already exists
+        if self.tables.contains_key(&stmt.table_name) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("Table '{}' already exists", stmt.table_name),
+            ));
+        }
+
+        let (schema, constraints) = Self::table_definition(&stmt, |name| {
+            self.tables.get(name).map(|table| table.schema())
+        })?;
+
+        // Create the heap table
+        let table = match &self.db_path {
+            Some(db_path) => {
+                let table_path = db_path.join(format!("{}.db", stmt.table_name));
+                HeapTable::create_in_pool(&stmt.table_name, schema, table_path, &self.buffer_pool)?
+            }
+            None => HeapTable::create_in_memory(&stmt.table_name, schema, &self.buffer_pool)?,
+        };
+
+        let table_name = stmt.table_name.clone();
+        self.tables.insert(stmt.table_name, table);
+        self.constraints.insert(table_name.clone(), constraints);
+        self.forget_orphaned_metadata(&table_name)?;
+        self.persist_constraints_metadata()?;
+
+        Ok(ExecutionResult::CreateTable { table_name })
+    }
+
+    /// Build the schema and constraints a CREATE TABLE describes, without
+    /// creating anything. `schema_of` looks up tables that foreign keys
+    /// reference; a foreign key to the table being created is checked
+    /// against its own new schema.
+    fn table_definition<'a>(
+        stmt: &CreateTableStmt,
+        schema_of: impl Fn(&str) -> Option<&'a Schema>,
+    ) -> io::Result<(Schema, TableConstraints)> {
+        // Convert AST column definitions to database schema
+        let columns: Vec<Column> = stmt
+            .columns
+            .iter()
+            .map(|col| Column::new(&col.name, Self::db_data_type(&col.data_type)))
+            .collect();
+
+        let schema = Schema::new(columns);
+        let row_size = RowSerializer::max_fixed_size(&schema);
+        if row_size > MAX_ROW_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Rows of table '{}' need up to {} bytes, but a row must fit in a page ({} bytes)",
+                    stmt.table_name, row_size, MAX_ROW_SIZE
+                ),
+            ));
+        }
+
+        let mut primary_key: Option<String> = None;
+        let mut unique: HashSet<String> = HashSet::new();
+        let mut not_null = HashSet::new();
+        let mut foreign_keys = Vec::new();
+        let mut checks = Vec::new();
+
+        for col_def in &stmt.columns {
+            if col_def.default.is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "DEFAULT on column '{}' is only supported by ALTER TABLE ADD COLUMN",
+                        col_def.name
+                    ),
+                ));
+            }
+            if col_def.is_primary_key {
+                if primary_key.is_some() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Only one PRIMARY KEY is supported",
+                    ));
+                }
+                primary_key = Some(col_def.name.clone());
+                unique.insert(col_def.name.clone());
+                not_null.insert(col_def.name.clone());
+            } else if col_def.is_unique {
+                unique.insert(col_def.name.clone());
+            }
+            if col_def.is_not_null {
+                not_null.insert(col_def.name.clone());
+            }
+
+            if let Some(ref expr) = col_def.check {
+                checks.push(expr.clone());
+            }
+
+            if let Some(ref fk) = col_def.references {
+                let referenced = if fk.table == stmt.table_name {
+                    Some(&schema)
+                } else {
+                    schema_of(&fk.table)
+                };
+                let referenced = referenced.ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Referenced table '{}' does not exist", fk.table),
+                    )
+                })?;
+                let (_, ref_col) = referenced.find_column(&fk.column).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "Referenced column '{}.{}' does not exist",
+                            fk.table, fk.column
+                        ),
+                    )
+                })?;
+
+                let (_, column) = schema.find_column(&col_def.name).ok_or_else(|| {
                     io::Error::new(
                         io::ErrorKind::InvalidInput,
                         format!("Column '{}' not found in schema", col_def.name),
@@ -541,16 +1522,8 @@ impl Executor {
             }
         }
 
-        // Create table file path
-        let table_path = self.db_path.join(format!("{}.db", stmt.table_name));
-
-        // Create the heap table
-        let table = HeapTable::create(&stmt.table_name, schema, table_path, self.buffer_pool_size)?;
-
-        let table_name = stmt.table_name.clone();
-        self.tables.insert(stmt.table_name, table);
-        self.constraints.insert(
-            table_name.clone(),
+        Ok((
+            schema,
             TableConstraints {
                 primary_key,
                 unique,
@@ -558,10 +1531,21 @@ impl Executor {
                 foreign_keys,
                 checks,
             },
-        );
-        self.persist_constraints_metadata()?;
+        ))
+    }
 
-        Ok(ExecutionResult::CreateTable { table_name })
+    fn db_data_type(data_type: &super::ast::DataType) -> DbDataType {
+        match data_type {
+            super::ast::DataType::Integer => DbDataType::Integer,
+            super::ast::DataType::Unsigned => DbDataType::Unsigned,
+            super::ast::DataType::Float => DbDataType::Float,
+            super::ast::DataType::Boolean => DbDataType::Boolean,
+            super::ast::DataType::Varchar => DbDataType::String,
+            super::ast::DataType::Date => DbDataType::Date,
+            super::ast::DataType::Timestamp => DbDataType::Timestamp,
+            super::ast::DataType::Decimal => DbDataType::Decimal,
+            super::ast::DataType::Json => DbDataType::Json,
+        }
     }
 
     /// Execute DROP TABLE statement
@@ -586,9 +1570,11 @@ impl Executor {
         self.persist_constraints_metadata()?;
 
         // Delete the table file from disk
-        let table_path = self.db_path.join(format!("{}.db", stmt.table_name));
-        if table_path.exists() {
-            fs::remove_file(table_path)?;
+        if let Some(db_path) = &self.db_path {
+            let table_path = db_path.join(format!("{}.db", stmt.table_name));
+            if table_path.exists() {
+                fs::remove_file(table_path)?;
+            }
         }
 
         Ok(ExecutionResult::DropTable {
@@ -610,6 +1596,9 @@ impl Executor {
             AlterTableAction::RenameTable { to } => {
                 self.execute_alter_table_rename_table(stmt.table_name, to)
             }
+            AlterTableAction::AlterColumnType { column, data_type } => {
+                self.execute_alter_table_alter_column_type(stmt.table_name, column, data_type)
+            }
         }
     }
 
@@ -618,15 +1607,25 @@ impl Executor {
         table_name: String,
         column_def: ColumnDef,
     ) -> io::Result<ExecutionResult> {
-        if column_def.is_primary_key
-            || column_def.is_unique
-            || column_def.is_not_null
-            || column_def.check.is_some()
-            || column_def.references.is_some()
+        let (column, default) = {
+            let table = self.tables.get(&table_name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Table '{}' does not exist", table_name),
+                )
+            })?;
+            Self::column_to_add(table.schema(), &column_def)?
+        };
+        if column_def.is_not_null
+            && default.is_null()
+            && !self.visible_rows_with_ids(&table_name)?.is_empty()
         {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "ALTER TABLE ADD COLUMN does not support constraints yet",
+                format!(
+                    "Cannot add NOT NULL column '{}' without a DEFAULT to non-empty table '{}'",
+                    column_def.name, table_name
+                ),
             ));
         }
 
@@ -641,30 +1640,21 @@ impl Executor {
             let mut rows = Vec::new();
             while let Some((row_id, meta, row)) = scan.next_with_metadata()? {
                 rows.push((row_id, meta, row));
-            }
-            rows
-        };
-
-        let data_type = match column_def.data_type {
-            super::ast::DataType::Integer => DbDataType::Integer,
-            super::ast::DataType::Unsigned => DbDataType::Unsigned,
-            super::ast::DataType::Float => DbDataType::Float,
-            super::ast::DataType::Boolean => DbDataType::Boolean,
-            super::ast::DataType::Varchar => DbDataType::String,
-            super::ast::DataType::Date => DbDataType::Date,
-            super::ast::DataType::Timestamp => DbDataType::Timestamp,
-            super::ast::DataType::Decimal => DbDataType::Decimal,
+            }
+            rows
         };
 
-        {
+        let stored_schema = {
             let table = self.tables.get_mut(&table_name).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
                     format!("Table '{}' does not exist", table_name),
                 )
             })?;
-            table.add_column(Column::new(column_def.name.clone(), data_type))?;
-        }
+            let stored_schema = table.schema().clone();
+            table.add_column(column)?;
+            stored_schema
+        };
 
         {
             let table = self.tables.get_mut(&table_name).ok_or_else(|| {
@@ -673,31 +1663,23 @@ impl Executor {
                     format!("Table '{}' does not exist", table_name),
                 )
             })?;
-            let schema = table.schema().clone();
             for (row_id, meta, mut row) in existing_rows {
-                row.push(Value::Null);
-                let serialized = RowSerializer::serialize_with_metadata(&row, Some(&schema), meta)
-                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                let page = table.buffer_pool_mut().fetch_page(row_id.page_id())?;
-                match page.update_row(row_id.slot_id(), &serialized) {
-                    Ok(()) => {
-                        table.buffer_pool_mut().unpin_page(row_id.page_id(), true);
-                    }
-                    Err(PageError::PageFull) => {
-                        table.buffer_pool_mut().unpin_page(row_id.page_id(), false);
-                        table.delete(row_id)?;
-                        let _new_row_id = table.insert_with_metadata(&row, meta)?;
-                    }
-                    Err(err) => {
-                        table.buffer_pool_mut().unpin_page(row_id.page_id(), false);
-                        return Err(io::Error::from(err));
-                    }
-                }
+                row.push(default.clone());
+                table.rewrite_with_metadata(row_id, &stored_schema, &row, meta)?;
             }
         }
 
         self.rebuild_indexes_for_table(&table_name)?;
 
+        if column_def.is_not_null {
+            self.constraints
+                .entry(table_name.clone())
+                .or_default()
+                .not_null
+                .insert(column_def.name.clone());
+            self.persist_constraints_metadata()?;
+        }
+
         Ok(ExecutionResult::AlterTable { table_name })
     }
 
@@ -706,84 +1688,45 @@ impl Executor {
         table_name: String,
         column_name: String,
     ) -> io::Result<ExecutionResult> {
-        self.ensure_no_indexes_on_column(&table_name, &column_name)?;
-        self.ensure_no_constraints_on_column(&table_name, &column_name)?;
-        self.ensure_no_fk_references_to_column(&table_name, &column_name)?;
-
-        let existing_rows = {
-            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
+        let stored_schema = self
+            .tables
+            .get(&table_name)
+            .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
                     format!("Table '{}' does not exist", table_name),
                 )
-            })?;
-            let schema = table.schema().clone();
-            let (drop_idx, _) = schema.find_column(&column_name).ok_or_else(|| {
+            })?
+            .schema()
+            .clone();
+        let (drop_idx, schema) = Self::schema_without_column(
+            self.index_keys(),
+            &self.constraints,
+            &table_name,
+            &stored_schema,
+            &column_name,
+        )?;
+
+        {
+            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
-                    format!("Column '{}' not found", column_name),
+                    format!("Table '{}' does not exist", table_name),
                 )
             })?;
-            if schema.column_count() <= 1 {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "Cannot drop the last column in a table",
-                ));
-            }
             let mut scan = TableScan::new(table);
             let mut rows = Vec::new();
             while let Some((row_id, meta, row)) = scan.next_with_metadata()? {
                 rows.push((row_id, meta, row));
             }
-            (drop_idx, rows)
-        };
 
-        let (drop_idx, rows) = existing_rows;
-
-        {
-            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("Table '{}' does not exist", table_name),
-                )
-            })?;
-            let mut columns = table.schema().columns().to_vec();
-            columns.remove(drop_idx);
-            let new_schema = Schema::new(columns);
-            table.set_schema(new_schema)?;
-        }
-
-        {
-            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("Table '{}' does not exist", table_name),
-                )
-            })?;
-            let schema = table.schema().clone();
+            table.set_schema(schema.clone())?;
             for (row_id, meta, mut row) in rows {
                 row.remove(drop_idx);
-                let serialized = RowSerializer::serialize_with_metadata(&row, Some(&schema), meta)
-                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                let page = table.buffer_pool_mut().fetch_page(row_id.page_id())?;
-                match page.update_row(row_id.slot_id(), &serialized) {
-                    Ok(()) => {
-                        table.buffer_pool_mut().unpin_page(row_id.page_id(), true);
-                    }
-                    Err(PageError::PageFull) => {
-                        table.buffer_pool_mut().unpin_page(row_id.page_id(), false);
-                        table.delete(row_id)?;
-                        let _new_row_id = table.insert_with_metadata(&row, meta)?;
-                    }
-                    Err(err) => {
-                        table.buffer_pool_mut().unpin_page(row_id.page_id(), false);
-                        return Err(io::Error::from(err));
-                    }
-                }
+                table.rewrite_with_metadata(row_id, &stored_schema, &row, meta)?;
             }
-
-            self.update_index_metadata_for_table(&table_name, &schema)?;
         }
+        self.update_index_metadata_for_table(&table_name, &schema)?;
 
         self.rebuild_indexes_for_table(&table_name)?;
 
@@ -800,9 +1743,6 @@ impl Executor {
             return Ok(ExecutionResult::AlterTable { table_name });
         }
 
-        self.ensure_no_fk_references_to_column(&table_name, &from)?;
-        self.ensure_no_checks_on_table(&table_name)?;
-
         {
             let table = self.tables.get_mut(&table_name).ok_or_else(|| {
                 io::Error::new(
@@ -810,21 +1750,13 @@ impl Executor {
                     format!("Table '{}' does not exist", table_name),
                 )
             })?;
-            let mut columns = table.schema().columns().to_vec();
-            let (idx, _) = table.schema().find_column(&from).ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("Column '{}' not found", from),
-                )
-            })?;
-            if table.schema().find_column(&to).is_some() {
-                return Err(io::Error::new(
-                    io::ErrorKind::AlreadyExists,
-                    format!("Column '{}' already exists", to),
-                ));
-            }
-            columns[idx] = Column::new(to.clone(), columns[idx].data_type());
-            let new_schema = Schema::new(columns);
+            let new_schema = Self::schema_with_renamed_column(
+                &self.constraints,
+                &table_name,
+                table.schema(),
+                &from,
+                &to,
+            )?;
             table.set_schema(new_schema)?;
         }
 
@@ -856,28 +1788,37 @@ impl Executor {
             )
         })?;
 
-        let old_path = self.db_path.join(format!("{}.db", table_name));
-        let new_path = self.db_path.join(format!("{}.db", to));
-        if new_path.exists() {
-            self.tables.insert(table_name.clone(), table);
-            return Err(io::Error::new(
-                io::ErrorKind::AlreadyExists,
-                format!("Table file '{}' already exists", new_path.display()),
-            ));
-        }
+        let paths = self.db_path.as_ref().map(|db_path| {
+            (
+                db_path.join(format!("{}.db", table_name)),
+                db_path.join(format!("{}.db", to)),
+            )
+        });
+        if let Some((old_path, new_path)) = &paths {
+            if new_path.exists() {
+                self.tables.insert(table_name.clone(), table);
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("Table file '{}' already exists", new_path.display()),
+                ));
+            }
 
-        if let Err(err) = fs::rename(&old_path, &new_path) {
-            self.tables.insert(table_name.clone(), table);
-            return Err(err);
+            if let Err(err) = fs::rename(old_path, new_path) {
+                self.tables.insert(table_name.clone(), table);
+                return Err(err);
+            }
         }
 
         if let Err(err) = table.rename(&to) {
-            let _ = fs::rename(&new_path, &old_path);
+            if let Some((old_path, new_path)) = &paths {
+                let _ = fs::rename(new_path, old_path);
+            }
             self.tables.insert(table_name.clone(), table);
             return Err(err);
         }
 
         self.tables.insert(to.clone(), table);
+        self.forget_orphaned_metadata(&to)?;
 
         self.update_index_names_for_table_rename(&table_name, &to)?;
         self.update_constraints_for_table_rename(&table_name, &to)?;
@@ -885,6 +1826,146 @@ impl Executor {
         Ok(ExecutionResult::AlterTable { table_name })
     }
 
+    /// Change a column's type, converting every stored value
+    ///
+    /// All values are converted before anything is written, so one that
+    /// doesn't fit the new type leaves the table unchanged. Indexes on the
+    /// column are rebuilt with the converted keys.
+    ///
+    /// Dead versions, deleted or replaced by a committed transaction or
+    /// written by an aborted one, are converted too, since historical reads
+    /// can still see them until VACUUM removes them. A dead value that
+    /// doesn't fit becomes NULL instead of blocking the change.
+    fn execute_alter_table_alter_column_type(
+        &mut self,
+        table_name: String,
+        column_name: String,
+        data_type: super::ast::DataType,
+    ) -> io::Result<ExecutionResult> {
+        let (stored_schema, column_idx, schema) = {
+            let stored_schema = self
+                .tables
+                .get(&table_name)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Table '{}' does not exist", table_name),
+                    )
+                })?
+                .schema()
+                .clone();
+            let Some((column_idx, schema)) = Self::schema_with_column_type(
+                self.index_keys(),
+                &self.constraints,
+                &table_name,
+                &stored_schema,
+                &column_name,
+                Self::db_data_type(&data_type),
+                |name| self.tables.get(name).map(|table| table.schema()),
+            )?
+            else {
+                return Ok(ExecutionResult::AlterTable { table_name });
+            };
+            (stored_schema, column_idx, schema)
+        };
+        let new_type = schema.columns()[column_idx].data_type();
+
+        let rows = {
+            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Table '{}' does not exist", table_name),
+                )
+            })?;
+            let state_of = |txn_id: TxnId| {
+                self.txn_states
+                    .get(&txn_id)
+                    .copied()
+                    .unwrap_or(TxnState::Committed)
+            };
+            let mut scan = TableScan::new(table);
+            let mut rows = Vec::new();
+            while let Some((row_id, meta, mut row)) = scan.next_with_metadata()? {
+                let dead = (meta.xmin != 0 && state_of(meta.xmin) == TxnState::Aborted)
+                    || (meta.xmax != 0 && state_of(meta.xmax) == TxnState::Committed);
+                let value = std::mem::replace(&mut row[column_idx], Value::Null);
+                row[column_idx] = match Self::coerce_value_to_type(value.clone(), new_type) {
+                    Ok(converted) => converted,
+                    Err(_) if dead => Value::Null,
+                    Err(err) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!(
+                                "Cannot convert value {} in column '{}' to {}: {}",
+                                value, column_name, new_type, err
+                            ),
+                        ));
+                    }
+                };
+                rows.push((row_id, meta, row));
+            }
+            rows
+        };
+
+        {
+            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Table '{}' does not exist", table_name),
+                )
+            })?;
+            table.set_schema(schema.clone())?;
+            for (row_id, meta, row) in rows {
+                table.rewrite_with_metadata(row_id, &stored_schema, &row, meta)?;
+            }
+
+            self.update_index_metadata_for_table(&table_name, &schema)?;
+        }
+
+        self.rebuild_indexes_for_table(&table_name)?;
+
+        Ok(ExecutionResult::AlterTable { table_name })
+    }
+
+    fn unindexable_type_change(column: &str, index_name: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Column '{}' is indexed by '{}'; only INTEGER, UNSIGNED or VARCHAR columns can be indexed",
+                column, index_name
+            ),
+        )
+    }
+
+    /// Column types an index can hold keys for
+    fn is_indexable(data_type: DbDataType) -> bool {
+        matches!(
+            data_type,
+            DbDataType::Integer | DbDataType::Unsigned | DbDataType::String
+        )
+    }
+
+    fn unindexable_column() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Only INTEGER, UNSIGNED or VARCHAR columns can be indexed",
+        )
+    }
+
+    /// `text LIKE pattern`, for two non-NULL values
+    fn like_matches(text: &Value, pattern: &Value) -> io::Result<bool> {
+        match (text, pattern) {
+            (Value::String(text), Value::String(pattern)) => Ok(pattern::matches(text, pattern)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "LIKE requires VARCHAR operands, got {} and {}",
+                    text, pattern
+                ),
+            )),
+        }
+    }
+
     /// Execute DELETE statement
     fn execute_delete(&mut self, stmt: DeleteStmt) -> io::Result<ExecutionResult> {
         let table_name = stmt.table_name;
@@ -914,14 +1995,10 @@ impl Executor {
             ScanPlan::SeqScan => None,
         };
 
-        let snapshot = self.current_snapshot();
-        let current_txn_id = self.current_txn_id;
-        let txn_states = self.txn_states.clone();
-
         // Collect target rows
-        let mut conflict_row: Option<RowId> = None;
+        let mut conflict: Option<(RowId, TxnId)> = None;
         let rows_to_delete: Vec<(RowId, Vec<Value>)> = {
-            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
+            let (table, visibility) = self.table_with_visibility(&table_name).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
                     format!("Table '{}' does not exist", table_name),
@@ -934,12 +2011,7 @@ impl Executor {
                 for row_id in row_ids {
                     let (meta, row) = match table.get_with_metadata(row_id) {
                         Ok((meta, row)) => {
-                            if !Self::is_visible_for_snapshot(
-                                &meta,
-                                snapshot.as_ref(),
-                                current_txn_id,
-                                &txn_states,
-                            ) {
+                            if !visibility.is_visible(&meta) {
                                 continue;
                             }
                             (meta, row)
@@ -954,8 +2026,12 @@ impl Executor {
                         continue;
                     }
 
-                    if Self::has_write_conflict(&meta, current_txn_id, &txn_states) {
-                        conflict_row = Some(row_id);
+                    if let Some(holder) = Self::conflicting_writer(
+                        &meta,
+                        visibility.current_txn_id,
+                        visibility.txn_states,
+                    ) {
+                        conflict = Some((row_id, holder));
                         break;
                     }
 
@@ -964,12 +2040,7 @@ impl Executor {
             } else {
                 let mut scan = TableScan::new(table);
                 while let Some((row_id, meta, row)) = scan.next_with_metadata()? {
-                    if !Self::is_visible_for_snapshot(
-                        &meta,
-                        snapshot.as_ref(),
-                        current_txn_id,
-                        &txn_states,
-                    ) {
+                    if !visibility.visible_in_scan(&scan, &meta) {
                         continue;
                     }
                     if let Some(ref expr) = where_clause
@@ -977,8 +2048,12 @@ impl Executor {
                     {
                         continue;
                     }
-                    if Self::has_write_conflict(&meta, current_txn_id, &txn_states) {
-                        conflict_row = Some(row_id);
+                    if let Some(holder) = Self::conflicting_writer(
+                        &meta,
+                        visibility.current_txn_id,
+                        visibility.txn_states,
+                    ) {
+                        conflict = Some((row_id, holder));
                         break;
                     }
 
@@ -989,14 +2064,8 @@ impl Executor {
             matches
         };
 
-        if let Some(row_id) = conflict_row {
-            if self.in_transaction {
-                self.abort_current_transaction()?;
-            }
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Write conflict detected on row {:?}", row_id),
-            ));
+        if let Some((row_id, holder)) = conflict {
+            return Err(self.write_conflict(row_id, holder));
         }
 
         for (_row_id, row) in &rows_to_delete {
@@ -1026,15 +2095,9 @@ impl Executor {
                     None => continue,
                 };
 
-                let (mut meta, values) = table.get_with_metadata(row_id)?;
+                let (mut meta, _) = table.get_with_metadata(row_id)?;
                 meta.xmax = txn_id;
-                let serialized =
-                    RowSerializer::serialize_with_metadata(&values, Some(table.schema()), meta)
-                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                let page = table.buffer_pool_mut().fetch_page(row_id.page_id())?;
-                page.update_row(row_id.slot_id(), &serialized)
-                    .map_err(io::Error::from)?;
-                table.buffer_pool_mut().unpin_page(row_id.page_id(), true);
+                table.set_metadata(row_id, meta)?;
 
                 rows_deleted += 1;
                 wal_records.push(WalRecord::Delete {
@@ -1065,13 +2128,6 @@ impl Executor {
 
     /// Execute UPDATE statement
     fn execute_update(&mut self, stmt: UpdateStmt) -> io::Result<ExecutionResult> {
-        if stmt.assignments.is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "UPDATE must specify at least one column",
-            ));
-        }
-
         let table_name = stmt.table_name;
         let where_clause = stmt.where_clause;
 
@@ -1088,29 +2144,12 @@ impl Executor {
         let columns_meta = Self::build_column_metadata_for_table(&table_name, &schema);
 
         // Resolve assignment targets and pre-validate literals
-        let mut seen_columns = HashSet::new();
-        let mut assignments = Vec::new();
-        for (col_name, expr) in stmt.assignments {
-            let (idx, column) = schema.find_column(&col_name).ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("Column '{}' not found in table '{}'", col_name, table_name),
-                )
-            })?;
-
-            if !seen_columns.insert(idx) {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("Duplicate assignment for column '{}'", col_name),
-                ));
-            }
-
-            if let Expr::Literal(ref lit) = expr {
-                Self::literal_to_typed_value(lit, column.data_type())?;
-            }
-
-            assignments.push((idx, expr));
-        }
+        let assignments = Self::resolve_assignments(
+            &table_name,
+            &schema,
+            self.constraints.get(&table_name),
+            &stmt.assignments,
+        )?;
 
         let planner = Planner::new(self.index_metadata());
         let scan_plan = planner.plan_scan(&table_name, where_clause.as_ref());
@@ -1123,13 +2162,10 @@ impl Executor {
             ScanPlan::SeqScan => None,
         };
 
-        let snapshot = self.current_snapshot();
-        let current_txn_id = self.current_txn_id;
-        let txn_states = self.txn_states.clone();
         let mut rows_updated = 0;
-        let mut conflict_row: Option<RowId> = None;
+        let mut conflict: Option<(RowId, TxnId)> = None;
         let pending_updates: Vec<(RowId, Vec<Value>, Vec<Value>)> = {
-            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
+            let (table, visibility) = self.table_with_visibility(&table_name).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
                     format!("Table '{}' does not exist", table_name),
@@ -1142,12 +2178,7 @@ impl Executor {
                 for row_id in row_ids {
                     let (meta, row) = match table.get_with_metadata(row_id) {
                         Ok((meta, row)) => {
-                            if !Self::is_visible_for_snapshot(
-                                &meta,
-                                snapshot.as_ref(),
-                                current_txn_id,
-                                &txn_states,
-                            ) {
+                            if !visibility.is_visible(&meta) {
                                 continue;
                             }
                             (meta, row)
@@ -1164,8 +2195,12 @@ impl Executor {
 
                     let new_row =
                         Self::apply_assignments(&row, &assignments, &schema, &columns_meta)?;
-                    if Self::has_write_conflict(&meta, current_txn_id, &txn_states) {
-                        conflict_row = Some(row_id);
+                    if let Some(holder) = Self::conflicting_writer(
+                        &meta,
+                        visibility.current_txn_id,
+                        visibility.txn_states,
+                    ) {
+                        conflict = Some((row_id, holder));
                         break;
                     }
 
@@ -1174,12 +2209,7 @@ impl Executor {
             } else {
                 let mut scan = TableScan::new(table);
                 while let Some((row_id, meta, row)) = scan.next_with_metadata()? {
-                    if !Self::is_visible_for_snapshot(
-                        &meta,
-                        snapshot.as_ref(),
-                        current_txn_id,
-                        &txn_states,
-                    ) {
+                    if !visibility.visible_in_scan(&scan, &meta) {
                         continue;
                     }
                     if let Some(ref expr) = where_clause
@@ -1190,8 +2220,12 @@ impl Executor {
 
                     let new_row =
                         Self::apply_assignments(&row, &assignments, &schema, &columns_meta)?;
-                    if Self::has_write_conflict(&meta, current_txn_id, &txn_states) {
-                        conflict_row = Some(row_id);
+                    if let Some(holder) = Self::conflicting_writer(
+                        &meta,
+                        visibility.current_txn_id,
+                        visibility.txn_states,
+                    ) {
+                        conflict = Some((row_id, holder));
                         break;
                     }
 
@@ -1202,14 +2236,8 @@ impl Executor {
             pending
         };
 
-        if let Some(row_id) = conflict_row {
-            if self.in_transaction {
-                self.abort_current_transaction()?;
-            }
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Write conflict detected on row {:?}", row_id),
-            ));
+        if let Some((row_id, holder)) = conflict {
+            return Err(self.write_conflict(row_id, holder));
         }
 
         let updated_rows: Vec<Vec<Value>> = pending_updates
@@ -1243,7 +2271,7 @@ impl Executor {
                     None => continue,
                 };
 
-                let (mut old_meta, old_values) = table.get_with_metadata(row_id)?;
+                let (mut old_meta, _) = table.get_with_metadata(row_id)?;
                 let new_meta = RowMetadata {
                     xmin: txn_id,
                     xmax: 0,
@@ -1251,16 +2279,7 @@ impl Executor {
                 let _new_row_id = table.insert_with_metadata(&new_row, new_meta)?;
 
                 old_meta.xmax = txn_id;
-                let serialized = RowSerializer::serialize_with_metadata(
-                    &old_values,
-                    Some(table.schema()),
-                    old_meta,
-                )
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                let page = table.buffer_pool_mut().fetch_page(row_id.page_id())?;
-                page.update_row(row_id.slot_id(), &serialized)
-                    .map_err(io::Error::from)?;
-                table.buffer_pool_mut().unpin_page(row_id.page_id(), true);
+                table.set_metadata(row_id, old_meta)?;
 
                 rows_updated += 1;
                 wal_records.push(WalRecord::Update {
@@ -1375,21 +2394,46 @@ impl Executor {
             table.schema().clone()
         };
 
+        // Every row is converted before anything is written, so a bad row
+        // leaves the table untouched
         let mut prepared_rows = Vec::new();
-        for row_values in stmt.values {
-            if row_values.len() != schema.column_count() {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "Row does not match table schema",
-                ));
+        match stmt.source {
+            InsertSource::Values(rows) => {
+                for row_values in rows {
+                    prepared_rows.push(Self::typed_values_row(&schema, &row_values)?);
+                }
             }
+            InsertSource::Select(select) => {
+                self.check_select_ordering(&select)?;
+                let select_columns = select.columns.clone();
+                let (column_names, rows) = match self.execute_select(*select)? {
+                    ExecutionResult::Select {
+                        column_names, rows, ..
+                    } => (column_names, rows),
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "INSERT source must be a SELECT",
+                        ));
+                    }
+                };
+                Self::check_insert_select(
+                    &table_name,
+                    &schema,
+                    self.constraints.get(&table_name),
+                    &select_columns,
+                    column_names.len(),
+                )?;
 
-            let values: Vec<Value> = row_values
-                .iter()
-                .zip(schema.columns())
-                .map(|(lit, col)| Self::literal_to_typed_value(lit, col.data_type()))
-                .collect::<io::Result<_>>()?;
-            prepared_rows.push(values);
+                for row in rows {
+                    let values: Vec<Value> = row
+                        .into_iter()
+                        .zip(schema.columns())
+                        .map(|(value, col)| Self::insert_select_value(value, col))
+                        .collect::<io::Result<_>>()?;
+                    prepared_rows.push(values);
+                }
+            }
         }
 
         if !prepared_rows.is_empty() {
@@ -1431,7 +2475,7 @@ impl Executor {
                     )?;
 
                     // Check uniqueness constraint for unique indexes
-                    if index.is_unique && !index.lookup_eq(&key).is_empty() {
+                    if index.is_unique && !key.has_null() && !index.lookup_eq(&key).is_empty() {
                         return Err(io::Error::new(
                             io::ErrorKind::InvalidInput,
                             format!(
@@ -1465,85 +2509,49 @@ impl Executor {
         }
 
         if let Some((txn_id, implicit)) = wal_context {
-            if implicit {
-                self.wal.append(&WalRecord::Commit { txn_id })?;
-                self.set_txn_state(txn_id, TxnState::Committed);
-            }
-        }
-
-        Ok(ExecutionResult::Insert { row_ids })
-    }
-
-    /// Execute CREATE INDEX statement
-    fn execute_create_index(&mut self, stmt: CreateIndexStmt) -> io::Result<ExecutionResult> {
-        // Check if table exists
-        let table = self.tables.get_mut(&stmt.table_name).ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::NotFound,
-                format!("Table '{}' does not exist", stmt.table_name),
-            )
-        })?;
-
-        let schema = table.schema().clone();
-
-        if stmt.columns.is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Index must include at least one column",
-            ));
-        }
-
-        // Ensure index name and column set are unique
-        if self.indexes.iter().any(|idx| idx.name == stmt.index_name) {
-            return Err(io::Error::new(
-                io::ErrorKind::AlreadyExists,
-                format!("Index '{}' already exists", stmt.index_name),
-            ));
-        }
-
-        if self
-            .indexes
-            .iter()
-            .any(|idx| idx.key.table == stmt.table_name && idx.key.columns == stmt.columns)
-        {
-            return Err(io::Error::new(
-                io::ErrorKind::AlreadyExists,
-                format!(
-                    "Index on {}({}) already exists",
-                    stmt.table_name,
-                    stmt.columns.join(", ")
-                ),
-            ));
+            if implicit {
+                self.wal.append(&WalRecord::Commit { txn_id })?;
+                self.set_txn_state(txn_id, TxnState::Committed);
+            }
         }
 
-        // Resolve and validate columns
-        let mut column_indices = Vec::new();
-        let mut column_types = Vec::new();
-        for col_name in &stmt.columns {
-            let (idx, column) = schema.find_column(col_name).ok_or_else(|| {
+        Ok(ExecutionResult::Insert { row_ids })
+    }
+
+    /// Execute CREATE INDEX statement
+    fn execute_create_index(&mut self, stmt: CreateIndexStmt) -> io::Result<ExecutionResult> {
+        // Check if table exists
+        let schema = self
+            .tables
+            .get(&stmt.table_name)
+            .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
-                    format!(
-                        "Column '{}' not found in table '{}'",
-                        col_name, stmt.table_name
-                    ),
+                    format!("Table '{}' does not exist", stmt.table_name),
                 )
-            })?;
-
-            if !matches!(
-                column.data_type(),
-                DbDataType::Integer | DbDataType::Unsigned
-            ) {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "Only INTEGER or UNSIGNED columns can be indexed",
-                ));
-            }
+            })?
+            .schema()
+            .clone();
 
-            column_indices.push(idx);
-            column_types.push(column.data_type());
+        let existing = self
+            .indexes
+            .iter()
+            .map(|idx| (idx.name.as_str(), &idx.key, idx.index_type));
+        if let Some(existing) = Self::check_new_index(&stmt, existing)? {
+            return Ok(ExecutionResult::IndexExists {
+                index_name: existing.to_string(),
+            });
         }
 
+        // Resolve and validate columns
+        let (column_indices, column_types) = Self::index_key_columns(&stmt, &schema)?;
+        let table = self.tables.get_mut(&stmt.table_name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Table '{}' does not exist", stmt.table_name),
+            )
+        })?;
+
         // Create the index and populate it with existing data
         let mut data = match stmt.index_type {
             IndexType::BTree => IndexData::BTree(BPlusTree::new()),
@@ -1557,7 +2565,7 @@ impl Executor {
             let key = Self::build_composite_key(&row, &column_indices, &column_types)?;
 
             // If this is a unique index, check for duplicates
-            if stmt.is_unique && !seen_keys.insert(key.clone()) {
+            if stmt.is_unique && !key.has_null() && !seen_keys.insert(key.clone()) {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidInput,
                     format!(
@@ -1598,31 +2606,184 @@ impl Executor {
         })
     }
 
+    /// Execute SHOW TABLES / SHOW INDEXES
+    ///
+    /// The catalog is returned as a result set, sorted by name.
+    fn execute_show(&self, stmt: ShowStmt) -> io::Result<ExecutionResult> {
+        match stmt.target {
+            ShowTarget::Tables => {
+                let mut tables = self.list_tables();
+                tables.sort_by(|a, b| a.0.cmp(&b.0));
+                let rows = tables
+                    .into_iter()
+                    .map(|(name, schema)| {
+                        let columns: Vec<String> = schema
+                            .columns()
+                            .iter()
+                            .map(|c| format!("{} {}", c.name(), c.data_type()))
+                            .collect();
+                        vec![Value::String(name), Value::String(columns.join(", "))]
+                    })
+                    .collect();
+
+                Ok(ExecutionResult::Select {
+                    column_names: vec!["name".to_string(), "columns".to_string()],
+                    rows,
+                    plan: Vec::new(),
+                })
+            }
+            ShowTarget::Indexes { table_name } => {
+                if let Some(table) = &table_name
+                    && !self.tables.contains_key(table)
+                {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Table '{}' does not exist", table),
+                    ));
+                }
+
+                let mut indexes = self.list_indexes();
+                indexes.retain(|(_, table, _, _, _)| {
+                    table_name.as_ref().is_none_or(|name| name == table)
+                });
+                indexes.sort_by(|a, b| a.0.cmp(&b.0));
+                let rows = indexes
+                    .into_iter()
+                    .map(|(name, table, columns, index_type, is_unique)| {
+                        vec![
+                            Value::String(name),
+                            Value::String(table),
+                            Value::String(columns.join(", ")),
+                            Value::String(index_type.to_string()),
+                            Value::Boolean(is_unique),
+                        ]
+                    })
+                    .collect();
+
+                Ok(ExecutionResult::Select {
+                    column_names: ["name", "table", "columns", "type", "unique"]
+                        .iter()
+                        .map(|c| c.to_string())
+                        .collect(),
+                    rows,
+                    plan: Vec::new(),
+                })
+            }
+        }
+    }
+
+    /// Execute DESCRIBE: one row per column, in schema order
+    fn execute_describe(&self, stmt: DescribeStmt) -> io::Result<ExecutionResult> {
+        let table = self.tables.get(&stmt.table_name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Table '{}' does not exist", stmt.table_name),
+            )
+        })?;
+        let constraints = self.constraints.get(&stmt.table_name);
+
+        let rows = table
+            .schema()
+            .columns()
+            .iter()
+            .map(|column| {
+                let name = column.name();
+                let is_primary =
+                    constraints.is_some_and(|c| c.primary_key.as_deref() == Some(name));
+                let is_unique = constraints.is_some_and(|c| c.unique.contains(name));
+                let is_not_null = constraints.is_some_and(|c| c.not_null.contains(name));
+                vec![
+                    Value::String(name.to_string()),
+                    Value::String(column.data_type().to_string()),
+                    Value::Boolean(!is_not_null),
+                    Value::Boolean(is_primary),
+                    Value::Boolean(is_unique),
+                    Value::Null,
+                ]
+            })
+            .collect();
+
+        Ok(ExecutionResult::Select {
+            column_names: [
+                "name",
+                "type",
+                "nullable",
+                "primary_key",
+                "unique",
+                "default",
+            ]
+            .iter()
+            .map(|c| c.to_string())
+            .collect(),
+            rows,
+            plan: Vec::new(),
+        })
+    }
+
     /// Execute DROP INDEX statement
     fn execute_drop_index(&mut self, stmt: DropIndexStmt) -> io::Result<ExecutionResult> {
-        // Find the index by name
-        let index_pos = self
-            .indexes
-            .iter()
-            .position(|idx| idx.name == stmt.index_name)
-            .ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("Index '{}' does not exist", stmt.index_name),
-                )
-            })?;
+        let index_pos = match &stmt.target {
+            DropIndexTarget::Name(index_name) => self
+                .indexes
+                .iter()
+                .position(|idx| idx.name == *index_name)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Index '{}' does not exist", index_name),
+                    )
+                })?,
+            DropIndexTarget::Columns {
+                table_name,
+                columns,
+            } => self.find_index_by_columns(table_name, columns)?,
+        };
 
         // Remove the index
-        self.indexes.remove(index_pos);
+        let removed = self.indexes.remove(index_pos);
 
         // Persist updated index metadata
         self.persist_index_metadata()?;
 
         Ok(ExecutionResult::DropIndex {
-            index_name: stmt.index_name,
+            index_name: removed.name,
         })
     }
 
+    /// Locate the single index on exactly `columns` of `table_name`.
+    fn find_index_by_columns(&self, table_name: &str, columns: &[String]) -> io::Result<usize> {
+        let matches: Vec<usize> = self
+            .indexes
+            .iter()
+            .enumerate()
+            .filter(|(_, idx)| idx.key.table == table_name && idx.key.columns == columns)
+            .map(|(pos, _)| pos)
+            .collect();
+
+        match matches.as_slice() {
+            [pos] => Ok(*pos),
+            [] => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No index on {}({}) exists", table_name, columns.join(", ")),
+            )),
+            _ => {
+                let names: Vec<&str> = matches
+                    .iter()
+                    .map(|&pos| self.indexes[pos].name.as_str())
+                    .collect();
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Multiple indexes on {}({}): {}; drop one by name",
+                        table_name,
+                        columns.join(", "),
+                        names.join(", ")
+                    ),
+                ))
+            }
+        }
+    }
+
     /// Execute SELECT statement
     fn execute_select(&mut self, stmt: SelectStmt) -> io::Result<ExecutionResult> {
         let planner = Planner::new(self.index_metadata());
@@ -1662,54 +2823,15 @@ impl Executor {
         group_by: &[ColumnRef],
         distinct: bool,
         order_by: &[OrderByExpr],
-        limit: Option<usize>,
-        offset: Option<usize>,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> io::Result<ExecutionResult> {
         let mut plan_steps = Vec::new();
         plan_steps.push(Self::describe_scan(&table_name, &scan_plan));
         if let Some(ref predicate) = where_clause {
             plan_steps.push(format!("Filter: {}", Self::describe_expr(predicate)));
         }
-        if !order_by.is_empty() {
-            let order_desc = order_by
-                .iter()
-                .map(|expr| {
-                    format!(
-                        "{} {}",
-                        Self::format_column_ref(&expr.column),
-                        if expr.ascending { "ASC" } else { "DESC" }
-                    )
-                })
-                .collect::<Vec<_>>()
-                .join(", ");
-            plan_steps.push(format!("Order by: {}", order_desc));
-        }
-        if let Some(limit) = limit {
-            plan_steps.push(format!("Limit: {}", limit));
-        }
-        if let Some(offset) = offset {
-            plan_steps.push(format!("Offset: {}", offset));
-        }
-        if !order_by.is_empty() {
-            let order_desc = order_by
-                .iter()
-                .map(|expr| {
-                    format!(
-                        "{} {}",
-                        Self::format_column_ref(&expr.column),
-                        if expr.ascending { "ASC" } else { "DESC" }
-                    )
-                })
-                .collect::<Vec<_>>()
-                .join(", ");
-            plan_steps.push(format!("Order by: {}", order_desc));
-        }
-        if let Some(limit) = limit {
-            plan_steps.push(format!("Limit: {}", limit));
-        }
-        if let Some(offset) = offset {
-            plan_steps.push(format!("Offset: {}", offset));
-        }
+        plan_steps.extend(Self::describe_order_limit(order_by, limit, offset));
 
         // Get schema first (before any mutable borrows)
         let schema = {
@@ -1724,10 +2846,6 @@ impl Executor {
 
         let columns_meta = Self::build_column_metadata_for_table(&table_name, &schema);
 
-        let snapshot = self.current_snapshot();
-        let current_txn_id = self.current_txn_id;
-        let txn_states = self.txn_states.clone();
-
         let row_ids = match scan_plan {
             ScanPlan::IndexScan {
                 index_columns,
@@ -1737,32 +2855,66 @@ impl Executor {
             ScanPlan::SeqScan => None,
         };
 
-        // Get the table again for mutable access
+        // Pick the top rows while scanning when the filter can run without
+        // the executor, which the scan keeps borrowed
+        let top_k_keys = Self::top_k_during_scan(
+            &columns,
+            &columns_meta,
+            group_by,
+            distinct,
+            order_by,
+            limit,
+            offset,
+        )
+        .filter(|_| {
+            where_clause
+                .as_ref()
+                .is_none_or(|expr| !expr.has_subquery())
+        });
+        let mut top_k = top_k_keys
+            .as_ref()
+            .map(|(order_indices, keep)| TopK::new(order_indices, *keep));
         let mut result_rows = Vec::new();
+        let mut collect = |row: Vec<Value>| -> io::Result<()> {
+            match &mut top_k {
+                Some(top_k) => {
+                    if let Some(ref where_expr) = where_clause
+                        && !Self::evaluate_predicate_static(where_expr, &row, &columns_meta)?
+                    {
+                        return Ok(());
+                    }
+                    top_k.push(row);
+                }
+                None => result_rows.push(row),
+            }
+            Ok(())
+        };
 
         if let Some(row_ids) = row_ids {
-            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
+            let (table, visibility) = self.table_with_visibility(&table_name).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
                     format!("Table '{}' does not exist", table_name),
                 )
             })?;
+            // Rows tied on every ORDER BY key keep the order they arrive in,
+            // so visit them by RowId, as a sequential scan would. The row id
+            // then acts as a hidden final sort key whichever path reads them.
+            let mut row_ids = row_ids;
+            if !order_by.is_empty() {
+                row_ids.sort_unstable();
+            }
             // Index scan: fetch specific rows
             for row_id in row_ids {
                 let (meta, row) = table.get_with_metadata(row_id)?;
-                if !Self::is_visible_for_snapshot(
-                    &meta,
-                    snapshot.as_ref(),
-                    current_txn_id,
-                    &txn_states,
-                ) {
+                if !visibility.is_visible(&meta) {
                     continue;
                 }
 
-                result_rows.push(row);
+                collect(row)?;
             }
         } else {
-            let table = self.tables.get_mut(&table_name).ok_or_else(|| {
+            let (table, visibility) = self.table_with_visibility(&table_name).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
                     format!("Table '{}' does not exist", table_name),
@@ -1772,19 +2924,17 @@ impl Executor {
             let mut scan = TableScan::new(table);
 
             while let Some((_row_id, meta, row)) = scan.next_with_metadata()? {
-                if !Self::is_visible_for_snapshot(
-                    &meta,
-                    snapshot.as_ref(),
-                    current_txn_id,
-                    &txn_states,
-                ) {
+                if !visibility.visible_in_scan(&scan, &meta) {
                     continue;
                 }
-                result_rows.push(row);
+                collect(row)?;
             }
         }
 
-        if let Some(ref where_expr) = where_clause {
+        if let Some(top_k) = top_k {
+            plan_steps.push(top_k.describe());
+            result_rows = top_k.into_sorted_rows();
+        } else if let Some(ref where_expr) = where_clause {
             let mut filtered = Vec::with_capacity(result_rows.len());
             for row in result_rows {
                 if self.evaluate_predicate(where_expr, &row, &columns_meta)? {
@@ -1794,6 +2944,9 @@ impl Executor {
             result_rows = filtered;
         }
 
+        let mut columns_meta = columns_meta;
+        let columns =
+            self.materialize_select_exprs(&columns, &mut result_rows, &mut columns_meta)?;
         let (column_names, mut result_rows, output_meta) =
             Self::apply_select_items(result_rows, &columns_meta, &columns, group_by, false)?;
         if distinct {
@@ -1826,8 +2979,8 @@ impl Executor {
         group_by: &[ColumnRef],
         distinct: bool,
         order_by: &[OrderByExpr],
-        limit: Option<usize>,
-        offset: Option<usize>,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> io::Result<ExecutionResult> {
         // Fetch schemas before mutable borrows
         let left_schema = {
@@ -1849,17 +3002,22 @@ impl Executor {
             table.schema().clone()
         };
 
-        // Resolve join columns
-        let left_join_idx = Self::resolve_schema_column_index(
-            &left_schema,
-            &join_plan.outer_table,
-            &join_plan.outer_column,
-        )?;
-        let right_join_idx = Self::resolve_schema_column_index(
-            &right_schema,
-            &join_plan.inner_table,
-            &join_plan.inner_column,
-        )?;
+        // Resolve join key columns
+        let join_indices = match &join_plan.join_key {
+            Some((outer_column, inner_column)) => Some((
+                Self::resolve_schema_column_index(
+                    &left_schema,
+                    &join_plan.outer_table,
+                    outer_column,
+                )?,
+                Self::resolve_schema_column_index(
+                    &right_schema,
+                    &join_plan.inner_table,
+                    inner_column,
+                )?,
+            )),
+            None => None,
+        };
 
         let combined_meta = Self::build_join_column_metadata(
             &join_plan.outer_table,
@@ -1872,8 +3030,7 @@ impl Executor {
             JoinStrategy::NestedLoop { inner_has_index } => self.execute_nested_loop_join(
                 join_plan,
                 where_clause,
-                left_join_idx,
-                right_join_idx,
+                join_indices,
                 &right_schema,
                 &combined_meta,
                 &columns,
@@ -1884,19 +3041,27 @@ impl Executor {
                 limit,
                 offset,
             ),
-            JoinStrategy::MergeJoin => self.execute_merge_join(
-                join_plan,
-                where_clause,
-                left_join_idx,
-                right_join_idx,
-                &combined_meta,
-                &columns,
-                group_by,
-                distinct,
-                order_by,
-                limit,
-                offset,
-            ),
+            JoinStrategy::MergeJoin => {
+                let (left_join_idx, right_join_idx) = join_indices.ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Merge join requires an equality join key",
+                    )
+                })?;
+                self.execute_merge_join(
+                    join_plan,
+                    where_clause,
+                    left_join_idx,
+                    right_join_idx,
+                    &combined_meta,
+                    &columns,
+                    group_by,
+                    distinct,
+                    order_by,
+                    limit,
+                    offset,
+                )
+            }
         }
     }
 
@@ -1905,8 +3070,7 @@ impl Executor {
         &mut self,
         join_plan: JoinPlan,
         where_clause: Option<Expr>,
-        left_join_idx: usize,
-        right_join_idx: usize,
+        join_indices: Option<(usize, usize)>,
         right_schema: &Schema,
         combined_meta: &[(Option<String>, String)],
         columns: &SelectColumn,
@@ -1914,17 +3078,15 @@ impl Executor {
         inner_has_index: bool,
         distinct: bool,
         order_by: &[OrderByExpr],
-        limit: Option<usize>,
-        offset: Option<usize>,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> io::Result<ExecutionResult> {
-        let snapshot = self.current_snapshot();
-        let current_txn_id = self.current_txn_id;
-        let txn_states = self.txn_states.clone();
-
         // Preload left rows (outer loop)
         let left_rows = {
             let mut rows = Vec::new();
-            let left_table_ref = self.tables.get_mut(&join_plan.outer_table).ok_or_else(|| {
+            let (left_table_ref, visibility) = self
+                .table_with_visibility(&join_plan.outer_table)
+                .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
                     format!("Table '{}' does not exist", join_plan.outer_table),
@@ -1932,12 +3094,7 @@ impl Executor {
             })?;
             let mut scan = TableScan::new(left_table_ref);
             while let Some((_row_id, meta, row)) = scan.next_with_metadata()? {
-                if !Self::is_visible_for_snapshot(
-                    &meta,
-                    snapshot.as_ref(),
-                    current_txn_id,
-                    &txn_states,
-                ) {
+                if !visibility.visible_in_scan(&scan, &meta) {
                     continue;
                 }
                 rows.push(row);
@@ -1945,33 +3102,32 @@ impl Executor {
             rows
         };
 
-        let index_key = (
-            join_plan.inner_table.clone(),
-            right_schema.columns()[right_join_idx].name().to_string(),
-        );
-        let right_join_is_integer = matches!(
-            right_schema.columns()[right_join_idx].data_type(),
-            DbDataType::Integer | DbDataType::Unsigned
-        );
-        let use_right_index = inner_has_index
-            && right_join_is_integer
-            && self
-                .find_index_on_first_column(&index_key.0, &index_key.1)
-                .is_some();
+        // Inner index lookups need an equality key with an index on it
+        let index_lookup = match join_indices {
+            Some((left_join_idx, right_join_idx)) if inner_has_index => {
+                let column = &right_schema.columns()[right_join_idx];
+                let usable = Self::is_indexable(column.data_type())
+                    && self
+                        .find_index_on_first_column(&join_plan.inner_table, column.name())
+                        .is_some();
+                usable.then(|| (left_join_idx, column.name().to_string()))
+            }
+            _ => None,
+        };
+        let use_right_index = index_lookup.is_some();
 
         let mut plan_steps = Vec::new();
         plan_steps.push(format!("Seq scan outer table {}", join_plan.outer_table));
         plan_steps.push(format!(
-            "Nested loop join outer={} inner={} on {} = {}",
+            "Nested loop join outer={} inner={} on {}",
             join_plan.outer_table,
             join_plan.inner_table,
-            Self::format_column_ref(&join_plan.outer_column),
-            Self::format_column_ref(&join_plan.inner_column),
+            Self::describe_join_condition(&join_plan),
         ));
-        if use_right_index {
+        if let Some((_, column)) = &index_lookup {
             plan_steps.push(format!(
                 "Use index on {}.{} for inner lookups",
-                join_plan.inner_table, join_plan.inner_column.column
+                join_plan.inner_table, column
             ));
         } else {
             plan_steps.push(format!("Seq scan inner table {}", join_plan.inner_table));
@@ -1979,46 +3135,24 @@ impl Executor {
         if let Some(ref predicate) = where_clause {
             plan_steps.push(format!("Filter: {}", Self::describe_expr(predicate)));
         }
-        if !order_by.is_empty() {
-            let order_desc = order_by
-                .iter()
-                .map(|expr| {
-                    format!(
-                        "{} {}",
-                        Self::format_column_ref(&expr.column),
-                        if expr.ascending { "ASC" } else { "DESC" }
-                    )
-                })
-                .collect::<Vec<_>>()
-                .join(", ");
-            plan_steps.push(format!("Order by: {}", order_desc));
-        }
-        if let Some(limit) = limit {
-            plan_steps.push(format!("Limit: {}", limit));
-        }
-        if let Some(offset) = offset {
-            plan_steps.push(format!("Offset: {}", offset));
-        }
+        plan_steps.extend(Self::describe_order_limit(order_by, limit, offset));
 
         // If not using index, load right rows once
         let right_rows_cache: Option<Vec<Vec<Value>>> = if use_right_index {
             None
         } else {
             let mut rows = Vec::new();
-            let right_table_ref = self.tables.get_mut(&join_plan.inner_table).ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("Table '{}' does not exist", join_plan.inner_table),
-                )
-            })?;
+            let (right_table_ref, visibility) = self
+                .table_with_visibility(&join_plan.inner_table)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Table '{}' does not exist", join_plan.inner_table),
+                    )
+                })?;
             let mut scan = TableScan::new(right_table_ref);
             while let Some((_row_id, meta, row)) = scan.next_with_metadata()? {
-                if !Self::is_visible_for_snapshot(
-                    &meta,
-                    snapshot.as_ref(),
-                    current_txn_id,
-                    &txn_states,
-                ) {
+                if !visibility.visible_in_scan(&scan, &meta) {
                     continue;
                 }
                 rows.push(row);
@@ -2026,16 +3160,30 @@ impl Executor {
             Some(rows)
         };
 
+        let top_k_keys = Self::top_k_during_scan(
+            columns,
+            combined_meta,
+            group_by,
+            distinct,
+            order_by,
+            limit,
+            offset,
+        );
+        let mut top_k = top_k_keys
+            .as_ref()
+            .map(|(order_indices, keep)| TopK::new(order_indices, *keep));
         let mut result_rows = Vec::new();
 
         for left_row in left_rows {
-            let left_key = left_row[left_join_idx].clone();
             let mut matching_right_rows = Vec::new();
 
-            if use_right_index {
+            if let Some((left_join_idx, index_column)) = &index_lookup {
+                let left_key = left_row[*left_join_idx].clone();
                 // Look up matching row IDs via index first
                 let mut matched_ids = Vec::new();
-                if let Some(index) = self.find_index_on_first_column(&index_key.0, &index_key.1) {
+                if let Some(index) =
+                    self.find_index_on_first_column(&join_plan.inner_table, index_column)
+                {
                     let coerced_key =
                         Self::coerce_value_to_type(left_key.clone(), index.column_types[0])?;
                     let Some(index_value) = IndexValue::from_value(&coerced_key) else {
@@ -2045,31 +3193,27 @@ impl Executor {
                     matched_ids.extend(index.lookup_eq(&key));
                 }
 
+                let (right_table_ref, visibility) = self
+                    .table_with_visibility(&join_plan.inner_table)
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("Table '{}' does not exist", join_plan.inner_table),
+                        )
+                    })?;
                 for row_id in matched_ids {
-                    let right_row = {
-                        let right_table_ref =
-                            self.tables.get_mut(&join_plan.inner_table).ok_or_else(|| {
-                                io::Error::new(
-                                    io::ErrorKind::NotFound,
-                                    format!("Table '{}' does not exist", join_plan.inner_table),
-                                )
-                            })?;
-                        let (meta, row) = right_table_ref.get_with_metadata(row_id)?;
-                        if !Self::is_visible_for_snapshot(
-                            &meta,
-                            snapshot.as_ref(),
-                            current_txn_id,
-                            &txn_states,
-                        ) {
-                            continue;
-                        }
-                        row
-                    };
-                    matching_right_rows.push(right_row);
+                    let (meta, row) = right_table_ref.get_with_metadata(row_id)?;
+                    if !visibility.is_visible(&meta) {
+                        continue;
+                    }
+                    matching_right_rows.push(row);
                 }
             } else if let Some(ref right_rows) = right_rows_cache {
                 for right_row in right_rows {
-                    if right_row[right_join_idx] == left_key {
+                    let keys_match = join_indices.is_none_or(|(left_join_idx, right_join_idx)| {
+                        right_row[right_join_idx] == left_row[left_join_idx]
+                    });
+                    if keys_match {
                         matching_right_rows.push(right_row.clone());
                     }
                 }
@@ -2080,18 +3224,34 @@ impl Executor {
                 combined_row.extend(left_row.clone());
                 combined_row.extend(right_row);
 
+                if let Some(ref join_filter) = join_plan.join_filter
+                    && !Self::evaluate_predicate_static(join_filter, &combined_row, combined_meta)?
+                {
+                    continue;
+                }
+
                 if let Some(ref where_expr) = where_clause
                     && !self.evaluate_predicate(where_expr, &combined_row, combined_meta)?
                 {
                     continue;
                 }
 
-                result_rows.push(combined_row);
+                match &mut top_k {
+                    Some(top_k) => top_k.push(combined_row),
+                    None => result_rows.push(combined_row),
+                }
             }
         }
+        if let Some(top_k) = top_k {
+            plan_steps.push(top_k.describe());
+            result_rows = top_k.into_sorted_rows();
+        }
 
+        let mut combined_meta = combined_meta.to_vec();
+        let columns =
+            self.materialize_select_exprs(columns, &mut result_rows, &mut combined_meta)?;
         let (column_names, mut result_rows, output_meta) =
-            Self::apply_select_items(result_rows, combined_meta, columns, group_by, true)?;
+            Self::apply_select_items(result_rows, &combined_meta, &columns, group_by, true)?;
         if distinct {
             Self::apply_distinct(&mut result_rows);
         }
@@ -2125,48 +3285,62 @@ impl Executor {
         group_by: &[ColumnRef],
         distinct: bool,
         order_by: &[OrderByExpr],
-        limit: Option<usize>,
-        offset: Option<usize>,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> io::Result<ExecutionResult> {
+        // Sort both sides by join key, spilling to disk if they are large
+        let mut left_rows = self.sort_table_on(&join_plan.outer_table, left_join_idx)?;
+        let mut right_rows = self.sort_table_on(&join_plan.inner_table, right_join_idx)?;
+
         let mut plan_steps = Vec::new();
         plan_steps.push(format!(
-            "Merge join on {} = {}",
-            Self::format_column_ref(&join_plan.outer_column),
-            Self::format_column_ref(&join_plan.inner_column),
+            "Merge join on {}",
+            Self::describe_join_condition(&join_plan)
         ));
-        plan_steps.push(format!("Sort {} on join key", join_plan.outer_table));
-        plan_steps.push(format!("Sort {} on join key", join_plan.inner_table));
+        plan_steps.push(Self::describe_sort(&join_plan.outer_table, &left_rows));
+        plan_steps.push(Self::describe_sort(&join_plan.inner_table, &right_rows));
         if let Some(ref predicate) = where_clause {
             plan_steps.push(format!("Filter: {}", Self::describe_expr(predicate)));
         }
+        plan_steps.extend(Self::describe_order_limit(order_by, limit, offset));
 
-        // Load and sort both sides by join key
-        let mut left_rows = self.load_sorted_rows(&join_plan.outer_table, left_join_idx)?;
-        let mut right_rows = self.load_sorted_rows(&join_plan.inner_table, right_join_idx)?;
-
-        left_rows.sort_by(|a, b| a.0.cmp(&b.0));
-        right_rows.sort_by(|a, b| a.0.cmp(&b.0));
+        let top_k_keys = Self::top_k_during_scan(
+            columns,
+            combined_meta,
+            group_by,
+            distinct,
+            order_by,
+            limit,
+            offset,
+        );
+        let mut top_k = top_k_keys
+            .as_ref()
+            .map(|(order_indices, keep)| TopK::new(order_indices, *keep));
 
-        let mut i = 0usize;
-        let mut j = 0usize;
+        // Each step joins one run of equal keys from each side
         let mut result_rows = Vec::new();
-
-        while i < left_rows.len() && j < right_rows.len() {
-            let left_key = &left_rows[i].0;
-            let right_key = &right_rows[j].0;
-
-            match left_key.cmp(right_key) {
-                std::cmp::Ordering::Less => i += 1,
-                std::cmp::Ordering::Greater => j += 1,
+        let mut left_group = left_rows.next_group()?;
+        let mut right_group = right_rows.next_group()?;
+        while let (Some(left), Some(right)) = (&left_group, &right_group) {
+            match left[0][left_join_idx].cmp(&right[0][right_join_idx]) {
+                std::cmp::Ordering::Less => left_group = left_rows.next_group()?,
+                std::cmp::Ordering::Greater => right_group = right_rows.next_group()?,
                 std::cmp::Ordering::Equal => {
-                    let i_end = Self::advance_run_end(&left_rows, i);
-                    let j_end = Self::advance_run_end(&right_rows, j);
-
-                    for li in i..i_end {
-                        for rj in j..j_end {
+                    for left_row in left {
+                        for right_row in right {
                             let mut combined = Vec::new();
-                            combined.extend(left_rows[li].1.clone());
-                            combined.extend(right_rows[rj].1.clone());
+                            combined.extend(left_row.iter().cloned());
+                            combined.extend(right_row.iter().cloned());
+
+                            if let Some(ref join_filter) = join_plan.join_filter
+                                && !Self::evaluate_predicate_static(
+                                    join_filter,
+                                    &combined,
+                                    combined_meta,
+                                )?
+                            {
+                                continue;
+                            }
 
                             if let Some(ref where_expr) = where_clause
                                 && !self.evaluate_predicate(where_expr, &combined, combined_meta)?
@@ -2174,18 +3348,28 @@ impl Executor {
                                 continue;
                             }
 
-                            result_rows.push(combined);
+                            match &mut top_k {
+                                Some(top_k) => top_k.push(combined),
+                                None => result_rows.push(combined),
+                            }
                         }
                     }
 
-                    i = i_end;
-                    j = j_end;
+                    left_group = left_rows.next_group()?;
+                    right_group = right_rows.next_group()?;
                 }
             }
         }
+        if let Some(top_k) = top_k {
+            plan_steps.push(top_k.describe());
+            result_rows = top_k.into_sorted_rows();
+        }
 
+        let mut combined_meta = combined_meta.to_vec();
+        let columns =
+            self.materialize_select_exprs(columns, &mut result_rows, &mut combined_meta)?;
         let (column_names, mut result_rows, output_meta) =
-            Self::apply_select_items(result_rows, combined_meta, columns, group_by, true)?;
+            Self::apply_select_items(result_rows, &combined_meta, &columns, group_by, true)?;
         if distinct {
             Self::apply_distinct(&mut result_rows);
         }
@@ -2207,49 +3391,40 @@ impl Executor {
         })
     }
 
-    fn load_sorted_rows(
-        &mut self,
-        table_name: &str,
-        join_idx: usize,
-    ) -> io::Result<Vec<(Value, Vec<Value>)>> {
-        let snapshot = self.current_snapshot();
-        let current_txn_id = self.current_txn_id;
-        let txn_states = self.txn_states.clone();
-        let table_ref = self.tables.get_mut(table_name).ok_or_else(|| {
+    /// Sort the visible rows of `table_name` on column `key_idx`.
+    fn sort_table_on(&mut self, table_name: &str, key_idx: usize) -> io::Result<SortedRows> {
+        // With no directory to spill into, an in-memory database sorts
+        // entirely in memory
+        let (run_size, spill_dir) = match &self.db_path {
+            Some(db_path) => (self.sort_run_size, db_path.clone()),
+            None => (usize::MAX, PathBuf::new()),
+        };
+        let (table_ref, visibility) = self.table_with_visibility(table_name).ok_or_else(|| {
             io::Error::new(
                 io::ErrorKind::NotFound,
                 format!("Table '{}' does not exist", table_name),
             )
         })?;
 
+        let mut sort = ExternalSort::new(table_ref.schema().clone(), key_idx, run_size, &spill_dir);
         let mut scan = TableScan::new(table_ref);
-        let mut rows = Vec::new();
         while let Some((_row_id, meta, row)) = scan.next_with_metadata()? {
-            if !Self::is_visible_for_snapshot(&meta, snapshot.as_ref(), current_txn_id, &txn_states)
-            {
+            if !visibility.visible_in_scan(&scan, &meta) {
                 continue;
             }
-            if join_idx >= row.len() {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!(
-                        "Join column index {} out of bounds for table {}",
-                        join_idx, table_name
-                    ),
-                ));
-            }
-            rows.push((row[join_idx].clone(), row));
+            sort.push(row)?;
         }
-        Ok(rows)
+        sort.finish()
     }
 
-    fn advance_run_end(rows: &[(Value, Vec<Value>)], start: usize) -> usize {
-        let key = &rows[start].0;
-        let mut idx = start + 1;
-        while idx < rows.len() && rows[idx].0 == *key {
-            idx += 1;
+    fn describe_sort(table_name: &str, rows: &SortedRows) -> String {
+        match rows.spilled_runs() {
+            0 => format!("Sort {} on join key", table_name),
+            runs => format!(
+                "Sort {} on join key (external, {} runs spilled to disk)",
+                table_name, runs
+            ),
         }
-        idx
     }
 
     /// Use an index for a simple predicate if available.
@@ -2268,10 +3443,9 @@ impl Executor {
 
         match index.index_type {
             IndexType::BTree => {
-                let ranges = Self::build_ranges(index, predicates)?;
-                if ranges.is_empty() {
+                let Some(ranges) = Self::build_ranges(index, predicates)? else {
                     return Ok(None);
-                }
+                };
                 Ok(Some(index.lookup_range(&ranges)))
             }
             IndexType::Hash => {
@@ -2363,6 +3537,7 @@ impl Executor {
                 })?;
                 Ok(Value::Decimal(decimal))
             }
+            Literal::Interval(interval) => Ok(Value::Interval(*interval)),
             Literal::Null => Ok(Value::Null),
         }
     }
@@ -2379,21 +3554,36 @@ impl Executor {
             (DbDataType::Integer, Value::Unsigned(u)) if u <= i64::MAX as u64 => {
                 Ok(Value::Integer(u as i64))
             }
-            (DbDataType::Integer, Value::Float(fv))
-                if fv.fract() == 0.0 && fv <= i64::MAX as f64 =>
-            {
-                Ok(Value::Integer(fv as i64))
+            // `i64::MAX as f64` rounds up to 2^63, so the upper bound is exclusive
+            (DbDataType::Integer, Value::Float(fv)) if fv.fract() == 0.0 => {
+                if fv >= i64::MIN as f64 && fv < i64::MAX as f64 {
+                    Ok(Value::Integer(fv as i64))
+                } else {
+                    Err(Self::out_of_range(&Value::Float(fv), data_type))
+                }
             }
             (DbDataType::Unsigned, Value::Unsigned(u)) => Ok(Value::Unsigned(u)),
             (DbDataType::Unsigned, Value::Integer(i)) if i >= 0 => Ok(Value::Unsigned(i as u64)),
-            (DbDataType::Unsigned, Value::Float(fv)) if fv.fract() == 0.0 && fv >= 0.0 => {
-                Ok(Value::Unsigned(fv as u64))
+            (DbDataType::Unsigned, Value::Float(fv)) if fv.fract() == 0.0 => {
+                if fv >= 0.0 && fv < u64::MAX as f64 {
+                    Ok(Value::Unsigned(fv as u64))
+                } else {
+                    Err(Self::out_of_range(&Value::Float(fv), data_type))
+                }
             }
+            (DbDataType::Float, Value::Float(fv)) if fv.is_nan() => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "NaN is not a valid FLOAT value",
+            )),
             (DbDataType::Float, Value::Float(fv)) => Ok(Value::Float(fv)),
             (DbDataType::Float, Value::Integer(i)) => Ok(Value::Float(i as f64)),
             (DbDataType::Float, Value::Unsigned(u)) => Ok(Value::Float(u as f64)),
             (DbDataType::Boolean, Value::Boolean(b)) => Ok(Value::Boolean(b)),
             (DbDataType::String, Value::String(s)) => Ok(Value::String(s)),
+            (DbDataType::Json, Value::String(s)) => match Json::parse(&s) {
+                Ok(_) => Ok(Value::String(s)),
+                Err(e) => Err(io::Error::new(io::ErrorKind::InvalidInput, e)),
+            },
             (DbDataType::Date, Value::Date(d)) => Ok(Value::Date(d)),
             (DbDataType::Date, Value::String(s)) => crate::types::Date::parse(&s)
                 .map(Value::Date)
@@ -2424,6 +3614,13 @@ impl Executor {
         }
     }
 
+    fn out_of_range(value: &Value, data_type: DbDataType) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Value {} is out of range for {}", value, data_type),
+        )
+    }
+
     /// Evaluate a predicate expression against a row (static version)
     fn evaluate_predicate_static(
         expr: &Expr,
@@ -2439,10 +3636,18 @@ impl Executor {
                     }
                     return Self::evaluate_predicate_static(right, row, columns);
                 }
+                if op.is_arithmetic() {
+                    return Self::predicate_truth(Self::evaluate_expr_static(expr, row, columns)?);
+                }
 
                 let left_val = Self::evaluate_expr_static(left, row, columns)?;
                 let right_val = Self::evaluate_expr_static(right, row, columns)?;
 
+                match op {
+                    BinaryOp::IsDistinctFrom => return Ok(left_val != right_val),
+                    BinaryOp::IsNotDistinctFrom => return Ok(left_val == right_val),
+                    _ => {}
+                }
                 if left_val.is_null() || right_val.is_null() {
                     return Ok(false);
                 }
@@ -2454,14 +3659,31 @@ impl Executor {
                     BinaryOp::LtEq => left_val <= right_val,
                     BinaryOp::Gt => left_val > right_val,
                     BinaryOp::GtEq => left_val >= right_val,
-                    BinaryOp::And => unreachable!(),
+                    BinaryOp::Like => Self::like_matches(&left_val, &right_val)?,
+                    BinaryOp::And
+                    | BinaryOp::Plus
+                    | BinaryOp::Minus
+                    | BinaryOp::Divide
+                    | BinaryOp::Mod
+                    | BinaryOp::IsDistinctFrom
+                    | BinaryOp::IsNotDistinctFrom => unreachable!(),
                 };
 
                 Ok(result)
             }
-            _ => Err(io::Error::new(
+            _ => Self::predicate_truth(Self::evaluate_expr_static(expr, row, columns)?),
+        }
+    }
+
+    /// Truth of a predicate that is a plain value, such as a BOOLEAN column.
+    /// NULL counts as false, like a comparison with NULL.
+    fn predicate_truth(value: Value) -> io::Result<bool> {
+        match value {
+            Value::Boolean(b) => Ok(b),
+            Value::Null => Ok(false),
+            other => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "WHERE clause must be a comparison expression",
+                format!("WHERE clause must be a boolean expression, got {}", other),
             )),
         }
     }
@@ -2478,7 +3700,8 @@ impl Executor {
                 if left_val.is_null() {
                     return Ok(false);
                 }
-                let result = self.execute_select((**subquery).clone())?;
+                let bound = self.bind_outer_row(subquery, row, columns)?;
+                let result = self.execute_select(bound)?;
                 let (column_names, rows) = match result {
                     ExecutionResult::Select {
                         column_names, rows, ..
@@ -2508,6 +3731,11 @@ impl Executor {
                 }
                 Ok(false)
             }
+            Expr::Exists { subquery, negated } => {
+                let bound = self.bind_outer_row(subquery, row, columns)?;
+                let found = self.subquery_has_rows(bound)?;
+                Ok(found != *negated)
+            }
             Expr::BinaryOp { left, op, right } => {
                 if *op == BinaryOp::And {
                     let left_result = self.evaluate_predicate(left, row, columns)?;
@@ -2516,10 +3744,18 @@ impl Executor {
                     }
                     return self.evaluate_predicate(right, row, columns);
                 }
+                if op.is_arithmetic() {
+                    return Self::predicate_truth(self.evaluate_expr(expr, row, columns)?);
+                }
 
-                let left_val = Self::evaluate_expr_static(left, row, columns)?;
-                let right_val = Self::evaluate_expr_static(right, row, columns)?;
+                let left_val = self.evaluate_expr(left, row, columns)?;
+                let right_val = self.evaluate_expr(right, row, columns)?;
 
+                match op {
+                    BinaryOp::IsDistinctFrom => return Ok(left_val != right_val),
+                    BinaryOp::IsNotDistinctFrom => return Ok(left_val == right_val),
+                    _ => {}
+                }
                 if left_val.is_null() || right_val.is_null() {
                     return Ok(false);
                 }
@@ -2531,18 +3767,295 @@ impl Executor {
                     BinaryOp::LtEq => left_val <= right_val,
                     BinaryOp::Gt => left_val > right_val,
                     BinaryOp::GtEq => left_val >= right_val,
-                    BinaryOp::And => unreachable!(),
+                    BinaryOp::Like => Self::like_matches(&left_val, &right_val)?,
+                    BinaryOp::And
+                    | BinaryOp::Plus
+                    | BinaryOp::Minus
+                    | BinaryOp::Divide
+                    | BinaryOp::Mod
+                    | BinaryOp::IsDistinctFrom
+                    | BinaryOp::IsNotDistinctFrom => unreachable!(),
                 };
 
                 Ok(result)
             }
+            _ => Self::predicate_truth(self.evaluate_expr(expr, row, columns)?),
+        }
+    }
+
+    /// Substitute the outer row's values for correlated column references in
+    /// a subquery.
+    ///
+    /// A column is correlated when it is qualified with a table that is not in
+    /// the subquery's FROM clause, or when it is unqualified, absent from every
+    /// inner table and present in the outer row. References are bound in the
+    /// WHERE clause, the join condition and computed select items, including
+    /// those of nested subqueries; tables of the enclosing subqueries stay in
+    /// scope for the nested ones.
+    fn bind_outer_row(
+        &self,
+        subquery: &SelectStmt,
+        row: &[Value],
+        columns: &[(Option<String>, String)],
+    ) -> io::Result<SelectStmt> {
+        self.bind_outer_select(subquery.clone(), &[], row, columns)
+    }
+
+    fn bind_outer_select(
+        &self,
+        mut select: SelectStmt,
+        enclosing: &[&str],
+        row: &[Value],
+        columns: &[(Option<String>, String)],
+    ) -> io::Result<SelectStmt> {
+        let from_tables = match &select.from {
+            FromClause::Table(name) => vec![name.clone()],
+            FromClause::Join {
+                left_table,
+                right_table,
+                ..
+            } => vec![left_table.clone(), right_table.clone()],
+        };
+        let mut scope = enclosing.to_vec();
+        scope.extend(from_tables.iter().map(String::as_str));
+
+        // These positions only hold column names, so a value can't go there
+        let mut name_only: Vec<&ColumnRef> = select.group_by.iter().collect();
+        name_only.extend(select.order_by.iter().map(|order| &order.column));
+        if let SelectColumn::Items(items) = &select.columns {
+            name_only.extend(items.iter().filter_map(|item| match item {
+                SelectItem::Column(col_ref)
+                | SelectItem::Aggregate(AggregateExpr {
+                    target: AggregateTarget::Column(col_ref),
+                    ..
+                }) => Some(col_ref),
+                _ => None,
+            }));
+        }
+        if let Some(col_ref) = name_only
+            .into_iter()
+            .find(|col_ref| self.outer_column_index(col_ref, &scope, columns).is_some())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Correlated column '{}' is only supported in the WHERE clause, join condition or computed expressions of a subquery",
+                    Self::format_column_ref(col_ref)
+                ),
+            ));
+        }
+
+        if let Some(where_clause) = select.where_clause.take() {
+            select.where_clause =
+                Some(self.bind_outer_columns(where_clause, &scope, row, columns)?);
+        }
+        if let FromClause::Join { condition, .. } = &mut select.from {
+            let bound = self.bind_outer_columns(
+                std::mem::replace(condition, Expr::Literal(Literal::Null)),
+                &scope,
+                row,
+                columns,
+            )?;
+            *condition = bound;
+        }
+        if let SelectColumn::Items(items) = &mut select.columns {
+            for item in items.iter_mut() {
+                if let SelectItem::Expr(expr) = item {
+                    let bound = self.bind_outer_columns(
+                        std::mem::replace(expr, Expr::Literal(Literal::Null)),
+                        &scope,
+                        row,
+                        columns,
+                    )?;
+                    *expr = bound;
+                }
+            }
+        }
+        Ok(select)
+    }
+
+    fn bind_outer_columns(
+        &self,
+        expr: Expr,
+        scope: &[&str],
+        row: &[Value],
+        columns: &[(Option<String>, String)],
+    ) -> io::Result<Expr> {
+        let bind_select = |subquery: Box<SelectStmt>| -> io::Result<Box<SelectStmt>> {
+            Ok(Box::new(
+                self.bind_outer_select(*subquery, scope, row, columns)?,
+            ))
+        };
+        Ok(match expr {
+            Expr::Column(col_ref) => match self.outer_column_index(&col_ref, scope, columns) {
+                Some(idx) => Expr::Literal(Self::value_to_literal(&row[idx])),
+                None => Expr::Column(col_ref),
+            },
+            Expr::Literal(_) | Expr::CurrentTimestamp | Expr::CurrentDate => expr,
+            Expr::JsonExtract { expr, path } => Expr::JsonExtract {
+                expr: Box::new(self.bind_outer_columns(*expr, scope, row, columns)?),
+                path,
+            },
+            Expr::BinaryOp { left, op, right } => Expr::binary_op(
+                self.bind_outer_columns(*left, scope, row, columns)?,
+                op,
+                self.bind_outer_columns(*right, scope, row, columns)?,
+            ),
+            Expr::InSubquery { expr, subquery } => Expr::InSubquery {
+                expr: Box::new(self.bind_outer_columns(*expr, scope, row, columns)?),
+                subquery: bind_select(subquery)?,
+            },
+            Expr::Exists { subquery, negated } => Expr::Exists {
+                subquery: bind_select(subquery)?,
+                negated,
+            },
+            Expr::ScalarSubquery(subquery) => Expr::ScalarSubquery(bind_select(subquery)?),
+        })
+    }
+
+    /// Position of `col_ref` in the outer row, if it names an outer column
+    /// rather than one of the tables in `scope`.
+    fn outer_column_index(
+        &self,
+        col_ref: &ColumnRef,
+        scope: &[&str],
+        columns: &[(Option<String>, String)],
+    ) -> Option<usize> {
+        let is_outer = match &col_ref.table {
+            Some(table) => !scope.contains(&table.as_str()),
+            None => scope.iter().all(|name| {
+                self.tables
+                    .get(*name)
+                    .is_none_or(|table| table.schema().find_column(&col_ref.column).is_none())
+            }),
+        };
+        // Not an outer column either; let the subquery report it
+        is_outer
+            .then(|| Self::resolve_column_index(columns, col_ref).ok())
+            .flatten()
+    }
+
+    fn value_to_literal(value: &Value) -> Literal {
+        match value {
+            Value::Integer(i) => Literal::Integer(*i as i128),
+            Value::Unsigned(u) => Literal::Integer(*u as i128),
+            Value::Float(fv) => Literal::Float(*fv),
+            Value::Boolean(b) => Literal::Boolean(*b),
+            Value::String(s) => Literal::String(s.clone()),
+            Value::Date(d) => Literal::Date(d.to_string()),
+            Value::Timestamp(t) => Literal::Timestamp(t.to_string()),
+            Value::Decimal(d) => Literal::Decimal(d.to_string()),
+            Value::Interval(interval) => Literal::Interval(*interval),
+            Value::Null => Literal::Null,
+        }
+    }
+
+    /// Check whether a (bound) subquery produces at least one row.
+    fn subquery_has_rows(&mut self, mut subquery: SelectStmt) -> io::Result<bool> {
+        // Only existence matters, so stop after the first row
+        subquery.limit = Some(subquery.limit.map_or(1, |limit| limit.min(1)));
+        match self.execute_select(subquery)? {
+            ExecutionResult::Select { rows, .. } => Ok(!rows.is_empty()),
             _ => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "WHERE clause must be a comparison expression",
+                "Subquery must be a SELECT",
             )),
         }
     }
 
+    /// Evaluate an expression to a value, running scalar subqueries against
+    /// the current row.
+    fn evaluate_expr(
+        &mut self,
+        expr: &Expr,
+        row: &[Value],
+        columns: &[(Option<String>, String)],
+    ) -> io::Result<Value> {
+        match expr {
+            Expr::ScalarSubquery(subquery) => {
+                let mut bound = self.bind_outer_row(subquery, row, columns)?;
+                // A second row is enough to know the subquery isn't scalar
+                bound.limit = Some(bound.limit.map_or(2, |limit| limit.min(2)));
+                let (column_names, rows) = match self.execute_select(bound)? {
+                    ExecutionResult::Select {
+                        column_names, rows, ..
+                    } => (column_names, rows),
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "Subquery must be a SELECT",
+                        ));
+                    }
+                };
+                if column_names.len() != 1 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Scalar subquery must return exactly one column",
+                    ));
+                }
+                match rows.len() {
+                    0 => Ok(Value::Null),
+                    1 => Ok(rows[0][0].clone()),
+                    _ => Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Scalar subquery returned more than one row",
+                    )),
+                }
+            }
+            Expr::BinaryOp { left, op, right } if op.is_arithmetic() => {
+                let left_val = self.evaluate_expr(left, row, columns)?;
+                let right_val = self.evaluate_expr(right, row, columns)?;
+                Self::apply_arithmetic(*op, left_val, right_val)
+            }
+            Expr::JsonExtract { expr, path } => {
+                Self::json_extract(self.evaluate_expr(expr, row, columns)?, path)
+            }
+            _ => Self::evaluate_expr_static(expr, row, columns),
+        }
+    }
+
+    /// Evaluate the select list's computed expressions for every row.
+    ///
+    /// Each value is appended to its row as an extra column and the item is
+    /// replaced by a reference to that column, so projection, DISTINCT and
+    /// ORDER BY treat it like any other column.
+    fn materialize_select_exprs(
+        &mut self,
+        selection: &SelectColumn,
+        rows: &mut [Vec<Value>],
+        columns_meta: &mut Vec<(Option<String>, String)>,
+    ) -> io::Result<SelectColumn> {
+        let SelectColumn::Items(items) = selection else {
+            return Ok(selection.clone());
+        };
+        let input_meta = columns_meta.clone();
+
+        let mut rewritten = Vec::with_capacity(items.len());
+        for item in items {
+            let SelectItem::Expr(expr) = item else {
+                rewritten.push(item.clone());
+                continue;
+            };
+
+            for row in rows.iter_mut() {
+                let value = self.evaluate_expr(expr, row, &input_meta)?;
+                row.push(value);
+            }
+
+            let base = Self::describe_expr(expr);
+            let mut name = base.clone();
+            let mut suffix = 1;
+            while columns_meta.iter().any(|(_, existing)| *existing == name) {
+                suffix += 1;
+                name = format!("{} {}", base, suffix);
+            }
+            columns_meta.push((None, name.clone()));
+            rewritten.push(SelectItem::Column(ColumnRef::new(None, name)));
+        }
+
+        Ok(SelectColumn::Items(rewritten))
+    }
+
     /// Evaluate an expression to a value (static version)
     fn evaluate_expr_static(
         expr: &Expr,
@@ -2552,10 +4065,22 @@ impl Executor {
         match expr {
             Expr::Column(col_ref) => Self::resolve_column_value(row, columns, col_ref),
             Expr::Literal(lit) => Self::literal_to_value(lit),
-            Expr::InSubquery { .. } => Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Subquery expressions cannot be evaluated as values",
-            )),
+            Expr::InSubquery { .. } | Expr::Exists { .. } | Expr::ScalarSubquery(_) => {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Subquery expressions cannot be evaluated as values",
+                ))
+            }
+            Expr::CurrentTimestamp => Ok(Value::Timestamp(crate::types::Timestamp::now())),
+            Expr::CurrentDate => Ok(Value::Date(crate::types::Date::today())),
+            Expr::JsonExtract { expr, path } => {
+                Self::json_extract(Self::evaluate_expr_static(expr, row, columns)?, path)
+            }
+            Expr::BinaryOp { left, op, right } if op.is_arithmetic() => {
+                let left_val = Self::evaluate_expr_static(left, row, columns)?;
+                let right_val = Self::evaluate_expr_static(right, row, columns)?;
+                Self::apply_arithmetic(*op, left_val, right_val)
+            }
             Expr::BinaryOp { .. } => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 "Binary operations cannot be directly evaluated as values",
@@ -2563,75 +4088,307 @@ impl Executor {
         }
     }
 
+    /// The element of the JSON document `document` at `path`, or NULL if the
+    /// document is NULL or has no such element
+    fn json_extract(document: Value, path: &str) -> io::Result<Value> {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidInput, e);
+        let text = match document {
+            Value::Null => return Ok(Value::Null),
+            Value::String(text) => text,
+            other => {
+                return Err(invalid(format!(
+                    "json_extract requires a JSON document, got {}",
+                    other
+                )));
+            }
+        };
+        let path = JsonPath::parse(path).map_err(invalid)?;
+        let json = Json::parse(&text).map_err(invalid)?;
+        Ok(json.get(&path).map_or(Value::Null, Json::to_value))
+    }
+
+    /// Apply `+`, `-`, `/` or `%` between two numbers, or `+` or `-` between
+    /// a date or timestamp and an interval, or between two intervals.
+    ///
+    /// `/` and `%` between integers truncate toward zero; with a FLOAT on
+    /// either side they use floating point.
+    ///
+    /// A date stays a date when the interval is a whole number of days and
+    /// becomes a timestamp at midnight otherwise. NULL on either side gives
+    /// NULL.
+    fn apply_arithmetic(op: BinaryOp, left: Value, right: Value) -> io::Result<Value> {
+        if left.is_null() || right.is_null() {
+            return Ok(Value::Null);
+        }
+
+        match (&left, &right) {
+            (Value::Integer(_) | Value::Unsigned(_), Value::Integer(_) | Value::Unsigned(_)) => {
+                return Self::integer_arithmetic(op, &left, &right);
+            }
+            (
+                Value::Integer(_) | Value::Unsigned(_) | Value::Float(_),
+                Value::Integer(_) | Value::Unsigned(_) | Value::Float(_),
+            ) => {
+                let (Some(a), Some(b)) =
+                    (Self::numeric_to_f64(&left)?, Self::numeric_to_f64(&right)?)
+                else {
+                    unreachable!("numeric operands always convert");
+                };
+                let result = match op {
+                    BinaryOp::Plus => a + b,
+                    BinaryOp::Minus => a - b,
+                    BinaryOp::Divide | BinaryOp::Mod if b == 0.0 => {
+                        return Err(Self::division_by_zero(op, &left, &right));
+                    }
+                    BinaryOp::Divide => a / b,
+                    BinaryOp::Mod => a % b,
+                    _ => unreachable!("arithmetic operators only"),
+                };
+                if !result.is_finite() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "Floating point overflow: {} {} {}",
+                            left,
+                            Self::format_binary_op(op),
+                            right
+                        ),
+                    ));
+                }
+                return Ok(Value::Float(result));
+            }
+            _ => {}
+        }
+
+        if matches!(op, BinaryOp::Divide | BinaryOp::Mod) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Operator '{}' is only supported between numbers",
+                    Self::format_binary_op(op)
+                ),
+            ));
+        }
+
+        let out_of_range = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Date/time arithmetic result out of range",
+            )
+        };
+        let signed = |interval: &crate::types::Interval| match op {
+            BinaryOp::Minus => interval.checked_neg().ok_or_else(out_of_range),
+            _ => Ok(*interval),
+        };
+
+        match (&left, &right) {
+            (Value::Timestamp(ts), Value::Interval(interval)) => ts
+                .checked_add(&signed(interval)?)
+                .map(Value::Timestamp)
+                .ok_or_else(out_of_range),
+            (Value::Interval(interval), Value::Timestamp(ts)) if op == BinaryOp::Plus => ts
+                .checked_add(interval)
+                .map(Value::Timestamp)
+                .ok_or_else(out_of_range),
+            (Value::Date(date), Value::Interval(interval))
+            | (Value::Interval(interval), Value::Date(date))
+                if op == BinaryOp::Plus || left.is_date() =>
+            {
+                let interval = signed(interval)?;
+                if interval.has_time_part() {
+                    crate::types::Timestamp::from(*date)
+                        .checked_add(&interval)
+                        .map(Value::Timestamp)
+                        .ok_or_else(out_of_range)
+                } else {
+                    date.checked_add(&interval)
+                        .map(Value::Date)
+                        .ok_or_else(out_of_range)
+                }
+            }
+            (Value::Interval(a), Value::Interval(b)) => a
+                .checked_add(&signed(b)?)
+                .map(Value::Interval)
+                .ok_or_else(out_of_range),
+            (Value::Interval(_), Value::Date(_) | Value::Timestamp(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot subtract a date or timestamp from an INTERVAL",
+            )),
+            (Value::Interval(_), other) | (other, Value::Interval(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Cannot apply '{}' to an INTERVAL and non-temporal value {}",
+                    Self::format_binary_op(op),
+                    other
+                ),
+            )),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Operator '{}' is only supported between numbers, dates, timestamps and intervals",
+                    Self::format_binary_op(op)
+                ),
+            )),
+        }
+    }
+
+    /// Integer arithmetic, rejecting results that don't fit instead of
+    /// wrapping
+    ///
+    /// Two INTEGERs give an INTEGER and two UNSIGNEDs an UNSIGNED; a mix gives
+    /// an INTEGER when the result fits one and an UNSIGNED otherwise.
+    fn integer_arithmetic(op: BinaryOp, left: &Value, right: &Value) -> io::Result<Value> {
+        let widen = |value: &Value| match value {
+            Value::Integer(i) => *i as i128,
+            Value::Unsigned(u) => *u as i128,
+            _ => unreachable!("integer operands only"),
+        };
+        // 64-bit operands can't overflow an i128
+        let (a, b) = (widen(left), widen(right));
+        let result = match op {
+            BinaryOp::Plus => a + b,
+            BinaryOp::Minus => a - b,
+            BinaryOp::Divide | BinaryOp::Mod if b == 0 => {
+                return Err(Self::division_by_zero(op, left, right));
+            }
+            BinaryOp::Divide => a / b,
+            BinaryOp::Mod => a % b,
+            _ => unreachable!("arithmetic operators only"),
+        };
+
+        let narrowed = match (left, right) {
+            (Value::Integer(_), Value::Integer(_)) => {
+                i64::try_from(result).ok().map(Value::Integer)
+            }
+            (Value::Unsigned(_), Value::Unsigned(_)) => {
+                u64::try_from(result).ok().map(Value::Unsigned)
+            }
+            _ => i64::try_from(result)
+                .ok()
+                .map(Value::Integer)
+                .or_else(|| u64::try_from(result).ok().map(Value::Unsigned)),
+        };
+        narrowed.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Integer overflow: {} {} {}",
+                    left,
+                    Self::format_binary_op(op),
+                    right
+                ),
+            )
+        })
+    }
+
+    fn division_by_zero(op: BinaryOp, left: &Value, right: &Value) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Division by zero: {} {} {}",
+                left,
+                Self::format_binary_op(op),
+                right
+            ),
+        )
+    }
+
     /// Get a table by name
     pub fn get_table(&mut self, name: &str) -> Option<&mut HeapTable> {
         self.tables.get_mut(name)
     }
 
     fn rebuild_indexes_for_table(&mut self, table_name: &str) -> io::Result<()> {
-        let snapshot = self.current_snapshot();
-        let current_txn_id = self.current_txn_id;
-        let txn_states = self.txn_states.clone();
-        let rows = {
-            let table = self.tables.get_mut(table_name).ok_or_else(|| {
+        let rows = self.visible_rows_with_ids(table_name)?;
+
+        for index in self
+            .indexes
+            .iter_mut()
+            .filter(|idx| idx.key.table == table_name)
+        {
+            index.data = Self::build_index_data(index, &rows)?;
+        }
+
+        Ok(())
+    }
+
+    /// Rebuild a single index from its table's rows
+    ///
+    /// The index keeps its definition; only its entries are repopulated, so
+    /// this recovers from a stale or corrupt in-memory index without a DROP
+    /// and CREATE.
+    ///
+    /// # Errors
+    /// Returns `NotFound` if no index has this name, or `InvalidInput` if a
+    /// unique index finds duplicate keys (the old entries are then kept).
+    pub fn reindex(&mut self, index_name: &str) -> io::Result<()> {
+        let index_pos = self
+            .indexes
+            .iter()
+            .position(|idx| idx.name == index_name)
+            .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
-                    format!("Table '{}' does not exist", table_name),
+                    format!("Index '{}' does not exist", index_name),
                 )
             })?;
+        let table_name = self.indexes[index_pos].key.table.clone();
+        let rows = self.visible_rows_with_ids(&table_name)?;
 
-            let mut scan = TableScan::new(table);
-            let mut rows = Vec::new();
-            while let Some((row_id, meta, row)) = scan.next_with_metadata()? {
-                if !Self::is_visible_for_snapshot(
-                    &meta,
-                    snapshot.as_ref(),
-                    current_txn_id,
-                    &txn_states,
-                ) {
-                    continue;
-                }
-                rows.push((row_id, row));
-            }
-            rows
-        };
+        let index = &mut self.indexes[index_pos];
+        index.data = Self::build_index_data(index, &rows)?;
+        Ok(())
+    }
+
+    /// Rows of `table_name` visible to the current snapshot, with their ids
+    fn visible_rows_with_ids(&mut self, table_name: &str) -> io::Result<Vec<(RowId, Vec<Value>)>> {
+        let (table, visibility) = self.table_with_visibility(table_name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Table '{}' does not exist", table_name),
+            )
+        })?;
 
-        for index in self
-            .indexes
-            .iter_mut()
-            .filter(|idx| idx.key.table == table_name)
-        {
-            let mut data = match index.index_type {
-                IndexType::BTree => IndexData::BTree(BPlusTree::new()),
-                IndexType::Hash => IndexData::Hash(HashIndex::new()),
-            };
+        let mut scan = TableScan::new(table);
+        let mut rows = Vec::new();
+        while let Some((row_id, meta, row)) = scan.next_with_metadata()? {
+            if !visibility.visible_in_scan(&scan, &meta) {
+                continue;
+            }
+            rows.push((row_id, row));
+        }
+        Ok(rows)
+    }
 
-            let mut seen_keys = std::collections::HashSet::new();
-            for (row_id, row) in &rows {
-                let key =
-                    Self::build_composite_key(row, &index.column_indices, &index.column_types)?;
+    /// Build fresh entries for `index` from `rows`
+    fn build_index_data(index: &IndexEntry, rows: &[(RowId, Vec<Value>)]) -> io::Result<IndexData> {
+        let mut data = match index.index_type {
+            IndexType::BTree => IndexData::BTree(BPlusTree::new()),
+            IndexType::Hash => IndexData::Hash(HashIndex::new()),
+        };
 
-                // Check uniqueness constraint for unique indexes
-                if index.is_unique && !seen_keys.insert(key.clone()) {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        format!(
-                            "Unique constraint violation on index '{}': duplicate key value",
-                            index.name
-                        ),
-                    ));
-                }
+        let mut seen_keys = std::collections::HashSet::new();
+        for (row_id, row) in rows {
+            let key = Self::build_composite_key(row, &index.column_indices, &index.column_types)?;
 
-                match &mut data {
-                    IndexData::BTree(tree) => tree.insert(key, *row_id),
-                    IndexData::Hash(hash) => hash.insert(key, *row_id),
-                }
+            // Check uniqueness constraint for unique indexes
+            if index.is_unique && !key.has_null() && !seen_keys.insert(key.clone()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Unique constraint violation on index '{}': duplicate key value",
+                        index.name
+                    ),
+                ));
             }
 
-            index.data = data;
+            match &mut data {
+                IndexData::BTree(tree) => tree.insert(key, *row_id),
+                IndexData::Hash(hash) => hash.insert(key, *row_id),
+            }
         }
 
-        Ok(())
+        Ok(data)
     }
 
     fn index_metadata(&self) -> Vec<IndexMetadata> {
@@ -2701,12 +4458,27 @@ impl Executor {
                             indices.push(idx);
                             names.push(Self::format_column_name(&columns_meta[idx], use_qualified));
                         }
+                        SelectItem::QualifiedAll(table) => {
+                            for idx in Self::qualified_all_indices(columns_meta, table)? {
+                                indices.push(idx);
+                                names.push(Self::format_column_name(
+                                    &columns_meta[idx],
+                                    use_qualified,
+                                ));
+                            }
+                        }
                         SelectItem::Aggregate(_) => {
                             return Err(io::Error::new(
                                 io::ErrorKind::InvalidInput,
                                 "Cannot project aggregate without GROUP BY",
                             ));
                         }
+                        SelectItem::Expr(_) => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                "Select expressions must be evaluated before projection",
+                            ));
+                        }
                         SelectItem::All => {
                             return Err(io::Error::new(
                                 io::ErrorKind::InvalidInput,
@@ -2720,13 +4492,38 @@ impl Executor {
         }
     }
 
+    /// Indices of every column that `table.*` expands to, in table order
+    fn qualified_all_indices(
+        columns_meta: &[(Option<String>, String)],
+        table: &str,
+    ) -> io::Result<Vec<usize>> {
+        let indices: Vec<usize> = columns_meta
+            .iter()
+            .enumerate()
+            .filter(|(_, (qualifier, _))| qualifier.as_deref() == Some(table))
+            .map(|(idx, _)| idx)
+            .collect();
+        if indices.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Table '{}' is not in the FROM clause", table),
+            ));
+        }
+        Ok(indices)
+    }
+
+    /// Sort by `order_by`, then apply OFFSET and LIMIT
+    ///
+    /// The sort is stable, so rows tied on every key keep their input order.
+    /// With a LIMIT, only the first OFFSET + LIMIT rows are kept while
+    /// sorting, in a bounded heap, so the rest are never sorted.
     fn apply_order_limit(
         rows: &mut Vec<Vec<Value>>,
         columns_meta: &[(Option<String>, String)],
         column_indices: &[usize],
         order_by: &[OrderByExpr],
-        limit: Option<usize>,
-        offset: Option<usize>,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> io::Result<()> {
         if !order_by.is_empty() {
             let mut order_indices = Vec::with_capacity(order_by.len());
@@ -2747,34 +4544,83 @@ impl Executor {
                 order_indices.push((projected_idx, expr.ascending));
             }
 
-            rows.sort_by(|a, b| {
-                for (idx, asc) in &order_indices {
-                    let ord = a[*idx].cmp(&b[*idx]);
-                    if ord != std::cmp::Ordering::Equal {
-                        return if *asc { ord } else { ord.reverse() };
-                    }
+            let keep = limit.map(|limit| offset.unwrap_or(0).saturating_add(limit));
+            match keep {
+                // `keep` is below rows.len(), so it fits a usize
+                Some(keep) if keep < rows.len() as u64 => {
+                    Self::retain_top_k(rows, &order_indices, keep as usize)
                 }
-                std::cmp::Ordering::Equal
-            });
+                _ => rows.sort_by(|a, b| compare_order_keys(a, b, &order_indices)),
+            }
         }
 
         if limit.is_some() || offset.is_some() {
+            let row_count = rows.len() as u64;
             let start = offset.unwrap_or(0);
-            if start >= rows.len() {
+            if start >= row_count {
                 rows.clear();
                 return Ok(());
             }
             let end = match limit {
-                Some(limit) => start.saturating_add(limit).min(rows.len()),
-                None => rows.len(),
+                Some(limit) => start.saturating_add(limit).min(row_count),
+                None => row_count,
             };
-            let sliced = rows[start..end].to_vec();
+            // Both bounds are now at most rows.len(), so they fit a usize
+            let sliced = rows[start as usize..end as usize].to_vec();
             *rows = sliced;
         }
 
         Ok(())
     }
 
+    /// Keep the first `k` rows in `order_indices` order, sorted
+    fn retain_top_k(rows: &mut Vec<Vec<Value>>, order_indices: &[(usize, bool)], k: usize) {
+        let mut top_k = TopK::new(order_indices, k);
+        for row in rows.drain(..) {
+            top_k.push(row);
+        }
+        *rows = top_k.into_sorted_rows();
+    }
+
+    /// ORDER BY keys as indices into the rows a SELECT scans, and how many
+    /// rows to keep, when the top OFFSET + LIMIT rows can be picked as rows
+    /// are scanned
+    ///
+    /// That takes a LIMIT and a select list that turns each row into one
+    /// output row (no GROUP BY, DISTINCT or aggregates), ordered by columns
+    /// of the scanned rows.
+    fn top_k_during_scan(
+        columns: &SelectColumn,
+        columns_meta: &[(Option<String>, String)],
+        group_by: &[ColumnRef],
+        distinct: bool,
+        order_by: &[OrderByExpr],
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Option<(Vec<(usize, bool)>, usize)> {
+        let limit = limit?;
+        if order_by.is_empty() || !group_by.is_empty() || distinct {
+            return None;
+        }
+        if let SelectColumn::Items(items) = columns
+            && items
+                .iter()
+                .any(|item| matches!(item, SelectItem::Aggregate(_)))
+        {
+            return None;
+        }
+        let keep = usize::try_from(offset.unwrap_or(0).saturating_add(limit)).ok()?;
+        let order_indices = order_by
+            .iter()
+            .map(|expr| {
+                Self::resolve_column_index(columns_meta, &expr.column)
+                    .ok()
+                    .map(|idx| (idx, expr.ascending))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some((order_indices, keep))
+    }
+
     fn apply_distinct(rows: &mut Vec<Vec<Value>>) {
         let mut seen = std::collections::BTreeSet::new();
         rows.retain(|row| seen.insert(row.clone()));
@@ -2845,20 +4691,395 @@ impl Executor {
         Ok(())
     }
 
-    fn ensure_no_indexes_on_column(&self, table_name: &str, column: &str) -> io::Result<()> {
-        for index in &self.indexes {
-            if index.key.table == table_name && index.key.columns.contains(&column.to_string()) {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("Column '{}' is indexed by '{}'", column, index.name),
-                ));
-            }
+    /// Names and keys of the loaded indexes
+    fn index_keys(&self) -> impl Iterator<Item = (&str, &IndexKey)> {
+        self.indexes
+            .iter()
+            .map(|index| (index.name.as_str(), &index.key))
+    }
+
+    /// Name of an index on `table_name` that covers `column`, if any
+    fn index_on_column<'a>(
+        indexes: impl IntoIterator<Item = (&'a str, &'a IndexKey)>,
+        table_name: &str,
+        column: &str,
+    ) -> Option<&'a str> {
+        indexes
+            .into_iter()
+            .find(|(_, key)| key.table == table_name && key.columns.iter().any(|c| c == column))
+            .map(|(name, _)| name)
+    }
+
+    fn ensure_no_indexes_on_column<'a>(
+        indexes: impl IntoIterator<Item = (&'a str, &'a IndexKey)>,
+        table_name: &str,
+        column: &str,
+    ) -> io::Result<()> {
+        match Self::index_on_column(indexes, table_name, column) {
+            Some(index_name) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Column '{}' is indexed by '{}'", column, index_name),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The column ADD COLUMN appends to `schema`, with the value existing
+    /// rows get for it
+    fn column_to_add(schema: &Schema, column_def: &ColumnDef) -> io::Result<(Column, Value)> {
+        Self::ensure_unconstrained_column(column_def)?;
+        if schema.find_column(&column_def.name).is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("Column '{}' already exists", column_def.name),
+            ));
+        }
+        let data_type = Self::db_data_type(&column_def.data_type);
+        let default = Self::add_column_default(column_def, data_type)?;
+        Ok((Column::new(column_def.name.clone(), data_type), default))
+    }
+
+    /// `schema` without `column`, and the position it was removed from
+    fn schema_without_column<'a>(
+        indexes: impl IntoIterator<Item = (&'a str, &'a IndexKey)>,
+        constraints: &HashMap<String, TableConstraints>,
+        table_name: &str,
+        schema: &Schema,
+        column: &str,
+    ) -> io::Result<(usize, Schema)> {
+        Self::ensure_no_indexes_on_column(indexes, table_name, column)?;
+        Self::ensure_no_constraints_on_column(constraints, table_name, column)?;
+        Self::ensure_no_fk_references_to_column(constraints, table_name, column)?;
+        let (drop_idx, _) = schema.find_column(column).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Column '{}' not found", column),
+            )
+        })?;
+        if schema.column_count() <= 1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot drop the last column in a table",
+            ));
+        }
+        let mut columns = schema.columns().to_vec();
+        columns.remove(drop_idx);
+        Ok((drop_idx, Schema::new(columns)))
+    }
+
+    /// `schema` with column `from` renamed to `to`
+    fn schema_with_renamed_column(
+        constraints: &HashMap<String, TableConstraints>,
+        table_name: &str,
+        schema: &Schema,
+        from: &str,
+        to: &str,
+    ) -> io::Result<Schema> {
+        Self::ensure_no_fk_references_to_column(constraints, table_name, from)?;
+        Self::ensure_no_checks_on_table(constraints, table_name)?;
+        let (idx, column) = schema.find_column(from).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Column '{}' not found", from),
+            )
+        })?;
+        if schema.find_column(to).is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("Column '{}' already exists", to),
+            ));
+        }
+        let mut columns = schema.columns().to_vec();
+        columns[idx] = Column::new(to.to_string(), column.data_type());
+        Ok(Schema::new(columns))
+    }
+
+    /// `schema` with `column` changed to `new_type`, and the column's
+    /// position; `None` if the column already has that type
+    fn schema_with_column_type<'a, 's>(
+        indexes: impl IntoIterator<Item = (&'a str, &'a IndexKey)>,
+        constraints: &HashMap<String, TableConstraints>,
+        table_name: &str,
+        schema: &Schema,
+        column: &str,
+        new_type: DbDataType,
+        schema_of: impl Fn(&str) -> Option<&'s Schema>,
+    ) -> io::Result<Option<(usize, Schema)>> {
+        let (idx, old_column) = schema.find_column(column).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Column '{}' not found", column),
+            )
+        })?;
+        if old_column.data_type() == new_type {
+            return Ok(None);
+        }
+        Self::ensure_fk_types_after_type_change(
+            constraints,
+            table_name,
+            column,
+            new_type,
+            schema_of,
+        )?;
+        if !Self::is_indexable(new_type)
+            && let Some(index_name) = Self::index_on_column(indexes, table_name, column)
+        {
+            return Err(Self::unindexable_type_change(column, index_name));
+        }
+        let mut columns = schema.columns().to_vec();
+        columns[idx] = Column::new(column.to_string(), new_type);
+        Ok(Some((idx, Schema::new(columns))))
+    }
+
+    /// Convert one VALUES row to the table's column types
+    fn typed_values_row(schema: &Schema, row_values: &[Literal]) -> io::Result<Vec<Value>> {
+        if row_values.len() != schema.column_count() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Row does not match table schema",
+            ));
+        }
+        row_values
+            .iter()
+            .zip(schema.columns())
+            .map(|(lit, col)| Self::literal_to_typed_value(lit, col.data_type()))
+            .collect()
+    }
+
+    /// Convert a value produced by INSERT ... SELECT to its column's type
+    fn insert_select_value(value: Value, column: &Column) -> io::Result<Value> {
+        Self::coerce_value_to_type(value, column.data_type())
+            .map_err(|e| io::Error::new(e.kind(), format!("Column '{}': {}", column.name(), e)))
+    }
+
+    /// Check INSERT ... SELECT against the target table
+    ///
+    /// The SELECT must produce `width` columns, one per table column, and its
+    /// constant items must fit the column they feed and satisfy the
+    /// constraints they alone decide.
+    fn check_insert_select(
+        table_name: &str,
+        schema: &Schema,
+        constraints: Option<&TableConstraints>,
+        select_columns: &SelectColumn,
+        width: usize,
+    ) -> io::Result<()> {
+        if width != schema.column_count() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "SELECT returns {} columns but table '{}' has {}",
+                    width,
+                    table_name,
+                    schema.column_count()
+                ),
+            ));
+        }
+        // Positions only line up when every item is a single column
+        let SelectColumn::Items(items) = select_columns else {
+            return Ok(());
+        };
+        if items
+            .iter()
+            .any(|item| matches!(item, SelectItem::All | SelectItem::QualifiedAll(_)))
+        {
+            return Ok(());
+        }
+        let mut known = Vec::new();
+        for (idx, (item, column)) in items.iter().zip(schema.columns()).enumerate() {
+            if let SelectItem::Expr(Expr::Literal(lit)) = item {
+                let value = Self::insert_select_value(Self::literal_to_value(lit)?, column)?;
+                known.push((idx, value));
+            }
+        }
+        Self::check_known_columns(table_name, schema, constraints, &known)
+    }
+
+    /// Resolve UPDATE assignments to column positions
+    ///
+    /// Rejects what no row could accept: unknown or repeated columns,
+    /// literals that don't fit their column, and literals that break a NOT
+    /// NULL or CHECK constraint on the assigned columns alone.
+    fn resolve_assignments(
+        table_name: &str,
+        schema: &Schema,
+        constraints: Option<&TableConstraints>,
+        assignments: &[(String, Expr)],
+    ) -> io::Result<Vec<(usize, Expr)>> {
+        if assignments.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "UPDATE must specify at least one column",
+            ));
+        }
+
+        let mut resolved: Vec<(usize, Expr)> = Vec::new();
+        let mut known = Vec::new();
+        for (col_name, expr) in assignments {
+            let (idx, column) = schema.find_column(col_name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Column '{}' not found in table '{}'", col_name, table_name),
+                )
+            })?;
+            if resolved.iter().any(|(seen, _)| *seen == idx) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Duplicate assignment for column '{}'", col_name),
+                ));
+            }
+            if let Expr::Literal(lit) = expr {
+                known.push((idx, Self::literal_to_typed_value(lit, column.data_type())?));
+            }
+            resolved.push((idx, expr.clone()));
+        }
+        Self::check_known_columns(table_name, schema, constraints, &known)?;
+        Ok(resolved)
+    }
+
+    /// Check the NOT NULL and CHECK constraints that the `known` column
+    /// values decide on their own, whatever the rest of the row holds
+    fn check_known_columns(
+        table_name: &str,
+        schema: &Schema,
+        constraints: Option<&TableConstraints>,
+        known: &[(usize, Value)],
+    ) -> io::Result<()> {
+        let Some(constraints) = constraints else {
+            return Ok(());
+        };
+        for (idx, value) in known {
+            let name = schema.columns()[*idx].name();
+            if value.is_null() && constraints.not_null.contains(name) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("NOT NULL constraint violated on {}", name),
+                ));
+            }
+        }
+        if constraints.checks.is_empty() {
+            return Ok(());
+        }
+
+        let columns_meta = Self::build_column_metadata_for_table(table_name, schema);
+        let mut row = vec![Value::Null; schema.column_count()];
+        for (idx, value) in known {
+            row[*idx] = value.clone();
+        }
+        for expr in &constraints.checks {
+            let decided = !expr.has_subquery()
+                && expr.column_refs().into_iter().all(|col_ref| {
+                    Self::resolve_column_index(&columns_meta, col_ref)
+                        .is_ok_and(|idx| known.iter().any(|(known_idx, _)| *known_idx == idx))
+                });
+            if decided && !Self::evaluate_predicate_static(expr, &row, &columns_meta)? {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "CHECK constraint violated",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check CREATE INDEX against the indexes that already exist
+    ///
+    /// Returns the name of an existing index that IF NOT EXISTS accepts as
+    /// is, or `None` if a new index should be built.
+    fn check_new_index<'a>(
+        stmt: &CreateIndexStmt,
+        indexes: impl IntoIterator<Item = (&'a str, &'a IndexKey, IndexType)> + Clone,
+    ) -> io::Result<Option<&'a str>> {
+        if stmt.columns.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Index must include at least one column",
+            ));
+        }
+
+        let same_columns =
+            |key: &IndexKey| key.table == stmt.table_name && key.columns == stmt.columns;
+        // IF NOT EXISTS accepts an index of the same name, or of the same
+        // columns and type, as is
+        if let Some((name, _, _)) = indexes.clone().into_iter().find(|(name, key, index_type)| {
+            *name == stmt.index_name
+                || (stmt.if_not_exists && same_columns(key) && *index_type == stmt.index_type)
+        }) {
+            if stmt.if_not_exists {
+                return Ok(Some(name));
+            }
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("Index '{}' already exists", stmt.index_name),
+            ));
+        }
+        if indexes.into_iter().any(|(_, key, _)| same_columns(key)) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "Index on {}({}) already exists",
+                    stmt.table_name,
+                    stmt.columns.join(", ")
+                ),
+            ));
+        }
+        Ok(None)
+    }
+
+    /// Positions and types of an index's key columns
+    fn index_key_columns(
+        stmt: &CreateIndexStmt,
+        schema: &Schema,
+    ) -> io::Result<(Vec<usize>, Vec<DbDataType>)> {
+        let mut column_indices = Vec::new();
+        let mut column_types = Vec::new();
+        for col_name in &stmt.columns {
+            let (idx, column) = schema.find_column(col_name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "Column '{}' not found in table '{}'",
+                        col_name, stmt.table_name
+                    ),
+                )
+            })?;
+            if !Self::is_indexable(column.data_type()) {
+                return Err(Self::unindexable_column());
+            }
+            column_indices.push(idx);
+            column_types.push(column.data_type());
+        }
+        Ok((column_indices, column_types))
+    }
+
+    fn ensure_unconstrained_column(column_def: &ColumnDef) -> io::Result<()> {
+        if column_def.is_primary_key
+            || column_def.is_unique
+            || column_def.check.is_some()
+            || column_def.references.is_some()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ALTER TABLE ADD COLUMN supports only NOT NULL and DEFAULT",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Value written into existing rows by ALTER TABLE ADD COLUMN
+    fn add_column_default(column_def: &ColumnDef, data_type: DbDataType) -> io::Result<Value> {
+        match &column_def.default {
+            Some(literal) => Self::literal_to_typed_value(literal, data_type),
+            None => Ok(Value::Null),
         }
-        Ok(())
     }
 
-    fn ensure_no_constraints_on_column(&self, table_name: &str, column: &str) -> io::Result<()> {
-        let Some(constraints) = self.constraints.get(table_name) else {
+    fn ensure_no_constraints_on_column(
+        constraints: &HashMap<String, TableConstraints>,
+        table_name: &str,
+        column: &str,
+    ) -> io::Result<()> {
+        let Some(constraints) = constraints.get(table_name) else {
             return Ok(());
         };
         if constraints.primary_key.as_deref() == Some(column)
@@ -2886,8 +5107,11 @@ impl Executor {
         Ok(())
     }
 
-    fn ensure_no_checks_on_table(&self, table_name: &str) -> io::Result<()> {
-        if let Some(constraints) = self.constraints.get(table_name)
+    fn ensure_no_checks_on_table(
+        constraints: &HashMap<String, TableConstraints>,
+        table_name: &str,
+    ) -> io::Result<()> {
+        if let Some(constraints) = constraints.get(table_name)
             && !constraints.checks.is_empty()
         {
             return Err(io::Error::new(
@@ -2898,8 +5122,12 @@ impl Executor {
         Ok(())
     }
 
-    fn ensure_no_fk_references_to_column(&self, table_name: &str, column: &str) -> io::Result<()> {
-        for (table, constraints) in &self.constraints {
+    fn ensure_no_fk_references_to_column(
+        constraints: &HashMap<String, TableConstraints>,
+        table_name: &str,
+        column: &str,
+    ) -> io::Result<()> {
+        for (table, constraints) in constraints {
             for fk in &constraints.foreign_keys {
                 if fk.ref_table == table_name && fk.ref_column == column {
                     return Err(io::Error::new(
@@ -2915,6 +5143,50 @@ impl Executor {
         Ok(())
     }
 
+    /// Reject giving `table_name.column` the type `new_type` if a foreign key
+    /// on it, or referencing it, would then link columns of different types
+    fn ensure_fk_types_after_type_change<'a>(
+        constraints: &HashMap<String, TableConstraints>,
+        table_name: &str,
+        column: &str,
+        new_type: DbDataType,
+        schema_of: impl Fn(&str) -> Option<&'a Schema>,
+    ) -> io::Result<()> {
+        let type_of = |table: &str, name: &str| {
+            if table == table_name && name == column {
+                return Some(new_type);
+            }
+            schema_of(table)
+                .and_then(|schema| schema.find_column(name))
+                .map(|(_, col)| col.data_type())
+        };
+        for (table, constraints) in constraints {
+            for fk in &constraints.foreign_keys {
+                let touches_column = (table == table_name && fk.column == column)
+                    || (fk.ref_table == table_name && fk.ref_column == column);
+                if !touches_column {
+                    continue;
+                }
+                let (Some(column_type), Some(ref_type)) = (
+                    type_of(table, &fk.column),
+                    type_of(&fk.ref_table, &fk.ref_column),
+                ) else {
+                    continue;
+                };
+                if column_type != ref_type {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "Foreign key type mismatch: {}.{} ({}) references {}.{} ({})",
+                            table, fk.column, column_type, fk.ref_table, fk.ref_column, ref_type
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn update_constraints_for_column_rename(
         &mut self,
         table_name: &str,
@@ -2930,42 +5202,39 @@ impl Executor {
                 "ALTER TABLE not supported with CHECK constraints",
             ));
         }
-        if constraints.primary_key.as_deref() == Some(from) {
-            constraints.primary_key = Some(to.to_string());
-        }
-        if constraints.unique.remove(from) {
-            constraints.unique.insert(to.to_string());
-        }
-        if constraints.not_null.remove(from) {
-            constraints.not_null.insert(to.to_string());
-        }
-        for fk in &mut constraints.foreign_keys {
-            if fk.column == from {
-                fk.column = to.to_string();
-            }
-        }
+        constraints.rename_column(from, to);
         self.persist_constraints_metadata()?;
         Ok(())
     }
 
     fn update_constraints_for_table_rename(&mut self, from: &str, to: &str) -> io::Result<()> {
+        if Self::rename_constrained_table(&mut self.constraints, from, to) {
+            self.persist_constraints_metadata()?;
+        }
+        Ok(())
+    }
+
+    /// Move `from`'s constraints to `to` and repoint foreign keys that
+    /// reference it. Returns whether anything changed.
+    fn rename_constrained_table(
+        constraints: &mut HashMap<String, TableConstraints>,
+        from: &str,
+        to: &str,
+    ) -> bool {
         let mut touched = false;
-        if let Some(constraints) = self.constraints.remove(from) {
-            self.constraints.insert(to.to_string(), constraints);
+        if let Some(table_constraints) = constraints.remove(from) {
+            constraints.insert(to.to_string(), table_constraints);
             touched = true;
         }
-        for constraints in self.constraints.values_mut() {
-            for fk in &mut constraints.foreign_keys {
+        for table_constraints in constraints.values_mut() {
+            for fk in &mut table_constraints.foreign_keys {
                 if fk.ref_table == from {
                     fk.ref_table = to.to_string();
                     touched = true;
                 }
             }
         }
-        if touched {
-            self.persist_constraints_metadata()?;
-        }
-        Ok(())
+        touched
     }
 
     fn apply_select_items(
@@ -3004,6 +5273,24 @@ impl Executor {
             SelectColumn::Items(items) => items,
             SelectColumn::All => unreachable!("handled above"),
         };
+        // `table.*` stands for its columns, so each one follows the GROUP BY
+        // rules of a plain column reference.
+        let mut expanded = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                SelectItem::QualifiedAll(table) => {
+                    for idx in Self::qualified_all_indices(columns_meta, table)? {
+                        let (qualifier, name) = &columns_meta[idx];
+                        expanded.push(SelectItem::Column(ColumnRef::new(
+                            qualifier.clone(),
+                            name.clone(),
+                        )));
+                    }
+                }
+                _ => expanded.push(item.clone()),
+            }
+        }
+        let items = &expanded;
 
         let mut group_by_indices = Vec::with_capacity(group_by.len());
         for col in group_by {
@@ -3018,11 +5305,97 @@ impl Executor {
             count_all: bool,
         }
 
+        /// Running total for SUM and AVG
+        ///
+        /// Inputs are summed exactly as a decimal until a FLOAT value is seen;
+        /// the exact total is only reported when at least one input was a
+        /// DECIMAL, so integer-only aggregates keep returning FLOAT.
+        #[derive(Clone)]
+        struct NumericSum {
+            float: f64,
+            exact: crate::types::Decimal,
+            count: u64,
+            has_decimal: bool,
+            has_float: bool,
+        }
+
+        impl NumericSum {
+            /// Scale used for AVG over decimals when the inputs carry fewer digits
+            const MIN_AVG_SCALE: u32 = 6;
+
+            fn new() -> Self {
+                Self {
+                    float: 0.0,
+                    exact: crate::types::Decimal::from_i128(0),
+                    count: 0,
+                    has_decimal: false,
+                    has_float: false,
+                }
+            }
+
+            fn add(&mut self, value: &Value) -> io::Result<()> {
+                let Some(num) = Executor::numeric_to_f64(value)? else {
+                    return Ok(());
+                };
+                self.float += num;
+                self.count += 1;
+
+                let exact = match value {
+                    Value::Integer(i) => crate::types::Decimal::from_i128(*i as i128),
+                    Value::Unsigned(u) => crate::types::Decimal::from_i128(*u as i128),
+                    Value::Decimal(d) => {
+                        self.has_decimal = true;
+                        *d
+                    }
+                    _ => {
+                        self.has_float = true;
+                        return Ok(());
+                    }
+                };
+                if !self.has_float {
+                    self.exact = self.exact.checked_add(&exact).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "Decimal aggregate overflow")
+                    })?;
+                }
+                Ok(())
+            }
+
+            fn is_exact(&self) -> bool {
+                self.has_decimal && !self.has_float
+            }
+
+            fn sum(self) -> Value {
+                if self.count == 0 {
+                    Value::Null
+                } else if self.is_exact() {
+                    Value::Decimal(self.exact)
+                } else {
+                    Value::Float(self.float)
+                }
+            }
+
+            fn avg(self) -> io::Result<Value> {
+                if self.count == 0 {
+                    return Ok(Value::Null);
+                }
+                if !self.is_exact() {
+                    return Ok(Value::Float(self.float / self.count as f64));
+                }
+                let scale = self.exact.scale.max(Self::MIN_AVG_SCALE);
+                self.exact
+                    .checked_div_int(self.count as i128, scale)
+                    .map(Value::Decimal)
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "Decimal aggregate overflow")
+                    })
+            }
+        }
+
         #[derive(Clone)]
         enum AggState {
             Count(i64),
-            Sum { sum: f64, count: u64 },
-            Avg { sum: f64, count: u64 },
+            Sum(NumericSum),
+            Avg(NumericSum),
             Min(Option<Value>),
             Max(Option<Value>),
         }
@@ -3031,33 +5404,21 @@ impl Executor {
             fn new(spec: &AggSpec) -> Self {
                 match spec.func {
                     AggregateFunc::Count => AggState::Count(0),
-                    AggregateFunc::Sum => AggState::Sum { sum: 0.0, count: 0 },
-                    AggregateFunc::Avg => AggState::Avg { sum: 0.0, count: 0 },
+                    AggregateFunc::Sum => AggState::Sum(NumericSum::new()),
+                    AggregateFunc::Avg => AggState::Avg(NumericSum::new()),
                     AggregateFunc::Min => AggState::Min(None),
                     AggregateFunc::Max => AggState::Max(None),
                 }
             }
 
-            fn finish(self) -> Value {
-                match self {
+            fn finish(self) -> io::Result<Value> {
+                Ok(match self {
                     AggState::Count(count) => Value::Integer(count),
-                    AggState::Sum { sum, count } => {
-                        if count == 0 {
-                            Value::Null
-                        } else {
-                            Value::Float(sum)
-                        }
-                    }
-                    AggState::Avg { sum, count } => {
-                        if count == 0 {
-                            Value::Null
-                        } else {
-                            Value::Float(sum / count as f64)
-                        }
-                    }
+                    AggState::Sum(sum) => sum.sum(),
+                    AggState::Avg(sum) => sum.avg()?,
                     AggState::Min(value) => value.unwrap_or(Value::Null),
                     AggState::Max(value) => value.unwrap_or(Value::Null),
-                }
+                })
             }
         }
 
@@ -3084,19 +5445,18 @@ impl Executor {
             }
         }
 
+        // Without GROUP BY the whole input is a single group, so any plain
+        // column is as illegal as one missing from the GROUP BY list.
         for (item, agg_index) in items.iter().zip(item_to_agg_index.iter()) {
             if let SelectItem::Column(col) = item {
-                if group_by_indices.is_empty() {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        "Non-aggregate columns require GROUP BY",
-                    ));
-                }
                 let idx = Self::resolve_column_index(columns_meta, col)?;
                 if !group_by_indices.contains(&idx) {
                     return Err(io::Error::new(
                         io::ErrorKind::InvalidInput,
-                        format!("Column '{}' must appear in GROUP BY", col.column),
+                        format!(
+                            "Column '{}' must appear in GROUP BY or be used in an aggregate function",
+                            col.column
+                        ),
                     ));
                 }
             }
@@ -3122,12 +5482,18 @@ impl Executor {
                     output_meta.push((None, name.clone()));
                     column_names.push(name);
                 }
-                SelectItem::All => {
+                SelectItem::All | SelectItem::QualifiedAll(_) => {
                     return Err(io::Error::new(
                         io::ErrorKind::InvalidInput,
                         "Invalid '*' in select list",
                     ));
                 }
+                SelectItem::Expr(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Select expressions must be evaluated before projection",
+                    ));
+                }
             }
         }
 
@@ -3166,20 +5532,10 @@ impl Executor {
                             }
                         }
                     }
-                    (AggState::Sum { sum, count }, AggregateFunc::Sum) => {
-                        if let Some(value) = value_opt {
-                            if let Some(num) = Self::numeric_to_f64(&value)? {
-                                *sum += num;
-                                *count += 1;
-                            }
-                        }
-                    }
-                    (AggState::Avg { sum, count }, AggregateFunc::Avg) => {
+                    (AggState::Sum(sum), AggregateFunc::Sum)
+                    | (AggState::Avg(sum), AggregateFunc::Avg) => {
                         if let Some(value) = value_opt {
-                            if let Some(num) = Self::numeric_to_f64(&value)? {
-                                *sum += num;
-                                *count += 1;
-                            }
+                            sum.add(&value)?;
                         }
                     }
                     (AggState::Min(current), AggregateFunc::Min) => {
@@ -3239,7 +5595,7 @@ impl Executor {
                         row.push(group_key[group_pos].clone());
                     }
                     (SelectItem::Aggregate(_), Some(agg_idx)) => {
-                        row.push(agg_states[*agg_idx].clone().finish());
+                        row.push(agg_states[*agg_idx].clone().finish()?);
                     }
                     _ => {
                         return Err(io::Error::new(
@@ -3371,6 +5727,7 @@ impl Executor {
             })?;
 
             let index_value = match (data_type, value) {
+                (_, Value::Null) => IndexValue::Null,
                 (DbDataType::Integer, Value::Integer(i)) => IndexValue::Signed(*i),
                 (DbDataType::Integer, Value::Unsigned(u)) if *u <= i64::MAX as u64 => {
                     IndexValue::Signed(*u as i64)
@@ -3379,12 +5736,8 @@ impl Executor {
                 (DbDataType::Unsigned, Value::Integer(i)) if *i >= 0 => {
                     IndexValue::Unsigned(*i as u64)
                 }
-                _ => {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        "Indexing currently only supports INTEGER or UNSIGNED columns",
-                    ));
-                }
+                (DbDataType::String, Value::String(s)) => IndexValue::Str(s.clone()),
+                _ => return Err(Self::unindexable_column()),
             };
             values.push(index_value);
         }
@@ -3408,77 +5761,131 @@ impl Executor {
         })
     }
 
+    /// Build B-tree key ranges from predicates on the index's leading columns.
+    ///
+    /// All predicates on a column are intersected into one bound, so
+    /// `price > 100 AND price < 300` uses both ends. `name LIKE 'Al%'` scans
+    /// the names from `'Al'` up to `'Am'`. Returns `None` when the predicates
+    /// can't drive the index, and no ranges when they contradict each other so
+    /// that no row can match.
+    ///
+    /// Bounds on strings are always inclusive, since no string sorts
+    /// immediately before or after another; the WHERE clause, which is
+    /// re-checked on every row, drops the bound itself where it is exclusive.
     fn build_ranges(
         index: &IndexEntry,
         predicates: &[(String, BinaryOp, Literal)],
-    ) -> io::Result<Vec<(CompositeKey, CompositeKey)>> {
+    ) -> io::Result<Option<Vec<(CompositeKey, CompositeKey)>>> {
         if predicates.is_empty() {
-            return Ok(Vec::new());
+            return Ok(None);
         }
 
         let mut start = CompositeKey::min_values(&index.column_types);
         let mut end = CompositeKey::max_values(&index.column_types);
-        let mut eq_prefix = true;
 
         for (i, col_name) in index.key.columns.iter().enumerate() {
-            let pred = predicates.iter().find(|(c, _, _)| c == col_name);
-            let Some((_, op, lit)) = pred else {
-                break;
-            };
+            let column_type = &index.column_types[i];
+            let mut lower = IndexValue::min_value(column_type);
+            let mut upper = IndexValue::max_value(column_type);
+            let mut excluded = None;
+            let mut constrained = false;
+
+            for (_, op, lit) in predicates.iter().filter(|(c, _, _)| c == col_name) {
+                let Some(value) = IndexValue::from_literal(lit, column_type) else {
+                    return Ok(None);
+                };
+                constrained = true;
 
-            let Some(value) = IndexValue::from_literal(lit, &index.column_types[i]) else {
-                return Ok(Vec::new());
-            };
+                match op {
+                    BinaryOp::Eq => {
+                        lower = lower.max(value.clone());
+                        upper = upper.min(value);
+                    }
+                    BinaryOp::Lt => match value.checked_sub_one() {
+                        Some(bound) => upper = upper.min(bound),
+                        None if value.is_discrete() => return Ok(Some(Vec::new())),
+                        None => upper = upper.min(value),
+                    },
+                    BinaryOp::LtEq => upper = upper.min(value),
+                    BinaryOp::Gt => match value.checked_add_one() {
+                        Some(bound) => lower = lower.max(bound),
+                        None if value.is_discrete() => return Ok(Some(Vec::new())),
+                        None => lower = lower.max(value),
+                    },
+                    BinaryOp::GtEq => lower = lower.max(value),
+                    // Only the leading column can be split around a single
+                    // excluded value; the WHERE clause re-checks the rest.
+                    BinaryOp::NotEq => {
+                        if excluded.is_none() && value.is_discrete() {
+                            excluded = Some(value);
+                        }
+                    }
+                    BinaryOp::Like => {
+                        let IndexValue::Str(like_pattern) = value else {
+                            return Ok(None);
+                        };
+                        let (prefix, exact) = pattern::literal_prefix(&like_pattern);
+                        if prefix.is_empty() {
+                            return Ok(None);
+                        }
+                        if exact {
+                            upper = upper.min(IndexValue::Str(prefix.clone()));
+                        } else if let Some(bound) = pattern::prefix_upper_bound(&prefix) {
+                            upper = upper.min(IndexValue::Str(bound));
+                        }
+                        lower = lower.max(IndexValue::Str(prefix));
+                    }
+                    BinaryOp::And
+                    | BinaryOp::Plus
+                    | BinaryOp::Minus
+                    | BinaryOp::Divide
+                    | BinaryOp::Mod
+                    | BinaryOp::IsDistinctFrom
+                    | BinaryOp::IsNotDistinctFrom => unreachable!(),
+                }
+            }
 
-            if !eq_prefix {
+            if !constrained {
                 break;
             }
+            if lower > upper {
+                return Ok(Some(Vec::new()));
+            }
 
-            match op {
-                BinaryOp::Eq => {
-                    start.values[i] = value.clone();
-                    end.values[i] = value;
-                }
-                BinaryOp::Lt => {
-                    end.values[i] = value.saturating_sub_one();
-                    eq_prefix = false;
-                }
-                BinaryOp::LtEq => {
-                    end.values[i] = value;
-                    eq_prefix = false;
-                }
-                BinaryOp::Gt => {
-                    start.values[i] = value.saturating_add_one();
-                    eq_prefix = false;
-                }
-                BinaryOp::GtEq => {
-                    start.values[i] = value;
-                    eq_prefix = false;
+            if i == 0
+                && let Some(value) = excluded
+                && value >= lower
+                && value <= upper
+            {
+                let mut ranges = Vec::new();
+                if let Some(left_upper) = value.checked_sub_one()
+                    && left_upper >= lower
+                {
+                    let (mut left_start, mut left_end) = (start.clone(), end.clone());
+                    left_start.values[0] = lower.clone();
+                    left_end.values[0] = left_upper;
+                    ranges.push((left_start, left_end));
                 }
-                BinaryOp::NotEq => {
-                    if i == 0 {
-                        let mut ranges = Vec::new();
-                        if value > IndexValue::min_value(&index.column_types[0]) {
-                            let mut left_end = end.clone();
-                            left_end.values[0] = value.saturating_sub_one();
-                            ranges.push((start.clone(), left_end));
-                        }
-                        if value < IndexValue::max_value(&index.column_types[0]) {
-                            let mut right_start = start.clone();
-                            right_start.values[0] = value.saturating_add_one();
-                            ranges
-                                .push((right_start, CompositeKey::max_values(&index.column_types)));
-                        }
-                        return Ok(ranges);
-                    } else {
-                        return Ok(Vec::new());
-                    }
+                if let Some(right_lower) = value.checked_add_one()
+                    && right_lower <= upper
+                {
+                    let (mut right_start, mut right_end) = (start.clone(), end.clone());
+                    right_start.values[0] = right_lower;
+                    right_end.values[0] = upper;
+                    ranges.push((right_start, right_end));
                 }
-                BinaryOp::And => unreachable!(),
+                return Ok(Some(ranges));
+            }
+
+            let is_point = lower == upper;
+            start.values[i] = lower;
+            end.values[i] = upper;
+            if !is_point {
+                break;
             }
         }
 
-        Ok(vec![(start, end)])
+        Ok(Some(vec![(start, end)]))
     }
 
     fn build_hash_key(
@@ -3507,7 +5914,10 @@ impl Executor {
     }
 
     fn persist_index_metadata(&self) -> io::Result<()> {
-        let path = self.db_path.join("indexes.meta");
+        let Some(db_path) = &self.db_path else {
+            return Ok(());
+        };
+        let path = db_path.join("indexes.meta");
         let mut buf = String::new();
         for idx in &self.indexes {
             buf.push_str(&format!(
@@ -3519,11 +5929,20 @@ impl Executor {
                 idx.key.columns.join(",")
             ));
         }
+        for orphan in &self.orphaned_metadata {
+            if orphan.file == "indexes.meta" {
+                buf.push_str(&orphan.line);
+                buf.push('\n');
+            }
+        }
         fs::write(path, buf)
     }
 
     fn persist_constraints_metadata(&self) -> io::Result<()> {
-        let path = self.db_path.join("constraints.meta");
+        let Some(db_path) = &self.db_path else {
+            return Ok(());
+        };
+        let path = db_path.join("constraints.meta");
         let mut buf = String::new();
         for (table, constraints) in &self.constraints {
             let primary = constraints.primary_key.clone().unwrap_or_default();
@@ -3550,11 +5969,20 @@ impl Executor {
                 table, primary, unique_str, fk_str, not_null_str, check_str
             ));
         }
+        for orphan in &self.orphaned_metadata {
+            if orphan.file == "constraints.meta" {
+                buf.push_str(&orphan.line);
+                buf.push('\n');
+            }
+        }
         fs::write(path, buf)
     }
 
     fn load_indexes_from_metadata(&mut self) -> io::Result<()> {
-        let path = self.db_path.join("indexes.meta");
+        let Some(db_path) = &self.db_path else {
+            return Ok(());
+        };
+        let path = db_path.join("indexes.meta");
         if !path.exists() {
             return Ok(());
         }
@@ -3591,7 +6019,14 @@ impl Executor {
 
             let table_ref = match self.tables.get_mut(table) {
                 Some(t) => t,
-                None => continue,
+                None => {
+                    self.orphaned_metadata.push(OrphanedMetadata {
+                        file: "indexes.meta",
+                        table: table.to_string(),
+                        line: line.to_string(),
+                    });
+                    continue;
+                }
             };
             let schema = table_ref.schema().clone();
 
@@ -3599,10 +6034,7 @@ impl Executor {
             let mut column_types = Vec::new();
             for col in &columns {
                 if let Some((idx, column)) = schema.find_column(col) {
-                    if !matches!(
-                        column.data_type(),
-                        DbDataType::Integer | DbDataType::Unsigned
-                    ) {
+                    if !Self::is_indexable(column.data_type()) {
                         column_indices.clear();
                         column_types.clear();
                         break;
@@ -3650,19 +6082,27 @@ impl Executor {
     }
 
     fn load_constraints_metadata(&mut self) -> io::Result<()> {
-        let path = self.db_path.join("constraints.meta");
+        let Some(db_path) = &self.db_path else {
+            return Ok(());
+        };
+        let path = db_path.join("constraints.meta");
         if !path.exists() {
             return Ok(());
         }
 
         let data = fs::read_to_string(&path)?;
         for line in data.lines() {
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.is_empty() {
+            if line.is_empty() {
                 continue;
             }
+            let parts: Vec<&str> = line.split('|').collect();
             let table = parts[0].to_string();
             if !self.tables.contains_key(&table) {
+                self.orphaned_metadata.push(OrphanedMetadata {
+                    file: "constraints.meta",
+                    table,
+                    line: line.to_string(),
+                });
                 continue;
             }
 
@@ -3802,6 +6242,59 @@ impl Executor {
         }
     }
 
+    fn describe_join_condition(join_plan: &JoinPlan) -> String {
+        let key = join_plan.join_key.as_ref().map(|(outer, inner)| {
+            format!(
+                "{} = {}",
+                Self::format_column_ref(outer),
+                Self::format_column_ref(inner)
+            )
+        });
+        let filter = join_plan.join_filter.as_ref().map(Self::describe_expr);
+        match (key, filter) {
+            (Some(key), Some(filter)) => format!("{} AND {}", key, filter),
+            (Some(key), None) => key,
+            (None, Some(filter)) => filter,
+            (None, None) => "TRUE".to_string(),
+        }
+    }
+
+    fn describe_order_limit(
+        order_by: &[OrderByExpr],
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Vec<String> {
+        let mut steps = Vec::new();
+        if !order_by.is_empty() {
+            let order_desc = order_by
+                .iter()
+                .map(|expr| {
+                    format!(
+                        "{} {}",
+                        Self::format_column_ref(&expr.column),
+                        if expr.ascending { "ASC" } else { "DESC" }
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            steps.push(format!("Order by: {}", order_desc));
+        }
+        if let Some(limit) = limit {
+            steps.push(format!("Limit: {}", limit));
+            if !order_by.is_empty() {
+                let keep = offset.unwrap_or(0).saturating_add(limit);
+                steps.push(format!("Top-k sort: keep {} row(s)", keep));
+            }
+        }
+        if let Some(offset) = offset {
+            steps.push(format!("Offset: {}", offset));
+        }
+        if order_by.is_empty() && (limit.is_some() || offset.is_some()) {
+            steps.push(UNSTABLE_ORDERING_WARNING.to_string());
+        }
+        steps
+    }
+
     fn describe_expr(expr: &Expr) -> String {
         match expr {
             Expr::Column(col_ref) => Self::format_column_ref(col_ref),
@@ -3815,6 +6308,19 @@ impl Executor {
             Expr::InSubquery { expr, .. } => {
                 format!("{} IN (subquery)", Self::describe_expr(expr))
             }
+            Expr::Exists { negated, .. } => {
+                if *negated {
+                    "NOT EXISTS (subquery)".to_string()
+                } else {
+                    "EXISTS (subquery)".to_string()
+                }
+            }
+            Expr::ScalarSubquery(_) => "(subquery)".to_string(),
+            Expr::CurrentTimestamp => "CURRENT_TIMESTAMP".to_string(),
+            Expr::CurrentDate => "CURRENT_DATE".to_string(),
+            Expr::JsonExtract { expr, path } => {
+                format!("json_extract({}, '{}')", Self::describe_expr(expr), path)
+            }
         }
     }
 
@@ -3826,7 +6332,14 @@ impl Executor {
             BinaryOp::LtEq => "<=",
             BinaryOp::Gt => ">",
             BinaryOp::GtEq => ">=",
+            BinaryOp::Like => "LIKE",
             BinaryOp::And => "AND",
+            BinaryOp::Plus => "+",
+            BinaryOp::Minus => "-",
+            BinaryOp::Divide => "/",
+            BinaryOp::Mod => "%",
+            BinaryOp::IsDistinctFrom => "IS DISTINCT FROM",
+            BinaryOp::IsNotDistinctFrom => "IS NOT DISTINCT FROM",
         }
     }
 
@@ -3963,27 +6476,10 @@ impl Executor {
             table.schema().clone()
         };
 
-        for col in &constraints.not_null {
-            let (idx, _) = schema.find_column(col).ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("Column '{}' not found in table '{}'", col, table_name),
-                )
-            })?;
-            if row[idx].is_null() {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("NOT NULL constraint violated on {}", col),
-                ));
-            }
-        }
-
-        let snapshot = self.current_snapshot();
-        let current_txn_id = self.current_txn_id;
-        let txn_states = self.txn_states.clone();
+        Self::check_not_null(table_name, &schema, &constraints, row)?;
 
-        for col in constraints.unique {
-            let (idx, _) = schema.find_column(&col).ok_or_else(|| {
+        for col in &constraints.unique {
+            let (idx, _) = schema.find_column(col).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::InvalidInput,
                     format!("Column '{}' not found in table '{}'", col, table_name),
@@ -3993,7 +6489,7 @@ impl Executor {
             if value.is_null() {
                 continue;
             }
-            let table = self.tables.get_mut(table_name).ok_or_else(|| {
+            let (table, visibility) = self.table_with_visibility(table_name).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
                     format!("Table '{}' does not exist", table_name),
@@ -4004,12 +6500,7 @@ impl Executor {
                 if Some(row_id) == exclude_row {
                     continue;
                 }
-                if !Self::is_visible_for_snapshot(
-                    &meta,
-                    snapshot.as_ref(),
-                    current_txn_id,
-                    &txn_states,
-                ) {
+                if !visibility.visible_in_scan(&scan, &meta) {
                     continue;
                 }
                 if existing[idx] == *value {
@@ -4021,7 +6512,7 @@ impl Executor {
             }
         }
 
-        for fk in constraints.foreign_keys {
+        for fk in &constraints.foreign_keys {
             let (idx, _) = schema.find_column(&fk.column).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::InvalidInput,
@@ -4050,52 +6541,82 @@ impl Executor {
                     ),
                 )
             })?;
-            let table = self.tables.get_mut(&fk.ref_table).ok_or_else(|| {
+            if let Some(found) =
+                self.find_through_index(&fk.ref_table, &fk.ref_column, ref_idx, value)?
+            {
+                if !found {
+                    return Err(Self::foreign_key_violation(table_name, fk));
+                }
+                continue;
+            }
+            let (table, visibility) =
+                self.table_with_visibility(&fk.ref_table).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Referenced table '{}' does not exist", fk.ref_table),
+                    )
+                })?;
+            let mut scan = TableScan::new(table);
+            let mut found = false;
+            while let Some((_row_id, meta, existing)) = scan.next_with_metadata()? {
+                if !visibility.visible_in_scan(&scan, &meta) {
+                    continue;
+                }
+                if existing[ref_idx] == *value {
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                return Err(Self::foreign_key_violation(table_name, fk));
+            }
+        }
+
+        Self::check_row_checks(table_name, &schema, &constraints, row)
+    }
+
+    fn check_not_null(
+        table_name: &str,
+        schema: &Schema,
+        constraints: &TableConstraints,
+        row: &[Value],
+    ) -> io::Result<()> {
+        for col in &constraints.not_null {
+            let (idx, _) = schema.find_column(col).ok_or_else(|| {
                 io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("Referenced table '{}' does not exist", fk.ref_table),
+                    io::ErrorKind::InvalidInput,
+                    format!("Column '{}' not found in table '{}'", col, table_name),
                 )
             })?;
-            let mut scan = TableScan::new(table);
-            let mut found = false;
-            while let Some((_row_id, meta, existing)) = scan.next_with_metadata()? {
-                if !Self::is_visible_for_snapshot(
-                    &meta,
-                    snapshot.as_ref(),
-                    current_txn_id,
-                    &txn_states,
-                ) {
-                    continue;
-                }
-                if existing[ref_idx] == *value {
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
+            if row[idx].is_null() {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidInput,
-                    format!(
-                        "Foreign key violation on {}.{} -> {}.{}",
-                        table_name, fk.column, fk.ref_table, fk.ref_column
-                    ),
+                    format!("NOT NULL constraint violated on {}", col),
                 ));
             }
         }
+        Ok(())
+    }
 
-        if !constraints.checks.is_empty() {
-            let columns_meta = Self::build_column_metadata_for_table(table_name, &schema);
-            for expr in constraints.checks {
-                let ok = Self::evaluate_predicate_static(&expr, row, &columns_meta)?;
-                if !ok {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        "CHECK constraint violated",
-                    ));
-                }
+    fn check_row_checks(
+        table_name: &str,
+        schema: &Schema,
+        constraints: &TableConstraints,
+        row: &[Value],
+    ) -> io::Result<()> {
+        if constraints.checks.is_empty() {
+            return Ok(());
+        }
+        let columns_meta = Self::build_column_metadata_for_table(table_name, schema);
+        for expr in &constraints.checks {
+            let ok = Self::evaluate_predicate_static(expr, row, &columns_meta)?;
+            if !ok {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "CHECK constraint violated",
+                ));
             }
         }
-
         Ok(())
     }
 
@@ -4110,10 +6631,6 @@ impl Executor {
             return Ok(false);
         }
 
-        let snapshot = self.current_snapshot();
-        let current_txn_id = self.current_txn_id;
-        let txn_states = self.txn_states.clone();
-
         for (child_table, table_constraints) in constraints {
             for fk in table_constraints.foreign_keys {
                 if fk.ref_table != ref_table || fk.ref_column != ref_column {
@@ -4137,20 +6654,21 @@ impl Executor {
                         ),
                     )
                 })?;
-                let table = self.tables.get_mut(&child_table).ok_or_else(|| {
-                    io::Error::new(
-                        io::ErrorKind::NotFound,
-                        format!("Table '{}' does not exist", child_table),
-                    )
-                })?;
+                match self.find_through_index(&child_table, &fk.column, child_idx, value)? {
+                    Some(true) => return Ok(true),
+                    Some(false) => continue,
+                    None => {}
+                }
+                let (table, visibility) =
+                    self.table_with_visibility(&child_table).ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("Table '{}' does not exist", child_table),
+                        )
+                    })?;
                 let mut scan = TableScan::new(table);
                 while let Some((_row_id, meta, row)) = scan.next_with_metadata()? {
-                    if !Self::is_visible_for_snapshot(
-                        &meta,
-                        snapshot.as_ref(),
-                        current_txn_id,
-                        &txn_states,
-                    ) {
+                    if !visibility.visible_in_scan(&scan, &meta) {
                         continue;
                     }
                     if row[child_idx] == *value {
@@ -4163,6 +6681,60 @@ impl Executor {
         Ok(false)
     }
 
+    /// Whether a visible row of `table_name` has `value` in `column`, found
+    /// through an index on just that column
+    ///
+    /// Returns `None` when no such index exists (or `value` can't be an index
+    /// key), leaving the caller to scan the table.
+    fn find_through_index(
+        &mut self,
+        table_name: &str,
+        column: &str,
+        column_idx: usize,
+        value: &Value,
+    ) -> io::Result<Option<bool>> {
+        let Some(index) = self
+            .indexes
+            .iter()
+            .filter(|idx| idx.key.table == table_name && idx.key.columns == [column])
+            .max_by_key(|idx| idx.is_unique)
+        else {
+            return Ok(None);
+        };
+        let Some(key) = Self::coerce_value_to_type(value.clone(), index.column_types[0])
+            .ok()
+            .as_ref()
+            .and_then(IndexValue::from_value)
+        else {
+            return Ok(None);
+        };
+        let row_ids = index.lookup_eq(&CompositeKey::new(vec![key]));
+
+        let (table, visibility) = self.table_with_visibility(table_name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Table '{}' does not exist", table_name),
+            )
+        })?;
+        for row_id in row_ids {
+            let (meta, row) = table.get_with_metadata(row_id)?;
+            if row[column_idx] == *value && visibility.is_visible(&meta) {
+                return Ok(Some(true));
+            }
+        }
+        Ok(Some(false))
+    }
+
+    fn foreign_key_violation(table_name: &str, fk: &ForeignKey) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Foreign key violation on {}.{} -> {}.{}",
+                table_name, fk.column, fk.ref_table, fk.ref_column
+            ),
+        )
+    }
+
     fn enforce_no_fk_references(&mut self, table_name: &str, row: &[Value]) -> io::Result<()> {
         let schema = {
             let table = self.tables.get(table_name).ok_or_else(|| {
@@ -4253,20 +6825,37 @@ impl Executor {
         Ok(())
     }
 
-    fn has_write_conflict(
+    /// Transaction that deleted or replaced the row, unless it was the
+    /// current one or has aborted.
+    fn conflicting_writer(
         meta: &RowMetadata,
         current_txn_id: Option<TxnId>,
         txn_states: &HashMap<TxnId, TxnState>,
-    ) -> bool {
+    ) -> Option<TxnId> {
         let deleter = meta.xmax;
         if deleter == 0 || Some(deleter) == current_txn_id {
-            return false;
+            return None;
         }
         let deleter_state = txn_states
             .get(&deleter)
             .copied()
             .unwrap_or(TxnState::Committed);
-        deleter_state != TxnState::Aborted
+        (deleter_state != TxnState::Aborted).then_some(deleter)
+    }
+
+    /// Build the error for a conflict on `row_id`, aborting the current
+    /// transaction first unless the policy allows a retry.
+    fn write_conflict(&mut self, row_id: RowId, holder: TxnId) -> io::Error {
+        let retryable = self.conflict_policy == ConflictPolicy::Retry;
+        if !retryable && let Err(err) = self.abort_current_transaction() {
+            return err;
+        }
+        WriteConflict {
+            row_id,
+            txn_id: holder,
+            retryable,
+        }
+        .into()
     }
 
     fn abort_current_transaction(&mut self) -> io::Result<()> {
@@ -4287,6 +6876,9 @@ impl Executor {
 
     /// Flush all tables
     pub fn flush_all(&mut self) -> io::Result<()> {
+        if self.db_path.is_none() {
+            return Ok(());
+        }
         for table in self.tables.values_mut() {
             table.flush()?;
         }
@@ -4294,6 +6886,26 @@ impl Executor {
         Ok(())
     }
 
+    /// Compress a table's pages with `compression` whenever they are flushed
+    ///
+    /// Intended for cold, read-mostly tables. The setting lasts for the
+    /// lifetime of this executor; existing pages are rewritten on next flush
+    /// only if they are dirty.
+    pub fn set_table_compression(
+        &mut self,
+        table_name: &str,
+        compression: Compression,
+    ) -> io::Result<()> {
+        let table = self.tables.get_mut(table_name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Table '{}' does not exist", table_name),
+            )
+        })?;
+        table.set_compression(compression);
+        Ok(())
+    }
+
     pub fn vacuum_table(&mut self, table_name: &str) -> io::Result<usize> {
         if !self.active_txns.is_empty() {
             return Err(io::Error::new(
@@ -4303,7 +6915,7 @@ impl Executor {
         }
 
         let txn_states = self.txn_states.clone();
-        let row_ids = {
+        let (row_ids, all_visible_pages) = {
             let table = self.tables.get_mut(table_name).ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::NotFound,
@@ -4313,33 +6925,48 @@ impl Executor {
 
             let mut scan = TableScan::new(table);
             let mut dead_rows = Vec::new();
+            // With no transaction active, a surviving row that nobody has
+            // tried to delete is visible to every future snapshot
+            let mut page_visibility: std::collections::BTreeMap<_, bool> =
+                std::collections::BTreeMap::new();
             while let Some((row_id, meta, _row)) = scan.next_with_metadata()? {
-                if !Self::is_visible_for_snapshot(&meta, None, None, &txn_states) {
+                if scan.on_all_visible_page() {
+                    continue;
+                }
+                let visible = Self::is_visible_for_snapshot(&meta, None, None, &txn_states);
+                if !visible {
                     dead_rows.push(row_id);
                 }
+                let all_visible = page_visibility.entry(row_id.page_id()).or_insert(true);
+                *all_visible &= !visible || meta.xmax == 0;
             }
-            dead_rows
+            let all_visible_pages: Vec<_> = page_visibility
+                .into_iter()
+                .filter_map(|(page_id, all_visible)| all_visible.then_some(page_id))
+                .collect();
+            (dead_rows, all_visible_pages)
         };
 
-        if row_ids.is_empty() {
-            return Ok(0);
-        }
-
         let table = self.tables.get_mut(table_name).ok_or_else(|| {
             io::Error::new(
                 io::ErrorKind::NotFound,
                 format!("Table '{}' does not exist", table_name),
             )
         })?;
-        let mut removed = 0usize;
-        for row_id in row_ids {
-            match table.delete(row_id) {
-                Ok(()) => removed += 1,
-                Err(err)
-                    if err.kind() == io::ErrorKind::NotFound
-                        || err.kind() == io::ErrorKind::UnexpectedEof => {}
-                Err(err) => return Err(err),
-            }
+        let removed = table.delete_many(&row_ids)?;
+
+        // Surviving rows never move, so indexes only need rebuilding for removed rows
+        table.reclaim_empty_pages()?;
+
+        // Deleting dead rows cleared the flag on their pages; set it again
+        // where every remaining row is visible to all. Pages truncated by the
+        // reclaim above are gone.
+        let num_pages = table.buffer_pool_mut().num_pages()?;
+        for page_id in all_visible_pages
+            .into_iter()
+            .filter(|&page_id| page_id < num_pages)
+        {
+            table.mark_all_visible(page_id)?;
         }
 
         if removed > 0 {
@@ -4358,6 +6985,232 @@ impl Executor {
         Ok(removed)
     }
 
+    /// Estimate how many rows a SELECT would read and return.
+    ///
+    /// The estimate follows the planner's scan choice and uses only table
+    /// statistics (stored row counts and known distinct counts from unique
+    /// constraints and indexes), so no table data is scanned. Equality
+    /// predicates are assumed to match 1/ndistinct of the rows and range
+    /// predicates 1/3.
+    pub fn estimate_rows(&self, stmt: &SelectStmt) -> io::Result<RowEstimate> {
+        let planner = Planner::new(self.index_metadata());
+        let plan = planner.plan_select(stmt);
+
+        let (rows_scanned, filter_stats, candidates) = match &plan.from {
+            FromClausePlan::Single { table, scan } => {
+                let stats = self.table_stats(table)?;
+                let rows_scanned = match scan {
+                    ScanPlan::SeqScan => stats.row_count,
+                    ScanPlan::IndexScan { predicates, .. } => {
+                        let selectivity = predicates
+                            .iter()
+                            .map(|(column, op, _)| predicate_selectivity(column, *op, &stats))
+                            .product();
+                        apply_selectivity(stats.row_count, selectivity)
+                    }
+                };
+                (rows_scanned, stats.clone(), stats.row_count)
+            }
+            FromClausePlan::Join(join) => {
+                let outer = self.table_stats(&join.outer_table)?;
+                let inner = self.table_stats(&join.inner_table)?;
+                let mut combined = TableStats::new(0);
+                combined
+                    .distinct_values
+                    .extend(inner.distinct_values.clone());
+                combined
+                    .distinct_values
+                    .extend(outer.distinct_values.clone());
+
+                let mut join_selectivity = match &join.join_key {
+                    Some((outer_column, inner_column)) => outer
+                        .eq_selectivity(&outer_column.column)
+                        .min(inner.eq_selectivity(&inner_column.column)),
+                    None => 1.0,
+                };
+                if let Some(join_filter) = &join.join_filter {
+                    join_selectivity *= estimate_selectivity(join_filter, &combined);
+                }
+                let joined = apply_selectivity(
+                    outer.row_count.saturating_mul(inner.row_count),
+                    join_selectivity,
+                );
+                let rows_scanned = match join.strategy {
+                    JoinStrategy::NestedLoop {
+                        inner_has_index: true,
+                    } => outer.row_count.saturating_add(joined),
+                    _ => outer.row_count.saturating_add(inner.row_count),
+                };
+
+                combined.row_count = joined;
+                (rows_scanned, combined, joined)
+            }
+        };
+
+        let mut rows_returned = match &plan.filter {
+            Some(filter) => {
+                apply_selectivity(candidates, estimate_selectivity(filter, &filter_stats))
+            }
+            None => candidates,
+        };
+
+        let has_aggregate = matches!(
+            &stmt.columns,
+            SelectColumn::Items(items)
+                if items.iter().any(|item| matches!(item, SelectItem::Aggregate(_)))
+        );
+        if stmt.group_by.is_empty() {
+            if has_aggregate {
+                rows_returned = 1;
+            }
+        } else {
+            let groups = stmt
+                .group_by
+                .iter()
+                .map(|col| filter_stats.distinct_values.get(&col.column).copied())
+                .try_fold(1usize, |acc, nd| nd.map(|nd| acc.saturating_mul(nd)));
+            if let Some(groups) = groups {
+                rows_returned = rows_returned.min(groups);
+            }
+        }
+
+        if let Some(offset) = stmt.offset {
+            rows_returned =
+                rows_returned.saturating_sub(usize::try_from(offset).unwrap_or(usize::MAX));
+        }
+        if let Some(limit) = stmt.limit {
+            rows_returned = rows_returned.min(usize::try_from(limit).unwrap_or(usize::MAX));
+        }
+
+        Ok(RowEstimate {
+            rows_scanned,
+            rows_returned,
+        })
+    }
+
+    fn table_stats(&self, table_name: &str) -> io::Result<TableStats> {
+        let table = self.tables.get(table_name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Table '{}' does not exist", table_name),
+            )
+        })?;
+        let mut stats = TableStats::new(table.row_count());
+
+        if let Some(constraints) = self.constraints.get(table_name) {
+            for column in constraints
+                .primary_key
+                .iter()
+                .chain(constraints.unique.iter())
+            {
+                stats
+                    .distinct_values
+                    .insert(column.clone(), stats.row_count);
+            }
+        }
+
+        for index in self
+            .indexes
+            .iter()
+            .filter(|idx| idx.key.table == table_name && idx.key.columns.len() == 1)
+        {
+            let distinct = if index.is_unique {
+                Some(stats.row_count)
+            } else {
+                index.distinct_keys()
+            };
+            if let Some(distinct) = distinct {
+                stats
+                    .distinct_values
+                    .entry(index.key.columns[0].clone())
+                    .or_insert(distinct);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Check a batch of statements without executing any of it.
+    ///
+    /// Each statement is checked against the catalog as the statements
+    /// before it would leave it, with the same checks execution runs before
+    /// touching any row: tables, columns and indexes must resolve, literals
+    /// must fit their column types, and literal rows, UPDATE assignments and
+    /// constant INSERT ... SELECT items must satisfy the NOT NULL and CHECK
+    /// constraints they decide. Nothing is read from or written to table
+    /// pages or the WAL, so checks that depend on stored rows (uniqueness,
+    /// foreign key matches, arithmetic overflow) are left to execution.
+    ///
+    /// # Errors
+    /// Returns the first failing statement as an [`InvalidStatement`]
+    /// payload; the error kind is that of the underlying failure.
+    pub fn validate(&self, stmts: &[Statement]) -> io::Result<()> {
+        let mut catalog = DryRunCatalog {
+            tables: self
+                .tables
+                .iter()
+                .map(|(name, table)| (name.clone(), table.schema().clone()))
+                .collect(),
+            constraints: self.constraints.clone(),
+            indexes: self
+                .indexes
+                .iter()
+                .map(|index| (index.name.clone(), index.key.clone(), index.index_type))
+                .collect(),
+            in_transaction: self.in_transaction,
+        };
+
+        for (index, stmt) in stmts.iter().enumerate() {
+            self.dry_run(&mut catalog, stmt)
+                .map_err(|error| InvalidStatement { index, error })?;
+        }
+        Ok(())
+    }
+
+    fn dry_run(&self, catalog: &mut DryRunCatalog, stmt: &Statement) -> io::Result<()> {
+        match stmt {
+            Statement::CreateTable(create) => catalog.create_table(create),
+            Statement::DropTable(drop) => catalog.drop_table(&drop.table_name),
+            Statement::AlterTable(alter) => catalog.alter_table(alter),
+            Statement::Insert(insert) => {
+                if let InsertSource::Select(select) = &insert.source {
+                    self.check_select_ordering(select)?;
+                }
+                catalog.insert(insert)
+            }
+            Statement::Select(select) => {
+                self.check_select_ordering(select)?;
+                catalog.select(select, &[]).map(|_| ())
+            }
+            Statement::CreateIndex(create_index) => catalog.create_index(create_index),
+            Statement::DropIndex(drop_index) => catalog.drop_index(&drop_index.target),
+            Statement::Reindex(reindex) => catalog.index_position(&reindex.index_name).map(|_| ()),
+            Statement::Delete(delete) => catalog.delete(delete),
+            Statement::Update(update) => catalog.update(update),
+            Statement::Transaction(txn) => catalog.transaction(txn.command),
+            Statement::Show(show) => match &show.target {
+                ShowTarget::Indexes {
+                    table_name: Some(table_name),
+                } => catalog.schema(table_name).map(|_| ()),
+                _ => Ok(()),
+            },
+            Statement::Describe(describe) => catalog.schema(&describe.table_name).map(|_| ()),
+        }
+    }
+
+    /// Drop the index covering exactly `columns` of `table_name`.
+    ///
+    /// Equivalent to `DROP INDEX ON table (cols)`; fails if no index or more
+    /// than one index matches.
+    pub fn drop_index_on(
+        &mut self,
+        table_name: &str,
+        columns: &[&str],
+    ) -> io::Result<ExecutionResult> {
+        let columns = columns.iter().map(|col| col.to_string()).collect();
+        self.execute_drop_index(DropIndexStmt::on_columns(table_name, columns))
+    }
+
     /// Return table names and schemas currently loaded.
     pub fn list_tables(&self) -> Vec<(String, Schema)> {
         self.tables
@@ -4366,6 +7219,40 @@ impl Executor {
             .collect()
     }
 
+    /// Return the constraints declared on `table_name`, or `None` if the
+    /// table does not exist.
+    pub fn table_constraints(&self, table_name: &str) -> Option<ConstraintInfo> {
+        let schema = self.tables.get(table_name)?.schema();
+        let Some(constraints) = self.constraints.get(table_name) else {
+            return Some(ConstraintInfo::default());
+        };
+        let columns_in = |set: &HashSet<String>| {
+            schema
+                .columns()
+                .iter()
+                .map(|column| column.name())
+                .filter(|name| set.contains(*name))
+                .map(str::to_string)
+                .collect()
+        };
+
+        Some(ConstraintInfo {
+            primary_key: constraints.primary_key.clone(),
+            unique: columns_in(&constraints.unique),
+            not_null: columns_in(&constraints.not_null),
+            foreign_keys: constraints
+                .foreign_keys
+                .iter()
+                .map(|fk| ForeignKeyInfo {
+                    column: fk.column.clone(),
+                    ref_table: fk.ref_table.clone(),
+                    ref_column: fk.ref_column.clone(),
+                })
+                .collect(),
+            checks: constraints.checks.clone(),
+        })
+    }
+
     /// Report whether a transaction is active.
     pub fn in_transaction(&self) -> bool {
         self.in_transaction
@@ -4379,16 +7266,159 @@ impl Executor {
         self.txn_states.get(&txn_id).copied()
     }
 
+    /// Number of transactions whose state is still tracked
+    ///
+    /// Committed transactions are forgotten at each WAL checkpoint once no
+    /// snapshot can see them as running, so this stays bounded in a
+    /// long-lived process that flushes regularly.
+    pub fn tracked_txn_count(&self) -> usize {
+        self.txn_states.len()
+    }
+
     pub fn current_txn_state(&self) -> Option<TxnState> {
         self.current_txn_id
             .and_then(|txn_id| self.transaction_state(txn_id))
     }
 
+    /// `table_name` together with what the running statement may see in it.
+    /// The visibility borrows the snapshot and transaction states rather than
+    /// copying them, so callers re-fetch both instead of holding them across
+    /// calls that need the whole executor.
+    fn table_with_visibility(
+        &mut self,
+        table_name: &str,
+    ) -> Option<(&mut HeapTable, RowVisibility<'_>)> {
+        let snapshot = match &self.as_of_snapshot {
+            Some(snapshot) => Some(snapshot),
+            None => self
+                .current_txn_id
+                .and_then(|txn_id| self.snapshots.get(&txn_id)),
+        };
+        let visibility = RowVisibility {
+            snapshot,
+            check_every_row: self.as_of_snapshot.is_some(),
+            current_txn_id: self.current_txn_id,
+            txn_states: &self.txn_states,
+        };
+        let table = self.tables.get_mut(table_name)?;
+        Some((table, visibility))
+    }
+
     pub fn current_snapshot(&self) -> Option<Snapshot> {
+        if let Some(snapshot) = &self.as_of_snapshot {
+            return Some(snapshot.clone());
+        }
         let txn_id = self.current_txn_id?;
         self.snapshots.get(&txn_id).cloned()
     }
 
+    /// Run `stmt` against the database as it stood when transaction `txn_id`
+    /// finished: its own committed changes and those of every transaction
+    /// that committed before it are visible, whatever their ids. Transactions
+    /// that committed later, aborted or are still running are not. For a
+    /// transaction that is still running, the changes committed so far are
+    /// visible but its own are not.
+    ///
+    /// Commit order is only known for transactions since the last checkpoint.
+    /// Once a checkpoint has forgotten `txn_id`, the query falls back to id
+    /// order and sees the transactions that started before it; transactions
+    /// forgotten by a checkpoint always count as committed.
+    ///
+    /// No transaction is started and nothing is logged. Versions already
+    /// reclaimed by VACUUM cannot be recovered, so history older than the
+    /// last vacuum may be incomplete.
+    pub fn query_as_of(&mut self, txn_id: TxnId, stmt: SelectStmt) -> io::Result<ExecutionResult> {
+        if txn_id >= self.next_txn_id {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Transaction {} has not started", txn_id),
+            ));
+        }
+        let snapshot = if self.txn_states.contains_key(&txn_id) {
+            let finished = self
+                .finish_order
+                .get(&txn_id)
+                .copied()
+                .unwrap_or(self.next_finish);
+            let active: HashSet<TxnId> = self
+                .txn_states
+                .keys()
+                .filter(|id| self.finish_order.get(id).is_none_or(|&at| at > finished))
+                .copied()
+                .collect();
+            Snapshot {
+                xmin: active.iter().copied().min().unwrap_or(self.next_txn_id),
+                xmax: self.next_txn_id,
+                active,
+            }
+        } else {
+            let active: HashSet<TxnId> = self
+                .txn_states
+                .iter()
+                .filter(|(id, state)| **id < txn_id && **state == TxnState::Active)
+                .map(|(id, _)| *id)
+                .collect();
+            Snapshot {
+                xmin: active.iter().copied().min().unwrap_or(txn_id),
+                xmax: txn_id.saturating_add(1),
+                active,
+            }
+        };
+
+        let saved_txn_id = self.current_txn_id.take();
+        self.as_of_snapshot = Some(snapshot);
+        let result = self.execute_select(stmt);
+        self.as_of_snapshot = None;
+        self.current_txn_id = saved_txn_id;
+        result
+    }
+
+    /// Row changes made by transactions after `txn_id`, as a logical change
+    /// feed
+    ///
+    /// Returns the Insert, Update and Delete records of every committed
+    /// transaction with a larger id, grouped by transaction in commit order.
+    /// Transactions that rolled back, were aborted by recovery or are still
+    /// running contribute nothing; a running one shows up in a later call
+    /// once it commits. Pass the largest id seen so far to resume.
+    ///
+    /// # Errors
+    /// Returns `NotFound` if a checkpoint has already discarded some of the
+    /// requested transactions; the consumer must then resynchronize from a
+    /// copy of the tables.
+    pub fn wal_changes_since(&self, txn_id: TxnId) -> io::Result<Vec<WalRecord>> {
+        if txn_id.saturating_add(1) < self.wal_horizon {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "Changes after transaction {} are no longer available; the WAL only covers transactions from {}",
+                    txn_id, self.wal_horizon
+                ),
+            ));
+        }
+
+        let mut pending: HashMap<TxnId, Vec<WalRecord>> = HashMap::new();
+        let mut changes = Vec::new();
+        for record in self.wal.read_all()? {
+            if record.txn_id() <= txn_id {
+                continue;
+            }
+            match record {
+                WalRecord::Begin { .. } => {}
+                WalRecord::Commit { txn_id } => {
+                    changes.extend(pending.remove(&txn_id).unwrap_or_default());
+                }
+                WalRecord::Rollback { txn_id } => {
+                    pending.remove(&txn_id);
+                }
+                WalRecord::Insert { .. } | WalRecord::Update { .. } | WalRecord::Delete { .. } => {
+                    pending.entry(record.txn_id()).or_default().push(record);
+                }
+            }
+        }
+        Ok(changes)
+    }
+
     /// Return index metadata currently loaded.
     pub fn list_indexes(&self) -> Vec<(String, String, Vec<String>, IndexType, bool)> {
         self.indexes
@@ -4418,6 +7448,11 @@ impl Executor {
             }
             TxnState::Committed | TxnState::Aborted => {
                 self.active_txns.remove(&txn_id);
+                let next_finish = &mut self.next_finish;
+                self.finish_order.entry(txn_id).or_insert_with(|| {
+                    *next_finish += 1;
+                    *next_finish - 1
+                });
             }
         }
         self.txn_states.insert(txn_id, state);
@@ -4445,7 +7480,57 @@ impl Executor {
             ));
         }
 
-        self.wal.truncate()
+        // Record the horizon first, so a crash before the truncate only
+        // makes the change feed more cautious
+        self.wal_horizon = self.next_txn_id;
+        if let Some(db_path) = &self.db_path {
+            fs::write(db_path.join("wal.meta"), format!("{}\n", self.wal_horizon))?;
+        }
+        self.wal.truncate()?;
+        self.prune_txn_states();
+        Ok(())
+    }
+
+    /// Forget committed transactions the WAL no longer covers and that are
+    /// older than every open snapshot
+    ///
+    /// A missing state reads as committed, so no visibility decision
+    /// changes. Aborted transactions are kept: rows they inserted or deleted
+    /// may still carry their id.
+    fn prune_txn_states(&mut self) {
+        let horizon = self
+            .snapshots
+            .values()
+            .chain(self.as_of_snapshot.as_ref())
+            .map(|snapshot| snapshot.xmin)
+            .fold(self.wal_horizon, TxnId::min);
+        self.txn_states
+            .retain(|&txn_id, state| txn_id >= horizon || *state != TxnState::Committed);
+        let txn_states = &self.txn_states;
+        self.finish_order
+            .retain(|txn_id, _| txn_states.contains_key(txn_id));
+    }
+
+    /// Restore the checkpoint horizon, so transaction ids keep increasing
+    /// after the WAL that recorded them is gone
+    fn load_wal_horizon(&mut self) -> io::Result<()> {
+        let Some(db_path) = &self.db_path else {
+            return Ok(());
+        };
+        let path = db_path.join("wal.meta");
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let data = fs::read_to_string(&path)?;
+        self.wal_horizon = data.trim().parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid WAL horizon in {}", path.display()),
+            )
+        })?;
+        self.next_txn_id = self.next_txn_id.max(self.wal_horizon);
+        Ok(())
     }
 
     fn undo_transaction(&mut self) -> io::Result<()> {
@@ -4532,6 +7617,7 @@ impl Executor {
         }
 
         let mut recovered_states: HashMap<TxnId, TxnState> = HashMap::new();
+        let mut finished: Vec<TxnId> = Vec::new();
         let mut max_txn_id = 0;
         for record in &records {
             let txn_id = match record {
@@ -4549,24 +7635,33 @@ impl Executor {
                 }
                 WalRecord::Commit { txn_id } => {
                     recovered_states.insert(*txn_id, TxnState::Committed);
+                    finished.push(*txn_id);
                 }
                 WalRecord::Rollback { txn_id } => {
                     recovered_states.insert(*txn_id, TxnState::Aborted);
+                    finished.push(*txn_id);
                 }
                 _ => {}
             }
         }
 
-        for state in recovered_states.values_mut() {
-            if *state == TxnState::Active {
-                *state = TxnState::Aborted;
-            }
+        // Transactions cut off by the crash finish last, in id order
+        let mut unfinished: Vec<TxnId> = recovered_states
+            .iter()
+            .filter(|(_, state)| **state == TxnState::Active)
+            .map(|(txn_id, _)| *txn_id)
+            .collect();
+        unfinished.sort_unstable();
+        for txn_id in &unfinished {
+            recovered_states.insert(*txn_id, TxnState::Aborted);
         }
+        finished.extend(unfinished);
 
         self.active_txns.clear();
         self.txn_states.clear();
-        for (txn_id, state) in recovered_states {
-            self.set_txn_state(txn_id, state);
+        self.finish_order.clear();
+        for txn_id in finished {
+            self.set_txn_state(txn_id, recovered_states[&txn_id]);
         }
         if max_txn_id > 0 {
             self.next_txn_id = max_txn_id.saturating_add(1);
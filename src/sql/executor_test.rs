@@ -2,10 +2,12 @@ mod tests {
     use crate::{
         serialization::RowMetadata,
         sql::{
-            ExecutionResult, Executor, IndexType, TransactionCommand, TxnState, parser::parse_sql,
+            ConflictPolicy, ExecutionResult, Executor, IndexType, InvalidStatement, Statement,
+            TransactionCommand, TxnState, WriteConflict, parse_sql_statements, parser::parse_sql,
         },
-        table::RowId,
+        table::{RowId, TableScan},
         types::Value,
+        wal::{WalFile, WalRecord},
     };
     use tempfile::TempDir;
 
@@ -802,17 +804,18 @@ mod tests {
     }
 
     #[test]
-    fn test_create_index_varchar_fails() {
+    fn test_create_index_boolean_fails() {
         let temp_dir = TempDir::new().unwrap();
         let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
 
         // Create table
         executor
-            .execute(parse_sql("CREATE TABLE users (id INTEGER, name VARCHAR)").unwrap())
+            .execute(parse_sql("CREATE TABLE users (id INTEGER, active BOOLEAN)").unwrap())
             .unwrap();
 
-        // Try to create index on VARCHAR column (should fail)
-        let result = executor.execute(parse_sql("CREATE INDEX idx_name ON users(name)").unwrap());
+        // Try to create index on BOOLEAN column (should fail)
+        let result =
+            executor.execute(parse_sql("CREATE INDEX idx_active ON users(active)").unwrap());
         assert!(result.is_err());
     }
 
@@ -987,6 +990,102 @@ mod tests {
         }
     }
 
+    fn count_rows(executor: &mut Executor, table: &str) -> Value {
+        let stmt = parse_sql(&format!("SELECT COUNT(*) FROM {}", table)).unwrap();
+        match executor.execute(stmt).unwrap() {
+            ExecutionResult::Select { rows, .. } => rows[0][0].clone(),
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_execute_batch_commits_or_rolls_back_as_a_whole() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+        executor
+            .execute(
+                parse_sql("CREATE TABLE users (id INTEGER PRIMARY KEY, name VARCHAR)").unwrap(),
+            )
+            .unwrap();
+
+        let batch = parse_sql_statements(
+            "INSERT INTO users VALUES (1, 'Alice'); \
+             INSERT INTO users VALUES (2, 'Bob'); \
+             SELECT COUNT(*) FROM users",
+        )
+        .unwrap();
+        let results = executor.execute_batch(batch).unwrap();
+        assert_eq!(results.len(), 3);
+        match &results[2] {
+            ExecutionResult::Select { rows, .. } => assert_eq!(rows[0][0], Value::Integer(2)),
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+        assert!(!executor.in_transaction());
+
+        // The duplicate key fails the third statement and undoes the first two
+        let batch = parse_sql_statements(
+            "INSERT INTO users VALUES (3, 'Carol'); \
+             UPDATE users SET name = 'Alicia' WHERE id = 1; \
+             INSERT INTO users VALUES (2, 'Bobby'); \
+             INSERT INTO users VALUES (4, 'Dave')",
+        )
+        .unwrap();
+        let err = executor.execute_batch(batch).unwrap_err();
+        let invalid = err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<InvalidStatement>())
+            .expect("InvalidStatement payload");
+        assert_eq!(invalid.index, 2);
+        assert!(!executor.in_transaction());
+        assert_eq!(count_rows(&mut executor, "users"), Value::Integer(2));
+        match executor
+            .execute(parse_sql("SELECT name FROM users WHERE id = 1").unwrap())
+            .unwrap()
+        {
+            ExecutionResult::Select { rows, .. } => {
+                assert_eq!(rows, vec![vec![Value::String("Alice".to_string())]])
+            }
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+
+        // The rolled back key is free again
+        executor
+            .execute_batch(parse_sql_statements("INSERT INTO users VALUES (3, 'Carol')").unwrap())
+            .unwrap();
+        assert_eq!(count_rows(&mut executor, "users"), Value::Integer(3));
+    }
+
+    #[test]
+    fn test_execute_batch_inside_a_transaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+        executor
+            .execute(parse_sql("CREATE TABLE t (id INTEGER)").unwrap())
+            .unwrap();
+
+        let err = executor
+            .execute_batch(parse_sql_statements("INSERT INTO t VALUES (1); COMMIT").unwrap())
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(count_rows(&mut executor, "t"), Value::Integer(0));
+
+        // The batch joins the open transaction, which survives a failure
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        executor
+            .execute_batch(parse_sql_statements("INSERT INTO t VALUES (1)").unwrap())
+            .unwrap();
+        executor
+            .execute_batch(
+                parse_sql_statements("INSERT INTO t VALUES (2); INSERT INTO t VALUES ('x')")
+                    .unwrap(),
+            )
+            .unwrap_err();
+        assert!(executor.in_transaction());
+        assert_eq!(count_rows(&mut executor, "t"), Value::Integer(2));
+        executor.execute(parse_sql("ROLLBACK").unwrap()).unwrap();
+        assert_eq!(count_rows(&mut executor, "t"), Value::Integer(0));
+    }
+
     #[test]
     fn test_begin_sets_transaction_state_and_rejects_nested() {
         let temp_dir = TempDir::new().unwrap();
@@ -1051,6 +1150,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_checkpoint_prunes_committed_transaction_states() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+
+        executor
+            .execute(parse_sql("CREATE TABLE users (id INTEGER, name VARCHAR)").unwrap())
+            .unwrap();
+        for id in 0..20 {
+            executor
+                .execute(parse_sql(&format!("INSERT INTO users VALUES ({}, 'u')", id)).unwrap())
+                .unwrap();
+        }
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        executor
+            .execute(parse_sql("DELETE FROM users WHERE id = 0").unwrap())
+            .unwrap();
+        executor
+            .execute(parse_sql("UPDATE users SET name = 'x' WHERE id = 1").unwrap())
+            .unwrap();
+        let aborted = executor.current_txn_id().expect("txn id");
+        executor.execute(parse_sql("ROLLBACK").unwrap()).unwrap();
+        executor
+            .execute(parse_sql("DELETE FROM users WHERE id = 2").unwrap())
+            .unwrap();
+        let committed = aborted + 1;
+        assert_eq!(
+            executor.transaction_state(committed),
+            Some(TxnState::Committed)
+        );
+        assert_eq!(executor.tracked_txn_count(), 22);
+
+        executor.flush_all().unwrap();
+        assert_eq!(executor.tracked_txn_count(), 1);
+        assert_eq!(executor.transaction_state(committed), None);
+        assert_eq!(executor.transaction_state(aborted), Some(TxnState::Aborted));
+
+        // Pruned writers read as committed and the rolled-back delete stays
+        // undone, inside a transaction and out
+        let ids = |executor: &mut Executor| match executor
+            .execute(parse_sql("SELECT id FROM users ORDER BY id").unwrap())
+            .unwrap()
+        {
+            ExecutionResult::Select { rows, .. } => rows,
+            other => panic!("Expected Select result, got: {:?}", other),
+        };
+        let expected: Vec<Vec<Value>> = (0..20)
+            .filter(|&id| id != 2)
+            .map(|id| vec![Value::Integer(id)])
+            .collect();
+        assert_eq!(ids(&mut executor), expected);
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        assert_eq!(ids(&mut executor), expected);
+        executor.execute(parse_sql("COMMIT").unwrap()).unwrap();
+    }
+
     #[test]
     fn test_snapshot_visibility_skips_future_xmin() {
         let temp_dir = TempDir::new().unwrap();
@@ -1270,6 +1425,12 @@ mod tests {
             .execute(parse_sql("DELETE FROM users WHERE id = 10").unwrap())
             .unwrap_err();
         assert!(err.to_string().contains("Write conflict"));
+        let conflict = err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<WriteConflict>())
+            .expect("write conflict payload");
+        assert_eq!(conflict.txn_id, snapshot.xmax);
+        assert!(!conflict.retryable);
         assert!(!executor.in_transaction());
         assert_eq!(executor.transaction_state(txn_id), Some(TxnState::Aborted));
     }
@@ -1307,6 +1468,60 @@ mod tests {
         assert_eq!(executor.transaction_state(txn_id), Some(TxnState::Aborted));
     }
 
+    #[test]
+    fn test_write_conflict_retry_policy_keeps_transaction_open() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+        executor.set_conflict_policy(ConflictPolicy::Retry);
+
+        executor
+            .execute(parse_sql("CREATE TABLE users (id INTEGER, name VARCHAR)").unwrap())
+            .unwrap();
+        executor
+            .execute(parse_sql("INSERT INTO users VALUES (1, 'Alice')").unwrap())
+            .unwrap();
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        let txn_id = executor.current_txn_id().expect("txn id");
+        let snapshot = executor.current_snapshot().expect("snapshot");
+
+        let conflict_row_id = {
+            let table = executor.get_table("users").expect("table");
+            table
+                .insert_with_metadata(
+                    &[Value::Integer(12), Value::String("Conflict".to_string())],
+                    RowMetadata {
+                        xmin: 0,
+                        xmax: snapshot.xmax,
+                    },
+                )
+                .unwrap()
+        };
+
+        let err = executor
+            .execute(parse_sql("UPDATE users SET name = 'X' WHERE id = 12").unwrap())
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Interrupted);
+        let conflict = err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<WriteConflict>())
+            .expect("write conflict payload");
+        assert_eq!(conflict.row_id, conflict_row_id);
+        assert_eq!(conflict.txn_id, snapshot.xmax);
+        assert!(conflict.retryable);
+        assert!(executor.in_transaction());
+        assert_eq!(executor.transaction_state(txn_id), Some(TxnState::Active));
+
+        // The failed statement wrote nothing, so the transaction carries on
+        executor
+            .execute(parse_sql("DELETE FROM users WHERE id = 1").unwrap())
+            .unwrap();
+        executor.execute(parse_sql("COMMIT").unwrap()).unwrap();
+        assert_eq!(
+            executor.transaction_state(txn_id),
+            Some(TxnState::Committed)
+        );
+    }
+
     #[test]
     fn test_vacuum_removes_dead_versions() {
         let temp_dir = TempDir::new().unwrap();
@@ -1374,6 +1589,346 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_vacuum_marks_pages_all_visible() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+
+        executor
+            .execute(parse_sql("CREATE TABLE users (id INTEGER, name VARCHAR)").unwrap())
+            .unwrap();
+        executor
+            .execute(parse_sql("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob')").unwrap())
+            .unwrap();
+        // The update leaves a dead version and a row stamped with its
+        // transaction on the page
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        executor
+            .execute(parse_sql("UPDATE users SET name = 'Carol' WHERE id = 2").unwrap())
+            .unwrap();
+        executor.execute(parse_sql("COMMIT").unwrap()).unwrap();
+
+        let page_id = {
+            let table = executor.get_table("users").expect("table");
+            let mut scan = TableScan::new(table);
+            let (row_id, _) = scan.next().unwrap().expect("row");
+            assert!(!scan.on_all_visible_page());
+            row_id.page_id()
+        };
+
+        assert_eq!(executor.vacuum_table("users").unwrap(), 1);
+        let table = executor.get_table("users").expect("table");
+        assert!(table.is_page_all_visible(page_id).unwrap());
+
+        // An uncommitted delete clears the flag, so the deleting transaction
+        // no longer sees the row while a rollback brings it back
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        executor
+            .execute(parse_sql("DELETE FROM users WHERE id = 1").unwrap())
+            .unwrap();
+        let table = executor.get_table("users").expect("table");
+        assert!(!table.is_page_all_visible(page_id).unwrap());
+        match executor
+            .execute(parse_sql("SELECT id FROM users").unwrap())
+            .unwrap()
+        {
+            ExecutionResult::Select { rows, .. } => {
+                assert_eq!(rows, vec![vec![Value::Integer(2)]]);
+            }
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+        executor.execute(parse_sql("ROLLBACK").unwrap()).unwrap();
+
+        match executor
+            .execute(parse_sql("SELECT id FROM users").unwrap())
+            .unwrap()
+        {
+            ExecutionResult::Select { rows, .. } => assert_eq!(rows.len(), 2),
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+    }
+
+    fn as_of(executor: &mut Executor, txn_id: u64, sql: &str) -> Vec<Vec<Value>> {
+        let Statement::Select(stmt) = parse_sql(sql).unwrap() else {
+            panic!("Expected SELECT");
+        };
+        match executor.query_as_of(txn_id, stmt).unwrap() {
+            ExecutionResult::Select { rows, .. } => rows,
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_query_as_of_shows_historical_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+
+        executor
+            .execute(parse_sql("CREATE TABLE users (id INTEGER, name VARCHAR)").unwrap())
+            .unwrap();
+        executor
+            .execute(parse_sql("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob')").unwrap())
+            .unwrap();
+
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        let rename = executor.current_txn_id().unwrap();
+        executor
+            .execute(parse_sql("UPDATE users SET name = 'Carol' WHERE id = 2").unwrap())
+            .unwrap();
+        executor.execute(parse_sql("COMMIT").unwrap()).unwrap();
+
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        let delete = executor.current_txn_id().unwrap();
+        executor
+            .execute(parse_sql("DELETE FROM users WHERE id = 1").unwrap())
+            .unwrap();
+        executor.execute(parse_sql("COMMIT").unwrap()).unwrap();
+
+        let query = "SELECT id, name FROM users ORDER BY id";
+        assert_eq!(
+            as_of(&mut executor, rename - 1, query),
+            vec![
+                vec![Value::Integer(1), Value::String("Alice".to_string())],
+                vec![Value::Integer(2), Value::String("Bob".to_string())],
+            ]
+        );
+        assert_eq!(
+            as_of(&mut executor, rename, query),
+            vec![
+                vec![Value::Integer(1), Value::String("Alice".to_string())],
+                vec![Value::Integer(2), Value::String("Carol".to_string())],
+            ]
+        );
+        assert_eq!(
+            as_of(&mut executor, delete, query),
+            vec![vec![Value::Integer(2), Value::String("Carol".to_string())]]
+        );
+
+        // Historical reads neither start a transaction nor consume an id
+        assert_eq!(executor.current_txn_id(), None);
+        assert!(!executor.in_transaction());
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        assert_eq!(executor.current_txn_id(), Some(delete + 1));
+
+        // An open transaction keeps its own view once the historical read ends
+        executor
+            .execute(parse_sql("DELETE FROM users WHERE id = 2").unwrap())
+            .unwrap();
+        assert_eq!(as_of(&mut executor, delete, query).len(), 1);
+        assert_eq!(executor.current_txn_id(), Some(delete + 1));
+        match executor.execute(parse_sql(query).unwrap()).unwrap() {
+            ExecutionResult::Select { rows, .. } => assert!(rows.is_empty()),
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+        executor.execute(parse_sql("ROLLBACK").unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_query_as_of_follows_commit_order() {
+        let temp_dir = TempDir::new().unwrap();
+        {
+            let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+            executor
+                .execute(parse_sql("CREATE TABLE users (id INTEGER)").unwrap())
+                .unwrap();
+            let table = executor.get_table("users").expect("table");
+            for (id, xmin) in [(1, 100), (2, 101)] {
+                table
+                    .insert_with_metadata(&[Value::Integer(id)], RowMetadata { xmin, xmax: 0 })
+                    .unwrap();
+            }
+            executor.flush_all().unwrap();
+        }
+
+        // Transaction 101 starts after 100 but commits first
+        let wal = WalFile::new(temp_dir.path().join("wal.log"));
+        for record in [
+            WalRecord::Begin { txn_id: 100 },
+            WalRecord::Begin { txn_id: 101 },
+            WalRecord::Commit { txn_id: 101 },
+            WalRecord::Commit { txn_id: 100 },
+        ] {
+            wal.append(&record).unwrap();
+        }
+
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+        let query = "SELECT id FROM users ORDER BY id";
+        assert_eq!(
+            as_of(&mut executor, 101, query),
+            vec![vec![Value::Integer(2)]]
+        );
+        assert_eq!(
+            as_of(&mut executor, 100, query),
+            vec![vec![Value::Integer(1)], vec![Value::Integer(2)]]
+        );
+    }
+
+    #[test]
+    fn test_query_as_of_checks_rows_on_all_visible_pages() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+
+        executor
+            .execute(parse_sql("CREATE TABLE users (id INTEGER, name VARCHAR)").unwrap())
+            .unwrap();
+        executor
+            .execute(parse_sql("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob')").unwrap())
+            .unwrap();
+        executor.execute(parse_sql("BEGIN").unwrap()).unwrap();
+        let rename = executor.current_txn_id().unwrap();
+        executor
+            .execute(parse_sql("UPDATE users SET name = 'Carol' WHERE id = 2").unwrap())
+            .unwrap();
+        executor.execute(parse_sql("COMMIT").unwrap()).unwrap();
+        assert_eq!(executor.vacuum_table("users").unwrap(), 1);
+
+        // The page is flagged all-visible, but the rewritten row is still
+        // newer than a snapshot taken before the update
+        assert_eq!(
+            as_of(&mut executor, rename - 1, "SELECT id FROM users"),
+            vec![vec![Value::Integer(1)]]
+        );
+        assert_eq!(
+            as_of(&mut executor, rename, "SELECT id FROM users ORDER BY id").len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_query_as_of_rejects_future_transactions() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+
+        executor
+            .execute(parse_sql("CREATE TABLE users (id INTEGER)").unwrap())
+            .unwrap();
+        let Statement::Select(stmt) = parse_sql("SELECT id FROM users").unwrap() else {
+            panic!("Expected SELECT");
+        };
+        let err = executor.query_as_of(1_000, stmt).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_vacuum_truncates_empty_pages() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+
+        executor
+            .execute(parse_sql("CREATE TABLE logs (id INTEGER, body VARCHAR)").unwrap())
+            .unwrap();
+        let body = "x".repeat(200);
+        for i in 0..100 {
+            let sql = format!("INSERT INTO logs VALUES ({}, '{}')", i, body);
+            executor.execute(parse_sql(&sql).unwrap()).unwrap();
+        }
+        executor
+            .execute(parse_sql("DELETE FROM logs WHERE id >= 10").unwrap())
+            .unwrap();
+        executor.flush_all().unwrap();
+
+        let table_path = temp_dir.path().join("logs.db");
+        let before = std::fs::metadata(&table_path).unwrap().len();
+
+        let removed = executor.vacuum_table("logs").unwrap();
+        assert_eq!(removed, 90);
+        executor.flush_all().unwrap();
+
+        let after = std::fs::metadata(&table_path).unwrap().len();
+        assert!(after < before);
+
+        let result = executor
+            .execute(parse_sql("SELECT id FROM logs").unwrap())
+            .unwrap();
+        match result {
+            ExecutionResult::Select { rows, .. } => assert_eq!(rows.len(), 10),
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_estimate_rows_without_scanning() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+
+        executor
+            .execute(parse_sql("CREATE TABLE users (id INTEGER PRIMARY KEY, age INTEGER)").unwrap())
+            .unwrap();
+        for i in 0..300 {
+            let sql = format!("INSERT INTO users VALUES ({}, {})", i, i % 60);
+            executor.execute(parse_sql(&sql).unwrap()).unwrap();
+        }
+
+        let estimate = |executor: &Executor, sql: &str| match parse_sql(sql).unwrap() {
+            Statement::Select(stmt) => executor.estimate_rows(&stmt).unwrap(),
+            other => panic!("Expected Select statement, got: {:?}", other),
+        };
+
+        let all = estimate(&executor, "SELECT * FROM users");
+        assert_eq!(all.rows_scanned, 300);
+        assert_eq!(all.rows_returned, 300);
+
+        let by_key = estimate(&executor, "SELECT * FROM users WHERE id = 5");
+        assert_eq!(by_key.rows_returned, 1);
+
+        let range = estimate(&executor, "SELECT * FROM users WHERE age > 30");
+        assert_eq!(range.rows_scanned, 300);
+        assert_eq!(range.rows_returned, 100);
+
+        let limited = estimate(&executor, "SELECT * FROM users WHERE age > 30 LIMIT 10");
+        assert_eq!(limited.rows_returned, 10);
+
+        let count = estimate(&executor, "SELECT COUNT(*) FROM users");
+        assert_eq!(count.rows_returned, 1);
+
+        executor
+            .execute(parse_sql("CREATE INDEX idx_age ON users USING HASH (age)").unwrap())
+            .unwrap();
+        let indexed = estimate(&executor, "SELECT * FROM users WHERE age = 12");
+        assert_eq!(indexed.rows_scanned, 5);
+        assert_eq!(indexed.rows_returned, 5);
+    }
+
+    #[test]
+    fn test_strict_ordering_requires_order_by() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+        executor
+            .execute(parse_sql("CREATE TABLE t (id INTEGER)").unwrap())
+            .unwrap();
+        executor
+            .execute(parse_sql("INSERT INTO t VALUES (1), (2)").unwrap())
+            .unwrap();
+
+        executor.set_strict_ordering(true);
+        let err = executor
+            .execute(parse_sql("SELECT id FROM t LIMIT 1").unwrap())
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(
+            executor
+                .execute(parse_sql("SELECT id FROM t ORDER BY id ASC LIMIT 1").unwrap())
+                .is_ok()
+        );
+
+        // Internal EXISTS probes page their subquery but are not user queries
+        assert!(
+            executor
+                .execute(
+                    parse_sql("SELECT id FROM t WHERE EXISTS (SELECT 1 FROM t) ORDER BY id ASC")
+                        .unwrap()
+                )
+                .is_ok()
+        );
+
+        executor.set_strict_ordering(false);
+        assert!(
+            executor
+                .execute(parse_sql("SELECT id FROM t LIMIT 1").unwrap())
+                .is_ok()
+        );
+    }
+
     #[test]
     fn test_commit_clears_transaction_state() {
         let temp_dir = TempDir::new().unwrap();
@@ -1417,4 +1972,284 @@ mod tests {
             .unwrap_err();
         assert!(err.to_string().contains("No active transaction"));
     }
+
+    /// Run `sql`, which must succeed
+    fn run(executor: &mut Executor, sql: &str) -> ExecutionResult {
+        executor.execute(parse_sql(sql).unwrap()).unwrap()
+    }
+
+    /// Page fetches on `table` while running `sql`
+    fn fetches_during(executor: &mut Executor, table: &str, sql: &str) -> u64 {
+        let before = executor
+            .get_table(table)
+            .unwrap()
+            .buffer_pool_mut()
+            .fetch_count();
+        executor.execute(parse_sql(sql).unwrap()).unwrap();
+        executor
+            .get_table(table)
+            .unwrap()
+            .buffer_pool_mut()
+            .fetch_count()
+            - before
+    }
+
+    #[test]
+    fn test_foreign_key_checks_use_indexes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+        let run = |executor: &mut Executor, sql: &str| {
+            executor.execute(parse_sql(sql).unwrap()).unwrap();
+        };
+
+        run(
+            &mut executor,
+            "CREATE TABLE parent (id INTEGER, name VARCHAR)",
+        );
+        run(
+            &mut executor,
+            "CREATE TABLE child (id INTEGER, parent_id INTEGER REFERENCES parent(id))",
+        );
+        let parents = (1..=1000)
+            .map(|i| format!("({}, 'parent number {}')", i, i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        run(
+            &mut executor,
+            &format!("INSERT INTO parent VALUES {}", parents),
+        );
+        let children = (1..=1000)
+            .map(|i| format!("({}, {})", i, i % 500 + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        run(
+            &mut executor,
+            &format!("INSERT INTO child VALUES {}", children),
+        );
+
+        // Without indexes each check scans the other table
+        let scanned_insert = fetches_during(
+            &mut executor,
+            "parent",
+            "INSERT INTO child VALUES (2000, 999)",
+        );
+        let scanned_delete =
+            fetches_during(&mut executor, "child", "DELETE FROM parent WHERE id = 1000");
+
+        run(
+            &mut executor,
+            "CREATE UNIQUE INDEX idx_parent ON parent(id)",
+        );
+        run(&mut executor, "CREATE INDEX idx_child ON child(parent_id)");
+        let indexed_insert = fetches_during(
+            &mut executor,
+            "parent",
+            "INSERT INTO child VALUES (2001, 998)",
+        );
+        let indexed_delete =
+            fetches_during(&mut executor, "child", "DELETE FROM parent WHERE id = 997");
+
+        assert!(
+            indexed_insert * 4 < scanned_insert,
+            "insert: {} fetches with an index, {} without",
+            indexed_insert,
+            scanned_insert
+        );
+        assert!(
+            indexed_delete * 4 < scanned_delete,
+            "delete: {} fetches with an index, {} without",
+            indexed_delete,
+            scanned_delete
+        );
+
+        // The indexed checks reach the same verdicts as the scans
+        let err = executor
+            .execute(parse_sql("INSERT INTO child VALUES (3000, 1000)").unwrap())
+            .unwrap_err();
+        assert!(err.to_string().contains("Foreign key violation"));
+        let err = executor
+            .execute(parse_sql("DELETE FROM parent WHERE id = 2").unwrap())
+            .unwrap_err();
+        assert!(err.to_string().contains("Foreign key"));
+        run(&mut executor, "DELETE FROM child WHERE parent_id = 2");
+        run(&mut executor, "DELETE FROM parent WHERE id = 2");
+    }
+
+    #[test]
+    fn test_merge_join_spills_sorted_runs_to_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+
+        run(
+            &mut executor,
+            "CREATE TABLE users (id INTEGER, name VARCHAR)",
+        );
+        run(
+            &mut executor,
+            "CREATE TABLE orders (id INTEGER, user_id INTEGER)",
+        );
+        for id in 0..40 {
+            run(
+                &mut executor,
+                &format!(
+                    "INSERT INTO users VALUES ({}, 'user{}')",
+                    (id * 17) % 40,
+                    id
+                ),
+            );
+        }
+        for id in 0..120 {
+            run(
+                &mut executor,
+                &format!("INSERT INTO orders VALUES ({}, {})", id, (id * 7) % 45),
+            );
+        }
+
+        let query =
+            "SELECT users.name, orders.id FROM users JOIN orders ON users.id = orders.user_id";
+        let ExecutionResult::Select {
+            rows: in_memory,
+            plan,
+            ..
+        } = run(&mut executor, query)
+        else {
+            panic!("Expected Select result");
+        };
+        assert!(plan.iter().any(|step| step.starts_with("Merge join")));
+        assert!(!plan.iter().any(|step| step.contains("external")));
+        assert_eq!(in_memory.len(), 107);
+
+        executor.set_sort_run_size(7);
+        let ExecutionResult::Select { rows, plan, .. } = run(&mut executor, query) else {
+            panic!("Expected Select result");
+        };
+        assert!(
+            plan.contains(&"Sort users on join key (external, 6 runs spilled to disk)".to_string())
+        );
+        assert_eq!(rows, in_memory);
+
+        // Spill files are removed once the join is done
+        let leftovers = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    == Some("tmp")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn test_tables_share_a_small_buffer_pool() {
+        let temp_dir = TempDir::new().unwrap();
+        {
+            // Four frames in total, for two tables that outgrow them
+            let mut executor = Executor::new(temp_dir.path(), 4).unwrap();
+            run(
+                &mut executor,
+                "CREATE TABLE users (id INTEGER, name VARCHAR)",
+            );
+            run(
+                &mut executor,
+                "CREATE TABLE orders (id INTEGER, user_id INTEGER, note VARCHAR)",
+            );
+            for id in 0..150 {
+                run(
+                    &mut executor,
+                    &format!("INSERT INTO users VALUES ({}, 'user{:0>200}')", id, id),
+                );
+                run(
+                    &mut executor,
+                    &format!(
+                        "INSERT INTO orders VALUES ({}, {}, '{:0>200}')",
+                        id,
+                        id % 50,
+                        id
+                    ),
+                );
+            }
+            executor.flush_all().unwrap();
+        }
+
+        let mut executor = Executor::new(temp_dir.path(), 4).unwrap();
+        let ExecutionResult::Select { rows, .. } = run(
+            &mut executor,
+            "SELECT COUNT(*) FROM users JOIN orders ON users.id = orders.user_id",
+        ) else {
+            panic!("Expected Select result");
+        };
+        assert_eq!(rows, vec![vec![Value::Integer(150)]]);
+    }
+
+    #[test]
+    fn test_in_memory_executor() {
+        let mut executor = Executor::in_memory();
+
+        run(
+            &mut executor,
+            "CREATE TABLE users (id INTEGER, name VARCHAR)",
+        );
+        run(
+            &mut executor,
+            "CREATE TABLE orders (id INTEGER, user_id INTEGER)",
+        );
+        for id in 0..20 {
+            run(
+                &mut executor,
+                &format!("INSERT INTO users VALUES ({}, 'user{}')", id, id),
+            );
+        }
+        for id in 0..20 {
+            run(
+                &mut executor,
+                &format!("INSERT INTO orders VALUES ({}, {})", id, (id * 3) % 20),
+            );
+        }
+        run(&mut executor, "BEGIN");
+        run(&mut executor, "DELETE FROM orders WHERE id >= 10");
+        run(&mut executor, "ROLLBACK");
+
+        // A tiny run size would spill a file-backed sort; this one stays in memory
+        executor.set_sort_run_size(3);
+        let ExecutionResult::Select { rows, plan, .. } = run(
+            &mut executor,
+            "SELECT users.name, orders.id FROM users JOIN orders ON users.id = orders.user_id",
+        ) else {
+            panic!("Expected Select result");
+        };
+        assert_eq!(rows.len(), 20);
+        assert!(plan.iter().any(|step| step.starts_with("Merge join")));
+        assert!(!plan.iter().any(|step| step.contains("external")));
+
+        run(&mut executor, "ALTER TABLE orders RENAME TO purchases");
+        run(&mut executor, "DELETE FROM purchases WHERE id < 5");
+        assert_eq!(executor.vacuum_all().unwrap(), 5);
+        run(&mut executor, "DROP TABLE purchases");
+        executor.flush_all().unwrap();
+
+        let ExecutionResult::Select { rows, .. } = run(&mut executor, "SELECT COUNT(*) FROM users")
+        else {
+            panic!("Expected Select result");
+        };
+        assert_eq!(rows, vec![vec![Value::Integer(20)]]);
+        assert!(executor.wal_changes_since(0).unwrap().is_empty());
+
+        // Nothing outlives the executor
+        assert!(Executor::in_memory().list_tables().is_empty());
+    }
+
+    #[test]
+    fn test_leftover_sort_runs_are_removed_on_open() {
+        let temp_dir = TempDir::new().unwrap();
+        let leftover = temp_dir.path().join("sort-1234-0.tmp");
+        std::fs::write(&leftover, b"partial run").unwrap();
+
+        Executor::new(temp_dir.path(), 10).unwrap();
+        assert!(!leftover.exists());
+    }
 }
@@ -1,6 +1,7 @@
 pub mod ast;
 pub mod executor;
 pub mod parser;
+pub mod pattern;
 
 #[cfg(test)]
 mod executor_test;
@@ -11,10 +12,19 @@ mod ast_test;
 #[cfg(test)]
 mod parser_test;
 
+#[cfg(test)]
+mod pattern_test;
+
 pub use crate::wal::TxnId;
 pub use ast::{
-    AlterTableStmt, CreateTableStmt, DataType, DeleteStmt, DropIndexStmt, DropTableStmt, IndexType,
-    InsertStmt, Statement, TransactionCommand, TransactionStmt, UpdateStmt,
+    AlterTableStmt, CreateTableStmt, DataType, DeleteStmt, DescribeStmt, DropIndexStmt,
+    DropIndexTarget, DropTableStmt, IndexType, InsertSource, InsertStmt, ReindexStmt, ShowStmt,
+    ShowTarget, Statement, TransactionCommand, TransactionStmt, UpdateStmt,
+};
+pub use executor::{
+    ConflictPolicy, ConstraintInfo, ExecutionResult, Executor, ForeignKeyInfo, InvalidStatement,
+    OrphanedMetadata, RowEstimate, Snapshot, TxnState, WriteConflict,
+};
+pub use parser::{
+    ParseError, ParsedStatement, parse_sql, parse_sql_statements, parse_sql_statements_spanned,
 };
-pub use executor::{ExecutionResult, Executor, Snapshot, TxnState};
-pub use parser::{ParseError, parse_sql, parse_sql_statements};
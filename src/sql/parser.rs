@@ -1,10 +1,13 @@
 use super::ast::{
     AggregateExpr, AggregateFunc, AggregateTarget, AlterTableAction, AlterTableStmt, BinaryOp,
-    ColumnDef, ColumnRef, CreateIndexStmt, CreateTableStmt, DataType, DeleteStmt, DropIndexStmt,
-    DropTableStmt, Expr, ForeignKeyRef, FromClause, IndexType, InsertStmt, Literal, OrderByExpr,
-    SelectColumn, SelectItem, SelectStmt, Statement, TransactionCommand, TransactionStmt,
-    UpdateStmt,
+    ColumnDef, ColumnRef, CreateIndexStmt, CreateTableStmt, DataType, DeleteStmt, DescribeStmt,
+    DropIndexStmt, DropTableStmt, Expr, ForeignKeyRef, FromClause, IndexType, InsertStmt, Literal,
+    OrderByExpr, ReindexStmt, SelectColumn, SelectItem, SelectStmt, ShowStmt, Statement,
+    TransactionCommand, TransactionStmt, UpdateStmt,
 };
+use super::pattern;
+use crate::types::JsonPath;
+use std::ops::Range;
 
 /// Parse errors
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -28,6 +31,21 @@ impl std::fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
+/// One statement of a multi-statement input, parsed independently of the others
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedStatement {
+    /// Byte range of the statement in the input, without its terminating semicolon
+    pub span: Range<usize>,
+    pub result: Result<Statement, ParseError>,
+}
+
+impl ParsedStatement {
+    /// Source text of the statement
+    pub fn source<'a>(&self, sql: &'a str) -> &'a str {
+        &sql[self.span.clone()]
+    }
+}
+
 /// Token types
 #[derive(Debug, Clone)]
 pub(crate) enum Token {
@@ -55,6 +73,7 @@ pub(crate) enum Token {
     Timestamp,
     Decimal,
     Numeric,
+    Json,
     Order,
     By,
     Group,
@@ -92,6 +111,22 @@ pub(crate) enum Token {
     Not,
     Null,
     Check,
+    Default,
+    Exists,
+    If,
+    Like,
+    Escape,
+    Is,
+    Show,
+    Tables,
+    Indexes,
+    Describe,
+    Explain,
+    Reindex,
+    Columns,
+    Interval,
+    CurrentTimestamp,
+    CurrentDate,
 
     // Symbols
     LeftParen,
@@ -104,6 +139,10 @@ pub(crate) enum Token {
     LessThanEquals,
     GreaterThan,
     GreaterThanEquals,
+    Plus,
+    Minus,
+    Slash,
+    Percent,
 
     // Literals
     Identifier(String),
@@ -141,6 +180,7 @@ impl PartialEq for Token {
             | (Token::Timestamp, Token::Timestamp)
             | (Token::Decimal, Token::Decimal)
             | (Token::Numeric, Token::Numeric)
+            | (Token::Json, Token::Json)
             | (Token::Order, Token::Order)
             | (Token::By, Token::By)
             | (Token::Group, Token::Group)
@@ -178,6 +218,22 @@ impl PartialEq for Token {
             | (Token::Not, Token::Not)
             | (Token::Null, Token::Null)
             | (Token::Check, Token::Check)
+            | (Token::Default, Token::Default)
+            | (Token::Exists, Token::Exists)
+            | (Token::If, Token::If)
+            | (Token::Like, Token::Like)
+            | (Token::Escape, Token::Escape)
+            | (Token::Is, Token::Is)
+            | (Token::Show, Token::Show)
+            | (Token::Tables, Token::Tables)
+            | (Token::Indexes, Token::Indexes)
+            | (Token::Describe, Token::Describe)
+            | (Token::Explain, Token::Explain)
+            | (Token::Reindex, Token::Reindex)
+            | (Token::Columns, Token::Columns)
+            | (Token::Interval, Token::Interval)
+            | (Token::CurrentTimestamp, Token::CurrentTimestamp)
+            | (Token::CurrentDate, Token::CurrentDate)
             | (Token::LeftParen, Token::LeftParen)
             | (Token::RightParen, Token::RightParen)
             | (Token::Comma, Token::Comma)
@@ -188,6 +244,10 @@ impl PartialEq for Token {
             | (Token::LessThanEquals, Token::LessThanEquals)
             | (Token::GreaterThan, Token::GreaterThan)
             | (Token::GreaterThanEquals, Token::GreaterThanEquals)
+            | (Token::Plus, Token::Plus)
+            | (Token::Minus, Token::Minus)
+            | (Token::Slash, Token::Slash)
+            | (Token::Percent, Token::Percent)
             | (Token::Eof, Token::Eof) => true,
             (Token::Identifier(a), Token::Identifier(b)) => a == b,
             (Token::IntegerLiteral(a), Token::IntegerLiteral(b)) => a == b,
@@ -226,6 +286,7 @@ impl std::fmt::Display for Token {
             Token::Timestamp => write!(f, "TIMESTAMP"),
             Token::Decimal => write!(f, "DECIMAL"),
             Token::Numeric => write!(f, "NUMERIC"),
+            Token::Json => write!(f, "JSON"),
             Token::Order => write!(f, "ORDER"),
             Token::By => write!(f, "BY"),
             Token::Group => write!(f, "GROUP"),
@@ -261,6 +322,22 @@ impl std::fmt::Display for Token {
             Token::Not => write!(f, "NOT"),
             Token::Null => write!(f, "NULL"),
             Token::Check => write!(f, "CHECK"),
+            Token::Default => write!(f, "DEFAULT"),
+            Token::Exists => write!(f, "EXISTS"),
+            Token::If => write!(f, "IF"),
+            Token::Like => write!(f, "LIKE"),
+            Token::Escape => write!(f, "ESCAPE"),
+            Token::Is => write!(f, "IS"),
+            Token::Show => write!(f, "SHOW"),
+            Token::Tables => write!(f, "TABLES"),
+            Token::Indexes => write!(f, "INDEXES"),
+            Token::Describe => write!(f, "DESCRIBE"),
+            Token::Explain => write!(f, "EXPLAIN"),
+            Token::Reindex => write!(f, "REINDEX"),
+            Token::Columns => write!(f, "COLUMNS"),
+            Token::Interval => write!(f, "INTERVAL"),
+            Token::CurrentTimestamp => write!(f, "CURRENT_TIMESTAMP"),
+            Token::CurrentDate => write!(f, "CURRENT_DATE"),
             Token::True => write!(f, "TRUE"),
             Token::False => write!(f, "FALSE"),
             Token::LeftParen => write!(f, "("),
@@ -273,6 +350,10 @@ impl std::fmt::Display for Token {
             Token::LessThanEquals => write!(f, "<="),
             Token::GreaterThan => write!(f, ">"),
             Token::GreaterThanEquals => write!(f, ">="),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Slash => write!(f, "/"),
+            Token::Percent => write!(f, "%"),
             Token::Identifier(s) => write!(f, "identifier '{}'", s),
             Token::IntegerLiteral(i) => write!(f, "integer {}", i),
             Token::FloatLiteral(fv) => write!(f, "float {}", fv),
@@ -509,6 +590,29 @@ impl Tokenizer {
                 let s = self.read_string()?;
                 Ok(Token::StringLiteral(s))
             }
+            Some('+') => {
+                self.advance();
+                Ok(Token::Plus)
+            }
+            Some('/') => {
+                self.advance();
+                Ok(Token::Slash)
+            }
+            Some('%') => {
+                self.advance();
+                Ok(Token::Percent)
+            }
+            // A minus sign directly followed by a digit is a negative number;
+            // otherwise it is the subtraction operator
+            Some('-')
+                if !self
+                    .input
+                    .get(self.position + 1)
+                    .is_some_and(|c| c.is_ascii_digit()) =>
+            {
+                self.advance();
+                Ok(Token::Minus)
+            }
             Some(ch) if ch.is_ascii_digit() || ch == '-' => {
                 let num = self.read_number()?;
                 match num {
@@ -542,6 +646,7 @@ impl Tokenizer {
                     "TIMESTAMP" => Token::Timestamp,
                     "DECIMAL" => Token::Decimal,
                     "NUMERIC" => Token::Numeric,
+                    "JSON" => Token::Json,
                     "ORDER" => Token::Order,
                     "BY" => Token::By,
                     "GROUP" => Token::Group,
@@ -577,6 +682,22 @@ impl Tokenizer {
                     "NOT" => Token::Not,
                     "NULL" => Token::Null,
                     "CHECK" => Token::Check,
+                    "DEFAULT" => Token::Default,
+                    "EXISTS" => Token::Exists,
+                    "IF" => Token::If,
+                    "LIKE" => Token::Like,
+                    "ESCAPE" => Token::Escape,
+                    "IS" => Token::Is,
+                    "SHOW" => Token::Show,
+                    "TABLES" => Token::Tables,
+                    "INDEXES" => Token::Indexes,
+                    "DESCRIBE" => Token::Describe,
+                    "EXPLAIN" => Token::Explain,
+                    "REINDEX" => Token::Reindex,
+                    "COLUMNS" => Token::Columns,
+                    "INTERVAL" => Token::Interval,
+                    "CURRENT_TIMESTAMP" => Token::CurrentTimestamp,
+                    "CURRENT_DATE" => Token::CurrentDate,
                     _ => Token::Identifier(ident),
                 };
                 Ok(token)
@@ -626,6 +747,10 @@ impl Parser {
         }
     }
 
+    fn peek(&self) -> &Token {
+        self.tokens.get(self.position + 1).unwrap_or(&Token::Eof)
+    }
+
     fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
         let current = self.current().clone();
         if std::mem::discriminant(&current) == std::mem::discriminant(&expected) {
@@ -674,8 +799,12 @@ impl Parser {
                 self.advance();
                 Ok(DataType::Decimal)
             }
+            Token::Json => {
+                self.advance();
+                Ok(DataType::Json)
+            }
             _ => Err(ParseError::UnexpectedToken {
-                expected: "data type (INTEGER, UNSIGNED, FLOAT, BOOLEAN, VARCHAR, DATE, TIMESTAMP, or DECIMAL)".to_string(),
+                expected: "data type (INTEGER, UNSIGNED, FLOAT, BOOLEAN, VARCHAR, DATE, TIMESTAMP, DECIMAL, or JSON)".to_string(),
                 found: format!("{}", token),
             }),
         }
@@ -756,6 +885,10 @@ impl Parser {
                     self.expect(Token::RightParen)?;
                     column.check = Some(expr);
                 }
+                Token::Default => {
+                    self.advance();
+                    column.default = Some(self.parse_literal()?);
+                }
                 _ => break,
             }
         }
@@ -785,7 +918,14 @@ impl Parser {
 
         let mut columns = Vec::new();
         loop {
-            columns.push(self.parse_column_def()?);
+            let column = self.parse_column_def()?;
+            if column.default.is_some() {
+                return Err(ParseError::InvalidSyntax(format!(
+                    "DEFAULT on column '{}' is only supported by ALTER TABLE ADD COLUMN",
+                    column.name
+                )));
+            }
+            columns.push(column);
 
             if matches!(self.current(), Token::Comma) {
                 self.advance();
@@ -810,6 +950,13 @@ impl Parser {
 
         self.expect(Token::Index)?;
 
+        let if_not_exists = matches!(self.current(), Token::If);
+        if if_not_exists {
+            self.advance();
+            self.expect(Token::Not)?;
+            self.expect(Token::Exists)?;
+        }
+
         let index_name = match self.current() {
             Token::Identifier(s) => {
                 let name = s.clone();
@@ -856,6 +1003,37 @@ impl Parser {
             self.advance();
         }
 
+        let columns = self.parse_index_columns()?;
+
+        let mut stmt =
+            CreateIndexStmt::with_unique(index_name, table_name, columns, index_type, is_unique);
+        stmt.if_not_exists = if_not_exists;
+        Ok(stmt)
+    }
+
+    fn parse_drop_table(&mut self) -> Result<DropTableStmt, ParseError> {
+        self.expect(Token::Drop)?;
+        self.expect(Token::Table)?;
+
+        let table_name = match self.current() {
+            Token::Identifier(s) => {
+                let name = s.clone();
+                self.advance();
+                name
+            }
+            _ => {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "table name".to_string(),
+                    found: format!("{}", self.current()),
+                });
+            }
+        };
+
+        Ok(DropTableStmt::new(table_name))
+    }
+
+    /// Parse a parenthesized, comma-separated list of index column names
+    fn parse_index_columns(&mut self) -> Result<Vec<String>, ParseError> {
         self.expect(Token::LeftParen)?;
 
         let mut columns = Vec::new();
@@ -882,17 +1060,33 @@ impl Parser {
         }
 
         self.expect(Token::RightParen)?;
-
-        Ok(CreateIndexStmt::with_unique(
-            index_name, table_name, columns, index_type, is_unique,
-        ))
+        Ok(columns)
     }
 
-    fn parse_drop_table(&mut self) -> Result<DropTableStmt, ParseError> {
+    fn parse_drop_index(&mut self) -> Result<DropIndexStmt, ParseError> {
         self.expect(Token::Drop)?;
-        self.expect(Token::Table)?;
+        self.expect(Token::Index)?;
 
-        let table_name = match self.current() {
+        if matches!(self.current(), Token::On) {
+            self.advance();
+            let table_name = match self.current() {
+                Token::Identifier(s) => {
+                    let name = s.clone();
+                    self.advance();
+                    name
+                }
+                _ => {
+                    return Err(ParseError::UnexpectedToken {
+                        expected: "table name".to_string(),
+                        found: format!("{}", self.current()),
+                    });
+                }
+            };
+            let columns = self.parse_index_columns()?;
+            return Ok(DropIndexStmt::on_columns(table_name, columns));
+        }
+
+        let index_name = match self.current() {
             Token::Identifier(s) => {
                 let name = s.clone();
                 self.advance();
@@ -900,34 +1094,91 @@ impl Parser {
             }
             _ => {
                 return Err(ParseError::UnexpectedToken {
-                    expected: "table name".to_string(),
+                    expected: "index name".to_string(),
                     found: format!("{}", self.current()),
                 });
             }
         };
 
-        Ok(DropTableStmt::new(table_name))
+        Ok(DropIndexStmt::new(index_name))
     }
 
-    fn parse_drop_index(&mut self) -> Result<DropIndexStmt, ParseError> {
-        self.expect(Token::Drop)?;
-        self.expect(Token::Index)?;
+    fn parse_reindex(&mut self) -> Result<ReindexStmt, ParseError> {
+        self.expect(Token::Reindex)?;
 
-        let index_name = match self.current() {
+        match self.current() {
             Token::Identifier(s) => {
                 let name = s.clone();
                 self.advance();
-                name
+                Ok(ReindexStmt::new(name))
             }
+            _ => Err(ParseError::UnexpectedToken {
+                expected: "index name".to_string(),
+                found: format!("{}", self.current()),
+            }),
+        }
+    }
+
+    fn parse_show(&mut self) -> Result<ShowStmt, ParseError> {
+        self.expect(Token::Show)?;
+
+        match self.current() {
+            Token::Tables => {
+                self.advance();
+                Ok(ShowStmt::tables())
+            }
+            Token::Indexes => {
+                self.advance();
+                if !matches!(self.current(), Token::From) {
+                    return Ok(ShowStmt::indexes(None));
+                }
+                self.advance();
+                match self.current() {
+                    Token::Identifier(s) => {
+                        let name = s.clone();
+                        self.advance();
+                        Ok(ShowStmt::indexes(Some(name)))
+                    }
+                    _ => Err(ParseError::UnexpectedToken {
+                        expected: "table name".to_string(),
+                        found: format!("{}", self.current()),
+                    }),
+                }
+            }
+            token => Err(ParseError::UnexpectedToken {
+                expected: "TABLES or INDEXES".to_string(),
+                found: format!("{}", token),
+            }),
+        }
+    }
+
+    /// Parse `DESCRIBE t`, `DESC t` or `EXPLAIN COLUMNS [FROM] t`.
+    ///
+    /// `DESC` only means DESCRIBE at the start of a statement; elsewhere it is
+    /// the ORDER BY direction.
+    fn parse_describe(&mut self) -> Result<DescribeStmt, ParseError> {
+        match self.current() {
+            Token::Describe | Token::Desc => self.advance(),
             _ => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "index name".to_string(),
-                    found: format!("{}", self.current()),
-                });
+                self.expect(Token::Explain)?;
+                self.expect(Token::Columns)?;
+                if matches!(self.current(), Token::From) {
+                    self.advance();
+                }
             }
-        };
+        }
 
-        Ok(DropIndexStmt::new(index_name))
+        match self.current() {
+            Token::Identifier(s) => {
+                let name = s.clone();
+                self.advance();
+                Ok(DescribeStmt::new(name))
+            }
+            _ => Err(ParseError::UnexpectedToken {
+                expected: "table name".to_string(),
+                found: format!("{}", self.current()),
+            }),
+        }
     }
 
     fn parse_transaction_stmt(
@@ -1065,6 +1316,34 @@ impl Parser {
                 self.advance();
                 Ok(Literal::Null)
             }
+            Token::Interval => {
+                self.advance();
+                let quantity = match self.current() {
+                    Token::StringLiteral(s) => s.clone(),
+                    Token::IntegerLiteral(i) => i.to_string(),
+                    _ => {
+                        return Err(ParseError::UnexpectedToken {
+                            expected: "interval quantity".to_string(),
+                            found: format!("{}", self.current()),
+                        });
+                    }
+                };
+                self.advance();
+                let unit = match self.current() {
+                    Token::Identifier(unit) => unit.clone(),
+                    _ => {
+                        return Err(ParseError::UnexpectedToken {
+                            expected: "interval unit (YEAR, MONTH, DAY, HOUR, MINUTE, SECOND)"
+                                .to_string(),
+                            found: format!("{}", self.current()),
+                        });
+                    }
+                };
+                self.advance();
+                crate::types::Interval::parse(&quantity, &unit)
+                    .map(Literal::Interval)
+                    .map_err(ParseError::InvalidSyntax)
+            }
             Token::Date => {
                 self.advance();
                 let literal = match self.current() {
@@ -1141,6 +1420,11 @@ impl Parser {
             }
         };
 
+        if matches!(self.current(), Token::Select) {
+            let select = self.parse_select()?;
+            return Ok(InsertStmt::from_select(table_name, select));
+        }
+
         self.expect(Token::Values)?;
         let mut rows = Vec::new();
         loop {
@@ -1190,9 +1474,42 @@ impl Parser {
         Ok(op)
     }
 
+    /// Whether the current identifier `name` starts a `json_extract(...)` call
+    fn at_json_extract(&self, name: &str) -> bool {
+        name.eq_ignore_ascii_case("json_extract") && matches!(self.peek(), Token::LeftParen)
+    }
+
+    /// Parse `json_extract(expr, 'path')`, checking the path here so a
+    /// malformed one is reported before any row is read
+    fn parse_json_extract(&mut self) -> Result<Expr, ParseError> {
+        self.advance();
+        self.expect(Token::LeftParen)?;
+        let expr = self.parse_additive_expr()?;
+        self.expect(Token::Comma)?;
+        let path = match self.current().clone() {
+            Token::StringLiteral(path) => {
+                self.advance();
+                path
+            }
+            token => {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "JSON path string literal".to_string(),
+                    found: format!("{}", token),
+                });
+            }
+        };
+        JsonPath::parse(&path).map_err(ParseError::InvalidSyntax)?;
+        self.expect(Token::RightParen)?;
+        Ok(Expr::JsonExtract {
+            expr: Box::new(expr),
+            path,
+        })
+    }
+
     fn parse_primary_expr(&mut self) -> Result<Expr, ParseError> {
         let token = self.current().clone();
         match token {
+            Token::Identifier(ref name) if self.at_json_extract(name) => self.parse_json_extract(),
             Token::Identifier(_) => {
                 let col_ref = self.parse_column_ref()?;
                 Ok(Expr::Column(col_ref))
@@ -1217,10 +1534,29 @@ impl Parser {
                 self.advance();
                 Ok(Expr::Literal(Literal::Boolean(false)))
             }
-            Token::Null | Token::Date | Token::Timestamp | Token::Decimal | Token::Numeric => {
+            Token::Null
+            | Token::Date
+            | Token::Timestamp
+            | Token::Decimal
+            | Token::Numeric
+            | Token::Interval => {
                 let literal = self.parse_literal()?;
                 Ok(Expr::Literal(literal))
             }
+            Token::CurrentTimestamp => {
+                self.advance();
+                Ok(Expr::CurrentTimestamp)
+            }
+            Token::CurrentDate => {
+                self.advance();
+                Ok(Expr::CurrentDate)
+            }
+            Token::LeftParen if matches!(self.peek(), Token::Select) => {
+                self.advance();
+                let subquery = self.parse_select()?;
+                self.expect(Token::RightParen)?;
+                Ok(Expr::scalar_subquery(subquery))
+            }
             _ => Err(ParseError::UnexpectedToken {
                 expected: "column name or literal".to_string(),
                 found: format!("{}", token),
@@ -1228,6 +1564,48 @@ impl Parser {
         }
     }
 
+    /// Parse `+` and `-`, which bind tighter than comparisons
+    fn parse_additive_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_primary_expr()?;
+        self.parse_additive_tail(&mut expr)?;
+        Ok(expr)
+    }
+
+    /// Continue an additive expression whose first operand is `expr`
+    fn parse_additive_tail(&mut self, expr: &mut Expr) -> Result<(), ParseError> {
+        self.parse_multiplicative_tail(expr)?;
+        loop {
+            let op = match self.current() {
+                Token::Plus => BinaryOp::Plus,
+                Token::Minus => BinaryOp::Minus,
+                _ => return Ok(()),
+            };
+            self.advance();
+            let right = self.parse_multiplicative_expr()?;
+            *expr = Expr::binary_op(expr.clone(), op, right);
+        }
+    }
+
+    /// Parse `/` and `%`, which bind tighter than `+` and `-`
+    fn parse_multiplicative_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_primary_expr()?;
+        self.parse_multiplicative_tail(&mut expr)?;
+        Ok(expr)
+    }
+
+    fn parse_multiplicative_tail(&mut self, expr: &mut Expr) -> Result<(), ParseError> {
+        loop {
+            let op = match self.current() {
+                Token::Slash => BinaryOp::Divide,
+                Token::Percent => BinaryOp::Mod,
+                _ => return Ok(()),
+            };
+            self.advance();
+            let right = self.parse_primary_expr()?;
+            *expr = Expr::binary_op(expr.clone(), op, right);
+        }
+    }
+
     fn parse_expression(&mut self) -> Result<Expr, ParseError> {
         let mut expr = self.parse_comparison_expr()?;
 
@@ -1241,7 +1619,13 @@ impl Parser {
     }
 
     fn parse_comparison_expr(&mut self) -> Result<Expr, ParseError> {
-        let left = self.parse_primary_expr()?;
+        if matches!(self.current(), Token::Exists)
+            || (matches!(self.current(), Token::Not) && matches!(self.peek(), Token::Exists))
+        {
+            return self.parse_exists_expr();
+        }
+
+        let left = self.parse_additive_expr()?;
 
         // Check if there's a binary operator
         match self.current() {
@@ -1252,9 +1636,35 @@ impl Parser {
             | Token::GreaterThan
             | Token::GreaterThanEquals => {
                 let op = self.parse_binary_op()?;
-                let right = self.parse_primary_expr()?;
+                let right = self.parse_additive_expr()?;
                 Ok(Expr::binary_op(left, op, right))
             }
+            Token::Like => {
+                self.advance();
+                let mut right = self.parse_additive_expr()?;
+                if matches!(self.current(), Token::Escape) {
+                    self.advance();
+                    let escape = match self.current() {
+                        Token::StringLiteral(s) if s.chars().count() <= 1 => s.chars().next(),
+                        token => {
+                            return Err(ParseError::InvalidSyntax(format!(
+                                "ESCAPE expects a single character, found {}",
+                                token
+                            )));
+                        }
+                    };
+                    self.advance();
+                    let Expr::Literal(Literal::String(pattern)) = &right else {
+                        return Err(ParseError::InvalidSyntax(
+                            "ESCAPE requires a string literal pattern".to_string(),
+                        ));
+                    };
+                    let pattern =
+                        pattern::normalize(pattern, escape).map_err(ParseError::InvalidSyntax)?;
+                    right = Expr::Literal(Literal::String(pattern));
+                }
+                Ok(Expr::binary_op(left, BinaryOp::Like, right))
+            }
             Token::In => {
                 self.advance();
                 self.expect(Token::LeftParen)?;
@@ -1262,10 +1672,49 @@ impl Parser {
                 self.expect(Token::RightParen)?;
                 Ok(Expr::in_subquery(left, subquery))
             }
+            Token::Is => {
+                self.advance();
+                let op = if matches!(self.current(), Token::Not) {
+                    self.advance();
+                    BinaryOp::IsNotDistinctFrom
+                } else {
+                    BinaryOp::IsDistinctFrom
+                };
+                self.expect(Token::Distinct)?;
+                self.expect(Token::From)?;
+                let right = self.parse_additive_expr()?;
+                Ok(Expr::binary_op(left, op, right))
+            }
             _ => Ok(left),
         }
     }
 
+    fn parse_exists_expr(&mut self) -> Result<Expr, ParseError> {
+        let negated = if matches!(self.current(), Token::Not) {
+            self.advance();
+            true
+        } else {
+            false
+        };
+        self.expect(Token::Exists)?;
+        self.expect(Token::LeftParen)?;
+
+        // The select list of an EXISTS subquery is irrelevant, so also accept
+        // the conventional `SELECT 1`.
+        let subquery = if matches!(self.current(), Token::Select)
+            && matches!(self.peek(), Token::IntegerLiteral(_))
+        {
+            self.advance();
+            self.advance();
+            self.parse_select_from(SelectColumn::All, false)?
+        } else {
+            self.parse_select()?
+        };
+        self.expect(Token::RightParen)?;
+
+        Ok(Expr::exists(subquery, negated))
+    }
+
     fn parse_column_ref(&mut self) -> Result<ColumnRef, ParseError> {
         let base_name = match self.current() {
             Token::Identifier(name) => {
@@ -1331,6 +1780,15 @@ impl Parser {
             SelectColumn::Items(items)
         };
 
+        self.parse_select_from(columns, distinct)
+    }
+
+    /// Parse the remainder of a SELECT, starting at FROM.
+    fn parse_select_from(
+        &mut self,
+        columns: SelectColumn,
+        distinct: bool,
+    ) -> Result<SelectStmt, ParseError> {
         self.expect(Token::From)?;
 
         let left_table = match self.current() {
@@ -1367,20 +1825,12 @@ impl Parser {
             };
 
             self.expect(Token::On)?;
-            let left_column = self.parse_column_ref()?;
-            let op = self.parse_binary_op()?;
-            if op != BinaryOp::Eq {
-                return Err(ParseError::InvalidSyntax(
-                    "JOIN only supports equality conditions".to_string(),
-                ));
-            }
-            let right_column = self.parse_column_ref()?;
+            let condition = self.parse_expression()?;
 
             from = FromClause::Join {
                 left_table,
                 right_table,
-                left_column,
-                right_column,
+                condition,
             };
         }
 
@@ -1437,11 +1887,11 @@ impl Parser {
         let mut offset = None;
         if matches!(self.current(), Token::Limit) {
             self.advance();
-            limit = Some(self.parse_non_negative_usize("LIMIT")?);
+            limit = Some(self.parse_non_negative_u64("LIMIT")?);
         }
         if matches!(self.current(), Token::Offset) {
             self.advance();
-            offset = Some(self.parse_non_negative_usize("OFFSET")?);
+            offset = Some(self.parse_non_negative_u64("OFFSET")?);
         }
 
         Ok(SelectStmt::new(
@@ -1483,12 +1933,11 @@ impl Parser {
                 let column_def = self.parse_column_def()?;
                 if column_def.is_primary_key
                     || column_def.is_unique
-                    || column_def.is_not_null
                     || column_def.check.is_some()
                     || column_def.references.is_some()
                 {
                     return Err(ParseError::InvalidSyntax(
-                        "ALTER TABLE ADD COLUMN does not support constraints yet".to_string(),
+                        "ALTER TABLE ADD COLUMN supports only NOT NULL and DEFAULT".to_string(),
                     ));
                 }
                 Ok(AlterTableStmt::new(
@@ -1579,8 +2028,41 @@ impl Parser {
                     },
                 ))
             }
+            Token::Alter => {
+                self.advance();
+                if matches!(self.current(), Token::Column) {
+                    self.advance();
+                }
+                let column = match self.current() {
+                    Token::Identifier(name) => {
+                        let name = name.clone();
+                        self.advance();
+                        name
+                    }
+                    _ => {
+                        return Err(ParseError::UnexpectedToken {
+                            expected: "column name".to_string(),
+                            found: format!("{}", self.current()),
+                        });
+                    }
+                };
+                match self.current() {
+                    Token::Identifier(s) if s.eq_ignore_ascii_case("type") => self.advance(),
+                    token => {
+                        return Err(ParseError::UnexpectedToken {
+                            expected: "TYPE".to_string(),
+                            found: format!("{}", token),
+                        });
+                    }
+                }
+                let data_type = self.parse_data_type()?;
+                Ok(AlterTableStmt::new(
+                    table_name,
+                    AlterTableAction::AlterColumnType { column, data_type },
+                ))
+            }
             _ => Err(ParseError::UnexpectedToken {
-                expected: "ADD, DROP, or RENAME".to_string(),
+                expected: "ADD, DROP, RENAME, or ALTER".to_string(),
                 found: format!("{}", self.current()),
             }),
         }
@@ -1615,14 +2097,42 @@ impl Parser {
                 self.expect(Token::RightParen)?;
                 Ok(SelectItem::Aggregate(AggregateExpr::new(func, target)))
             }
+            Token::LeftParen if matches!(self.peek(), Token::Select) => {
+                Ok(SelectItem::Expr(self.parse_additive_expr()?))
+            }
+            Token::Identifier(ref name) if self.at_json_extract(name) => {
+                Ok(SelectItem::Expr(self.parse_additive_expr()?))
+            }
+            Token::Interval
+            | Token::CurrentTimestamp
+            | Token::CurrentDate
+            | Token::Date
+            | Token::Timestamp => Ok(SelectItem::Expr(self.parse_additive_expr()?)),
+            Token::Identifier(table)
+                if matches!(self.peek(), Token::Dot)
+                    && matches!(self.tokens.get(self.position + 2), Some(Token::Asterisk)) =>
+            {
+                self.advance();
+                self.advance();
+                self.advance();
+                Ok(SelectItem::QualifiedAll(table))
+            }
             _ => {
                 let col = self.parse_column_ref()?;
+                if matches!(
+                    self.current(),
+                    Token::Plus | Token::Minus | Token::Slash | Token::Percent
+                ) {
+                    let mut expr = Expr::Column(col);
+                    self.parse_additive_tail(&mut expr)?;
+                    return Ok(SelectItem::Expr(expr));
+                }
                 Ok(SelectItem::Column(col))
             }
         }
     }
 
-    fn parse_non_negative_usize(&mut self, label: &str) -> Result<usize, ParseError> {
+    fn parse_non_negative_u64(&mut self, label: &str) -> Result<u64, ParseError> {
         let token = self.current().clone();
         match token {
             Token::IntegerLiteral(value) => {
@@ -1706,6 +2216,10 @@ impl Parser {
                     }),
                 }
             }
+            Token::Reindex => {
+                let stmt = self.parse_reindex()?;
+                Ok(Statement::Reindex(stmt))
+            }
             Token::Alter => {
                 let stmt = self.parse_alter_table()?;
                 Ok(Statement::AlterTable(stmt))
@@ -1738,6 +2252,14 @@ impl Parser {
                 let stmt = self.parse_update()?;
                 Ok(Statement::Update(stmt))
             }
+            Token::Show => {
+                let stmt = self.parse_show()?;
+                Ok(Statement::Show(stmt))
+            }
+            Token::Describe | Token::Desc | Token::Explain => {
+                let stmt = self.parse_describe()?;
+                Ok(Statement::Describe(stmt))
+            }
             Token::Eof => Err(ParseError::UnexpectedEof),
             token => Err(ParseError::UnexpectedToken {
                 expected: "SQL statement".to_string(),
@@ -1791,3 +2313,78 @@ pub fn parse_sql_statements(sql: &str) -> Result<Vec<Statement>, ParseError> {
 
     Ok(statements)
 }
+
+/// Parse SQL input into semicolon-separated statements, each with its own result.
+///
+/// Unlike `parse_sql_statements`, an error in one statement does not abort the
+/// rest: the statement is reported with its span and parsing resumes after the
+/// next semicolon, so a caller can say which statement failed and decide
+/// whether to run the others.
+pub fn parse_sql_statements_spanned(sql: &str) -> Vec<ParsedStatement> {
+    let byte_offsets: Vec<usize> = sql
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(sql.len()))
+        .collect();
+    let mut tokenizer = Tokenizer::new(sql);
+    let mut statements = Vec::new();
+
+    loop {
+        tokenizer.skip_whitespace();
+        match tokenizer.current() {
+            None => break,
+            Some(';') => {
+                tokenizer.advance();
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        let start = tokenizer.position;
+        let mut end = start;
+        let mut tokens = Vec::new();
+        let mut error = None;
+        loop {
+            tokenizer.skip_whitespace();
+            let before = tokenizer.position;
+            match tokenizer.next_token() {
+                Ok(Token::Eof | Token::Semicolon) => break,
+                Ok(token) => tokens.push(token),
+                Err(e) => {
+                    // Keep the first error and make sure the tokenizer moves on
+                    error.get_or_insert(e);
+                    if tokenizer.position == before {
+                        tokenizer.advance();
+                    }
+                }
+            }
+            end = tokenizer.position;
+        }
+
+        let result = match error {
+            Some(e) => Err(e),
+            None => {
+                tokens.push(Token::Eof);
+                parse_single_statement(tokens)
+            }
+        };
+        statements.push(ParsedStatement {
+            span: byte_offsets[start]..byte_offsets[end],
+            result,
+        });
+    }
+
+    statements
+}
+
+fn parse_single_statement(tokens: Vec<Token>) -> Result<Statement, ParseError> {
+    let mut parser = Parser::new(tokens);
+    let stmt = parser.parse_statement()?;
+    if !matches!(parser.current(), Token::Eof) {
+        return Err(ParseError::UnexpectedToken {
+            expected: "end of statement".to_string(),
+            found: format!("{}", parser.current()),
+        });
+    }
+    Ok(stmt)
+}
@@ -1,11 +1,11 @@
 mod tests {
     use crate::sql::ast::{
-        BinaryOp, ColumnRef, Expr, ForeignKeyRef, FromClause, IndexType, Literal, SelectColumn,
-        SelectItem,
+        BinaryOp, ColumnRef, DropIndexTarget, Expr, ForeignKeyRef, FromClause, IndexType,
+        InsertSource, Literal, SelectColumn, SelectItem, ShowTarget,
     };
     use crate::sql::parser::{Token, Tokenizer};
     use crate::sql::{DataType, Statement, TransactionCommand};
-    use crate::sql::{parse_sql, parse_sql_statements};
+    use crate::sql::{ParseError, parse_sql, parse_sql_statements, parse_sql_statements_spanned};
 
     #[test]
     fn test_tokenize_create_table() {
@@ -123,12 +123,31 @@ mod tests {
 
         match stmt {
             Statement::Insert(insert) => {
+                let InsertSource::Values(values) = insert.source else {
+                    panic!("Expected VALUES source");
+                };
                 assert_eq!(insert.table_name, "users");
-                assert_eq!(insert.values.len(), 1);
-                assert_eq!(insert.values[0].len(), 3);
-                assert_eq!(insert.values[0][0], Literal::Integer(1));
-                assert_eq!(insert.values[0][1], Literal::Boolean(true));
-                assert_eq!(insert.values[0][2], Literal::String("Alice".to_string()));
+                assert_eq!(values.len(), 1);
+                assert_eq!(values[0].len(), 3);
+                assert_eq!(values[0][0], Literal::Integer(1));
+                assert_eq!(values[0][1], Literal::Boolean(true));
+                assert_eq!(values[0][2], Literal::String("Alice".to_string()));
+            }
+            _ => panic!("Expected Insert statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_insert_select() {
+        let sql = "INSERT INTO archive SELECT * FROM log WHERE id < 10";
+        match parse_sql(sql).unwrap() {
+            Statement::Insert(insert) => {
+                assert_eq!(insert.table_name, "archive");
+                let InsertSource::Select(select) = insert.source else {
+                    panic!("Expected SELECT source");
+                };
+                assert_eq!(select.columns, SelectColumn::All);
+                assert!(select.where_clause.is_some());
             }
             _ => panic!("Expected Insert statement"),
         }
@@ -141,11 +160,14 @@ mod tests {
 
         match stmt {
             Statement::Insert(insert) => {
-                assert_eq!(insert.values.len(), 1);
-                assert_eq!(insert.values[0][0], Literal::Integer(42));
-                assert_eq!(insert.values[0][1], Literal::String("hello".to_string()));
-                assert_eq!(insert.values[0][2], Literal::Integer(-100));
-                assert_eq!(insert.values[0][3], Literal::String("world".to_string()));
+                let InsertSource::Values(values) = insert.source else {
+                    panic!("Expected VALUES source");
+                };
+                assert_eq!(values.len(), 1);
+                assert_eq!(values[0][0], Literal::Integer(42));
+                assert_eq!(values[0][1], Literal::String("hello".to_string()));
+                assert_eq!(values[0][2], Literal::Integer(-100));
+                assert_eq!(values[0][3], Literal::String("world".to_string()));
             }
             _ => panic!("Expected Insert statement"),
         }
@@ -158,10 +180,10 @@ mod tests {
 
         match stmt {
             Statement::Insert(insert) => {
-                assert_eq!(
-                    insert.values[0][0],
-                    Literal::Integer(18446744073709551615i128)
-                );
+                let InsertSource::Values(values) = insert.source else {
+                    panic!("Expected VALUES source");
+                };
+                assert_eq!(values[0][0], Literal::Integer(18446744073709551615i128));
             }
             _ => panic!("Expected Insert statement"),
         }
@@ -174,9 +196,12 @@ mod tests {
 
         match stmt {
             Statement::Insert(insert) => {
-                assert_eq!(insert.values[0][0], Literal::Float(1.5));
-                assert_eq!(insert.values[0][1], Literal::Float(-2.0));
-                assert_eq!(insert.values[0][2], Literal::Float(300.0));
+                let InsertSource::Values(values) = insert.source else {
+                    panic!("Expected VALUES source");
+                };
+                assert_eq!(values[0][0], Literal::Float(1.5));
+                assert_eq!(values[0][1], Literal::Float(-2.0));
+                assert_eq!(values[0][2], Literal::Float(300.0));
             }
             _ => panic!("Expected Insert statement"),
         }
@@ -189,10 +214,10 @@ mod tests {
 
         match stmt {
             Statement::Insert(insert) => {
-                assert_eq!(
-                    insert.values[0][0],
-                    Literal::String("it's working".to_string())
-                );
+                let InsertSource::Values(values) = insert.source else {
+                    panic!("Expected VALUES source");
+                };
+                assert_eq!(values[0][0], Literal::String("it's working".to_string()));
             }
             _ => panic!("Expected Insert statement"),
         }
@@ -314,6 +339,249 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_exists_subquery() {
+        let sql = "SELECT * FROM users WHERE NOT EXISTS (SELECT 1 FROM orders WHERE orders.user_id = users.id)";
+        let stmt = parse_sql(sql).unwrap();
+
+        match stmt {
+            Statement::Select(select) => match select.where_clause.expect("where clause") {
+                Expr::Exists { subquery, negated } => {
+                    assert!(negated);
+                    assert!(subquery.where_clause.is_some());
+                }
+                other => panic!("Expected Exists, got: {:?}", other),
+            },
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scalar_subquery() {
+        let sql =
+            "SELECT name, (SELECT COUNT(*) FROM orders WHERE orders.user_id = users.id) FROM users";
+        let stmt = parse_sql(sql).unwrap();
+
+        match stmt {
+            Statement::Select(select) => match select.columns {
+                SelectColumn::Items(items) => {
+                    assert_eq!(items.len(), 2);
+                    match &items[1] {
+                        SelectItem::Expr(Expr::ScalarSubquery(subquery)) => {
+                            assert_eq!(subquery.from, FromClause::Table("orders".to_string()));
+                            assert!(subquery.where_clause.is_some());
+                        }
+                        other => panic!("Expected scalar subquery, got: {:?}", other),
+                    }
+                }
+                other => panic!("Expected select items, got: {:?}", other),
+            },
+            _ => panic!("Expected Select statement"),
+        }
+
+        let sql = "SELECT id FROM users WHERE id = (SELECT MAX(user_id) FROM orders)";
+        match parse_sql(sql).unwrap() {
+            Statement::Select(select) => match select.where_clause.expect("where clause") {
+                Expr::BinaryOp { right, .. } => {
+                    assert!(matches!(*right, Expr::ScalarSubquery(_)))
+                }
+                other => panic!("Expected comparison, got: {:?}", other),
+            },
+            _ => panic!("Expected Select statement"),
+        }
+
+        assert!(parse_sql("SELECT (SELECT id FROM users FROM users").is_err());
+    }
+
+    #[test]
+    fn test_parse_is_distinct_from() {
+        let sql = "SELECT id FROM users WHERE a IS NOT DISTINCT FROM b AND a IS DISTINCT FROM NULL";
+        match parse_sql(sql).unwrap() {
+            Statement::Select(select) => {
+                assert_eq!(
+                    select.where_clause.expect("where clause"),
+                    Expr::binary_op(
+                        Expr::binary_op(
+                            Expr::Column(ColumnRef::new(None, "a".to_string())),
+                            BinaryOp::IsNotDistinctFrom,
+                            Expr::Column(ColumnRef::new(None, "b".to_string())),
+                        ),
+                        BinaryOp::And,
+                        Expr::binary_op(
+                            Expr::Column(ColumnRef::new(None, "a".to_string())),
+                            BinaryOp::IsDistinctFrom,
+                            Expr::Literal(Literal::Null),
+                        ),
+                    )
+                );
+            }
+            _ => panic!("Expected Select statement"),
+        }
+
+        assert!(parse_sql("SELECT id FROM users WHERE a IS DISTINCT b").is_err());
+    }
+
+    #[test]
+    fn test_parse_interval_arithmetic() {
+        let sql = "SELECT id FROM events WHERE created > CURRENT_TIMESTAMP - INTERVAL '7' DAY";
+        match parse_sql(sql).unwrap() {
+            Statement::Select(select) => match select.where_clause.expect("where clause") {
+                Expr::BinaryOp { op, right, .. } => {
+                    assert_eq!(op, BinaryOp::Gt);
+                    assert_eq!(
+                        *right,
+                        Expr::binary_op(
+                            Expr::CurrentTimestamp,
+                            BinaryOp::Minus,
+                            Expr::Literal(Literal::Interval(
+                                crate::types::Interval::parse("7", "DAY").unwrap()
+                            )),
+                        )
+                    );
+                }
+                other => panic!("Expected comparison, got: {:?}", other),
+            },
+            _ => panic!("Expected Select statement"),
+        }
+
+        let sql = "SELECT id, created + INTERVAL '1' HOUR FROM events";
+        match parse_sql(sql).unwrap() {
+            Statement::Select(select) => match select.columns {
+                SelectColumn::Items(items) => match &items[1] {
+                    SelectItem::Expr(Expr::BinaryOp { left, op, .. }) => {
+                        assert_eq!(op, &BinaryOp::Plus);
+                        assert_eq!(
+                            **left,
+                            Expr::Column(ColumnRef::new(None, "created".to_string()))
+                        );
+                    }
+                    other => panic!("Expected arithmetic item, got: {:?}", other),
+                },
+                other => panic!("Expected select items, got: {:?}", other),
+            },
+            _ => panic!("Expected Select statement"),
+        }
+
+        // A minus sign before a digit is still a negative literal
+        let mut tokenizer = Tokenizer::new("-5 - INTERVAL");
+        assert_eq!(
+            tokenizer.tokenize().unwrap(),
+            vec![
+                Token::IntegerLiteral(-5),
+                Token::Minus,
+                Token::Interval,
+                Token::Eof
+            ]
+        );
+
+        assert!(parse_sql("SELECT id FROM t WHERE d > INTERVAL '1' FORTNIGHT").is_err());
+        assert!(parse_sql("SELECT id FROM t WHERE d > INTERVAL 'x' DAY").is_err());
+    }
+
+    #[test]
+    fn test_parse_division_and_modulo_bind_tighter_than_addition() {
+        let column = |name: &str| Expr::Column(ColumnRef::new(None, name.to_string()));
+        let sql = "SELECT id FROM t WHERE a - b / 2 % c = 1";
+        match parse_sql(sql).unwrap() {
+            Statement::Select(select) => match select.where_clause.expect("where clause") {
+                Expr::BinaryOp { left, op, .. } => {
+                    assert_eq!(op, BinaryOp::Eq);
+                    assert_eq!(
+                        *left,
+                        Expr::binary_op(
+                            column("a"),
+                            BinaryOp::Minus,
+                            Expr::binary_op(
+                                Expr::binary_op(
+                                    column("b"),
+                                    BinaryOp::Divide,
+                                    Expr::Literal(Literal::Integer(2)),
+                                ),
+                                BinaryOp::Mod,
+                                column("c"),
+                            ),
+                        )
+                    );
+                }
+                other => panic!("Expected comparison, got: {:?}", other),
+            },
+            _ => panic!("Expected Select statement"),
+        }
+
+        match parse_sql("SELECT id, id % 2 + 1 FROM t").unwrap() {
+            Statement::Select(select) => match select.columns {
+                SelectColumn::Items(items) => assert_eq!(
+                    items[1],
+                    SelectItem::Expr(Expr::binary_op(
+                        Expr::binary_op(
+                            column("id"),
+                            BinaryOp::Mod,
+                            Expr::Literal(Literal::Integer(2)),
+                        ),
+                        BinaryOp::Plus,
+                        Expr::Literal(Literal::Integer(1)),
+                    ))
+                ),
+                other => panic!("Expected select items, got: {:?}", other),
+            },
+            _ => panic!("Expected Select statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_like_normalizes_escape_clause() {
+        let like = |sql: &str| match parse_sql(sql).unwrap() {
+            Statement::Select(select) => select.where_clause.expect("where clause"),
+            _ => panic!("Expected Select statement"),
+        };
+        let expected = |pattern: &str| {
+            Expr::binary_op(
+                Expr::Column(ColumnRef::new(None, "name".to_string())),
+                BinaryOp::Like,
+                Expr::Literal(Literal::String(pattern.to_string())),
+            )
+        };
+
+        assert_eq!(
+            like("SELECT * FROM t WHERE name LIKE 'Al%'"),
+            expected("Al%")
+        );
+        assert_eq!(
+            like("SELECT * FROM t WHERE name LIKE '100!%%' ESCAPE '!'"),
+            expected("100\\%%")
+        );
+        assert!(parse_sql("SELECT * FROM t WHERE name LIKE 'a%' ESCAPE '!!'").is_err());
+        assert!(parse_sql("SELECT * FROM t WHERE name LIKE 'a!' ESCAPE '!'").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_column_and_json_extract() {
+        match parse_sql("CREATE TABLE docs (id INTEGER, body JSON)").unwrap() {
+            Statement::CreateTable(create) => {
+                assert_eq!(create.columns[1].data_type, DataType::Json)
+            }
+            _ => panic!("Expected CreateTable statement"),
+        }
+
+        match parse_sql("SELECT id FROM docs WHERE json_extract(body, '$.a[0]') = 1").unwrap() {
+            Statement::Select(select) => assert_eq!(
+                select.where_clause,
+                Some(Expr::binary_op(
+                    Expr::JsonExtract {
+                        expr: Box::new(Expr::Column(ColumnRef::new(None, "body".to_string()))),
+                        path: "$.a[0]".to_string(),
+                    },
+                    BinaryOp::Eq,
+                    Expr::Literal(Literal::Integer(1)),
+                ))
+            ),
+            _ => panic!("Expected Select statement"),
+        }
+
+        assert!(parse_sql("SELECT json_extract(body, 'a') FROM docs").is_err());
+        assert!(parse_sql("SELECT json_extract(body, id) FROM docs").is_err());
+    }
+
     #[test]
     fn test_parse_alter_table_add_column() {
         let sql = "ALTER TABLE users ADD COLUMN age INTEGER";
@@ -332,6 +600,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_alter_table_add_column_not_null_default() {
+        let sql = "ALTER TABLE users ADD COLUMN status VARCHAR NOT NULL DEFAULT 'active'";
+        match parse_sql(sql).unwrap() {
+            Statement::AlterTable(alter) => match alter.action {
+                crate::sql::ast::AlterTableAction::AddColumn(col) => {
+                    assert!(col.is_not_null);
+                    assert_eq!(col.default, Some(Literal::String("active".to_string())));
+                }
+                _ => panic!("Expected AddColumn action"),
+            },
+            _ => panic!("Expected AlterTable statement"),
+        }
+
+        assert!(parse_sql("ALTER TABLE users ADD COLUMN n INTEGER UNIQUE").is_err());
+        assert!(parse_sql("ALTER TABLE users ADD COLUMN n INTEGER DEFAULT").is_err());
+    }
+
+    #[test]
+    fn test_parse_create_table_rejects_default() {
+        let err = parse_sql("CREATE TABLE users (id INTEGER, age INTEGER DEFAULT 0)").unwrap_err();
+        assert!(err.to_string().contains("ADD COLUMN"), "{}", err);
+    }
+
     #[test]
     fn test_parse_alter_table_drop_column() {
         let sql = "ALTER TABLE users DROP COLUMN age";
@@ -367,6 +659,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_alter_table_alter_column_type() {
+        for sql in [
+            "ALTER TABLE users ALTER COLUMN age TYPE DECIMAL",
+            "ALTER TABLE users ALTER age type DECIMAL",
+        ] {
+            match parse_sql(sql).unwrap() {
+                Statement::AlterTable(alter) => match alter.action {
+                    crate::sql::ast::AlterTableAction::AlterColumnType { column, data_type } => {
+                        assert_eq!(alter.table_name, "users");
+                        assert_eq!(column, "age");
+                        assert_eq!(data_type, DataType::Decimal);
+                    }
+                    _ => panic!("Expected AlterColumnType action"),
+                },
+                _ => panic!("Expected AlterTable statement"),
+            }
+        }
+        assert!(parse_sql("ALTER TABLE users ALTER COLUMN age DECIMAL").is_err());
+    }
+
     #[test]
     fn test_parse_alter_table_rename_table() {
         let sql = "ALTER TABLE users RENAME TO customers";
@@ -426,6 +739,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_qualified_wildcard() {
+        let stmt = parse_sql(
+            "SELECT users.*, orders.id FROM users JOIN orders ON users.id = orders.user_id",
+        )
+        .unwrap();
+        let Statement::Select(select) = stmt else {
+            panic!("Expected SELECT statement");
+        };
+        assert_eq!(
+            select.columns,
+            SelectColumn::Items(vec![
+                SelectItem::QualifiedAll("users".to_string()),
+                SelectItem::Column(ColumnRef::new(Some("orders".to_string()), "id")),
+            ])
+        );
+    }
+
     #[test]
     fn test_parse_select_join() {
         let sql = "SELECT users.id, orders.amount FROM users JOIN orders ON users.id = orders.user_id WHERE orders.amount > 10";
@@ -457,15 +788,18 @@ mod tests {
                     FromClause::Join {
                         left_table,
                         right_table,
-                        left_column,
-                        right_column,
+                        condition,
                     } => {
                         assert_eq!(left_table, "users");
                         assert_eq!(right_table, "orders");
-                        assert_eq!(left_column.table.as_deref(), Some("users"));
-                        assert_eq!(left_column.column, "id");
-                        assert_eq!(right_column.table.as_deref(), Some("orders"));
-                        assert_eq!(right_column.column, "user_id");
+                        assert_eq!(
+                            condition,
+                            Expr::binary_op(
+                                Expr::Column(ColumnRef::new(Some("users".to_string()), "id")),
+                                BinaryOp::Eq,
+                                Expr::Column(ColumnRef::new(Some("orders".to_string()), "user_id")),
+                            )
+                        );
                     }
                     _ => panic!("Expected JOIN in FROM"),
                 }
@@ -474,6 +808,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_drop_index_on_columns() {
+        let stmt = parse_sql("DROP INDEX ON users (email, id)").unwrap();
+
+        match stmt {
+            Statement::DropIndex(drop) => assert_eq!(
+                drop.target,
+                DropIndexTarget::Columns {
+                    table_name: "users".to_string(),
+                    columns: vec!["email".to_string(), "id".to_string()],
+                }
+            ),
+            _ => panic!("Expected DropIndex statement"),
+        }
+
+        match parse_sql("DROP INDEX idx_email").unwrap() {
+            Statement::DropIndex(drop) => {
+                assert_eq!(drop.target, DropIndexTarget::Name("idx_email".to_string()))
+            }
+            _ => panic!("Expected DropIndex statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_reindex() {
+        match parse_sql("REINDEX idx_email;").unwrap() {
+            Statement::Reindex(reindex) => assert_eq!(reindex.index_name, "idx_email"),
+            _ => panic!("Expected Reindex statement"),
+        }
+
+        assert!(parse_sql("REINDEX").is_err());
+    }
+
+    #[test]
+    fn test_parse_show() {
+        match parse_sql("SHOW TABLES").unwrap() {
+            Statement::Show(show) => assert_eq!(show.target, ShowTarget::Tables),
+            _ => panic!("Expected Show statement"),
+        }
+
+        match parse_sql("show indexes").unwrap() {
+            Statement::Show(show) => {
+                assert_eq!(show.target, ShowTarget::Indexes { table_name: None })
+            }
+            _ => panic!("Expected Show statement"),
+        }
+
+        match parse_sql("SHOW INDEXES FROM users;").unwrap() {
+            Statement::Show(show) => assert_eq!(
+                show.target,
+                ShowTarget::Indexes {
+                    table_name: Some("users".to_string())
+                }
+            ),
+            _ => panic!("Expected Show statement"),
+        }
+
+        assert!(parse_sql("SHOW").is_err());
+        assert!(parse_sql("SHOW COLUMNS").is_err());
+        assert!(parse_sql("SHOW INDEXES FROM").is_err());
+    }
+
+    #[test]
+    fn test_parse_describe() {
+        for sql in [
+            "DESCRIBE users",
+            "desc users",
+            "EXPLAIN COLUMNS users",
+            "EXPLAIN COLUMNS FROM users;",
+        ] {
+            match parse_sql(sql).unwrap() {
+                Statement::Describe(describe) => assert_eq!(describe.table_name, "users"),
+                _ => panic!("Expected Describe statement for {}", sql),
+            }
+        }
+
+        // DESC keeps its meaning as a sort direction
+        match parse_sql("SELECT id FROM users ORDER BY id DESC").unwrap() {
+            Statement::Select(select) => assert!(!select.order_by[0].ascending),
+            _ => panic!("Expected Select statement"),
+        }
+
+        assert!(parse_sql("DESCRIBE").is_err());
+        assert!(parse_sql("EXPLAIN users").is_err());
+    }
+
     #[test]
     fn test_parse_delete_without_where() {
         let stmt = parse_sql("DELETE FROM users").unwrap();
@@ -531,6 +951,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_create_index_if_not_exists() {
+        let stmt = parse_sql("CREATE UNIQUE INDEX IF NOT EXISTS idx_id ON items(id)").unwrap();
+        match stmt {
+            Statement::CreateIndex(create) => {
+                assert_eq!(create.index_name, "idx_id");
+                assert!(create.is_unique);
+                assert!(create.if_not_exists);
+            }
+            _ => panic!("Expected CreateIndex statement"),
+        }
+
+        match parse_sql("CREATE INDEX idx_id ON items(id)").unwrap() {
+            Statement::CreateIndex(create) => assert!(!create.if_not_exists),
+            _ => panic!("Expected CreateIndex statement"),
+        }
+        assert!(parse_sql("CREATE INDEX IF EXISTS idx_id ON items(id)").is_err());
+    }
+
     #[test]
     fn test_parse_statement_with_trailing_semicolon() {
         let stmt = parse_sql("SELECT * FROM users;").unwrap();
@@ -560,6 +999,62 @@ mod tests {
         assert!(matches!(stmts[1], Statement::Insert(_)));
     }
 
+    #[test]
+    fn test_parse_statements_spanned_continues_past_errors() {
+        let sql = "CREATE TABLE t (id INTEGER);\n SELEC * FROM t ;; INSERT INTO t VALUES (1)";
+        let parsed = parse_sql_statements_spanned(sql);
+        assert_eq!(parsed.len(), 3);
+
+        assert_eq!(parsed[0].source(sql), "CREATE TABLE t (id INTEGER)");
+        assert!(matches!(parsed[0].result, Ok(Statement::CreateTable(_))));
+
+        assert_eq!(parsed[1].source(sql), "SELEC * FROM t");
+        assert!(matches!(
+            parsed[1].result,
+            Err(ParseError::UnexpectedToken { .. })
+        ));
+
+        assert_eq!(parsed[2].source(sql), "INSERT INTO t VALUES (1)");
+        assert!(matches!(parsed[2].result, Ok(Statement::Insert(_))));
+    }
+
+    #[test]
+    fn test_parse_statements_spanned_tokenizer_errors() {
+        let sql = "SELECT # FROM t; SELECT * FROM t WHERE id = 1e; SELECT * FROM t";
+        let parsed = parse_sql_statements_spanned(sql);
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0].source(sql), "SELECT # FROM t");
+        assert!(matches!(
+            parsed[0].result,
+            Err(ParseError::InvalidSyntax(_))
+        ));
+        assert_eq!(parsed[1].source(sql), "SELECT * FROM t WHERE id = 1e");
+        assert!(matches!(
+            parsed[1].result,
+            Err(ParseError::InvalidSyntax(_))
+        ));
+        assert!(matches!(parsed[2].result, Ok(Statement::Select(_))));
+
+        // An unterminated string swallows the rest of the input
+        let sql = "SELECT 'abc; SELECT * FROM t";
+        let parsed = parse_sql_statements_spanned(sql);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].source(sql), sql);
+        assert!(parsed[0].result.is_err());
+    }
+
+    #[test]
+    fn test_parse_statements_spanned_requires_separators() {
+        let parsed = parse_sql_statements_spanned("BEGIN COMMIT; ÄÖ;");
+        assert_eq!(parsed.len(), 2);
+        assert!(matches!(
+            parsed[0].result,
+            Err(ParseError::UnexpectedToken { .. })
+        ));
+        assert_eq!(parsed[1].span, 14..18);
+        assert!(parse_sql_statements_spanned(" ;; ").is_empty());
+    }
+
     #[test]
     fn test_parse_multiple_statements_with_extra_semicolons() {
         let stmts = parse_sql_statements("BEGIN;;COMMIT;").unwrap();
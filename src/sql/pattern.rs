@@ -0,0 +1,127 @@
+//! LIKE pattern matching
+//!
+//! Patterns use `%` for any run of characters, `_` for exactly one, and `\`
+//! to make the next character literal. The parser folds an `ESCAPE` clause
+//! into this form, so patterns reaching the executor always escape with `\`.
+
+/// Escape character of a pattern once the parser has normalized it
+pub const ESCAPE_CHAR: char = '\\';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    /// `%`
+    AnyRun,
+    /// `_`
+    AnyChar,
+    Literal(char),
+}
+
+fn tokenize(pattern: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '%' => Token::AnyRun,
+            '_' => Token::AnyChar,
+            // A trailing escape has nothing to quote and stands for itself
+            ESCAPE_CHAR => Token::Literal(chars.next().unwrap_or(ESCAPE_CHAR)),
+            c => Token::Literal(c),
+        });
+    }
+    tokens
+}
+
+/// Rewrite `pattern`, written with `escape` as its escape character, to use
+/// [`ESCAPE_CHAR`]
+///
+/// `escape` of `None` means the pattern has no escape character at all.
+///
+/// # Errors
+/// Returns an error if the pattern ends with the escape character
+pub fn normalize(pattern: &str, escape: Option<char>) -> Result<String, String> {
+    if escape == Some(ESCAPE_CHAR) {
+        return Ok(pattern.to_string());
+    }
+    let mut normalized = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if Some(c) == escape {
+            let quoted = chars.next().ok_or_else(|| {
+                format!("LIKE pattern '{}' ends with its escape character", pattern)
+            })?;
+            normalized.push(ESCAPE_CHAR);
+            normalized.push(quoted);
+        } else if c == ESCAPE_CHAR {
+            normalized.push(ESCAPE_CHAR);
+            normalized.push(c);
+        } else {
+            normalized.push(c);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Whether `text` matches the whole of `pattern`
+pub fn matches(text: &str, pattern: &str) -> bool {
+    let tokens = tokenize(pattern);
+    let text: Vec<char> = text.chars().collect();
+
+    // Greedy matching that backtracks to the most recent `%` on a mismatch
+    let (mut t, mut p) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match tokens.get(p) {
+            Some(Token::AnyRun) => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(Token::AnyChar) => {
+                t += 1;
+                p += 1;
+            }
+            Some(Token::Literal(c)) if *c == text[t] => {
+                t += 1;
+                p += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    backtrack = Some((star, start + 1));
+                    p = star + 1;
+                    t = start + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[p..].iter().all(|token| *token == Token::AnyRun)
+}
+
+/// Literal text every match of `pattern` starts with, and whether the pattern
+/// is nothing but that text
+pub fn literal_prefix(pattern: &str) -> (String, bool) {
+    let mut prefix = String::new();
+    for token in tokenize(pattern) {
+        match token {
+            Token::Literal(c) => prefix.push(c),
+            Token::AnyRun | Token::AnyChar => return (prefix, false),
+        }
+    }
+    (prefix, true)
+}
+
+/// Smallest string greater than every string starting with `prefix`, or
+/// `None` if there is no such string
+pub fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(last) = chars.pop() {
+        let next = match last {
+            '\u{D7FF}' => Some('\u{E000}'),
+            c => char::from_u32(c as u32 + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
@@ -0,0 +1,58 @@
+mod tests {
+    use crate::sql::pattern::{literal_prefix, matches, normalize, prefix_upper_bound};
+
+    #[test]
+    fn test_wildcards() {
+        assert!(matches("Alice", "Al%"));
+        assert!(matches("Al", "Al%"));
+        assert!(!matches("Bob", "Al%"));
+        assert!(matches("Alice", "%ice"));
+        assert!(matches("Alice", "A_i_e"));
+        assert!(!matches("Alice", "A_e"));
+        assert!(matches("banana", "%an%na"));
+        assert!(matches("", "%"));
+        assert!(!matches("", "_"));
+        assert!(matches("abc", "abc"));
+        assert!(!matches("abcd", "abc"));
+    }
+
+    #[test]
+    fn test_escaped_wildcards_are_literal() {
+        assert!(matches("100%", "100\\%"));
+        assert!(!matches("1000", "100\\%"));
+        assert!(matches("a_b", "a\\_b"));
+        assert!(!matches("axb", "a\\_b"));
+        assert!(matches("a\\b", "a\\\\b"));
+    }
+
+    #[test]
+    fn test_normalize_rewrites_the_escape_character() {
+        assert_eq!(normalize("100!%", Some('!')).unwrap(), "100\\%");
+        assert_eq!(normalize("a\\b!!", Some('!')).unwrap(), "a\\\\b\\!");
+        assert_eq!(normalize("a\\%", None).unwrap(), "a\\\\%");
+        assert_eq!(normalize("a\\%", Some('\\')).unwrap(), "a\\%");
+        assert!(normalize("abc!", Some('!')).is_err());
+
+        let pattern = normalize("50!% off%", Some('!')).unwrap();
+        assert!(matches("50% off today", &pattern));
+        assert!(!matches("50 off today", &pattern));
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(literal_prefix("Al%"), ("Al".to_string(), false));
+        assert_eq!(literal_prefix("Al_x"), ("Al".to_string(), false));
+        assert_eq!(literal_prefix("%x"), (String::new(), false));
+        assert_eq!(literal_prefix("Alice"), ("Alice".to_string(), true));
+        assert_eq!(literal_prefix("100\\%%"), ("100%".to_string(), false));
+    }
+
+    #[test]
+    fn test_prefix_upper_bound() {
+        assert_eq!(prefix_upper_bound("Al").as_deref(), Some("Am"));
+        assert_eq!(prefix_upper_bound("a\u{10FFFF}").as_deref(), Some("b"));
+        assert_eq!(prefix_upper_bound("\u{D7FF}").as_deref(), Some("\u{E000}"));
+        assert_eq!(prefix_upper_bound("\u{10FFFF}"), None);
+        assert_eq!(prefix_upper_bound(""), None);
+    }
+}
@@ -1,15 +1,32 @@
+use super::compression::Compression;
 use super::file::DiskManager;
 use super::page::{Page, PageId, PageType};
+use std::cell::{RefCell, RefMut};
 use std::collections::HashMap;
 use std::io;
+use std::ops::{Deref, DerefMut};
 use std::path::Path;
+use std::rc::Rc;
 
 /// Frame ID in the buffer pool
 type FrameId = usize;
 
+/// Identifies one of the files whose pages a buffer pool caches
+pub type FileId = u32;
+
+/// A buffer pool that several files (e.g. every table of a database) fetch
+/// their pages through, so they share one memory budget
+///
+/// The pool is single-threaded: it is shared through `Rc`, so it and
+/// everything holding it (tables, the executor) are not `Send`.
+pub type SharedBufferPool = Rc<RefCell<BufferPool>>;
+
 /// Buffer pool entry
 struct Frame {
-    page: Page,
+    file_id: FileId,
+    /// Shared with the [`PoolFile`] that last fetched the page, whose guard
+    /// borrows it in place
+    page: Rc<RefCell<Page>>,
     is_dirty: bool,
     pin_count: usize,
 }
@@ -18,35 +35,81 @@ struct Frame {
 ///
 /// Manages a fixed-size cache of pages in memory with dirty tracking.
 /// Pages are evicted using LRU (Least Recently Used) policy.
+///
+/// The pool caches pages of any number of files. Each file is opened in the
+/// pool once and then named by its [`FileId`]; an evicted dirty page is
+/// written back through the disk manager of the file it came from.
 pub struct BufferPool {
     /// Storage for page frames
     frames: Vec<Option<Frame>>,
-    /// Maps page_id to frame_id
-    page_table: HashMap<PageId, FrameId>,
+    /// Maps (file_id, page_id) to frame_id
+    page_table: HashMap<(FileId, PageId), FrameId>,
     /// LRU tracking: list of frame_ids in order of use (most recent at back)
     lru_list: Vec<FrameId>,
-    /// Disk manager for I/O
-    disk_manager: DiskManager,
+    /// Disk manager of every open file
+    files: HashMap<FileId, DiskManager>,
+    /// ID handed to the next file opened in the pool
+    next_file_id: FileId,
+    /// Number of `fetch_page` calls, whether or not they hit the cache
+    fetch_count: u64,
 }
 
 impl BufferPool {
-    /// Create a new buffer pool
+    /// Create a new buffer pool with no files open
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum number of pages to cache, across all files
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: (0..capacity).map(|_| None).collect(),
+            page_table: HashMap::new(),
+            lru_list: Vec::new(),
+            files: HashMap::new(),
+            next_file_id: 0,
+            fetch_count: 0,
+        }
+    }
+
+    /// Create a new buffer pool that can be shared between files
     ///
     /// # Arguments
-    /// * `capacity` - Maximum number of pages to cache
-    /// * `db_path` - Path to the database file
+    /// * `capacity` - Maximum number of pages to cache, across all files
+    pub fn shared(capacity: usize) -> SharedBufferPool {
+        Rc::new(RefCell::new(Self::new(capacity)))
+    }
+
+    /// Open a database file so its pages can be fetched through the pool
     ///
     /// # Errors
-    /// Returns error if database file cannot be opened
-    pub fn new(capacity: usize, db_path: impl AsRef<Path>) -> io::Result<Self> {
-        let disk_manager = DiskManager::open(db_path)?;
-        let frames = (0..capacity).map(|_| None).collect();
+    /// Returns error if the file cannot be opened
+    pub fn open_file(&mut self, path: impl AsRef<Path>) -> io::Result<FileId> {
+        Ok(self.add_file(DiskManager::open(path)?))
+    }
 
-        Ok(Self {
-            frames,
-            page_table: HashMap::new(),
-            lru_list: Vec::new(),
-            disk_manager,
+    /// Add a file whose pages live only in memory
+    pub fn open_in_memory_file(&mut self) -> FileId {
+        self.add_file(DiskManager::in_memory())
+    }
+
+    fn add_file(&mut self, disk_manager: DiskManager) -> FileId {
+        let file_id = self.next_file_id;
+        self.next_file_id += 1;
+        self.files.insert(file_id, disk_manager);
+        file_id
+    }
+
+    /// Close a file, discarding its cached pages without writing them back
+    pub fn close_file(&mut self, file_id: FileId) {
+        self.discard_pages(file_id, 0);
+        self.files.remove(&file_id);
+    }
+
+    fn disk_manager(&mut self, file_id: FileId) -> io::Result<&mut DiskManager> {
+        self.files.get_mut(&file_id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("File {} is not open in the buffer pool", file_id),
+            )
         })
     }
 
@@ -56,6 +119,7 @@ impl BufferPool {
     /// The page is pinned and marked as recently used.
     ///
     /// # Arguments
+    /// * `file_id` - File the page belongs to
     /// * `page_id` - ID of the page to fetch
     ///
     /// # Returns
@@ -63,34 +127,45 @@ impl BufferPool {
     ///
     /// # Errors
     /// Returns error if:
+    /// - The file is not open in the pool
     /// - All frames are pinned (cannot evict)
     /// - Disk I/O fails
-    pub fn fetch_page(&mut self, page_id: PageId) -> io::Result<&mut Page> {
+    pub fn fetch_page(&mut self, file_id: FileId, page_id: PageId) -> io::Result<&mut Page> {
+        let frame_id = self.fetch_frame(file_id, page_id)?;
+        Ok(self.frame_page_mut(frame_id))
+    }
+
+    /// Pin a page, loading it if needed, and return its frame
+    fn fetch_frame(&mut self, file_id: FileId, page_id: PageId) -> io::Result<FrameId> {
+        self.fetch_count += 1;
+
         // Check if page is already in buffer pool
-        if let Some(&frame_id) = self.page_table.get(&page_id) {
+        if let Some(&frame_id) = self.page_table.get(&(file_id, page_id)) {
             self.mark_recently_used(frame_id);
             let frame = self.frames[frame_id].as_mut().unwrap();
             frame.pin_count += 1;
-            return Ok(&mut frame.page);
+            return Ok(frame_id);
         }
 
-        // Need to load from disk - find a frame
-        let frame_id = self.find_victim_frame()?;
+        // Load page from disk before making room, so a missing page doesn't
+        // cost another page its frame
+        let page = self.disk_manager(file_id)?.read_page(page_id)?;
 
-        // Load page from disk
-        let page = self.disk_manager.read_page(page_id)?;
+        // Find a frame
+        let frame_id = self.find_victim_frame()?;
 
         // Insert into frame
         self.frames[frame_id] = Some(Frame {
-            page,
+            file_id,
+            page: Rc::new(RefCell::new(page)),
             is_dirty: false,
             pin_count: 1,
         });
 
-        self.page_table.insert(page_id, frame_id);
+        self.page_table.insert((file_id, page_id), frame_id);
         self.mark_recently_used(frame_id);
 
-        Ok(&mut self.frames[frame_id].as_mut().unwrap().page)
+        Ok(frame_id)
     }
 
     /// Create a new page
@@ -98,6 +173,7 @@ impl BufferPool {
     /// Allocates a new page on disk and loads it into the buffer pool.
     ///
     /// # Arguments
+    /// * `file_id` - File to allocate the page in
     /// * `page_type` - Type of page to create
     ///
     /// # Returns
@@ -105,9 +181,34 @@ impl BufferPool {
     ///
     /// # Errors
     /// Returns error if allocation or fetch fails
-    pub fn new_page(&mut self, page_type: PageType) -> io::Result<&mut Page> {
-        let page_id = self.disk_manager.allocate_page(page_type)?;
-        self.fetch_page(page_id)
+    pub fn new_page(&mut self, file_id: FileId, page_type: PageType) -> io::Result<&mut Page> {
+        let frame_id = self.new_frame(file_id, page_type)?;
+        Ok(self.frame_page_mut(frame_id))
+    }
+
+    /// Allocate a page, pin it and return its frame
+    fn new_frame(&mut self, file_id: FileId, page_type: PageType) -> io::Result<FrameId> {
+        let page_id = self.disk_manager(file_id)?.allocate_page(page_type)?;
+        self.fetch_frame(file_id, page_id)
+    }
+
+    /// The page cached in a pinned frame
+    ///
+    /// Pages of a [`PoolFile`] are only fetched through its handle, so the
+    /// frame's page is not shared here and is never copied.
+    fn frame_page_mut(&mut self, frame_id: FrameId) -> &mut Page {
+        let frame = self.frames[frame_id]
+            .as_mut()
+            .expect("pinned pages stay in their frame");
+        Rc::make_mut(&mut frame.page).get_mut()
+    }
+
+    /// The cell a pinned frame keeps its page in, for a [`PageGuard`] to borrow
+    fn frame_page(&self, frame_id: FrameId) -> Rc<RefCell<Page>> {
+        let frame = self.frames[frame_id]
+            .as_ref()
+            .expect("pinned pages stay in their frame");
+        Rc::clone(&frame.page)
     }
 
     /// Unpin a page
@@ -115,10 +216,11 @@ impl BufferPool {
     /// Decreases the pin count. When pin count reaches 0, the page can be evicted.
     ///
     /// # Arguments
+    /// * `file_id` - File the page belongs to
     /// * `page_id` - ID of the page to unpin
     /// * `is_dirty` - Whether the page was modified
-    pub fn unpin_page(&mut self, page_id: PageId, is_dirty: bool) {
-        if let Some(&frame_id) = self.page_table.get(&page_id)
+    pub fn unpin_page(&mut self, file_id: FileId, page_id: PageId, is_dirty: bool) {
+        if let Some(&frame_id) = self.page_table.get(&(file_id, page_id))
             && let Some(frame) = &mut self.frames[frame_id]
         {
             if frame.pin_count > 0 {
@@ -133,40 +235,124 @@ impl BufferPool {
     /// Flush a specific page to disk
     ///
     /// # Arguments
+    /// * `file_id` - File the page belongs to
     /// * `page_id` - ID of the page to flush
     ///
     /// # Errors
     /// Returns error if:
     /// - Page is not in buffer pool
     /// - Disk write fails
-    pub fn flush_page(&mut self, page_id: PageId) -> io::Result<()> {
-        let frame_id = self
+    pub fn flush_page(&mut self, file_id: FileId, page_id: PageId) -> io::Result<()> {
+        let frame_id = *self
             .page_table
-            .get(&page_id)
+            .get(&(file_id, page_id))
             .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Page not in buffer pool"))?;
 
-        if let Some(frame) = &mut self.frames[*frame_id]
+        if let Some(frame) = &mut self.frames[frame_id]
             && frame.is_dirty
         {
-            self.disk_manager.write_page(&frame.page)?;
+            let disk_manager = self.files.get_mut(&file_id).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "File not open in buffer pool")
+            })?;
+            disk_manager.write_page(&frame.page.borrow())?;
             frame.is_dirty = false;
         }
 
         Ok(())
     }
 
-    /// Flush all dirty pages to disk
+    /// Flush all dirty pages of one file to disk
     ///
     /// # Errors
     /// Returns error if any disk write fails
-    pub fn flush_all(&mut self) -> io::Result<()> {
+    pub fn flush_file(&mut self, file_id: FileId) -> io::Result<()> {
+        let disk_manager = self.files.get_mut(&file_id).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "File not open in buffer pool")
+        })?;
         for frame in self.frames.iter_mut().flatten() {
-            if frame.is_dirty {
-                self.disk_manager.write_page(&frame.page)?;
+            if frame.file_id == file_id && frame.is_dirty {
+                disk_manager.write_page(&frame.page.borrow())?;
                 frame.is_dirty = false;
             }
         }
-        self.disk_manager.flush()
+        disk_manager.flush()
+    }
+
+    /// Flush all dirty pages of every file to disk
+    ///
+    /// # Errors
+    /// Returns error if any disk write fails
+    pub fn flush_all(&mut self) -> io::Result<()> {
+        let mut file_ids: Vec<FileId> = self.files.keys().copied().collect();
+        file_ids.sort_unstable();
+        for file_id in file_ids {
+            self.flush_file(file_id)?;
+        }
+        Ok(())
+    }
+
+    /// Get the total number of pages in a file
+    pub fn num_pages(&mut self, file_id: FileId) -> io::Result<u32> {
+        self.disk_manager(file_id)?.num_pages()
+    }
+
+    /// Set the codec used when a file's pages are written back to disk
+    pub fn set_compression(&mut self, file_id: FileId, compression: Compression) {
+        if let Some(disk_manager) = self.files.get_mut(&file_id) {
+            disk_manager.set_compression(compression);
+        }
+    }
+
+    /// Total bytes occupied by a file's pages on disk
+    ///
+    /// Dirty pages that have not been flushed yet are counted as they were
+    /// last written. See [`DiskManager::stored_bytes`].
+    ///
+    /// # Errors
+    /// Returns error if the file is not open or its size cannot be read
+    pub fn stored_bytes(&mut self, file_id: FileId) -> io::Result<u64> {
+        self.disk_manager(file_id)?.stored_bytes()
+    }
+
+    /// Drop all pages at or beyond `num_pages` from the pool and the file
+    ///
+    /// Cached frames for the removed pages are discarded without being written back.
+    ///
+    /// # Errors
+    /// Returns error if any removed page is still pinned or the file cannot be shrunk
+    pub fn truncate(&mut self, file_id: FileId, num_pages: u32) -> io::Result<()> {
+        let pinned = self.page_table.iter().any(|(&(file, page_id), &frame_id)| {
+            file == file_id
+                && page_id >= num_pages
+                && self.frames[frame_id]
+                    .as_ref()
+                    .is_some_and(|frame| frame.pin_count > 0)
+        });
+        if pinned {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot truncate a pinned page",
+            ));
+        }
+
+        self.discard_pages(file_id, num_pages);
+        self.disk_manager(file_id)?.truncate(num_pages)
+    }
+
+    /// Forget the cached pages of `file_id` at or beyond `from_page`
+    fn discard_pages(&mut self, file_id: FileId, from_page: PageId) {
+        let removed: Vec<((FileId, PageId), FrameId)> = self
+            .page_table
+            .iter()
+            .filter(|&(&(file, page_id), _)| file == file_id && page_id >= from_page)
+            .map(|(&key, &frame_id)| (key, frame_id))
+            .collect();
+
+        for (key, frame_id) in removed {
+            self.page_table.remove(&key);
+            self.frames[frame_id] = None;
+            self.lru_list.retain(|&id| id != frame_id);
+        }
     }
 
     /// Find a victim frame for eviction using LRU policy
@@ -203,7 +389,7 @@ impl BufferPool {
 
     /// Evict a frame
     ///
-    /// If the frame is dirty, writes it to disk first.
+    /// If the frame is dirty, writes it to its file first.
     ///
     /// # Arguments
     /// * `frame_id` - Frame to evict
@@ -213,12 +399,15 @@ impl BufferPool {
     fn evict_frame(&mut self, frame_id: FrameId) -> io::Result<FrameId> {
         if let Some(frame) = &self.frames[frame_id] {
             // Write to disk if dirty
-            if frame.is_dirty {
-                self.disk_manager.write_page(&frame.page)?;
+            if frame.is_dirty
+                && let Some(disk_manager) = self.files.get_mut(&frame.file_id)
+            {
+                disk_manager.write_page(&frame.page.borrow())?;
             }
 
             // Remove from page table
-            self.page_table.remove(&frame.page.page_id());
+            self.page_table
+                .remove(&(frame.file_id, frame.page.borrow().page_id()));
         }
 
         // Clear the frame
@@ -242,4 +431,152 @@ impl BufferPool {
     pub fn size(&self) -> usize {
         self.page_table.len()
     }
+
+    /// Maximum number of pages the pool caches
+    pub fn capacity(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Total number of page fetches so far, a rough measure of work done
+    pub fn fetch_count(&self) -> u64 {
+        self.fetch_count
+    }
+}
+
+/// One file's pages in a (possibly shared) buffer pool
+///
+/// Offers the buffer pool's page API without the file ID. A fetched page is
+/// returned as a [`PageGuard`] that must be dropped before the next call on
+/// the same handle; the pool itself is not borrowed meanwhile, so other files
+/// can fetch their pages. Dropping the handle closes the file in the pool,
+/// discarding its cached pages without writing them back.
+pub struct PoolFile {
+    pool: SharedBufferPool,
+    file_id: FileId,
+    /// Frame of the page last fetched, which its guard borrows
+    held: Option<Rc<RefCell<Page>>>,
+}
+
+impl PoolFile {
+    /// Open a database file in `pool`
+    ///
+    /// # Errors
+    /// Returns error if the file cannot be opened
+    pub fn open(pool: &SharedBufferPool, path: impl AsRef<Path>) -> io::Result<Self> {
+        let file_id = pool.borrow_mut().open_file(path)?;
+        Ok(Self {
+            pool: Rc::clone(pool),
+            file_id,
+            held: None,
+        })
+    }
+
+    /// Add a file to `pool` whose pages live only in memory
+    pub fn in_memory(pool: &SharedBufferPool) -> Self {
+        let file_id = pool.borrow_mut().open_in_memory_file();
+        Self {
+            pool: Rc::clone(pool),
+            file_id,
+            held: None,
+        }
+    }
+
+    /// See [`BufferPool::fetch_page`]
+    pub fn fetch_page(&mut self, page_id: PageId) -> io::Result<PageGuard<'_>> {
+        let page = {
+            let mut pool = self.pool.borrow_mut();
+            let frame_id = pool.fetch_frame(self.file_id, page_id)?;
+            pool.frame_page(frame_id)
+        };
+        Ok(self.guard(page))
+    }
+
+    /// See [`BufferPool::new_page`]
+    pub fn new_page(&mut self, page_type: PageType) -> io::Result<PageGuard<'_>> {
+        let page = {
+            let mut pool = self.pool.borrow_mut();
+            let frame_id = pool.new_frame(self.file_id, page_type)?;
+            pool.frame_page(frame_id)
+        };
+        Ok(self.guard(page))
+    }
+
+    fn guard(&mut self, page: Rc<RefCell<Page>>) -> PageGuard<'_> {
+        PageGuard {
+            page: self.held.insert(page).borrow_mut(),
+        }
+    }
+
+    /// See [`BufferPool::unpin_page`]
+    pub fn unpin_page(&mut self, page_id: PageId, is_dirty: bool) {
+        self.pool
+            .borrow_mut()
+            .unpin_page(self.file_id, page_id, is_dirty);
+    }
+
+    /// See [`BufferPool::flush_page`]
+    pub fn flush_page(&mut self, page_id: PageId) -> io::Result<()> {
+        self.pool.borrow_mut().flush_page(self.file_id, page_id)
+    }
+
+    /// Flush all of this file's dirty pages to disk
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.pool.borrow_mut().flush_file(self.file_id)
+    }
+
+    /// See [`BufferPool::num_pages`]
+    pub fn num_pages(&mut self) -> io::Result<u32> {
+        self.pool.borrow_mut().num_pages(self.file_id)
+    }
+
+    /// See [`BufferPool::set_compression`]
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.pool
+            .borrow_mut()
+            .set_compression(self.file_id, compression);
+    }
+
+    /// See [`BufferPool::stored_bytes`]
+    pub fn stored_bytes(&mut self) -> io::Result<u64> {
+        self.pool.borrow_mut().stored_bytes(self.file_id)
+    }
+
+    /// See [`BufferPool::truncate`]
+    pub fn truncate(&mut self, num_pages: u32) -> io::Result<()> {
+        self.pool.borrow_mut().truncate(self.file_id, num_pages)
+    }
+
+    /// Page fetches made through the whole pool so far
+    pub fn fetch_count(&self) -> u64 {
+        self.pool.borrow().fetch_count()
+    }
+}
+
+/// A pinned page fetched through a [`PoolFile`]
+///
+/// The guard borrows the page in its frame, so changes land in the pool
+/// directly; flushing the whole pool while it is held panics if the page is
+/// dirty. Unpinning (and marking the page dirty) is still up to the caller.
+pub struct PageGuard<'a> {
+    page: RefMut<'a, Page>,
+}
+
+impl Deref for PageGuard<'_> {
+    type Target = Page;
+
+    fn deref(&self) -> &Page {
+        &self.page
+    }
+}
+
+impl DerefMut for PageGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Page {
+        &mut self.page
+    }
+}
+
+impl Drop for PoolFile {
+    fn drop(&mut self) {
+        self.pool.borrow_mut().close_file(self.file_id);
+    }
 }
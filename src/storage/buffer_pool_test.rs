@@ -4,16 +4,17 @@ use tempfile::NamedTempFile;
 #[test]
 fn test_create_buffer_pool() {
     let temp_file = NamedTempFile::new().unwrap();
-    let pool = BufferPool::new(10, temp_file.path());
-    assert!(pool.is_ok());
+    let mut pool = BufferPool::new(10);
+    assert!(pool.open_file(temp_file.path()).is_ok());
 }
 
 #[test]
 fn test_new_page() {
     let temp_file = NamedTempFile::new().unwrap();
-    let mut pool = BufferPool::new(10, temp_file.path()).unwrap();
+    let mut pool = BufferPool::new(10);
+    let file = pool.open_file(temp_file.path()).unwrap();
 
-    let page = pool.new_page(PageType::Heap).unwrap();
+    let page = pool.new_page(file, PageType::Heap).unwrap();
     assert_eq!(page.page_id(), 0);
     assert_eq!(page.page_type(), PageType::Heap);
 }
@@ -21,17 +22,18 @@ fn test_new_page() {
 #[test]
 fn test_fetch_and_unpin() {
     let temp_file = NamedTempFile::new().unwrap();
-    let mut pool = BufferPool::new(10, temp_file.path()).unwrap();
+    let mut pool = BufferPool::new(10);
+    let file = pool.open_file(temp_file.path()).unwrap();
 
     // Create a page
-    let page = pool.new_page(PageType::Heap).unwrap();
+    let page = pool.new_page(file, PageType::Heap).unwrap();
     let page_id = page.page_id();
 
     // Unpin it
-    pool.unpin_page(page_id, false);
+    pool.unpin_page(file, page_id, false);
 
     // Fetch it again
-    let fetched = pool.fetch_page(page_id).unwrap();
+    let fetched = pool.fetch_page(file, page_id).unwrap();
     assert_eq!(fetched.page_id(), page_id);
 }
 
@@ -41,24 +43,26 @@ fn test_dirty_page_written_on_flush() {
     let path = temp_file.path().to_owned();
 
     {
-        let mut pool = BufferPool::new(10, &path).unwrap();
+        let mut pool = BufferPool::new(10);
+        let file = pool.open_file(&path).unwrap();
 
         // Create and modify a page
-        let page = pool.new_page(PageType::Heap).unwrap();
+        let page = pool.new_page(file, PageType::Heap).unwrap();
         let page_id = page.page_id();
         page.add_row(b"Test data").unwrap();
 
         // Unpin as dirty
-        pool.unpin_page(page_id, true);
+        pool.unpin_page(file, page_id, true);
 
         // Flush
-        pool.flush_page(page_id).unwrap();
+        pool.flush_page(file, page_id).unwrap();
     }
 
     // Verify data persisted
     {
-        let mut pool = BufferPool::new(10, &path).unwrap();
-        let page = pool.fetch_page(0).unwrap();
+        let mut pool = BufferPool::new(10);
+        let file = pool.open_file(&path).unwrap();
+        let page = pool.fetch_page(file, 0).unwrap();
         assert_eq!(page.get_row(0).unwrap(), b"Test data");
     }
 }
@@ -66,59 +70,61 @@ fn test_dirty_page_written_on_flush() {
 #[test]
 fn test_lru_eviction() {
     let temp_file = NamedTempFile::new().unwrap();
-    let mut pool = BufferPool::new(3, temp_file.path()).unwrap();
+    let mut pool = BufferPool::new(3);
+    let file = pool.open_file(temp_file.path()).unwrap();
 
     // Create 3 pages (fills the pool)
-    let page0 = pool.new_page(PageType::Heap).unwrap();
+    let page0 = pool.new_page(file, PageType::Heap).unwrap();
     page0.add_row(b"Page 0").unwrap();
-    pool.unpin_page(0, true);
+    pool.unpin_page(file, 0, true);
 
-    let page1 = pool.new_page(PageType::Heap).unwrap();
+    let page1 = pool.new_page(file, PageType::Heap).unwrap();
     page1.add_row(b"Page 1").unwrap();
-    pool.unpin_page(1, true);
+    pool.unpin_page(file, 1, true);
 
-    let page2 = pool.new_page(PageType::Heap).unwrap();
+    let page2 = pool.new_page(file, PageType::Heap).unwrap();
     page2.add_row(b"Page 2").unwrap();
-    pool.unpin_page(2, true);
+    pool.unpin_page(file, 2, true);
 
     assert_eq!(pool.size(), 3);
 
     // Access page 0 to make it more recently used
-    pool.fetch_page(0).unwrap();
-    pool.unpin_page(0, false);
+    pool.fetch_page(file, 0).unwrap();
+    pool.unpin_page(file, 0, false);
 
     // Create page 3 - should evict page 1 (least recently used)
-    let page3 = pool.new_page(PageType::Heap).unwrap();
+    let page3 = pool.new_page(file, PageType::Heap).unwrap();
     page3.add_row(b"Page 3").unwrap();
-    pool.unpin_page(3, true);
+    pool.unpin_page(file, 3, true);
 
     assert_eq!(pool.size(), 3);
 
     // Verify page 0, 2, 3 are in pool (page 1 was evicted)
-    assert!(pool.fetch_page(0).is_ok());
-    pool.unpin_page(0, false);
+    assert!(pool.fetch_page(file, 0).is_ok());
+    pool.unpin_page(file, 0, false);
 
-    assert!(pool.fetch_page(2).is_ok());
-    pool.unpin_page(2, false);
+    assert!(pool.fetch_page(file, 2).is_ok());
+    pool.unpin_page(file, 2, false);
 
-    assert!(pool.fetch_page(3).is_ok());
-    pool.unpin_page(3, false);
+    assert!(pool.fetch_page(file, 3).is_ok());
+    pool.unpin_page(file, 3, false);
 }
 
 #[test]
 fn test_pinned_pages_not_evicted() {
     let temp_file = NamedTempFile::new().unwrap();
-    let mut pool = BufferPool::new(2, temp_file.path()).unwrap();
+    let mut pool = BufferPool::new(2);
+    let file = pool.open_file(temp_file.path()).unwrap();
 
     // Create 2 pages and keep them both pinned
-    pool.new_page(PageType::Heap).unwrap();
+    pool.new_page(file, PageType::Heap).unwrap();
     // Leave page 0 pinned (don't unpin)
 
-    pool.new_page(PageType::Heap).unwrap();
+    pool.new_page(file, PageType::Heap).unwrap();
     // Leave page 1 pinned too (don't unpin)
 
     // Try to create a third page - should fail (both pages are pinned, can't evict)
-    let result = pool.new_page(PageType::Heap);
+    let result = pool.new_page(file, PageType::Heap);
     assert!(result.is_err());
 }
 
@@ -128,13 +134,14 @@ fn test_flush_all() {
     let path = temp_file.path().to_owned();
 
     {
-        let mut pool = BufferPool::new(10, &path).unwrap();
+        let mut pool = BufferPool::new(10);
+        let file = pool.open_file(&path).unwrap();
 
         // Create multiple dirty pages
         for i in 0..5 {
-            let page = pool.new_page(PageType::Heap).unwrap();
+            let page = pool.new_page(file, PageType::Heap).unwrap();
             page.add_row(format!("Page {}", i).as_bytes()).unwrap();
-            pool.unpin_page(i, true);
+            pool.unpin_page(file, i, true);
         }
 
         // Flush all
@@ -143,9 +150,10 @@ fn test_flush_all() {
 
     // Verify all persisted
     {
-        let mut pool = BufferPool::new(10, &path).unwrap();
+        let mut pool = BufferPool::new(10);
+        let file = pool.open_file(&path).unwrap();
         for i in 0..5 {
-            let page = pool.fetch_page(i).unwrap();
+            let page = pool.fetch_page(file, i).unwrap();
             assert_eq!(page.get_row(0).unwrap(), format!("Page {}", i).as_bytes());
         }
     }
@@ -154,41 +162,171 @@ fn test_flush_all() {
 #[test]
 fn test_dirty_eviction_writes_to_disk() {
     let temp_file = NamedTempFile::new().unwrap();
-    let mut pool = BufferPool::new(2, temp_file.path()).unwrap();
+    let mut pool = BufferPool::new(2);
+    let file = pool.open_file(temp_file.path()).unwrap();
 
     // Create page 0 and modify it
-    let page0 = pool.new_page(PageType::Heap).unwrap();
+    let page0 = pool.new_page(file, PageType::Heap).unwrap();
     page0.add_row(b"Modified page 0").unwrap();
-    pool.unpin_page(0, true); // Mark as dirty
+    pool.unpin_page(file, 0, true); // Mark as dirty
 
     // Create page 1
-    pool.new_page(PageType::Heap).unwrap();
-    pool.unpin_page(1, false);
+    pool.new_page(file, PageType::Heap).unwrap();
+    pool.unpin_page(file, 1, false);
 
     // Create page 2 - should evict page 0 (and write it because it's dirty)
-    pool.new_page(PageType::Heap).unwrap();
-    pool.unpin_page(2, false);
+    pool.new_page(file, PageType::Heap).unwrap();
+    pool.unpin_page(file, 2, false);
 
     // Fetch page 0 again - should load from disk with modifications intact
-    let reloaded = pool.fetch_page(0).unwrap();
+    let reloaded = pool.fetch_page(file, 0).unwrap();
     assert_eq!(reloaded.get_row(0).unwrap(), b"Modified page 0");
 }
 
 #[test]
 fn test_multiple_pins() {
     let temp_file = NamedTempFile::new().unwrap();
-    let mut pool = BufferPool::new(10, temp_file.path()).unwrap();
+    let mut pool = BufferPool::new(10);
+    let file = pool.open_file(temp_file.path()).unwrap();
 
-    pool.new_page(PageType::Heap).unwrap();
+    pool.new_page(file, PageType::Heap).unwrap();
 
     // Pin multiple times
-    pool.fetch_page(0).unwrap();
-    pool.fetch_page(0).unwrap();
+    pool.fetch_page(file, 0).unwrap();
+    pool.fetch_page(file, 0).unwrap();
 
     // Need to unpin multiple times
-    pool.unpin_page(0, false);
-    pool.unpin_page(0, false);
-    pool.unpin_page(0, false); // Original pin from new_page
+    pool.unpin_page(file, 0, false);
+    pool.unpin_page(file, 0, false);
+    pool.unpin_page(file, 0, false); // Original pin from new_page
 
     assert_eq!(pool.size(), 1);
 }
+
+#[test]
+fn test_truncate_discards_pages() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut pool = BufferPool::new(10);
+    let file = pool.open_file(temp_file.path()).unwrap();
+
+    for _ in 0..4 {
+        let page_id = pool.new_page(file, PageType::Heap).unwrap().page_id();
+        pool.unpin_page(file, page_id, true);
+    }
+    assert_eq!(pool.num_pages(file,).unwrap(), 4);
+
+    pool.truncate(file, 2).unwrap();
+    assert_eq!(pool.num_pages(file,).unwrap(), 2);
+    assert_eq!(pool.size(), 2);
+    assert!(pool.fetch_page(file, 3).is_err());
+
+    // Newly allocated pages continue from the truncated end
+    let page_id = pool.new_page(file, PageType::Heap).unwrap().page_id();
+    assert_eq!(page_id, 2);
+}
+
+#[test]
+fn test_truncate_rejects_pinned_page() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut pool = BufferPool::new(10);
+    let file = pool.open_file(temp_file.path()).unwrap();
+
+    pool.new_page(file, PageType::Heap).unwrap();
+    pool.new_page(file, PageType::Heap).unwrap();
+    pool.unpin_page(file, 0, false);
+
+    assert!(pool.truncate(file, 1).is_err());
+}
+
+#[test]
+fn test_files_share_frames() {
+    let file_a = NamedTempFile::new().unwrap();
+    let file_b = NamedTempFile::new().unwrap();
+    let mut pool = BufferPool::new(2);
+    let a = pool.open_file(file_a.path()).unwrap();
+    let b = pool.open_file(file_b.path()).unwrap();
+
+    // Page 0 of each file is a different page
+    pool.new_page(a, PageType::Heap)
+        .unwrap()
+        .add_row(b"from a")
+        .unwrap();
+    pool.unpin_page(a, 0, true);
+    pool.new_page(b, PageType::Heap)
+        .unwrap()
+        .add_row(b"from b")
+        .unwrap();
+    pool.unpin_page(b, 0, true);
+    assert_eq!(pool.size(), 2);
+
+    // A third page evicts a's dirty page, which goes back to a's file
+    pool.new_page(b, PageType::Heap).unwrap();
+    pool.unpin_page(b, 1, false);
+    assert_eq!(
+        DiskManager::open(file_a.path())
+            .unwrap()
+            .read_page(0)
+            .unwrap()
+            .get_row(0),
+        Some(&b"from a"[..])
+    );
+    assert_eq!(
+        pool.fetch_page(a, 0).unwrap().get_row(0),
+        Some(&b"from a"[..])
+    );
+    pool.unpin_page(a, 0, false);
+    assert_eq!(
+        pool.fetch_page(b, 0).unwrap().get_row(0),
+        Some(&b"from b"[..])
+    );
+    pool.unpin_page(b, 0, false);
+
+    // Closing a file frees its frames without writing them back
+    pool.fetch_page(a, 0).unwrap().add_row(b"lost").unwrap();
+    pool.unpin_page(a, 0, true);
+    pool.close_file(a);
+    assert_eq!(pool.size(), 1);
+    assert!(pool.fetch_page(a, 0).is_err());
+    let page = DiskManager::open(file_a.path())
+        .unwrap()
+        .read_page(0)
+        .unwrap();
+    assert_eq!(page.num_rows(), 1);
+}
+
+#[test]
+fn test_pool_files_fetch_while_another_page_is_held() {
+    let file_a = NamedTempFile::new().unwrap();
+    let file_b = NamedTempFile::new().unwrap();
+    let pool = BufferPool::shared(4);
+    let mut table_a = PoolFile::open(&pool, file_a.path()).unwrap();
+    let mut table_b = PoolFile::open(&pool, file_b.path()).unwrap();
+    for table in [&mut table_a, &mut table_b] {
+        drop(table.new_page(PageType::Heap).unwrap());
+        table.unpin_page(0, true);
+    }
+
+    // Holding one table's page doesn't keep the other from fetching
+    let mut page_a = table_a.fetch_page(0).unwrap();
+    let mut page_b = table_b.fetch_page(0).unwrap();
+    page_a.add_row(b"from a").unwrap();
+    page_b.add_row(b"from b").unwrap();
+    drop(page_a);
+    drop(page_b);
+    table_a.unpin_page(0, true);
+    table_b.unpin_page(0, true);
+    table_a.flush().unwrap();
+    table_b.flush().unwrap();
+
+    let mut reread = BufferPool::new(2);
+    let file = reread.open_file(file_a.path()).unwrap();
+    assert_eq!(
+        reread.fetch_page(file, 0).unwrap().get_row(0),
+        Some(&b"from a"[..])
+    );
+    let file = reread.open_file(file_b.path()).unwrap();
+    assert_eq!(
+        reread.fetch_page(file, 0).unwrap().get_row(0),
+        Some(&b"from b"[..])
+    );
+}
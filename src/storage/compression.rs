@@ -0,0 +1,103 @@
+use std::io;
+
+/// Codec applied to pages when they are written to disk
+///
+/// Pages are always uncompressed in memory; the codec only changes the
+/// on-disk representation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Pages are written verbatim
+    #[default]
+    None,
+    /// Run-length encoding: cheap, and effective on the zero-filled free space
+    /// in the middle of a page and on repetitive row data
+    Rle,
+}
+
+impl Compression {
+    /// Compress `data`, returning `None` if the codec is disabled
+    pub fn compress(&self, data: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            Compression::None => None,
+            Compression::Rle => Some(rle_encode(data)),
+        }
+    }
+}
+
+/// Control bytes below this value start a literal sequence of `c + 1` bytes;
+/// control bytes at or above it repeat the next byte `c - RUN_FLAG + MIN_RUN`
+/// times.
+const RUN_FLAG: u8 = 0x80;
+const MAX_LITERAL: usize = RUN_FLAG as usize;
+const MIN_RUN: usize = 3;
+const MAX_RUN: usize = (u8::MAX - RUN_FLAG) as usize + MIN_RUN;
+
+/// Run-length encode `data`
+pub fn rle_encode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+
+    while i < data.len() {
+        let run = data[i..]
+            .iter()
+            .take(MAX_RUN)
+            .take_while(|&&b| b == data[i])
+            .count();
+        if run >= MIN_RUN {
+            push_literals(&mut out, &data[literal_start..i]);
+            out.push(RUN_FLAG + (run - MIN_RUN) as u8);
+            out.push(data[i]);
+            i += run;
+            literal_start = i;
+        } else {
+            i += 1;
+        }
+    }
+    push_literals(&mut out, &data[literal_start..]);
+
+    out
+}
+
+fn push_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(MAX_LITERAL) {
+        out.push((chunk.len() - 1) as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+/// Decode run-length encoded `data` that must expand to exactly `expected_len`
+/// bytes
+///
+/// # Errors
+/// Returns `InvalidData` if the input is truncated or decodes to the wrong
+/// length
+pub fn rle_decode(data: &[u8], expected_len: usize) -> io::Result<Vec<u8>> {
+    let corrupt = || io::Error::new(io::ErrorKind::InvalidData, "Corrupt RLE page data");
+
+    let mut out = Vec::with_capacity(expected_len);
+    let mut i = 0;
+    while i < data.len() {
+        let control = data[i];
+        i += 1;
+        if control >= RUN_FLAG {
+            let byte = *data.get(i).ok_or_else(corrupt)?;
+            let run = (control - RUN_FLAG) as usize + MIN_RUN;
+            out.resize(out.len() + run, byte);
+            i += 1;
+        } else {
+            let len = control as usize + 1;
+            let literals = data.get(i..i + len).ok_or_else(corrupt)?;
+            out.extend_from_slice(literals);
+            i += len;
+        }
+        if out.len() > expected_len {
+            return Err(corrupt());
+        }
+    }
+
+    if out.len() != expected_len {
+        return Err(corrupt());
+    }
+    Ok(out)
+}
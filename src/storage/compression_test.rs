@@ -0,0 +1,36 @@
+use super::compression::{Compression, rle_decode, rle_encode};
+
+#[test]
+fn test_rle_round_trip() {
+    let mut data = vec![0u8; 500];
+    data.extend_from_slice(b"abcabcabc");
+    data.extend(std::iter::repeat_n(7u8, 300));
+    data.extend((0..=255u8).cycle().take(400));
+
+    let encoded = rle_encode(&data);
+    assert!(encoded.len() < data.len());
+    assert_eq!(rle_decode(&encoded, data.len()).unwrap(), data);
+}
+
+#[test]
+fn test_rle_empty_and_short_inputs() {
+    for data in [&b""[..], b"a", b"aa", b"aaa", b"ab"] {
+        let encoded = rle_encode(data);
+        assert_eq!(rle_decode(&encoded, data.len()).unwrap(), data);
+    }
+}
+
+#[test]
+fn test_rle_decode_rejects_corrupt_input() {
+    let encoded = rle_encode(&[1u8; 64]);
+    assert!(rle_decode(&encoded, 63).is_err());
+    assert!(rle_decode(&encoded[..1], 64).is_err());
+    // Literal header claiming more bytes than remain
+    assert!(rle_decode(&[5, 1, 2], 6).is_err());
+}
+
+#[test]
+fn test_compression_none_is_passthrough() {
+    assert_eq!(Compression::None.compress(b"aaaa"), None);
+    assert!(Compression::Rle.compress(b"aaaa").is_some());
+}
@@ -1,13 +1,145 @@
+use super::compression::{Compression, rle_decode};
 use super::page::{PAGE_SIZE, Page, PageId, PageType};
 use std::fs::{File, OpenOptions};
 use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
+/// Set in the on-disk page type field of a compressed page
+const COMPRESSED_FLAG: u16 = 0x8000;
+
+/// Compressed page header:
+/// - 2 bytes: page_type | COMPRESSED_FLAG
+/// - 4 bytes: compressed payload length
+const COMPRESSED_HEADER_SIZE: usize = 6;
+
 /// Manages disk I/O for pages
 ///
 /// Handles reading and writing fixed-size pages to/from a database file.
+///
+/// Every page keeps its fixed `PAGE_SIZE` slot in the file. When compression
+/// is enabled, a page that shrinks is written as a short header plus payload
+/// at the start of its slot and the rest of the slot is handed back to the
+/// filesystem by punching a hole in it (on Linux, where the filesystem
+/// supports it), so the file's length stays a whole number of slots while it
+/// occupies fewer disk blocks. Pages that don't shrink are written
+/// uncompressed; a flag in the page type field tells the two apart on read.
+///
+/// An in-memory disk manager lays its slots out the same way in a byte
+/// buffer instead of a file, and never touches the filesystem.
 pub struct DiskManager {
-    file: File,
+    backing: Backing,
+    compression: Compression,
+}
+
+/// Where a [`DiskManager`] keeps its page slots
+enum Backing {
+    File(File),
+    Memory(Vec<u8>),
+}
+
+impl Backing {
+    fn len(&self) -> io::Result<u64> {
+        match self {
+            Backing::File(file) => Ok(file.metadata()?.len()),
+            Backing::Memory(bytes) => Ok(bytes.len() as u64),
+        }
+    }
+
+    fn read_exact_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        match self {
+            Backing::File(file) => {
+                file.seek(SeekFrom::Start(offset))?;
+                file.read_exact(buf)
+            }
+            Backing::Memory(bytes) => {
+                let src = usize::try_from(offset)
+                    .ok()
+                    .and_then(|start| bytes.get(start..start.checked_add(buf.len())?))
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::UnexpectedEof, "failed to fill whole buffer")
+                    })?;
+                buf.copy_from_slice(src);
+                Ok(())
+            }
+        }
+    }
+
+    /// Write `parts` back to back starting at `offset`
+    fn write_all_at(&mut self, offset: u64, parts: &[&[u8]]) -> io::Result<()> {
+        match self {
+            Backing::File(file) => {
+                file.seek(SeekFrom::Start(offset))?;
+                for part in parts {
+                    file.write_all(part)?;
+                }
+                Ok(())
+            }
+            Backing::Memory(bytes) => {
+                let mut pos = offset as usize;
+                for part in parts {
+                    let end = pos + part.len();
+                    if bytes.len() < end {
+                        bytes.resize(end, 0);
+                    }
+                    bytes[pos..end].copy_from_slice(part);
+                    pos = end;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Give the bytes in `offset..offset + len` back to the filesystem; they
+    /// read as zeros afterwards
+    fn punch_hole(&mut self, offset: u64, len: u64) -> io::Result<()> {
+        match self {
+            Backing::File(file) => punch_file_hole(file, offset, len),
+            Backing::Memory(bytes) => {
+                let start = (offset as usize).min(bytes.len());
+                let end = (offset.saturating_add(len) as usize).min(bytes.len());
+                bytes[start..end].fill(0);
+                Ok(())
+            }
+        }
+    }
+
+    /// Bytes of disk the backing occupies, if it is a file on a platform
+    /// that reports them
+    fn allocated_bytes(&self) -> io::Result<Option<u64>> {
+        match self {
+            #[cfg(unix)]
+            Backing::File(file) => {
+                use std::os::unix::fs::MetadataExt;
+                // `blocks` counts 512-byte units whatever the block size
+                Ok(Some(file.metadata()?.blocks() * 512))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        match self {
+            Backing::File(file) => file.set_len(len),
+            Backing::Memory(bytes) => {
+                bytes.resize(len as usize, 0);
+                Ok(())
+            }
+        }
+    }
+
+    fn sync_data(&mut self) -> io::Result<()> {
+        match self {
+            Backing::File(file) => file.sync_data(),
+            Backing::Memory(_) => Ok(()),
+        }
+    }
+
+    fn sync_all(&mut self) -> io::Result<()> {
+        match self {
+            Backing::File(file) => file.sync_all(),
+            Backing::Memory(_) => Ok(()),
+        }
+    }
 }
 
 impl DiskManager {
@@ -22,7 +154,30 @@ impl DiskManager {
             .create(true)
             .open(path)?;
 
-        Ok(Self { file })
+        Ok(Self {
+            backing: Backing::File(file),
+            compression: Compression::None,
+        })
+    }
+
+    /// Create an empty disk manager that keeps its pages in memory
+    pub fn in_memory() -> Self {
+        Self {
+            backing: Backing::Memory(Vec::new()),
+            compression: Compression::None,
+        }
+    }
+
+    /// Set the codec used for subsequent page writes
+    ///
+    /// Pages already on disk keep their current encoding until rewritten.
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.compression = compression;
+    }
+
+    /// Get the codec used for page writes
+    pub fn compression(&self) -> Compression {
+        self.compression
     }
 
     /// Read a page from disk
@@ -37,10 +192,13 @@ impl DiskManager {
     /// - Page data is invalid
     pub fn read_page(&mut self, page_id: PageId) -> io::Result<Page> {
         let offset = (page_id as u64) * (PAGE_SIZE as u64);
-        self.file.seek(SeekFrom::Start(offset))?;
-
         let mut buffer = vec![0u8; PAGE_SIZE];
-        self.file.read_exact(&mut buffer)?;
+        self.backing.read_exact_at(offset, &mut buffer)?;
+
+        if let Some(payload_len) = compressed_payload_len(&buffer)? {
+            let payload = &buffer[COMPRESSED_HEADER_SIZE..COMPRESSED_HEADER_SIZE + payload_len];
+            buffer = rle_decode(payload, PAGE_SIZE)?;
+        }
 
         Page::from_bytes(&buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
     }
@@ -58,13 +216,73 @@ impl DiskManager {
         let page_id = page.page_id();
         let offset = (page_id as u64) * (PAGE_SIZE as u64);
 
-        self.file.seek(SeekFrom::Start(offset))?;
-        self.file.write_all(page.to_bytes())?;
-        self.file.sync_data()?;
+        match self.compression.compress(page.to_bytes()) {
+            Some(payload) if COMPRESSED_HEADER_SIZE + payload.len() < PAGE_SIZE => {
+                let page_type = page.page_type() as u16 | COMPRESSED_FLAG;
+                self.backing.write_all_at(
+                    offset,
+                    &[
+                        &page_type.to_le_bytes(),
+                        &(payload.len() as u32).to_le_bytes(),
+                        &payload,
+                    ],
+                )?;
+
+                // Keep the file a whole number of slots long, and drop
+                // whatever an earlier version of the page left in the tail
+                let slot_end = offset + PAGE_SIZE as u64;
+                if self.backing.len()? < slot_end {
+                    self.backing.set_len(slot_end)?;
+                }
+                let stored = (COMPRESSED_HEADER_SIZE + payload.len()) as u64;
+                self.backing
+                    .punch_hole(offset + stored, PAGE_SIZE as u64 - stored)?;
+            }
+            _ => self.backing.write_all_at(offset, &[page.to_bytes()])?,
+        }
+        self.backing.sync_data()?;
 
         Ok(())
     }
 
+    /// Number of bytes the page actually occupies in its slot
+    ///
+    /// This is `PAGE_SIZE` for an uncompressed page and the header plus
+    /// payload length for a compressed one.
+    ///
+    /// # Errors
+    /// Returns error if the page header cannot be read
+    pub fn stored_page_size(&mut self, page_id: PageId) -> io::Result<usize> {
+        let offset = (page_id as u64) * (PAGE_SIZE as u64);
+        let mut header = [0u8; COMPRESSED_HEADER_SIZE];
+        self.backing.read_exact_at(offset, &mut header)?;
+
+        Ok(match compressed_payload_len(&header)? {
+            Some(payload_len) => COMPRESSED_HEADER_SIZE + payload_len,
+            None => PAGE_SIZE,
+        })
+    }
+
+    /// Bytes the file occupies on disk
+    ///
+    /// For a file this is what the filesystem has allocated to it, so slot
+    /// tails released by compressed pages don't count. For an in-memory disk
+    /// manager it is the sum of [`DiskManager::stored_page_size`] over all
+    /// pages.
+    ///
+    /// # Errors
+    /// Returns error if the file metadata or a page header cannot be read
+    pub fn stored_bytes(&mut self) -> io::Result<u64> {
+        if let Some(allocated) = self.backing.allocated_bytes()? {
+            return Ok(allocated);
+        }
+        let mut total = 0;
+        for page_id in 0..self.num_pages()? {
+            total += self.stored_page_size(page_id)? as u64;
+        }
+        Ok(total)
+    }
+
     /// Allocate a new page on disk
     ///
     /// Returns the ID of the newly allocated page
@@ -72,7 +290,7 @@ impl DiskManager {
     /// # Errors
     /// Returns error if file metadata cannot be read or page cannot be written
     pub fn allocate_page(&mut self, page_type: PageType) -> io::Result<PageId> {
-        let file_len = self.file.metadata()?.len();
+        let file_len = self.backing.len()?;
         let page_id = (file_len / PAGE_SIZE as u64) as PageId;
 
         let page = Page::new(page_id, page_type);
@@ -83,12 +301,87 @@ impl DiskManager {
 
     /// Get the total number of pages in the file
     pub fn num_pages(&mut self) -> io::Result<u32> {
-        let file_len = self.file.metadata()?.len();
+        let file_len = self.backing.len()?;
         Ok((file_len / PAGE_SIZE as u64) as u32)
     }
 
+    /// Shrink the file so it holds exactly `num_pages` pages
+    ///
+    /// Pages at or beyond `num_pages` are discarded.
+    ///
+    /// # Errors
+    /// Returns error if the file length cannot be changed
+    pub fn truncate(&mut self, num_pages: u32) -> io::Result<()> {
+        self.backing.set_len(num_pages as u64 * PAGE_SIZE as u64)?;
+        self.backing.sync_all()
+    }
+
     /// Flush all writes to disk
     pub fn flush(&mut self) -> io::Result<()> {
-        self.file.sync_all()
+        self.backing.sync_all()
+    }
+}
+
+/// Deallocate a byte range of `file` without changing its length
+///
+/// Filesystems without hole punching keep the bytes allocated; they are
+/// never read, so nothing else changes.
+#[cfg(target_os = "linux")]
+fn punch_file_hole(file: &File, offset: u64, len: u64) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let (Ok(offset), Ok(len)) = (libc::off_t::try_from(offset), libc::off_t::try_from(len)) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Hole is beyond the largest file offset",
+        ));
+    };
+    // SAFETY: fallocate only reads its integer arguments, and the descriptor
+    // belongs to `file`, which stays open for the call
+    let result = unsafe {
+        libc::fallocate(
+            file.as_raw_fd(),
+            libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE,
+            offset,
+            len,
+        )
+    };
+    if result == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EOPNOTSUPP) => Ok(()),
+        _ => Err(err),
+    }
+}
+
+/// Deallocate a byte range of `file` without changing its length
+///
+/// Hole punching is only implemented on Linux; elsewhere the bytes stay
+/// allocated. They are never read, so nothing else changes.
+#[cfg(not(target_os = "linux"))]
+fn punch_file_hole(_file: &File, _offset: u64, _len: u64) -> io::Result<()> {
+    Ok(())
+}
+
+/// Payload length of a compressed page, or `None` if the slot holds an
+/// uncompressed page
+fn compressed_payload_len(slot: &[u8]) -> io::Result<Option<usize>> {
+    let page_type = u16::from_le_bytes([slot[0], slot[1]]);
+    if page_type & COMPRESSED_FLAG == 0 {
+        return Ok(None);
+    }
+
+    let payload_len = u32::from_le_bytes([slot[2], slot[3], slot[4], slot[5]]) as usize;
+    if COMPRESSED_HEADER_SIZE + payload_len > PAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Compressed page payload of {} bytes is too large",
+                payload_len
+            ),
+        ));
     }
+    Ok(Some(payload_len))
 }
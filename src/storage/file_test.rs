@@ -1,4 +1,5 @@
 use super::*;
+use crate::storage::Compression;
 use tempfile::NamedTempFile;
 
 #[test]
@@ -119,3 +120,148 @@ fn test_overwrite_page() {
     assert_eq!(read_page.num_rows(), 1);
     assert_eq!(read_page.get_row(0).unwrap(), b"Updated");
 }
+
+/// Fill a page with rows of pseudo-random bytes so it doesn't compress
+fn incompressible_page(page_id: PageId) -> Page {
+    let mut page = Page::new(page_id, PageType::Heap);
+    let mut state = 0x2545_f491_u32;
+    loop {
+        let row: Vec<u8> = (0..200)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        if page.add_row(&row).is_err() {
+            return page;
+        }
+    }
+}
+
+#[test]
+fn test_compressed_page_round_trip() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut dm = DiskManager::open(temp_file.path()).unwrap();
+    dm.set_compression(Compression::Rle);
+
+    let mut page = Page::new(0, PageType::BTreeLeaf);
+    page.add_row(&[b'x'; 500]).unwrap();
+    page.add_row(b"Hello, World!").unwrap();
+    dm.write_page(&page).unwrap();
+
+    assert_eq!(dm.num_pages().unwrap(), 1);
+    assert!(dm.stored_page_size(0).unwrap() < PAGE_SIZE / 4);
+
+    let read_page = dm.read_page(0).unwrap();
+    assert_eq!(read_page.page_type(), PageType::BTreeLeaf);
+    assert_eq!(read_page.to_bytes(), page.to_bytes());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_compressed_rewrite_releases_slot_tail() {
+    use std::os::unix::fs::MetadataExt;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut dm = DiskManager::open(temp_file.path()).unwrap();
+    let mut page = Page::new(0, PageType::Heap);
+    page.add_row(&[b'x'; 500]).unwrap();
+    dm.write_page(&page).unwrap();
+    let uncompressed = dm.stored_bytes().unwrap();
+    assert!(uncompressed >= PAGE_SIZE as u64);
+
+    dm.set_compression(Compression::Rle);
+    dm.write_page(&page).unwrap();
+    let metadata = std::fs::metadata(temp_file.path()).unwrap();
+    assert_eq!(metadata.len(), PAGE_SIZE as u64);
+    assert!(metadata.blocks() * 512 < uncompressed);
+    assert_eq!(dm.stored_bytes().unwrap(), metadata.blocks() * 512);
+    assert_eq!(dm.read_page(0).unwrap().to_bytes(), page.to_bytes());
+}
+
+#[test]
+fn test_incompressible_page_falls_back() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut dm = DiskManager::open(temp_file.path()).unwrap();
+    dm.set_compression(Compression::Rle);
+
+    let page = incompressible_page(0);
+    dm.write_page(&page).unwrap();
+
+    assert_eq!(dm.stored_page_size(0).unwrap(), PAGE_SIZE);
+    assert_eq!(dm.read_page(0).unwrap().to_bytes(), page.to_bytes());
+}
+
+#[test]
+fn test_mixed_compressed_and_uncompressed_pages() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let path = temp_file.path().to_owned();
+
+    {
+        let mut dm = DiskManager::open(&path).unwrap();
+        let mut page = Page::new(0, PageType::Heap);
+        page.add_row(b"written uncompressed").unwrap();
+        dm.write_page(&page).unwrap();
+
+        dm.set_compression(Compression::Rle);
+        let mut page = Page::new(1, PageType::Heap);
+        page.add_row(b"written compressed").unwrap();
+        dm.write_page(&page).unwrap();
+        dm.write_page(&incompressible_page(2)).unwrap();
+    }
+
+    // Reading doesn't depend on the codec setting
+    let mut dm = DiskManager::open(&path).unwrap();
+    assert_eq!(dm.num_pages().unwrap(), 3);
+    assert_eq!(dm.stored_page_size(0).unwrap(), PAGE_SIZE);
+    assert!(dm.stored_page_size(1).unwrap() < PAGE_SIZE);
+    assert_eq!(dm.stored_page_size(2).unwrap(), PAGE_SIZE);
+    assert_eq!(
+        dm.read_page(0).unwrap().get_row(0).unwrap(),
+        b"written uncompressed"
+    );
+    assert_eq!(
+        dm.read_page(1).unwrap().get_row(0).unwrap(),
+        b"written compressed"
+    );
+    assert_eq!(
+        dm.read_page(2).unwrap().to_bytes(),
+        incompressible_page(2).to_bytes()
+    );
+}
+
+#[test]
+fn test_in_memory_disk_manager() {
+    let mut dm = DiskManager::in_memory();
+    assert_eq!(dm.num_pages().unwrap(), 0);
+    assert_eq!(
+        dm.read_page(0).unwrap_err().kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+
+    for expected in 0..3 {
+        assert_eq!(dm.allocate_page(PageType::Heap).unwrap(), expected);
+    }
+    let mut page = Page::new(1, PageType::Heap);
+    page.add_row(b"kept in memory").unwrap();
+    dm.write_page(&page).unwrap();
+    assert_eq!(
+        dm.read_page(1).unwrap().get_row(0),
+        Some(&b"kept in memory"[..])
+    );
+
+    // Compressed pages keep their slot, as they would in a file
+    dm.set_compression(Compression::Rle);
+    let mut page = Page::new(2, PageType::Heap);
+    page.add_row(&[7u8; 500]).unwrap();
+    dm.write_page(&page).unwrap();
+    assert!(dm.stored_page_size(2).unwrap() < PAGE_SIZE);
+    assert_eq!(dm.read_page(2).unwrap().get_row(0), Some(&[7u8; 500][..]));
+    assert_eq!(dm.num_pages().unwrap(), 3);
+
+    dm.truncate(1).unwrap();
+    assert_eq!(dm.num_pages().unwrap(), 1);
+    assert!(dm.read_page(1).is_err());
+}
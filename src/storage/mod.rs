@@ -1,14 +1,18 @@
 pub mod buffer_pool;
+pub mod compression;
 pub mod file;
 pub mod page;
 
-pub use buffer_pool::BufferPool;
+pub use buffer_pool::{BufferPool, FileId, PageGuard, PoolFile, SharedBufferPool};
+pub use compression::Compression;
 pub use file::DiskManager;
-pub use page::{PAGE_SIZE, Page, PageError, PageId, PageType, SlotId};
+pub use page::{MAX_ROW_SIZE, PAGE_SIZE, Page, PageError, PageId, PageType, SlotId};
 
 #[cfg(test)]
 mod buffer_pool_test;
 #[cfg(test)]
+mod compression_test;
+#[cfg(test)]
 mod file_test;
 #[cfg(test)]
 mod page_test;
@@ -4,15 +4,22 @@ use std::io::{self, Cursor};
 pub const PAGE_SIZE: usize = 8192;
 
 /// Page header size:
-/// - 2 bytes: page_type
+/// - 2 bytes: page_type, with ALL_VISIBLE_FLAG
 /// - 4 bytes: page_id
 /// - 2 bytes: num_rows
 /// - 2 bytes: free_space_offset
 pub(super) const PAGE_HEADER_SIZE: usize = 10;
 
+/// Set in the page type field when every row on the page is visible to all
+/// snapshots
+const ALL_VISIBLE_FLAG: u16 = 0x0100;
+
 /// Size of each slot directory entry (offset + length)
 pub(super) const SLOT_ENTRY_SIZE: usize = 4;
 
+/// Largest row that fits on an empty page
+pub const MAX_ROW_SIZE: usize = PAGE_SIZE - PAGE_HEADER_SIZE - SLOT_ENTRY_SIZE;
+
 /// Page ID type
 pub type PageId = u32;
 
@@ -26,6 +33,8 @@ pub enum PageType {
     Heap = 0,
     BTreeInternal = 1,
     BTreeLeaf = 2,
+    /// One piece of a value too large to keep in its row
+    Overflow = 3,
 }
 
 impl PageType {
@@ -34,6 +43,7 @@ impl PageType {
             0 => Ok(PageType::Heap),
             1 => Ok(PageType::BTreeInternal),
             2 => Ok(PageType::BTreeLeaf),
+            3 => Ok(PageType::Overflow),
             _ => Err(PageError::InvalidPageType(value)),
         }
     }
@@ -95,7 +105,7 @@ struct SlotEntry {
 ///
 /// Page Layout:
 /// ```text
-/// [2 bytes: page_type]
+/// [2 bytes: page_type | all-visible flag]
 /// [4 bytes: page_id]
 /// [2 bytes: num_rows]
 /// [2 bytes: free_space_offset]
@@ -103,9 +113,15 @@ struct SlotEntry {
 /// [...free space...]
 /// [rows stored bottom-up]
 /// ```
+///
+/// The all-visible flag is a hint that lets scans skip per-row MVCC checks.
+/// It is cleared by every row mutation and only set again by whoever has
+/// checked every row on the page (vacuum, or an insert of an always-visible
+/// row into a page that already had the flag).
 #[derive(Clone)]
 pub struct Page {
     page_type: PageType,
+    all_visible: bool,
     page_id: PageId,
     num_rows: u16,
     free_space_offset: u16,
@@ -117,6 +133,8 @@ impl Page {
     pub fn new(page_id: PageId, page_type: PageType) -> Self {
         let mut page = Self {
             page_type,
+            // An empty heap page trivially has no invisible rows
+            all_visible: page_type == PageType::Heap,
             page_id,
             num_rows: 0,
             free_space_offset: PAGE_SIZE as u16,
@@ -141,14 +159,31 @@ impl Page {
         self.num_rows
     }
 
+    /// Returns true if every row on the page is known to be visible to all
+    /// snapshots
+    pub fn is_all_visible(&self) -> bool {
+        self.all_visible
+    }
+
+    /// Set or clear the all-visible hint
+    ///
+    /// Only set it after checking every row on the page; row mutations clear
+    /// it automatically.
+    pub fn set_all_visible(&mut self, all_visible: bool) {
+        self.all_visible = all_visible;
+        self.write_header();
+    }
+
     /// Write the page header to the data array
     fn write_header(&mut self) {
         let mut cursor = Cursor::new(&mut self.data[..]);
         use std::io::Write;
 
-        cursor
-            .write_all(&(self.page_type as u16).to_le_bytes())
-            .unwrap();
+        let mut page_type = self.page_type as u16;
+        if self.all_visible {
+            page_type |= ALL_VISIBLE_FLAG;
+        }
+        cursor.write_all(&page_type.to_le_bytes()).unwrap();
         cursor.write_all(&self.page_id.to_le_bytes()).unwrap();
         cursor.write_all(&self.num_rows.to_le_bytes()).unwrap();
         cursor
@@ -164,7 +199,8 @@ impl Page {
         let mut buf = [0u8; 2];
         cursor.read_exact(&mut buf).unwrap();
         let page_type_value = u16::from_le_bytes(buf);
-        self.page_type = PageType::from_u16(page_type_value).unwrap();
+        self.all_visible = page_type_value & ALL_VISIBLE_FLAG != 0;
+        self.page_type = PageType::from_u16(page_type_value & !ALL_VISIBLE_FLAG).unwrap();
 
         let mut buf = [0u8; 4];
         cursor.read_exact(&mut buf).unwrap();
@@ -245,6 +281,7 @@ impl Page {
         // Update header
         self.num_rows += 1;
         self.free_space_offset = new_offset as u16;
+        self.all_visible = false;
         self.write_header();
 
         Ok(slot_id)
@@ -286,6 +323,7 @@ impl Page {
             length: row_data.len() as u16,
         };
         self.write_slot_entry(slot_id, new_entry);
+        self.set_all_visible(false);
 
         Ok(())
     }
@@ -321,10 +359,25 @@ impl Page {
             length: 0,
         };
         self.write_slot_entry(slot_id, deleted_entry);
+        self.set_all_visible(false);
 
         Ok(())
     }
 
+    /// Returns true if the page holds no live rows
+    ///
+    /// Slots that were deleted (length 0) do not count as live.
+    pub fn is_empty(&self) -> bool {
+        self.live_rows() == 0
+    }
+
+    /// Number of slots that hold a row (deleted slots are excluded)
+    pub fn live_rows(&self) -> usize {
+        (0..self.num_rows)
+            .filter(|&slot_id| self.get_row(slot_id).is_some())
+            .count()
+    }
+
     /// Serialize the page to bytes
     pub fn to_bytes(&self) -> &[u8] {
         &self.data
@@ -344,6 +397,7 @@ impl Page {
 
         let mut page = Self {
             page_type: PageType::Heap,
+            all_visible: false,
             page_id: 0,
             num_rows: 0,
             free_space_offset: PAGE_SIZE as u16,
@@ -359,6 +413,7 @@ impl std::fmt::Debug for Page {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Page")
             .field("page_type", &self.page_type)
+            .field("all_visible", &self.all_visible)
             .field("page_id", &self.page_id)
             .field("num_rows", &self.num_rows)
             .field("free_space_offset", &self.free_space_offset)
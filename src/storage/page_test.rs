@@ -174,4 +174,43 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn test_is_empty_after_deletes() {
+        let mut page = Page::new(1, PageType::Heap);
+        assert!(page.is_empty());
+
+        let slot_a = page.add_row(b"a").unwrap();
+        let slot_b = page.add_row(b"b").unwrap();
+        assert!(!page.is_empty());
+
+        page.delete_row(slot_a).unwrap();
+        assert!(!page.is_empty());
+        page.delete_row(slot_b).unwrap();
+        assert!(page.is_empty());
+    }
+
+    #[test]
+    fn test_all_visible_flag() {
+        let mut page = Page::new(1, PageType::Heap);
+        assert!(page.is_all_visible());
+        assert!(!Page::new(2, PageType::BTreeLeaf).is_all_visible());
+
+        let slot = page.add_row(b"row").unwrap();
+        assert!(!page.is_all_visible());
+
+        page.set_all_visible(true);
+        let restored = Page::from_bytes(page.to_bytes()).unwrap();
+        assert!(restored.is_all_visible());
+        assert_eq!(restored.page_type(), PageType::Heap);
+        assert_eq!(restored.get_row(slot), Some(&b"row"[..]));
+
+        page.update_row(slot, b"new").unwrap();
+        assert!(!page.is_all_visible());
+
+        page.set_all_visible(true);
+        page.delete_row(slot).unwrap();
+        assert!(!page.is_all_visible());
+        assert!(!Page::from_bytes(page.to_bytes()).unwrap().is_all_visible());
+    }
 }
@@ -1,11 +1,17 @@
-use crate::serialization::{RowMetadata, RowSerializer};
-use crate::storage::{BufferPool, Page, PageError, PageId, PageType, SlotId};
+use crate::serialization::{OVERFLOW_POINTER_SIZE, OverflowPointer, RowMetadata, RowSerializer};
+use crate::storage::{
+    BufferPool, Compression, MAX_ROW_SIZE, Page, PageError, PageId, PageType, PoolFile,
+    SharedBufferPool, SlotId,
+};
 use crate::types::{Column, Schema, Value};
+use std::collections::{BTreeMap, BTreeSet};
 use std::io;
 use std::path::Path;
 
 /// Row identifier (page_id, slot_id)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Ids order by physical position, the order a sequential scan returns rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RowId {
     page_id: PageId,
     slot_id: SlotId,
@@ -28,20 +34,49 @@ impl RowId {
     }
 }
 
+/// Rows that serialize larger than this move their largest strings to
+/// overflow pages
+const OVERFLOW_THRESHOLD: usize = MAX_ROW_SIZE / 4;
+
+/// Each overflow page holds the id of the next page in the chain (0 ends it)
+/// followed by up to this many bytes of the value
+const OVERFLOW_CHUNK_SIZE: usize = MAX_ROW_SIZE - 4;
+
+/// Slot of the metadata page holding the table statistics
+const STATS_SLOT: SlotId = 2;
+
+/// Prefix of the statistics row, which tells it apart from the schema row
+/// (as a schema's column count it would be over 20000 columns, more than a
+/// page can describe)
+const STATS_PREFIX: &[u8] = b"STATS:";
+
 /// Heap table - unordered collection of rows stored in pages
 ///
-/// The first page (page 0) is reserved for metadata (table name and schema).
-/// Data pages are allocated as needed starting from page 1.
+/// The first page (page 0) is reserved for metadata (table name, schema, and
+/// the row count and free page list as of the last flush, so opening a table
+/// never scans it).
+/// Data pages are allocated as needed starting from page 1. Strings too large
+/// to keep in their row live in chains of overflow pages interleaved with the
+/// data pages; scans skip them and reads reassemble the full row.
 pub struct HeapTable {
     name: String,
     schema: Schema,
-    buffer_pool: BufferPool,
+    /// The table file's pages, cached in a buffer pool that may be shared
+    /// with other tables
+    buffer_pool: PoolFile,
     /// ID of the last data page (for quick appends)
     last_page_id: Option<PageId>,
+    /// Empty data pages available for reuse by future inserts
+    free_pages: BTreeSet<PageId>,
+    /// Number of stored row versions (including ones not yet vacuumed)
+    row_count: usize,
+    /// Whether the row count or free page list changed since they were last
+    /// written to the metadata page
+    stats_changed: bool,
 }
 
 impl HeapTable {
-    /// Create a new heap table
+    /// Create a new heap table with a buffer pool of its own
     ///
     /// # Arguments
     /// * `name` - Table name
@@ -57,30 +92,72 @@ impl HeapTable {
         db_path: impl AsRef<Path>,
         buffer_pool_size: usize,
     ) -> io::Result<Self> {
-        let name = name.into();
-        let mut buffer_pool = BufferPool::new(buffer_pool_size, db_path)?;
+        let pool = BufferPool::shared(buffer_pool_size);
+        Self::create_in_pool(name, schema, db_path, &pool)
+    }
 
-        // Create metadata page (page 0)
-        let metadata_page = buffer_pool.new_page(PageType::Heap)?;
-        let metadata = format!("TABLE:{}\n", name);
-        metadata_page.add_row(metadata.as_bytes())?;
+    /// Create a new heap table whose pages are cached in `pool`
+    ///
+    /// # Arguments
+    /// * `name` - Table name
+    /// * `schema` - Table schema
+    /// * `db_path` - Path to database file
+    /// * `pool` - Buffer pool shared with other tables
+    ///
+    /// # Errors
+    /// Returns error if the file can't be opened or the metadata page write fails
+    pub fn create_in_pool(
+        name: impl Into<String>,
+        schema: Schema,
+        db_path: impl AsRef<Path>,
+        pool: &SharedBufferPool,
+    ) -> io::Result<Self> {
+        Self::create_with_pool(name, schema, PoolFile::open(pool, db_path)?)
+    }
+
+    /// Create a new heap table that keeps its pages in memory instead of a
+    /// file
+    ///
+    /// # Arguments
+    /// * `name` - Table name
+    /// * `schema` - Table schema
+    /// * `pool` - Buffer pool shared with other tables
+    ///
+    /// # Errors
+    /// Returns error if the metadata page can't be written
+    pub fn create_in_memory(
+        name: impl Into<String>,
+        schema: Schema,
+        pool: &SharedBufferPool,
+    ) -> io::Result<Self> {
+        Self::create_with_pool(name, schema, PoolFile::in_memory(pool))
+    }
 
-        // Store schema information
-        let schema_data = serialize_schema(&schema);
-        metadata_page.add_row(&schema_data)?;
+    fn create_with_pool(
+        name: impl Into<String>,
+        schema: Schema,
+        mut buffer_pool: PoolFile,
+    ) -> io::Result<Self> {
+        let name = name.into();
 
-        buffer_pool.unpin_page(0, true);
-        buffer_pool.flush_page(0)?;
+        // Create metadata page (page 0)
+        drop(buffer_pool.new_page(PageType::Heap)?);
+        buffer_pool.unpin_page(0, false);
+        let free_pages = BTreeSet::new();
+        write_metadata(&mut buffer_pool, &name, &schema, 0, &free_pages)?;
 
         Ok(Self {
             name,
             schema,
             buffer_pool,
             last_page_id: None,
+            free_pages,
+            row_count: 0,
+            stats_changed: false,
         })
     }
 
-    /// Open an existing heap table
+    /// Open an existing heap table with a buffer pool of its own
     ///
     /// # Arguments
     /// * `db_path` - Path to database file
@@ -89,7 +166,20 @@ impl HeapTable {
     /// # Errors
     /// Returns error if buffer pool creation or metadata read fails
     pub fn open(db_path: impl AsRef<Path>, buffer_pool_size: usize) -> io::Result<Self> {
-        let mut buffer_pool = BufferPool::new(buffer_pool_size, db_path)?;
+        let pool = BufferPool::shared(buffer_pool_size);
+        Self::open_in_pool(db_path, &pool)
+    }
+
+    /// Open an existing heap table whose pages are cached in `pool`
+    ///
+    /// # Arguments
+    /// * `db_path` - Path to database file
+    /// * `pool` - Buffer pool shared with other tables
+    ///
+    /// # Errors
+    /// Returns error if the file can't be opened or the metadata read fails
+    pub fn open_in_pool(db_path: impl AsRef<Path>, pool: &SharedBufferPool) -> io::Result<Self> {
+        let mut buffer_pool = PoolFile::open(pool, db_path)?;
 
         // Read metadata page
         let metadata_page = buffer_pool.fetch_page(0)?;
@@ -106,28 +196,82 @@ impl HeapTable {
             .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid table name format"))?
             .to_string();
 
-        // Read schema (use the last non-empty row after the table name)
+        // Read schema (use the last non-empty row after the table name) and
+        // the statistics row
         let mut schema_bytes = None;
+        let mut stats = None;
         for slot_id in 1..metadata_page.num_rows() {
-            if let Some(bytes) = metadata_page.get_row(slot_id) {
-                schema_bytes = Some(bytes);
+            match metadata_page.get_row(slot_id) {
+                Some(bytes) if bytes.starts_with(STATS_PREFIX) => {
+                    stats = Some(deserialize_stats(&bytes[STATS_PREFIX.len()..])?);
+                }
+                Some(bytes) => schema_bytes = Some(bytes),
+                None => {}
             }
         }
         let schema_bytes = schema_bytes.ok_or_else(|| {
             io::Error::new(io::ErrorKind::InvalidData, "Missing schema in metadata")
         })?;
         let schema = deserialize_schema(schema_bytes)?;
+        drop(metadata_page);
 
         buffer_pool.unpin_page(0, false);
 
+        let (row_count, free_pages) = match stats {
+            // Pages written back after the list was recorded may have been
+            // reused since, so keep only the ones still empty
+            Some((row_count, recorded)) => {
+                let num_pages = buffer_pool.num_pages()?;
+                let mut free_pages = BTreeSet::new();
+                for page_id in recorded.into_iter().filter(|&id| id < num_pages) {
+                    let is_empty = buffer_pool.fetch_page(page_id)?.is_empty();
+                    buffer_pool.unpin_page(page_id, false);
+                    if is_empty {
+                        free_pages.insert(page_id);
+                    }
+                }
+                (row_count, free_pages)
+            }
+            // Files written before the statistics row existed: count stored
+            // rows from the slot directories and collect empty pages once
+            None => {
+                let mut row_count = 0;
+                let mut free_pages = BTreeSet::new();
+                for page_id in 1..buffer_pool.num_pages()? {
+                    let page = buffer_pool.fetch_page(page_id)?;
+                    if page.page_type() == PageType::Heap {
+                        row_count += page.live_rows();
+                        if page.is_empty() {
+                            free_pages.insert(page_id);
+                        }
+                    }
+                    drop(page);
+                    buffer_pool.unpin_page(page_id, false);
+                }
+                (row_count, free_pages)
+            }
+        };
+
         Ok(Self {
             name,
             schema,
             buffer_pool,
             last_page_id: None,
+            free_pages,
+            row_count,
+            stats_changed: false,
         })
     }
 
+    /// Number of row versions currently stored in the table.
+    ///
+    /// Maintained incrementally and written to the metadata page on flush, so
+    /// reading it never touches the data pages.
+    /// Rows deleted under MVCC still count until vacuum removes them.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
     /// Get the table name
     pub fn name(&self) -> &str {
         &self.name
@@ -140,21 +284,13 @@ impl HeapTable {
 
     pub fn rename(&mut self, new_name: impl Into<String>) -> io::Result<()> {
         let new_name = new_name.into();
-        let metadata = format!("TABLE:{}\n", new_name);
-        let schema_data = serialize_schema(&self.schema);
-
-        let metadata_page = self.buffer_pool.fetch_page(0)?;
-        let new_page = Page::new(0, PageType::Heap);
-        *metadata_page = new_page;
-        metadata_page
-            .add_row(metadata.as_bytes())
-            .map_err(io::Error::from)?;
-        metadata_page
-            .add_row(&schema_data)
-            .map_err(io::Error::from)?;
-        self.buffer_pool.unpin_page(0, true);
-        self.buffer_pool.flush_page(0)?;
-
+        write_metadata(
+            &mut self.buffer_pool,
+            &new_name,
+            &self.schema,
+            self.row_count,
+            &self.free_pages,
+        )?;
         self.name = new_name;
         Ok(())
     }
@@ -180,18 +316,38 @@ impl HeapTable {
     }
 
     fn persist_schema(&mut self, schema: &Schema) -> io::Result<()> {
-        let schema_data = serialize_schema(schema);
+        write_metadata(
+            &mut self.buffer_pool,
+            &self.name,
+            schema,
+            self.row_count,
+            &self.free_pages,
+        )
+    }
 
-        let metadata_page = self.buffer_pool.fetch_page(0)?;
-        if metadata_page.get_row(1).is_some() {
-            metadata_page.delete_row(1).map_err(io::Error::from)?;
+    /// Record the row count and free page list in the metadata page
+    ///
+    /// The statistics row is overwritten in place when the new one fits;
+    /// otherwise the whole metadata page is rewritten.
+    fn persist_stats(&mut self) -> io::Result<()> {
+        let stats = serialize_stats(self.row_count, self.free_pages.iter().copied());
+        let mut metadata_page = self.buffer_pool.fetch_page(0)?;
+        let in_place = metadata_page
+            .get_row(STATS_SLOT)
+            .is_some_and(|row| row.starts_with(STATS_PREFIX))
+            && metadata_page.update_row(STATS_SLOT, &stats).is_ok();
+        drop(metadata_page);
+        self.buffer_pool.unpin_page(0, in_place);
+        if in_place {
+            return Ok(());
         }
-        metadata_page
-            .add_row(&schema_data)
-            .map_err(io::Error::from)?;
-        self.buffer_pool.unpin_page(0, true);
-        self.buffer_pool.flush_page(0)?;
-        Ok(())
+        write_metadata(
+            &mut self.buffer_pool,
+            &self.name,
+            &self.schema,
+            self.row_count,
+            &self.free_pages,
+        )
     }
 
     /// Insert a row into the table
@@ -224,29 +380,214 @@ impl HeapTable {
             )
         })?;
 
-        // Serialize the row
+        let row_data = self.encode_row(row, metadata)?;
+
+        // Rows written outside a transaction are visible to every snapshot,
+        // so they don't spoil an all-visible page
+        let always_visible = metadata.xmin == 0 && metadata.xmax == 0;
+        let row_id = self.place_row(&row_data, always_visible)?;
+        self.row_count += 1;
+        self.stats_changed = true;
+        Ok(row_id)
+    }
+
+    /// Serialize a row, moving its largest strings to overflow pages until it
+    /// is no larger than `OVERFLOW_THRESHOLD`
+    fn encode_row(&mut self, row: &[Value], metadata: RowMetadata) -> io::Result<Vec<u8>> {
         let row_data = RowSerializer::serialize_with_metadata(row, Some(&self.schema), metadata)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if row_data.len() <= OVERFLOW_THRESHOLD {
+            return Ok(row_data);
+        }
+
+        let mut strings: Vec<(usize, usize)> = row
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| match value {
+                Value::String(s) if s.len() > OVERFLOW_POINTER_SIZE => Some((i, s.len())),
+                _ => None,
+            })
+            .collect();
+        strings.sort_by_key(|&(_, len)| std::cmp::Reverse(len));
+
+        let mut size = row_data.len();
+        let mut moved = Vec::new();
+        for (i, len) in strings {
+            if size <= OVERFLOW_THRESHOLD {
+                break;
+            }
+            // The length prefix and the data give way to a pointer
+            size = size - (4 + len) + OVERFLOW_POINTER_SIZE;
+            moved.push(i);
+        }
+        if size > MAX_ROW_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Row is {} bytes even with its strings in overflow pages, but a page holds at most {}",
+                    size, MAX_ROW_SIZE
+                ),
+            ));
+        }
+
+        let mut overflow = vec![None; row.len()];
+        for i in moved {
+            if let Value::String(s) = &row[i] {
+                overflow[i] = Some(self.write_overflow(s.as_bytes())?);
+            }
+        }
+        RowSerializer::serialize_with_overflow(row, Some(&self.schema), metadata, &overflow)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Deserialize a stored row, reading any strings kept in overflow pages
+    pub(crate) fn decode_row(&mut self, row_data: &[u8]) -> io::Result<(RowMetadata, Vec<Value>)> {
+        let buffer_pool = &mut self.buffer_pool;
+        RowSerializer::deserialize_with_overflow(row_data, &self.schema, |pointer| {
+            read_overflow(buffer_pool, pointer)
+        })
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Store `data` in a chain of overflow pages
+    ///
+    /// Pieces are written last first so each page can point at its successor.
+    fn write_overflow(&mut self, data: &[u8]) -> io::Result<OverflowPointer> {
+        let mut next: PageId = 0;
+        for chunk in data.chunks(OVERFLOW_CHUNK_SIZE).rev() {
+            let mut page = loop {
+                let Some(page_id) = self.free_pages.pop_first() else {
+                    break self.buffer_pool.new_page(PageType::Overflow)?;
+                };
+                // A listed page that holds rows again is dropped from the list
+                let mut page = self.buffer_pool.fetch_page(page_id)?;
+                if page.is_empty() {
+                    if self.last_page_id == Some(page_id) {
+                        self.last_page_id = None;
+                    }
+                    *page = Page::new(page_id, PageType::Overflow);
+                    break page;
+                }
+                drop(page);
+                self.buffer_pool.unpin_page(page_id, false);
+            };
+            let page_id = page.page_id();
+            let mut piece = Vec::with_capacity(4 + chunk.len());
+            piece.extend_from_slice(&next.to_le_bytes());
+            piece.extend_from_slice(chunk);
+            page.add_row(&piece)?;
+            drop(page);
+            self.buffer_pool.unpin_page(page_id, true);
+            next = page_id;
+        }
+
+        Ok(OverflowPointer {
+            len: data.len() as u32,
+            first_page: next,
+        })
+    }
+
+    /// Return the pages of an overflow chain to the free list
+    fn free_overflow(&mut self, pointer: OverflowPointer) -> io::Result<()> {
+        let mut page_id = pointer.first_page;
+        while page_id != 0 {
+            let mut page = self.buffer_pool.fetch_page(page_id)?;
+            let next = overflow_piece(&page).map(|(next, _)| next);
+            if next.is_some() {
+                *page = Page::new(page_id, PageType::Heap);
+            }
+            drop(page);
+            self.buffer_pool.unpin_page(page_id, next.is_some());
+            let Some(next) = next else {
+                return Err(broken_overflow_chain(page_id));
+            };
+            self.free_pages.insert(page_id);
+            page_id = next;
+        }
+        Ok(())
+    }
 
+    /// Copy the stored bytes of a row
+    fn read_stored_row(&mut self, row_id: RowId) -> io::Result<Vec<u8>> {
+        let page = self.buffer_pool.fetch_page(row_id.page_id)?;
+        let row_data = if page.page_type() == PageType::Heap {
+            page.get_row(row_id.slot_id).map(<[u8]>::to_vec)
+        } else {
+            None
+        };
+        drop(page);
+        self.buffer_pool.unpin_page(row_id.page_id, false);
+
+        row_data.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Row not found: {:?}", row_id),
+            )
+        })
+    }
+
+    /// Overflow chains referenced by stored row bytes
+    fn stored_overflow(row_data: &[u8], schema: &Schema) -> io::Result<Vec<OverflowPointer>> {
+        RowSerializer::overflow_pointers(row_data, schema)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Store serialized row bytes on the first page with room for them
+    ///
+    /// The page keeps its all-visible flag only if it already had it and the
+    /// new row is `always_visible`.
+    fn place_row(&mut self, row_data: &[u8], always_visible: bool) -> io::Result<RowId> {
         // Try to insert into the last page first
         if let Some(last_page_id) = self.last_page_id {
-            let page = self.buffer_pool.fetch_page(last_page_id)?;
-            match page.add_row(&row_data) {
+            let mut page = self.buffer_pool.fetch_page(last_page_id)?;
+            let was_all_visible = page.is_all_visible();
+            match page.add_row(row_data) {
                 Ok(slot_id) => {
+                    page.set_all_visible(was_all_visible && always_visible);
+                    drop(page);
                     self.buffer_pool.unpin_page(last_page_id, true);
                     return Ok(RowId::new(last_page_id, slot_id));
                 }
                 Err(_) => {
                     // Page is full, unpin and create a new one
+                    drop(page);
                     self.buffer_pool.unpin_page(last_page_id, false);
                 }
             }
         }
 
+        // Reuse a reclaimed page before growing the file, dropping listed
+        // pages that hold rows again
+        while let Some(page_id) = self.free_pages.pop_first() {
+            let mut page = self.buffer_pool.fetch_page(page_id)?;
+            if !page.is_empty() {
+                drop(page);
+                self.buffer_pool.unpin_page(page_id, false);
+                continue;
+            }
+            let was_all_visible = page.is_all_visible();
+            match page.add_row(row_data) {
+                Ok(slot_id) => {
+                    page.set_all_visible(was_all_visible && always_visible);
+                    drop(page);
+                    self.buffer_pool.unpin_page(page_id, true);
+                    self.last_page_id = Some(page_id);
+                    return Ok(RowId::new(page_id, slot_id));
+                }
+                Err(_) => {
+                    drop(page);
+                    self.buffer_pool.unpin_page(page_id, false);
+                }
+            }
+        }
+
         // Create a new data page
-        let page = self.buffer_pool.new_page(PageType::Heap)?;
+        let mut page = self.buffer_pool.new_page(PageType::Heap)?;
         let page_id = page.page_id();
-        let slot_id = page.add_row(&row_data)?;
+        let was_all_visible = page.is_all_visible();
+        let slot_id = page.add_row(row_data)?;
+        page.set_all_visible(was_all_visible && always_visible);
+        drop(page);
 
         self.buffer_pool.unpin_page(page_id, true);
         self.last_page_id = Some(page_id);
@@ -273,21 +614,21 @@ impl HeapTable {
     }
 
     pub fn get_with_metadata(&mut self, row_id: RowId) -> io::Result<(RowMetadata, Vec<Value>)> {
-        let page = self.buffer_pool.fetch_page(row_id.page_id)?;
-
-        let row_data = page.get_row(row_id.slot_id).ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::NotFound,
-                format!("Row not found: {:?}", row_id),
-            )
-        })?;
-
-        let (metadata, values) = RowSerializer::deserialize_with_metadata(row_data, &self.schema)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let row_data = self.read_stored_row(row_id)?;
+        self.decode_row(&row_data)
+    }
 
-        self.buffer_pool.unpin_page(row_id.page_id, false);
+    /// Overwrite a row's MVCC metadata without rewriting its values
+    pub fn set_metadata(&mut self, row_id: RowId, metadata: RowMetadata) -> io::Result<()> {
+        let mut row_data = self.read_stored_row(row_id)?;
+        RowSerializer::write_metadata(&mut row_data, metadata);
 
-        Ok((metadata, values))
+        let mut page = self.buffer_pool.fetch_page(row_id.page_id)?;
+        page.update_row(row_id.slot_id, &row_data)
+            .map_err(io::Error::from)?;
+        drop(page);
+        self.buffer_pool.unpin_page(row_id.page_id, true);
+        Ok(())
     }
 
     /// Delete a row from the table
@@ -300,13 +641,95 @@ impl HeapTable {
     /// - Page cannot be fetched
     /// - Row doesn't exist
     pub fn delete(&mut self, row_id: RowId) -> io::Result<()> {
-        let page = self.buffer_pool.fetch_page(row_id.page_id)?;
+        let row_data = self.read_stored_row(row_id)?;
+        let overflow = Self::stored_overflow(&row_data, &self.schema)?;
+
+        self.delete_slot(row_id)?;
+        self.row_count -= 1;
+
+        for pointer in overflow {
+            self.free_overflow(pointer)?;
+        }
+        self.stats_changed = true;
+        Ok(())
+    }
+
+    /// Delete many rows, fetching each of their pages once
+    ///
+    /// Rows are grouped by page, so a page is fetched, cleared of every
+    /// targeted slot and marked dirty a single time. Rows that are already
+    /// gone are skipped.
+    ///
+    /// # Returns
+    /// The number of rows deleted
+    ///
+    /// # Errors
+    /// Returns error if a page cannot be fetched or a row's overflow chain is
+    /// broken
+    pub fn delete_many(&mut self, row_ids: &[RowId]) -> io::Result<usize> {
+        let mut slots_by_page: BTreeMap<PageId, Vec<SlotId>> = BTreeMap::new();
+        for row_id in row_ids {
+            slots_by_page
+                .entry(row_id.page_id)
+                .or_default()
+                .push(row_id.slot_id);
+        }
+
+        let mut deleted = 0;
+        let mut overflow = Vec::new();
+        for (page_id, slots) in slots_by_page {
+            let mut page = self.buffer_pool.fetch_page(page_id)?;
+            let result = Self::delete_page_slots(&mut page, &slots, &self.schema, &mut overflow);
+            drop(page);
+            // A failure part way may already have cleared some slots
+            self.buffer_pool
+                .unpin_page(page_id, !matches!(result, Ok(0)));
+            let page_deleted = result?;
+            deleted += page_deleted;
+            self.row_count -= page_deleted;
+        }
+
+        for pointer in overflow {
+            self.free_overflow(pointer)?;
+        }
+        if deleted > 0 {
+            self.stats_changed = true;
+        }
+        Ok(deleted)
+    }
+
+    /// Clear the live rows among `slots` of a heap page, collecting their
+    /// overflow chains, and return how many were cleared
+    fn delete_page_slots(
+        page: &mut Page,
+        slots: &[SlotId],
+        schema: &Schema,
+        overflow: &mut Vec<OverflowPointer>,
+    ) -> io::Result<usize> {
+        if page.page_type() != PageType::Heap {
+            return Ok(0);
+        }
+        let mut deleted = 0;
+        for &slot_id in slots {
+            let Some(row_data) = page.get_row(slot_id) else {
+                continue;
+            };
+            overflow.extend(Self::stored_overflow(row_data, schema)?);
+            page.delete_row(slot_id)
+                .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e))?;
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
+
+    fn delete_slot(&mut self, row_id: RowId) -> io::Result<()> {
+        let mut page = self.buffer_pool.fetch_page(row_id.page_id)?;
 
         page.delete_row(row_id.slot_id)
             .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e))?;
+        drop(page);
 
         self.buffer_pool.unpin_page(row_id.page_id, true);
-
         Ok(())
     }
 
@@ -315,6 +738,38 @@ impl HeapTable {
     /// Attempts to update in place; if the new row is larger than the existing slot,
     /// falls back to deleting and reinserting the row (which may change the RowId).
     pub fn update(&mut self, row_id: RowId, new_row: &[Value]) -> io::Result<RowId> {
+        self.update_with_metadata(row_id, new_row, RowMetadata::default())
+    }
+
+    /// Update a row and its MVCC metadata, moving it like [`HeapTable::update`]
+    pub fn update_with_metadata(
+        &mut self,
+        row_id: RowId,
+        new_row: &[Value],
+        metadata: RowMetadata,
+    ) -> io::Result<RowId> {
+        self.replace_row(row_id, None, new_row, metadata)
+    }
+
+    /// Rewrite a row stored under `stored_schema` (the schema before an
+    /// ALTER TABLE) so it matches the current schema
+    pub fn rewrite_with_metadata(
+        &mut self,
+        row_id: RowId,
+        stored_schema: &Schema,
+        new_row: &[Value],
+        metadata: RowMetadata,
+    ) -> io::Result<RowId> {
+        self.replace_row(row_id, Some(stored_schema), new_row, metadata)
+    }
+
+    fn replace_row(
+        &mut self,
+        row_id: RowId,
+        stored_schema: Option<&Schema>,
+        new_row: &[Value],
+        metadata: RowMetadata,
+    ) -> io::Result<RowId> {
         // Validate and serialize the new row
         self.schema.validate_row(new_row).map_err(|e| {
             io::Error::new(
@@ -323,43 +778,182 @@ impl HeapTable {
             )
         })?;
 
-        let row_data = RowSerializer::serialize(new_row, Some(&self.schema))
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let old_data = self.read_stored_row(row_id)?;
+        let old_overflow = Self::stored_overflow(&old_data, stored_schema.unwrap_or(&self.schema))?;
+        let row_data = self.encode_row(new_row, metadata)?;
 
         // Try to update in place first
-        let page = self.buffer_pool.fetch_page(row_id.page_id)?;
-        match page.update_row(row_id.slot_id, &row_data) {
+        let mut page = self.buffer_pool.fetch_page(row_id.page_id)?;
+        let updated = page.update_row(row_id.slot_id, &row_data);
+        drop(page);
+        let new_row_id = match updated {
             Ok(()) => {
                 self.buffer_pool.unpin_page(row_id.page_id, true);
-                return Ok(row_id);
+                row_id
             }
             Err(PageError::PageFull) => {
-                // Fall through to move the row
+                // If we couldn't update in place, delete and re-insert the row
                 self.buffer_pool.unpin_page(row_id.page_id, false);
+                self.delete_slot(row_id)?;
+                let always_visible = metadata.xmin == 0 && metadata.xmax == 0;
+                self.place_row(&row_data, always_visible)?
             }
             Err(e) => {
                 self.buffer_pool.unpin_page(row_id.page_id, false);
                 return Err(io::Error::from(e));
             }
-        }
+        };
 
-        // If we couldn't update in place, delete and re-insert the row
-        self.delete(row_id)?;
-        let new_row_id = self.insert(new_row)?;
+        for pointer in old_overflow {
+            self.free_overflow(pointer)?;
+        }
+        self.stats_changed = true;
         Ok(new_row_id)
     }
 
-    /// Flush all dirty pages to disk
+    /// Reclaim data pages that no longer hold any live rows.
+    ///
+    /// Trailing empty pages are truncated from the file. Empty pages in the
+    /// middle of the file are reset and kept on a free list so later inserts
+    /// reuse them. Rows on non-empty pages are never moved, so their RowIds
+    /// stay valid.
+    ///
+    /// # Returns
+    /// Number of pages reclaimed (truncated or added to the free list)
+    pub fn reclaim_empty_pages(&mut self) -> io::Result<usize> {
+        let num_pages = self.buffer_pool.num_pages()?;
+
+        let mut empty_pages = Vec::new();
+        for page_id in 1..num_pages {
+            let is_empty = self.buffer_pool.fetch_page(page_id)?.is_empty();
+            self.buffer_pool.unpin_page(page_id, false);
+            if is_empty {
+                empty_pages.push(page_id);
+            }
+        }
+
+        // Trailing empty pages can be dropped from the file entirely
+        let mut new_len = num_pages;
+        while new_len > 1 && empty_pages.last() == Some(&(new_len - 1)) {
+            empty_pages.pop();
+            new_len -= 1;
+        }
+        let truncated = (num_pages - new_len) as usize;
+        if truncated > 0 {
+            self.buffer_pool.truncate(new_len)?;
+            self.free_pages.retain(|&page_id| page_id < new_len);
+            if self.last_page_id.is_some_and(|page_id| page_id >= new_len) {
+                self.last_page_id = None;
+            }
+        }
+
+        // Remaining empty pages are reset so their space can be reused
+        let mut reused = 0usize;
+        for page_id in empty_pages {
+            *self.buffer_pool.fetch_page(page_id)? = Page::new(page_id, PageType::Heap);
+            self.buffer_pool.unpin_page(page_id, true);
+            if self.free_pages.insert(page_id) {
+                reused += 1;
+            }
+        }
+
+        if truncated + reused > 0 {
+            self.stats_changed = true;
+        }
+        Ok(truncated + reused)
+    }
+
+    /// Mark a data page as holding only rows visible to every snapshot
+    ///
+    /// The caller must have checked every row on the page; the flag is
+    /// cleared again by the next change to the page.
+    pub fn mark_all_visible(&mut self, page_id: PageId) -> io::Result<()> {
+        let mut page = self.buffer_pool.fetch_page(page_id)?;
+        let was_all_visible = page.is_all_visible();
+        page.set_all_visible(true);
+        drop(page);
+        self.buffer_pool.unpin_page(page_id, !was_all_visible);
+        Ok(())
+    }
+
+    /// Returns true if a data page is flagged as holding only rows visible to
+    /// every snapshot
+    pub fn is_page_all_visible(&mut self, page_id: PageId) -> io::Result<bool> {
+        let all_visible = self.buffer_pool.fetch_page(page_id)?.is_all_visible();
+        self.buffer_pool.unpin_page(page_id, false);
+        Ok(all_visible)
+    }
+
+    /// Flush all dirty pages to disk, recording the row count and free page
+    /// list in the metadata page first if they changed
     pub fn flush(&mut self) -> io::Result<()> {
-        self.buffer_pool.flush_all()
+        if self.stats_changed {
+            self.persist_stats()?;
+            self.stats_changed = false;
+        }
+        self.buffer_pool.flush()
+    }
+
+    /// Compress pages with `compression` as they are flushed
+    ///
+    /// The setting is not persisted; pages keep whatever encoding they were
+    /// last written with, and both encodings are readable regardless of it.
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.buffer_pool.set_compression(compression);
+    }
+
+    /// Total bytes the table's pages occupy on disk
+    pub fn stored_bytes(&mut self) -> io::Result<u64> {
+        self.buffer_pool.stored_bytes()
     }
 
     /// Get a reference to the buffer pool (for scanning)
-    pub(crate) fn buffer_pool_mut(&mut self) -> &mut BufferPool {
+    pub(crate) fn buffer_pool_mut(&mut self) -> &mut PoolFile {
         &mut self.buffer_pool
     }
 }
 
+/// Split an overflow page into the next page id and its piece of the value
+fn overflow_piece(page: &Page) -> Option<(PageId, &[u8])> {
+    if page.page_type() != PageType::Overflow {
+        return None;
+    }
+    let piece = page.get_row(0)?;
+    let next = PageId::from_le_bytes(piece.get(..4)?.try_into().ok()?);
+    Some((next, &piece[4..]))
+}
+
+fn broken_overflow_chain(page_id: PageId) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Broken overflow chain at page {}", page_id),
+    )
+}
+
+/// Reassemble a string from its overflow chain
+fn read_overflow(buffer_pool: &mut PoolFile, pointer: OverflowPointer) -> io::Result<String> {
+    let len = pointer.len as usize;
+    let mut data = Vec::with_capacity(len);
+    let mut page_id = pointer.first_page;
+    while data.len() < len {
+        if page_id == 0 {
+            return Err(broken_overflow_chain(page_id));
+        }
+        let page = buffer_pool.fetch_page(page_id)?;
+        let next = overflow_piece(&page)
+            .filter(|(_, piece)| !piece.is_empty())
+            .map(|(next, piece)| {
+                data.extend_from_slice(piece);
+                next
+            });
+        drop(page);
+        buffer_pool.unpin_page(page_id, false);
+        page_id = next.ok_or_else(|| broken_overflow_chain(page_id))?;
+    }
+    data.truncate(len);
+    String::from_utf8(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
 /// Serialize schema to bytes
 pub(crate) fn serialize_schema(schema: &Schema) -> Vec<u8> {
     use crate::serialization::codec;
@@ -380,6 +974,7 @@ pub(crate) fn serialize_schema(schema: &Schema) -> Vec<u8> {
             crate::types::DataType::Date => 5u8,
             crate::types::DataType::Timestamp => 6u8,
             crate::types::DataType::Decimal => 7u8,
+            crate::types::DataType::Json => 8u8,
         };
         codec::write_u8(&mut buf, type_byte).unwrap();
     }
@@ -387,6 +982,65 @@ pub(crate) fn serialize_schema(schema: &Schema) -> Vec<u8> {
     buf
 }
 
+/// Rewrite the metadata page: the table name, its schema and the statistics
+/// row
+///
+/// Free pages that don't fit on the page are left out of the statistics row;
+/// the next vacuum finds them again.
+fn write_metadata(
+    buffer_pool: &mut PoolFile,
+    name: &str,
+    schema: &Schema,
+    row_count: usize,
+    free_pages: &BTreeSet<PageId>,
+) -> io::Result<()> {
+    let mut page = Page::new(0, PageType::Heap);
+    page.add_row(format!("TABLE:{}\n", name).as_bytes())?;
+    page.add_row(&serialize_schema(schema))?;
+
+    let mut recorded: Vec<PageId> = free_pages.iter().copied().collect();
+    loop {
+        match page.add_row(&serialize_stats(row_count, recorded.iter().copied())) {
+            Ok(_) => break,
+            Err(PageError::PageFull) if !recorded.is_empty() => {
+                recorded.truncate(recorded.len() / 2);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    *buffer_pool.fetch_page(0)? = page;
+    buffer_pool.unpin_page(0, true);
+    buffer_pool.flush_page(0)
+}
+
+/// Serialize the statistics row: the row count and the free page ids
+fn serialize_stats(row_count: usize, free_pages: impl ExactSizeIterator<Item = PageId>) -> Vec<u8> {
+    use crate::serialization::codec;
+    let mut buf = STATS_PREFIX.to_vec();
+    codec::write_u64(&mut buf, row_count as u64).unwrap();
+    codec::write_u32(&mut buf, free_pages.len() as u32).unwrap();
+    for page_id in free_pages {
+        codec::write_u32(&mut buf, page_id).unwrap();
+    }
+    buf
+}
+
+/// Deserialize a statistics row (without its prefix)
+fn deserialize_stats(bytes: &[u8]) -> io::Result<(usize, BTreeSet<PageId>)> {
+    use crate::serialization::codec;
+    use std::io::Cursor;
+
+    let mut cursor = Cursor::new(bytes);
+    let row_count = codec::read_u64(&mut cursor)? as usize;
+    let free_count = codec::read_u32(&mut cursor)?;
+    let mut free_pages = BTreeSet::new();
+    for _ in 0..free_count {
+        free_pages.insert(codec::read_u32(&mut cursor)?);
+    }
+    Ok((row_count, free_pages))
+}
+
 /// Deserialize schema from bytes
 pub(crate) fn deserialize_schema(bytes: &[u8]) -> io::Result<Schema> {
     use crate::serialization::codec;
@@ -412,6 +1066,7 @@ pub(crate) fn deserialize_schema(bytes: &[u8]) -> io::Result<Schema> {
             5 => DataType::Date,
             6 => DataType::Timestamp,
             7 => DataType::Decimal,
+            8 => DataType::Json,
             _ => {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidData,
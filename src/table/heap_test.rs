@@ -1,5 +1,7 @@
 use super::heap::{HeapTable, deserialize_schema, serialize_schema};
-use crate::types::{Column, DataType, Schema, Value};
+use crate::serialization::RowMetadata;
+use crate::storage::{BufferPool, Compression, MAX_ROW_SIZE, PAGE_SIZE};
+use crate::types::{Column, DataType, Decimal, Schema, Value};
 use tempfile::NamedTempFile;
 
 fn create_test_schema() -> Schema {
@@ -163,3 +165,471 @@ fn test_schema_serialization() {
         assert_eq!(orig.data_type(), deser.data_type());
     }
 }
+
+#[test]
+fn test_reclaim_empty_pages() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let schema = Schema::new(vec![
+        Column::new("id", DataType::Integer),
+        Column::new("data", DataType::String),
+    ]);
+    let mut table = HeapTable::create("churn", schema, temp_file.path(), 10).unwrap();
+
+    let mut row_ids = Vec::new();
+    for i in 0..300 {
+        let row = vec![Value::Integer(i), Value::String("x".repeat(64))];
+        row_ids.push(table.insert(&row).unwrap());
+    }
+    let last_page = row_ids.last().unwrap().page_id();
+    assert!(last_page >= 3);
+
+    // Empty the first data page and every page after the second
+    for row_id in &row_ids {
+        if row_id.page_id() != 2 {
+            table.delete(*row_id).unwrap();
+        }
+    }
+
+    let reclaimed = table.reclaim_empty_pages().unwrap();
+    assert_eq!(reclaimed, (last_page - 2 + 1) as usize);
+    table.flush().unwrap();
+    let file_len = std::fs::metadata(temp_file.path()).unwrap().len();
+    assert_eq!(file_len, 3 * crate::storage::PAGE_SIZE as u64);
+
+    // Surviving rows keep their RowIds
+    for row_id in row_ids.iter().filter(|r| r.page_id() == 2) {
+        assert!(table.get(*row_id).is_ok());
+    }
+
+    // The freed page is reused before the file grows
+    let row = vec![Value::Integer(1000), Value::String("reuse".to_string())];
+    let new_id = table.insert(&row).unwrap();
+    assert_eq!(new_id.page_id(), 1);
+    assert_eq!(table.get(new_id).unwrap(), row);
+}
+
+#[test]
+fn test_free_pages_are_reused_after_reopen() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let path = temp_file.path().to_owned();
+    let schema = Schema::new(vec![
+        Column::new("id", DataType::Integer),
+        Column::new("data", DataType::String),
+    ]);
+
+    let (emptied, deleted) = {
+        let mut table = HeapTable::create("churn", schema, &path, 10).unwrap();
+        let mut row_ids = Vec::new();
+        for i in 0..300 {
+            let row = vec![Value::Integer(i), Value::String("x".repeat(64))];
+            row_ids.push(table.insert(&row).unwrap());
+        }
+        let last_page = row_ids.last().unwrap().page_id();
+        assert!(last_page >= 4);
+
+        // Empty two pages in the middle; the last page keeps its rows
+        let emptied = [1, 3];
+        let mut deleted = 0;
+        for row_id in &row_ids {
+            if emptied.contains(&row_id.page_id()) {
+                table.delete(*row_id).unwrap();
+                deleted += 1;
+            }
+        }
+        assert_eq!(table.reclaim_empty_pages().unwrap(), 2);
+        table.flush().unwrap();
+        (emptied, deleted)
+    };
+    let file_len = std::fs::metadata(&path).unwrap().len();
+
+    // As many rows as were deleted fit back into the emptied pages
+    let mut table = HeapTable::open(&path, 10).unwrap();
+    for i in 0..deleted {
+        let row = vec![Value::Integer(1000 + i), Value::String("y".repeat(64))];
+        let page_id = table.insert(&row).unwrap().page_id();
+        assert!(emptied.contains(&page_id), "row went to page {}", page_id);
+    }
+    table.flush().unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), file_len);
+}
+
+#[test]
+fn test_stale_free_pages_are_not_reused() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let path = temp_file.path().to_owned();
+    let schema = Schema::new(vec![
+        Column::new("id", DataType::Integer),
+        Column::new("data", DataType::String),
+    ]);
+
+    let refilled = {
+        let mut table = HeapTable::create("churn", schema, &path, 10).unwrap();
+        let mut row_ids = Vec::new();
+        for i in 0..300 {
+            let row = vec![Value::Integer(i), Value::String("x".repeat(64))];
+            row_ids.push(table.insert(&row).unwrap());
+        }
+        for row_id in &row_ids {
+            if [1, 3].contains(&row_id.page_id()) {
+                table.delete(*row_id).unwrap();
+            }
+        }
+        assert_eq!(table.reclaim_empty_pages().unwrap(), 2);
+        table.flush().unwrap();
+
+        // Fill the free pages again, then write the data pages back without
+        // the statistics, as evicting them would
+        let mut refilled = Vec::new();
+        for i in 0..row_ids
+            .iter()
+            .filter(|id| [1, 3].contains(&id.page_id()))
+            .count()
+        {
+            let row = vec![
+                Value::Integer(1000 + i as i64),
+                Value::String("y".repeat(64)),
+            ];
+            refilled.push((table.insert(&row).unwrap(), row));
+        }
+        table.buffer_pool_mut().flush().unwrap();
+        refilled
+    };
+
+    // The recorded free pages hold rows again, so a new overflow chain must
+    // not overwrite them
+    let mut table = HeapTable::open(&path, 10).unwrap();
+    let big_row = vec![
+        Value::Integer(2000),
+        Value::String("z".repeat(MAX_ROW_SIZE * 2)),
+    ];
+    let big_id = table.insert(&big_row).unwrap();
+    assert_eq!(table.get(big_id).unwrap(), big_row);
+    for (row_id, row) in &refilled {
+        assert_eq!(&table.get(*row_id).unwrap(), row);
+    }
+}
+
+#[test]
+fn test_row_count_survives_reopen() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let path = temp_file.path().to_owned();
+
+    {
+        let mut table = HeapTable::create("counted", create_test_schema(), &path, 10).unwrap();
+        let mut row_ids = Vec::new();
+        for i in 0..5 {
+            let row = vec![
+                Value::Integer(i),
+                Value::String(format!("user{}", i)),
+                Value::Integer(20),
+            ];
+            row_ids.push(table.insert(&row).unwrap());
+        }
+        table.delete(row_ids[0]).unwrap();
+        assert_eq!(table.row_count(), 4);
+        table.flush().unwrap();
+    }
+
+    // Only the metadata page is read to learn the count
+    let mut table = HeapTable::open(&path, 10).unwrap();
+    assert_eq!(table.row_count(), 4);
+    assert_eq!(table.buffer_pool_mut().fetch_count(), 1);
+}
+
+#[test]
+fn test_compression_shrinks_repetitive_table() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let path = temp_file.path().to_owned();
+    let row = |i: i64| {
+        vec![
+            Value::Integer(i % 4),
+            Value::String("x".repeat(64)),
+            Value::Integer(30),
+        ]
+    };
+
+    let (uncompressed, compressed, file_len) = {
+        let mut table = HeapTable::create("cold", create_test_schema(), &path, 10).unwrap();
+        for i in 0..500 {
+            table.insert(&row(i)).unwrap();
+        }
+        table.flush().unwrap();
+        let uncompressed = table.stored_bytes().unwrap();
+
+        table.set_compression(Compression::Rle);
+        for i in 500..600 {
+            table.insert(&row(i)).unwrap();
+        }
+        // Dirty every page so the flush rewrites it compressed
+        let num_pages = table.buffer_pool_mut().num_pages().unwrap();
+        for page_id in 0..num_pages {
+            table.buffer_pool_mut().fetch_page(page_id).unwrap();
+            table.buffer_pool_mut().unpin_page(page_id, true);
+        }
+        table.flush().unwrap();
+        let file_len = num_pages as u64 * PAGE_SIZE as u64;
+        (uncompressed, table.stored_bytes().unwrap(), file_len)
+    };
+
+    // Every page keeps its slot, but the tails of compressed slots are holes
+    let metadata = std::fs::metadata(&path).unwrap();
+    assert_eq!(metadata.len(), file_len);
+    assert!(compressed < uncompressed);
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let allocated = metadata.blocks() * 512;
+        assert_eq!(compressed, allocated);
+        // Each compressed page fits in one filesystem block of its two, plus
+        // whatever blocks the filesystem needs to map the holes
+        assert!(
+            allocated * 4 < file_len * 3,
+            "expected well under {} bytes allocated, got {}",
+            file_len,
+            allocated
+        );
+    }
+
+    let mut table = HeapTable::open(&path, 10).unwrap();
+    assert_eq!(table.row_count(), 600);
+    let mut scan = crate::table::TableScan::new(&mut table);
+    let mut seen = 0;
+    while let Some((_, values)) = scan.next().unwrap() {
+        assert_eq!(values[1], Value::String("x".repeat(64)));
+        seen += 1;
+    }
+    assert_eq!(seen, 600);
+}
+
+#[test]
+fn test_insert_maintains_all_visible_flag() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let schema = create_test_schema();
+    let mut table = HeapTable::create("users", schema, temp_file.path(), 10).unwrap();
+    let row = vec![
+        Value::Integer(1),
+        Value::String("Alice".to_string()),
+        Value::Integer(30),
+    ];
+
+    // Rows written outside a transaction keep a fresh page all-visible
+    let first = table.insert(&row).unwrap();
+    assert!(table.is_page_all_visible(first.page_id()).unwrap());
+
+    // A row from a transaction may be invisible to some snapshots
+    table
+        .insert_with_metadata(&row, RowMetadata { xmin: 7, xmax: 0 })
+        .unwrap();
+    assert!(!table.is_page_all_visible(first.page_id()).unwrap());
+
+    // The flag stays cleared until someone re-checks the whole page
+    table.insert(&row).unwrap();
+    assert!(!table.is_page_all_visible(first.page_id()).unwrap());
+    table.mark_all_visible(first.page_id()).unwrap();
+    assert!(table.is_page_all_visible(first.page_id()).unwrap());
+
+    table.delete(first).unwrap();
+    assert!(!table.is_page_all_visible(first.page_id()).unwrap());
+}
+
+#[test]
+fn test_oversized_rows_are_rejected() {
+    let temp_file = NamedTempFile::new().unwrap();
+    // Only fixed-size values count against the page once strings move out
+    let columns = (0..400)
+        .map(|i| Column::new(format!("d{}", i), DataType::Decimal))
+        .collect();
+    let mut table = HeapTable::create("wide", Schema::new(columns), temp_file.path(), 10).unwrap();
+
+    let nulls = vec![Value::Null; 400];
+    let row_id = table.insert(&nulls).unwrap();
+
+    let full = vec![Value::Decimal(Decimal::parse("1.50").unwrap()); 400];
+    let err = table.insert(&full).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("overflow pages"));
+
+    // A failed update must not move (and so delete) the original row
+    let err = table.update(row_id, &full).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(table.get(row_id).unwrap(), nulls);
+    assert_eq!(table.row_count(), 1);
+}
+
+#[test]
+fn test_large_strings_use_overflow_pages() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let path = temp_file.path().to_owned();
+    let huge = "x".repeat(MAX_ROW_SIZE * 3);
+    let big_row = vec![
+        Value::Integer(1),
+        Value::String(huge.clone()),
+        Value::Integer(30),
+    ];
+    let small_row = vec![
+        Value::Integer(2),
+        Value::String("Bob".to_string()),
+        Value::Integer(25),
+    ];
+
+    {
+        let mut table = HeapTable::create("users", create_test_schema(), &path, 10).unwrap();
+        let big_id = table.insert(&big_row).unwrap();
+        let small_id = table.insert(&small_row).unwrap();
+        // The row itself stays small enough to share its page
+        assert_eq!(big_id.page_id(), small_id.page_id());
+        assert_eq!(table.get(big_id).unwrap(), big_row);
+        assert_eq!(table.row_count(), 2);
+        table.flush().unwrap();
+    }
+
+    // Overflow pages are neither counted nor scanned as rows
+    let mut table = HeapTable::open(&path, 10).unwrap();
+    assert_eq!(table.row_count(), 2);
+    let mut scan = crate::table::TableScan::new(&mut table);
+    let mut rows = Vec::new();
+    while let Some((_, values)) = scan.next().unwrap() {
+        rows.push(values);
+    }
+    assert_eq!(rows, vec![big_row, small_row]);
+}
+
+#[test]
+fn test_overflow_pages_are_reused() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut table = HeapTable::create("users", create_test_schema(), temp_file.path(), 10).unwrap();
+    let row = |id: i64, text: &str| {
+        vec![
+            Value::Integer(id),
+            Value::String(text.to_string()),
+            Value::Integer(30),
+        ]
+    };
+
+    table.insert(&row(1, "Alice")).unwrap();
+    let big_id = table
+        .insert(&row(2, &"a".repeat(MAX_ROW_SIZE * 2)))
+        .unwrap();
+
+    // The new chain is written before the old one is released, which then
+    // serves the next large value
+    let big_id = table
+        .update(big_id, &row(2, &"b".repeat(MAX_ROW_SIZE * 2)))
+        .unwrap();
+    assert_eq!(
+        table.get(big_id).unwrap(),
+        row(2, &"b".repeat(MAX_ROW_SIZE * 2))
+    );
+    let pages = table.buffer_pool_mut().num_pages().unwrap();
+    let big_id = table
+        .update(big_id, &row(2, &"c".repeat(MAX_ROW_SIZE * 2)))
+        .unwrap();
+    assert_eq!(table.buffer_pool_mut().num_pages().unwrap(), pages);
+
+    // Shrinking the value releases its chain for the next large insert
+    let big_id = table.update(big_id, &row(2, "small")).unwrap();
+    assert_eq!(table.get(big_id).unwrap(), row(2, "small"));
+    let other = table
+        .insert(&row(3, &"d".repeat(MAX_ROW_SIZE * 2)))
+        .unwrap();
+    assert_eq!(table.buffer_pool_mut().num_pages().unwrap(), pages);
+
+    // Deleting frees the chain, and trailing empty pages can be reclaimed
+    table.delete(other).unwrap();
+    assert!(table.reclaim_empty_pages().unwrap() > 0);
+    assert!(table.buffer_pool_mut().num_pages().unwrap() < pages);
+    assert_eq!(table.row_count(), 2);
+}
+
+#[test]
+fn test_delete_many_fetches_each_page_once() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let schema = Schema::new(vec![
+        Column::new("id", DataType::Integer),
+        Column::new("body", DataType::String),
+    ]);
+    let mut table = HeapTable::create("bulk", schema, temp_file.path(), 10).unwrap();
+
+    let mut row_ids = Vec::new();
+    for i in 0..200 {
+        let row = vec![Value::Integer(i), Value::String("x".repeat(64))];
+        row_ids.push(table.insert(&row).unwrap());
+    }
+    // One row large enough to spill into overflow pages
+    let big = vec![
+        Value::Integer(200),
+        Value::String("y".repeat(3 * PAGE_SIZE)),
+    ];
+    let pages_before_big = table.buffer_pool_mut().num_pages().unwrap();
+    let big_id = table.insert(&big).unwrap();
+    let overflow_pages = table.buffer_pool_mut().num_pages().unwrap() - pages_before_big;
+
+    let mut doomed: Vec<_> = row_ids.iter().copied().step_by(2).collect();
+    doomed.push(big_id);
+    // Already-deleted and repeated rows are skipped
+    table.delete(doomed[0]).unwrap();
+    doomed.push(doomed[1]);
+    let pages: std::collections::BTreeSet<_> = doomed.iter().map(|r| r.page_id()).collect();
+
+    let before = table.buffer_pool_mut().fetch_count();
+    let deleted = table.delete_many(&doomed).unwrap();
+    let fetches = table.buffer_pool_mut().fetch_count() - before;
+
+    assert_eq!(deleted, 100);
+    assert_eq!(table.row_count(), 100);
+    // One fetch per data page, plus one per overflow page freed
+    assert_eq!(fetches as usize, pages.len() + overflow_pages as usize);
+    for (i, row_id) in row_ids.iter().enumerate() {
+        assert_eq!(table.get(*row_id).is_ok(), i % 2 == 1);
+    }
+    assert!(table.get(big_id).is_err());
+
+    // Freed overflow pages are reused rather than growing the file
+    let num_pages = table.buffer_pool_mut().num_pages().unwrap();
+    table.insert(&big).unwrap();
+    assert_eq!(table.buffer_pool_mut().num_pages().unwrap(), num_pages);
+}
+
+#[test]
+fn test_tables_share_a_buffer_pool() {
+    let users_file = NamedTempFile::new().unwrap();
+    let orders_file = NamedTempFile::new().unwrap();
+    let pool = BufferPool::shared(3);
+    let mut users =
+        HeapTable::create_in_pool("users", create_test_schema(), users_file.path(), &pool).unwrap();
+    let mut orders =
+        HeapTable::create_in_pool("orders", create_test_schema(), orders_file.path(), &pool)
+            .unwrap();
+
+    // Both tables grow well past the three shared frames
+    let row = |i: i64| {
+        vec![
+            Value::Integer(i),
+            Value::String("x".repeat(200)),
+            Value::Integer(i * 2),
+        ]
+    };
+    let mut user_ids = Vec::new();
+    let mut order_ids = Vec::new();
+    for i in 0..200 {
+        user_ids.push(users.insert(&row(i)).unwrap());
+        order_ids.push(orders.insert(&row(-i)).unwrap());
+    }
+    assert!(pool.borrow().size() <= 3);
+    assert_eq!(users.get(user_ids[17]).unwrap(), row(17));
+    assert_eq!(orders.get(order_ids[17]).unwrap(), row(-17));
+
+    users.flush().unwrap();
+    orders.flush().unwrap();
+    drop(users);
+    assert!(pool.borrow().size() <= 3);
+    drop(orders);
+    assert_eq!(pool.borrow().size(), 0);
+
+    let mut users = HeapTable::open(users_file.path(), 10).unwrap();
+    let mut orders = HeapTable::open(orders_file.path(), 10).unwrap();
+    assert_eq!(users.row_count(), 200);
+    assert_eq!(orders.row_count(), 200);
+    assert_eq!(users.get(user_ids[199]).unwrap(), row(199));
+    assert_eq!(orders.get(order_ids[199]).unwrap(), row(-199));
+}
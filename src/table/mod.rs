@@ -1,10 +1,14 @@
 pub mod heap;
 pub mod scan;
+pub mod sort;
 
 pub use heap::{HeapTable, RowId};
 pub use scan::TableScan;
+pub use sort::{ExternalSort, SortedRows};
 
 #[cfg(test)]
 mod heap_test;
 #[cfg(test)]
 mod scan_test;
+#[cfg(test)]
+mod sort_test;
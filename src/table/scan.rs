@@ -1,17 +1,21 @@
 use super::heap::{HeapTable, RowId};
-use crate::serialization::{RowMetadata, RowSerializer};
-use crate::storage::PageId;
+use crate::serialization::RowMetadata;
+use crate::storage::{PageId, PageType};
 use crate::types::Value;
 use std::io;
 
 /// Sequential table scanner
 ///
 /// Iterates through all rows in a heap table in physical storage order.
-/// Skips page 0 (metadata page) and only scans data pages.
+/// Skips page 0 (metadata page) and overflow pages, and only scans data pages.
+///
+/// Callers that check MVCC visibility can skip the check for rows where
+/// [`TableScan::on_all_visible_page`] is true.
 pub struct TableScan<'a> {
     table: &'a mut HeapTable,
     current_page_id: PageId,
     current_slot_id: u16,
+    page_all_visible: bool,
     finished: bool,
 }
 
@@ -22,10 +26,45 @@ impl<'a> TableScan<'a> {
             table,
             current_page_id: 1, // Start at page 1 (skip metadata page 0)
             current_slot_id: 0,
+            page_all_visible: false,
             finished: false,
         }
     }
 
+    /// Create a scanner that starts just after `last_row_id`
+    ///
+    /// Rows are yielded in the same physical order as [`TableScan::new`], so
+    /// passing the last RowId returned by an earlier scan continues where it
+    /// stopped without re-reading earlier pages. `last_row_id` need not still
+    /// exist: if it was deleted or vacuumed, the scan continues from the next
+    /// slot, and if its page was truncated away the scan is simply empty.
+    /// Rows inserted into earlier pages since (e.g. into pages reclaimed by
+    /// vacuum) are not visited.
+    pub fn resume_from(table: &'a mut HeapTable, last_row_id: RowId) -> Self {
+        let (page_id, slot_id) = if last_row_id.page_id() == 0 {
+            (1, 0)
+        } else {
+            match last_row_id.slot_id().checked_add(1) {
+                Some(slot_id) => (last_row_id.page_id(), slot_id),
+                None => (last_row_id.page_id() + 1, 0),
+            }
+        };
+
+        Self {
+            table,
+            current_page_id: page_id,
+            current_slot_id: slot_id,
+            page_all_visible: false,
+            finished: false,
+        }
+    }
+
+    /// Returns true if the row most recently returned came from a page whose
+    /// rows are all visible to every snapshot
+    pub fn on_all_visible_page(&self) -> bool {
+        self.page_all_visible
+    }
+
     /// Get the next row from the table
     ///
     /// Returns (RowId, Vec<Value>) for each row, or None when done
@@ -57,21 +96,26 @@ impl<'a> TableScan<'a> {
                 Err(e) => return Err(e),
             };
 
-            let num_rows = page.num_rows();
+            // Overflow pages hold pieces of values, not rows
+            let num_rows = if page.page_type() == PageType::Heap {
+                page.num_rows()
+            } else {
+                0
+            };
+
+            // Step over deleted slots without re-fetching the page
+            while self.current_slot_id < num_rows && page.get_row(self.current_slot_id).is_none() {
+                self.current_slot_id += 1;
+            }
 
             // Check if we have more rows on this page
             if self.current_slot_id < num_rows {
-                // Get the row data
-                let row_data = match page.get_row(self.current_slot_id) {
-                    Some(data) => data.to_vec(),
-                    None => {
-                        self.table
-                            .buffer_pool_mut()
-                            .unpin_page(self.current_page_id, false);
-                        self.current_slot_id += 1;
-                        continue;
-                    }
-                };
+                let row_data = page
+                    .get_row(self.current_slot_id)
+                    .expect("deleted slots skipped above")
+                    .to_vec();
+                self.page_all_visible = page.is_all_visible();
+                drop(page);
 
                 let row_id = RowId::new(self.current_page_id, self.current_slot_id);
 
@@ -80,9 +124,7 @@ impl<'a> TableScan<'a> {
                     .unpin_page(self.current_page_id, false);
 
                 // Deserialize the row
-                let (metadata, values) =
-                    RowSerializer::deserialize_with_metadata(&row_data, self.table.schema())
-                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let (metadata, values) = self.table.decode_row(&row_data)?;
 
                 // Move to next slot
                 self.current_slot_id += 1;
@@ -90,6 +132,7 @@ impl<'a> TableScan<'a> {
                 return Ok(Some((row_id, metadata, values)));
             } else {
                 // No more rows on this page, move to next page
+                drop(page);
                 self.table
                     .buffer_pool_mut()
                     .unpin_page(self.current_page_id, false);
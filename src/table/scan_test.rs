@@ -1,4 +1,4 @@
-use super::heap::HeapTable;
+use super::heap::{HeapTable, RowId};
 use super::scan::TableScan;
 use crate::types::{Column, DataType, Schema, Value};
 use tempfile::NamedTempFile;
@@ -143,3 +143,131 @@ fn test_scan_multiple_pages() {
     assert_eq!(count, 20);
     assert!(seen_multiple_pages, "Should have spanned multiple pages");
 }
+
+fn create_resume_table(path: &std::path::Path, rows: i64) -> (HeapTable, Vec<RowId>) {
+    let schema = Schema::new(vec![
+        Column::new("id", DataType::Integer),
+        Column::new("payload", DataType::String),
+    ]);
+    let mut table = HeapTable::create("test", schema, path, 10).unwrap();
+
+    // Large rows so the table spans several pages
+    let row_ids = (0..rows)
+        .map(|i| {
+            table
+                .insert(&[Value::Integer(i), Value::String("x".repeat(1000))])
+                .unwrap()
+        })
+        .collect();
+    (table, row_ids)
+}
+
+#[test]
+fn test_resume_from_continues_after_row() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (mut table, row_ids) = create_resume_table(temp_file.path(), 20);
+    assert!(row_ids.last().unwrap().page_id() > 1);
+
+    // Page through the table three rows at a time
+    let mut seen = Vec::new();
+    let mut last: Option<RowId> = None;
+    loop {
+        let mut scan = match last {
+            Some(row_id) => TableScan::resume_from(&mut table, row_id),
+            None => TableScan::new(&mut table),
+        };
+        let mut batch = Vec::new();
+        while batch.len() < 3
+            && let Some((row_id, _meta, values)) = scan.next_with_metadata().unwrap()
+        {
+            batch.push((row_id, values[0].clone()));
+        }
+        if batch.is_empty() {
+            break;
+        }
+        last = Some(batch.last().unwrap().0);
+        seen.extend(batch);
+    }
+
+    let expected: Vec<(RowId, Value)> = row_ids
+        .iter()
+        .enumerate()
+        .map(|(i, &row_id)| (row_id, Value::Integer(i as i64)))
+        .collect();
+    assert_eq!(seen, expected);
+}
+
+#[test]
+fn test_resume_from_deleted_row() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (mut table, row_ids) = create_resume_table(temp_file.path(), 10);
+
+    table.delete(row_ids[4]).unwrap();
+    table.delete(row_ids[5]).unwrap();
+
+    let mut scan = TableScan::resume_from(&mut table, row_ids[4]);
+    let (row_id, values) = scan.next().unwrap().unwrap();
+    assert_eq!(row_id, row_ids[6]);
+    assert_eq!(values[0], Value::Integer(6));
+}
+
+#[test]
+fn test_resume_from_vacuumed_page() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (mut table, row_ids) = create_resume_table(temp_file.path(), 20);
+
+    let last_page = row_ids.last().unwrap().page_id();
+    for &row_id in row_ids.iter().filter(|r| r.page_id() == last_page) {
+        table.delete(row_id).unwrap();
+    }
+    table.reclaim_empty_pages().unwrap();
+
+    let mut scan = TableScan::resume_from(&mut table, *row_ids.last().unwrap());
+    assert!(scan.next().unwrap().is_none());
+}
+
+#[test]
+fn test_resume_from_end_of_page() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (mut table, row_ids) = create_resume_table(temp_file.path(), 10);
+
+    let mut scan = TableScan::resume_from(&mut table, RowId::new(1, u16::MAX));
+    let (row_id, _) = scan.next().unwrap().unwrap();
+    assert_eq!(row_id.page_id(), 2);
+    assert!(row_ids.contains(&row_id));
+}
+
+#[test]
+fn test_scan_reports_all_visible_pages() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (mut table, row_ids) = create_resume_table(temp_file.path(), 20);
+    let first_page = row_ids[0].page_id();
+
+    // Deleting a row clears the flag on its page only
+    table.delete(row_ids[0]).unwrap();
+
+    let mut scan = TableScan::new(&mut table);
+    let mut seen = 0;
+    while let Some((row_id, _values)) = scan.next().unwrap() {
+        assert_eq!(scan.on_all_visible_page(), row_id.page_id() != first_page);
+        seen += 1;
+    }
+    assert_eq!(seen, 19);
+}
+
+#[test]
+fn test_scan_skips_deleted_slots_across_pages() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (mut table, row_ids) = create_resume_table(temp_file.path(), 20);
+
+    // Leave only the last row, so every earlier page holds only dead slots
+    for &row_id in &row_ids[..19] {
+        table.delete(row_id).unwrap();
+    }
+
+    let mut scan = TableScan::new(&mut table);
+    let (row_id, values) = scan.next().unwrap().unwrap();
+    assert_eq!(row_id, row_ids[19]);
+    assert_eq!(values[0], Value::Integer(19));
+    assert!(scan.next().unwrap().is_none());
+}
@@ -0,0 +1,373 @@
+use crate::serialization::RowSerializer;
+use crate::storage::{DiskManager, MAX_ROW_SIZE, Page, PageId, PageType};
+use crate::types::{Schema, Value};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicUsize};
+
+/// Prefix of spill file names, so leftovers from a crash can be recognised
+pub const SPILL_FILE_PREFIX: &str = "sort-";
+
+/// Distinguishes the spill files of sorts running at the same time
+static NEXT_SPILL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// External merge sort of rows on one column
+///
+/// Rows are buffered until `run_size` of them are held; the buffer is then
+/// sorted and written out as a run in a temporary file next to the tables.
+/// Inputs that never fill the buffer are sorted entirely in memory. Once
+/// spilled, runs are merged k ways as the sorted rows are read back, so only
+/// one row per run is held at a time.
+///
+/// The sort is stable: rows with equal keys come out in the order they were
+/// pushed, across runs as well as within one.
+pub struct ExternalSort {
+    schema: Schema,
+    key_idx: usize,
+    run_size: usize,
+    spill_dir: PathBuf,
+    spill: Option<SpillFile>,
+    buffer: Vec<Vec<Value>>,
+}
+
+impl ExternalSort {
+    /// Create a sort of rows shaped like `schema` on column `key_idx`
+    ///
+    /// # Arguments
+    /// * `run_size` - Rows held in memory before a run is spilled
+    /// * `spill_dir` - Directory for the temporary run file
+    pub fn new(
+        schema: Schema,
+        key_idx: usize,
+        run_size: usize,
+        spill_dir: impl AsRef<Path>,
+    ) -> Self {
+        Self {
+            schema,
+            key_idx,
+            run_size: run_size.max(1),
+            spill_dir: spill_dir.as_ref().to_path_buf(),
+            spill: None,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Add a row, spilling a run if the buffer is full
+    ///
+    /// # Errors
+    /// Returns error if the row has no key column or the run can't be written
+    pub fn push(&mut self, row: Vec<Value>) -> io::Result<()> {
+        if self.key_idx >= row.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Sort column index {} out of bounds for a row of {} columns",
+                    self.key_idx,
+                    row.len()
+                ),
+            ));
+        }
+        self.buffer.push(row);
+        if self.buffer.len() >= self.run_size {
+            self.spill_buffer()?;
+        }
+        Ok(())
+    }
+
+    /// Finish input and start reading rows back in key order
+    ///
+    /// # Errors
+    /// Returns error if the last run can't be written or the runs can't be
+    /// read
+    pub fn finish(mut self) -> io::Result<SortedRows> {
+        if self.spill.is_some() && !self.buffer.is_empty() {
+            self.spill_buffer()?;
+        }
+        let Self {
+            schema,
+            key_idx,
+            mut buffer,
+            spill,
+            ..
+        } = self;
+
+        let Some(mut spill) = spill else {
+            buffer.sort_by(|a, b| a[key_idx].cmp(&b[key_idx]));
+            return Ok(SortedRows {
+                key_idx,
+                source: Source::Memory(buffer.into_iter()),
+                peeked: None,
+            });
+        };
+
+        let mut readers: Vec<RunReader> = spill
+            .runs
+            .iter()
+            .map(|&(start, end)| RunReader::new(start, end))
+            .collect();
+        let mut heap = BinaryHeap::new();
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some(row) = reader.next_row(&mut spill.disk, &schema)? {
+                heap.push(MergeEntry { key_idx, run, row });
+            }
+        }
+
+        Ok(SortedRows {
+            key_idx,
+            source: Source::Merge {
+                spill,
+                schema,
+                readers,
+                heap,
+            },
+            peeked: None,
+        })
+    }
+
+    fn spill_buffer(&mut self) -> io::Result<()> {
+        let key_idx = self.key_idx;
+        self.buffer.sort_by(|a, b| a[key_idx].cmp(&b[key_idx]));
+        if self.spill.is_none() {
+            let id = NEXT_SPILL_ID.fetch_add(1, atomic::Ordering::Relaxed);
+            let path = self.spill_dir.join(format!(
+                "{}{}-{}.tmp",
+                SPILL_FILE_PREFIX,
+                std::process::id(),
+                id
+            ));
+            self.spill = Some(SpillFile {
+                disk: DiskManager::open(&path)?,
+                path,
+                next_page: 0,
+                runs: Vec::new(),
+            });
+        }
+        let spill = self.spill.as_mut().expect("spill file exists");
+
+        let start = spill.next_page;
+        let mut pending = Vec::new();
+        for row in self.buffer.drain(..) {
+            let bytes = RowSerializer::serialize(&row, Some(&self.schema))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            pending.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            pending.extend_from_slice(&bytes);
+            while pending.len() >= MAX_ROW_SIZE {
+                spill.write_chunk(&pending[..MAX_ROW_SIZE])?;
+                pending.drain(..MAX_ROW_SIZE);
+            }
+        }
+        if !pending.is_empty() {
+            spill.write_chunk(&pending)?;
+        }
+        spill.runs.push((start, spill.next_page));
+        Ok(())
+    }
+}
+
+/// Rows of an [`ExternalSort`] in key order
+pub struct SortedRows {
+    key_idx: usize,
+    source: Source,
+    peeked: Option<Vec<Value>>,
+}
+
+enum Source {
+    Memory(std::vec::IntoIter<Vec<Value>>),
+    Merge {
+        spill: SpillFile,
+        schema: Schema,
+        readers: Vec<RunReader>,
+        heap: BinaryHeap<MergeEntry>,
+    },
+}
+
+impl SortedRows {
+    /// Number of runs that were written to disk (0 for an in-memory sort)
+    pub fn spilled_runs(&self) -> usize {
+        match &self.source {
+            Source::Memory(_) => 0,
+            Source::Merge { spill, .. } => spill.runs.len(),
+        }
+    }
+
+    /// Next row in key order
+    ///
+    /// # Errors
+    /// Returns error if a run can't be read back
+    pub fn next_row(&mut self) -> io::Result<Option<Vec<Value>>> {
+        if let Some(row) = self.peeked.take() {
+            return Ok(Some(row));
+        }
+        match &mut self.source {
+            Source::Memory(rows) => Ok(rows.next()),
+            Source::Merge {
+                spill,
+                schema,
+                readers,
+                heap,
+            } => {
+                let Some(entry) = heap.pop() else {
+                    return Ok(None);
+                };
+                if let Some(row) = readers[entry.run].next_row(&mut spill.disk, schema)? {
+                    heap.push(MergeEntry {
+                        key_idx: self.key_idx,
+                        run: entry.run,
+                        row,
+                    });
+                }
+                Ok(Some(entry.row))
+            }
+        }
+    }
+
+    /// Next run of consecutive rows that share a key
+    ///
+    /// # Errors
+    /// Returns error if a run can't be read back
+    pub fn next_group(&mut self) -> io::Result<Option<Vec<Vec<Value>>>> {
+        let Some(first) = self.next_row()? else {
+            return Ok(None);
+        };
+        let mut group = vec![first];
+        while let Some(row) = self.next_row()? {
+            if row[self.key_idx] != group[0][self.key_idx] {
+                self.peeked = Some(row);
+                break;
+            }
+            group.push(row);
+        }
+        Ok(Some(group))
+    }
+}
+
+/// Temporary file holding spilled runs back to back, removed when dropped
+///
+/// Each run is a stream of length-prefixed serialized rows cut into
+/// page-sized chunks, one chunk per page, so rows may span pages.
+struct SpillFile {
+    disk: DiskManager,
+    path: PathBuf,
+    next_page: PageId,
+    /// Page range `[start, end)` of each run
+    runs: Vec<(PageId, PageId)>,
+}
+
+impl SpillFile {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()> {
+        let mut page = Page::new(self.next_page, PageType::Overflow);
+        page.add_row(chunk).map_err(io::Error::from)?;
+        self.disk.write_page(&page)?;
+        self.next_page += 1;
+        Ok(())
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Read position within one spilled run
+struct RunReader {
+    next_page: PageId,
+    end_page: PageId,
+    bytes: Vec<u8>,
+    pos: usize,
+}
+
+impl RunReader {
+    fn new(start: PageId, end: PageId) -> Self {
+        Self {
+            next_page: start,
+            end_page: end,
+            bytes: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Make at least `len` unread bytes available, returning false at the
+    /// end of the run
+    fn fill(&mut self, disk: &mut DiskManager, len: usize) -> io::Result<bool> {
+        while self.bytes.len() - self.pos < len {
+            if self.next_page == self.end_page {
+                return Ok(false);
+            }
+            self.bytes.drain(..self.pos);
+            self.pos = 0;
+            let page = disk.read_page(self.next_page)?;
+            let chunk = page.get_row(0).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Sort run page {} is empty", self.next_page),
+                )
+            })?;
+            self.bytes.extend_from_slice(chunk);
+            self.next_page += 1;
+        }
+        Ok(true)
+    }
+
+    fn next_row(
+        &mut self,
+        disk: &mut DiskManager,
+        schema: &Schema,
+    ) -> io::Result<Option<Vec<Value>>> {
+        if !self.fill(disk, 4)? {
+            if self.pos < self.bytes.len() {
+                return Err(truncated_run());
+            }
+            return Ok(None);
+        }
+        let len_bytes: [u8; 4] = self.bytes[self.pos..self.pos + 4]
+            .try_into()
+            .expect("four bytes");
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        self.pos += 4;
+        if !self.fill(disk, len)? {
+            return Err(truncated_run());
+        }
+        let row = RowSerializer::deserialize(&self.bytes[self.pos..self.pos + len], schema)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.pos += len;
+        Ok(Some(row))
+    }
+}
+
+fn truncated_run() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "Sort run ends mid-row")
+}
+
+/// The head row of one run, ordered so that `BinaryHeap` pops the smallest
+/// key first and, among equal keys, the earliest run
+struct MergeEntry {
+    key_idx: usize,
+    run: usize,
+    row: Vec<Value>,
+}
+
+impl PartialEq for MergeEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MergeEntry {}
+
+impl PartialOrd for MergeEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MergeEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.row[other.key_idx]
+            .cmp(&self.row[self.key_idx])
+            .then_with(|| other.run.cmp(&self.run))
+    }
+}
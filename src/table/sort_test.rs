@@ -0,0 +1,109 @@
+use super::sort::ExternalSort;
+use crate::types::{Column, DataType, Schema, Value};
+use tempfile::TempDir;
+
+fn schema() -> Schema {
+    Schema::new(vec![
+        Column::new("key", DataType::Integer),
+        Column::new("seq", DataType::Integer),
+        Column::new("body", DataType::String),
+    ])
+}
+
+fn row(key: i64, seq: i64, body: &str) -> Vec<Value> {
+    vec![
+        Value::Integer(key),
+        Value::Integer(seq),
+        Value::String(body.to_string()),
+    ]
+}
+
+#[test]
+fn test_small_inputs_sort_in_memory() {
+    let dir = TempDir::new().unwrap();
+    let mut sort = ExternalSort::new(schema(), 0, 10, dir.path());
+    for (seq, key) in [3, 1, 2].into_iter().enumerate() {
+        sort.push(row(key, seq as i64, "x")).unwrap();
+    }
+
+    let mut rows = sort.finish().unwrap();
+    assert_eq!(rows.spilled_runs(), 0);
+    let mut keys = Vec::new();
+    while let Some(row) = rows.next_row().unwrap() {
+        keys.push(row[0].clone());
+    }
+    assert_eq!(
+        keys,
+        vec![Value::Integer(1), Value::Integer(2), Value::Integer(3)]
+    );
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_spilled_runs_merge_in_stable_order() {
+    let dir = TempDir::new().unwrap();
+    let mut sort = ExternalSort::new(schema(), 0, 50, dir.path());
+    for seq in 0..1000 {
+        sort.push(row((seq * 7919) % 37, seq, "row")).unwrap();
+    }
+
+    let mut rows = sort.finish().unwrap();
+    assert_eq!(rows.spilled_runs(), 20);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    let mut seen = Vec::new();
+    while let Some(row) = rows.next_row().unwrap() {
+        seen.push((row[0].clone(), row[1].clone()));
+    }
+    assert_eq!(seen.len(), 1000);
+    // Sorted on key, and equal keys keep push order
+    assert!(seen.windows(2).all(|pair| pair[0] < pair[1]));
+
+    drop(rows);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_spilled_rows_may_span_pages() {
+    let dir = TempDir::new().unwrap();
+    let mut sort = ExternalSort::new(schema(), 0, 2, dir.path());
+    let bodies: Vec<String> = (0..5)
+        .map(|i| char::from(b'a' + i as u8).to_string().repeat(10_000))
+        .collect();
+    for (seq, body) in bodies.iter().enumerate().rev() {
+        sort.push(row(seq as i64, seq as i64, body)).unwrap();
+    }
+
+    let mut rows = sort.finish().unwrap();
+    assert_eq!(rows.spilled_runs(), 3);
+    for (seq, body) in bodies.iter().enumerate() {
+        assert_eq!(
+            rows.next_row().unwrap(),
+            Some(row(seq as i64, seq as i64, body))
+        );
+    }
+    assert_eq!(rows.next_row().unwrap(), None);
+}
+
+#[test]
+fn test_next_group_returns_rows_sharing_a_key() {
+    let dir = TempDir::new().unwrap();
+    let mut sort = ExternalSort::new(schema(), 0, 3, dir.path());
+    for (seq, key) in [2, 1, 2, 3, 1, 2].into_iter().enumerate() {
+        sort.push(row(key, seq as i64, "g")).unwrap();
+    }
+
+    let mut rows = sort.finish().unwrap();
+    let mut groups = Vec::new();
+    while let Some(group) = rows.next_group().unwrap() {
+        groups.push(group.iter().map(|row| row[1].clone()).collect::<Vec<_>>());
+    }
+    assert_eq!(
+        groups,
+        vec![
+            vec![Value::Integer(1), Value::Integer(4)],
+            vec![Value::Integer(0), Value::Integer(2), Value::Integer(5)],
+            vec![Value::Integer(3)],
+        ]
+    );
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use crate::types::{Json, JsonPath, Value};
+
+    fn extract(document: &str, path: &str) -> Option<Value> {
+        let json = Json::parse(document).unwrap();
+        json.get(&JsonPath::parse(path).unwrap())
+            .map(Json::to_value)
+    }
+
+    #[test]
+    fn test_parse_accepts_well_formed_documents() {
+        for text in [
+            "null",
+            " true ",
+            "-0.5e+3",
+            r#""caf\u00e9 \ud83d\ude00""#,
+            r#"{"a": [1, {"b": null}], "c": "x"}"#,
+            "[]",
+            "{}",
+        ] {
+            assert!(Json::parse(text).is_ok(), "{}", text);
+        }
+        assert_eq!(
+            Json::parse(r#"{ "a" : [1, "two\n"] }"#)
+                .unwrap()
+                .to_string(),
+            r#"{"a":[1,"two\n"]}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_documents() {
+        for text in [
+            "",
+            "{",
+            "{\"a\" 1}",
+            "[1,]",
+            "01",
+            "1.",
+            "tru",
+            "\"unterminated",
+            "\"\\x\"",
+            "\"\\ud800\"",
+            "{} {}",
+            "{a: 1}",
+        ] {
+            assert!(Json::parse(text).is_err(), "{}", text);
+        }
+        assert!(Json::parse(&"[".repeat(1000)).is_err());
+    }
+
+    #[test]
+    fn test_path_extraction_returns_scalars() {
+        let doc = r#"{"name": "Ada", "age": 36, "score": 9.5, "admin": false,
+                      "manager": null, "tags": ["x", "y"], "address": {"city": "London"}}"#;
+        assert_eq!(extract(doc, "$.name"), Some(Value::String("Ada".into())));
+        assert_eq!(extract(doc, "$.age"), Some(Value::Integer(36)));
+        assert_eq!(extract(doc, "$.score"), Some(Value::Float(9.5)));
+        assert_eq!(extract(doc, "$.admin"), Some(Value::Boolean(false)));
+        assert_eq!(extract(doc, "$.manager"), Some(Value::Null));
+        assert_eq!(extract(doc, "$.tags[1]"), Some(Value::String("y".into())));
+        assert_eq!(
+            extract(doc, "$.address.city"),
+            Some(Value::String("London".into()))
+        );
+        assert_eq!(
+            extract(doc, "$.tags"),
+            Some(Value::String(r#"["x","y"]"#.into()))
+        );
+        assert_eq!(extract(doc, "$.missing"), None);
+        assert_eq!(extract(doc, "$.tags[2]"), None);
+        assert_eq!(extract(doc, "$.name.first"), None);
+        assert_eq!(extract("[1, 2]", "$[0]"), Some(Value::Integer(1)));
+        assert_eq!(
+            extract("12345678901234567890", "$"),
+            Some(Value::Float(12345678901234567890.0))
+        );
+    }
+
+    #[test]
+    fn test_path_parse_rejects_malformed_paths() {
+        for path in ["", "name", "$.", "$..a", "$[x]", "$[-1]", "$[0", "$a"] {
+            assert!(JsonPath::parse(path).is_err(), "{}", path);
+        }
+    }
+}
@@ -1,10 +1,14 @@
+pub mod json;
 pub mod schema;
 pub mod value;
 
+#[cfg(test)]
+mod json_test;
 #[cfg(test)]
 mod schema_test;
 #[cfg(test)]
 mod value_test;
 
+pub use json::{Json, JsonPath};
 pub use schema::{Column, DataType, Schema, SchemaError};
-pub use value::{Date, Decimal, Timestamp, Value};
+pub use value::{Date, Decimal, Interval, Timestamp, Value};
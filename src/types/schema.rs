@@ -11,6 +11,8 @@ pub enum DataType {
     Date,
     Timestamp,
     Decimal,
+    /// JSON document, stored as its text
+    Json,
 }
 
 impl DataType {
@@ -30,6 +32,7 @@ impl DataType {
             (DataType::Date, Value::Date(_)) => true,
             (DataType::Timestamp, Value::Timestamp(_)) => true,
             (DataType::Decimal, Value::Decimal(_)) => true,
+            (DataType::Json, Value::String(_)) => true,
             _ => false,
         }
     }
@@ -46,6 +49,7 @@ impl std::fmt::Display for DataType {
             DataType::Date => write!(f, "DATE"),
             DataType::Timestamp => write!(f, "TIMESTAMP"),
             DataType::Decimal => write!(f, "DECIMAL"),
+            DataType::Json => write!(f, "JSON"),
         }
     }
 }
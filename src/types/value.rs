@@ -32,6 +32,30 @@ impl Date {
         Ok(Self { year, month, day })
     }
 
+    /// Today's date in UTC
+    pub fn today() -> Self {
+        let now = Timestamp::now();
+        Self {
+            year: now.year,
+            month: now.month,
+            day: now.day,
+        }
+    }
+
+    /// Add a whole-day interval, or `None` if the interval has a time-of-day
+    /// part or the result is outside years 0 through 9999
+    pub fn checked_add(&self, interval: &Interval) -> Option<Self> {
+        if interval.has_time_part() {
+            return None;
+        }
+        let ts = Timestamp::from(*self).checked_add(interval)?;
+        Some(Self {
+            year: ts.year,
+            month: ts.month,
+            day: ts.day,
+        })
+    }
+
     fn key(&self) -> i32 {
         self.year * 10_000 + self.month as i32 * 100 + self.day as i32
     }
@@ -105,6 +129,49 @@ impl Timestamp {
         })
     }
 
+    /// The current time in UTC, truncated to whole seconds
+    pub fn now() -> Self {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Self::from_unix_seconds(secs).expect("current time is within the supported range")
+    }
+
+    /// Add an interval, or `None` if the result is outside years 0 through 9999
+    ///
+    /// Months are applied first as calendar arithmetic, clamping the day to the
+    /// end of the resulting month (January 31 plus one month is February 28 or
+    /// 29); the fixed-duration part is then added in seconds.
+    pub fn checked_add(&self, interval: &Interval) -> Option<Self> {
+        let total_months = (self.year as i64) * 12 + (self.month as i64 - 1) + interval.months;
+        let year = i32::try_from(total_months.div_euclid(12)).ok()?;
+        let month = (total_months.rem_euclid(12) + 1) as u8;
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        let day = self.day.min(days_in_month(year, month));
+
+        let seconds = days_from_civil(year, month, day)
+            .checked_mul(SECONDS_PER_DAY)?
+            .checked_add(self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64)?
+            .checked_add(interval.seconds)?;
+        Self::from_unix_seconds(seconds)
+    }
+
+    fn from_unix_seconds(seconds: i64) -> Option<Self> {
+        let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY))?;
+        let time = seconds.rem_euclid(SECONDS_PER_DAY);
+        Some(Self {
+            year,
+            month,
+            day,
+            hour: (time / 3600) as u8,
+            minute: (time / 60 % 60) as u8,
+            second: (time % 60) as u8,
+        })
+    }
+
     fn key(&self) -> i64 {
         (self.year as i64) * 10_000_000_000
             + (self.month as i64) * 100_000_000
@@ -115,6 +182,20 @@ impl Timestamp {
     }
 }
 
+impl From<Date> for Timestamp {
+    /// Midnight at the start of `date`
+    fn from(date: Date) -> Self {
+        Self {
+            year: date.year,
+            month: date.month,
+            day: date.day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        }
+    }
+}
+
 impl fmt::Display for Timestamp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -125,6 +206,100 @@ impl fmt::Display for Timestamp {
     }
 }
 
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A span of time made of calendar months and a fixed number of seconds
+///
+/// YEAR and MONTH intervals are kept as months because their length depends
+/// on the date they are added to; DAY and smaller units are fixed durations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Interval {
+    pub months: i64,
+    pub seconds: i64,
+}
+
+impl Interval {
+    /// Build an interval from a quantity such as `"7"` and a unit such as
+    /// `"DAY"`; units are case-insensitive and may be plural
+    pub fn parse(quantity: &str, unit: &str) -> Result<Self, String> {
+        let amount: i64 = quantity
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid interval quantity '{}'", quantity))?;
+        let upper = unit.to_uppercase();
+        let (months, seconds) = match upper.strip_suffix('S').unwrap_or(&upper) {
+            "YEAR" => (12, 0),
+            "MONTH" => (1, 0),
+            "WEEK" => (0, 7 * SECONDS_PER_DAY),
+            "DAY" => (0, SECONDS_PER_DAY),
+            "HOUR" => (0, 3600),
+            "MINUTE" => (0, 60),
+            "SECOND" => (0, 1),
+            _ => return Err(format!("Unknown interval unit '{}'", unit)),
+        };
+        let overflow = || "Interval out of range".to_string();
+        Ok(Self {
+            months: amount.checked_mul(months).ok_or_else(overflow)?,
+            seconds: amount.checked_mul(seconds).ok_or_else(overflow)?,
+        })
+    }
+
+    /// True if the interval is not a whole number of days
+    pub fn has_time_part(&self) -> bool {
+        self.seconds % SECONDS_PER_DAY != 0
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            months: self.months.checked_add(other.months)?,
+            seconds: self.seconds.checked_add(other.seconds)?,
+        })
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Self {
+            months: self.months.checked_neg()?,
+            seconds: self.seconds.checked_neg()?,
+        })
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn plural(n: i64) -> &'static str {
+            if n.abs() == 1 { "" } else { "s" }
+        }
+
+        let mut parts = Vec::new();
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years != 0 {
+            parts.push(format!("{} year{}", years, plural(years)));
+        }
+        if months != 0 {
+            parts.push(format!("{} mon{}", months, plural(months)));
+        }
+        let (days, time) = (
+            self.seconds / SECONDS_PER_DAY,
+            self.seconds % SECONDS_PER_DAY,
+        );
+        if days != 0 {
+            parts.push(format!("{} day{}", days, plural(days)));
+        }
+        if time != 0 || parts.is_empty() {
+            let sign = if time < 0 { "-" } else { "" };
+            let time = time.abs();
+            parts.push(format!(
+                "{}{:02}:{:02}:{:02}",
+                sign,
+                time / 3600,
+                time / 60 % 60,
+                time % 60
+            ));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Decimal {
     pub value: i128,
@@ -210,6 +385,46 @@ impl Decimal {
         self.to_string().parse::<f64>().ok()
     }
 
+    /// Exact sum at the larger of the two scales, or `None` on overflow
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        let left = self.value.checked_mul(pow10_i128(scale - self.scale)?)?;
+        let right = other.value.checked_mul(pow10_i128(scale - other.scale)?)?;
+        Some(Self {
+            value: left.checked_add(right)?,
+            scale,
+        })
+    }
+
+    /// Divide by an integer, rounding half away from zero to `scale` digits
+    ///
+    /// Returns `None` on division by zero or overflow.
+    pub fn checked_div_int(&self, divisor: i128, scale: u32) -> Option<Self> {
+        if divisor == 0 {
+            return None;
+        }
+        let (numerator, denominator) = if scale >= self.scale {
+            (
+                self.value.checked_mul(pow10_i128(scale - self.scale)?)?,
+                divisor,
+            )
+        } else {
+            (
+                self.value,
+                divisor.checked_mul(pow10_i128(self.scale - scale)?)?,
+            )
+        };
+
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+        let value = if remainder.checked_abs()?.checked_mul(2)? >= denominator.checked_abs()? {
+            quotient + numerator.signum() * denominator.signum()
+        } else {
+            quotient
+        };
+        Some(Self { value, scale })
+    }
+
     fn rescale(&self, target_scale: u32) -> i128 {
         if self.scale == target_scale {
             self.value
@@ -272,6 +487,8 @@ pub enum Value {
     Date(Date),
     Timestamp(Timestamp),
     Decimal(Decimal),
+    /// Result of interval arithmetic; intervals cannot be stored in a table
+    Interval(Interval),
     Null,
 }
 
@@ -387,6 +604,7 @@ impl Value {
             Value::String(_) => ValueKind::String,
             Value::Date(_) => ValueKind::Date,
             Value::Timestamp(_) => ValueKind::Timestamp,
+            Value::Interval(_) => ValueKind::Interval,
             Value::Null => ValueKind::Null,
         }
     }
@@ -403,6 +621,7 @@ impl fmt::Display for Value {
             Value::Date(d) => write!(f, "{}", d),
             Value::Timestamp(t) => write!(f, "{}", t),
             Value::Decimal(d) => write!(f, "{}", d),
+            Value::Interval(i) => write!(f, "{}", i),
             Value::Null => write!(f, "NULL"),
         }
     }
@@ -416,17 +635,18 @@ impl PartialEq for Value {
             (Value::Integer(a), Value::Unsigned(b)) | (Value::Unsigned(b), Value::Integer(a)) => {
                 *a >= 0 && (*a as u64) == *b
             }
-            (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+            (Value::Float(a), Value::Float(b)) => cmp_floats(*a, *b) == Ordering::Equal,
             (Value::Float(a), Value::Integer(b)) | (Value::Integer(b), Value::Float(a)) => {
-                (*a as f64) == *b as f64
+                cmp_int_float(*b as i128, *a) == Ordering::Equal
             }
             (Value::Float(a), Value::Unsigned(b)) | (Value::Unsigned(b), Value::Float(a)) => {
-                (*a as f64) == *b as f64
+                cmp_int_float(*b as i128, *a) == Ordering::Equal
             }
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
             (Value::String(a), Value::String(b)) => a == b,
             (Value::Date(a), Value::Date(b)) => a == b,
             (Value::Timestamp(a), Value::Timestamp(b)) => a == b,
+            (Value::Interval(a), Value::Interval(b)) => a == b,
             (Value::Decimal(a), Value::Decimal(b)) => a.cmp_scaled(b) == Ordering::Equal,
             (Value::Decimal(a), Value::Integer(b)) | (Value::Integer(b), Value::Decimal(a)) => {
                 a.cmp_scaled(&Decimal::from_i128(*b as i128)) == Ordering::Equal
@@ -475,15 +695,26 @@ impl Ord for Value {
                     a.cmp(&(*b as u64))
                 }
             }
-            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
-            (Value::Float(a), Value::Integer(b)) => a.total_cmp(&(*b as f64)),
-            (Value::Float(a), Value::Unsigned(b)) => a.total_cmp(&(*b as f64)),
-            (Value::Integer(a), Value::Float(b)) => (*a as f64).total_cmp(b),
-            (Value::Unsigned(a), Value::Float(b)) => (*a as f64).total_cmp(b),
+            (Value::Float(a), Value::Float(b)) => cmp_floats(*a, *b),
+            (Value::Float(a), Value::Integer(b)) => cmp_int_float(*b as i128, *a).reverse(),
+            (Value::Float(a), Value::Unsigned(b)) => cmp_int_float(*b as i128, *a).reverse(),
+            (Value::Integer(a), Value::Float(b)) => cmp_int_float(*a as i128, *b),
+            (Value::Unsigned(a), Value::Float(b)) => cmp_int_float(*a as i128, *b),
             (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
             (Value::String(a), Value::String(b)) => a.cmp(b),
             (Value::Date(a), Value::Date(b)) => a.key().cmp(&b.key()),
             (Value::Timestamp(a), Value::Timestamp(b)) => a.key().cmp(&b.key()),
+            // A date sorts as its midnight, just before that timestamp itself
+            (Value::Date(a), Value::Timestamp(b)) => {
+                Timestamp::from(*a).key().cmp(&b.key()).then(Ordering::Less)
+            }
+            (Value::Timestamp(a), Value::Date(b)) => a
+                .key()
+                .cmp(&Timestamp::from(*b).key())
+                .then(Ordering::Greater),
+            (Value::Interval(a), Value::Interval(b)) => {
+                (a.months, a.seconds).cmp(&(b.months, b.seconds))
+            }
             (Value::Decimal(a), Value::Decimal(b)) => a.cmp_scaled(b),
             (Value::Decimal(a), Value::Integer(b)) => a.cmp_scaled(&Decimal::from_i128(*b as i128)),
             (Value::Integer(a), Value::Decimal(b)) => Decimal::from_i128(*a as i128).cmp_scaled(b),
@@ -493,21 +724,19 @@ impl Ord for Value {
             (Value::Unsigned(a), Value::Decimal(b)) => Decimal::from_i128(*a as i128).cmp_scaled(b),
             (Value::Decimal(a), Value::Float(b)) => Decimal::from_f64(*b)
                 .map(|dec| a.cmp_scaled(&dec))
-                .unwrap_or_else(|| a.to_f64().unwrap_or(0.0).total_cmp(b)),
+                .unwrap_or_else(|| cmp_floats(a.to_f64().unwrap_or(0.0), *b)),
             (Value::Float(a), Value::Decimal(b)) => Decimal::from_f64(*a)
                 .map(|dec| dec.cmp_scaled(b))
-                .unwrap_or_else(|| a.total_cmp(&b.to_f64().unwrap_or(0.0))),
+                .unwrap_or_else(|| cmp_floats(*a, b.to_f64().unwrap_or(0.0))),
             _ => match (self.kind(), other.kind()) {
                 (ValueKind::Numeric, ValueKind::Date) => Ordering::Less,
                 (ValueKind::Numeric, ValueKind::Timestamp) => Ordering::Less,
                 (ValueKind::Numeric, ValueKind::Boolean) => Ordering::Less,
                 (ValueKind::Numeric, ValueKind::String) => Ordering::Less,
                 (ValueKind::Date, ValueKind::Numeric) => Ordering::Greater,
-                (ValueKind::Date, ValueKind::Timestamp) => Ordering::Less,
                 (ValueKind::Date, ValueKind::Boolean) => Ordering::Less,
                 (ValueKind::Date, ValueKind::String) => Ordering::Less,
                 (ValueKind::Timestamp, ValueKind::Numeric) => Ordering::Greater,
-                (ValueKind::Timestamp, ValueKind::Date) => Ordering::Greater,
                 (ValueKind::Timestamp, ValueKind::Boolean) => Ordering::Less,
                 (ValueKind::Timestamp, ValueKind::String) => Ordering::Less,
                 (ValueKind::Boolean, ValueKind::Numeric) => Ordering::Greater,
@@ -518,12 +747,36 @@ impl Ord for Value {
                 (ValueKind::String, ValueKind::Date) => Ordering::Greater,
                 (ValueKind::String, ValueKind::Timestamp) => Ordering::Greater,
                 (ValueKind::String, ValueKind::Boolean) => Ordering::Greater,
+                (ValueKind::Interval, _) => Ordering::Greater,
+                (_, ValueKind::Interval) => Ordering::Less,
                 _ => Ordering::Equal,
             },
         }
     }
 }
 
+/// Total order of floats for sorting, grouping and DISTINCT: -0.0 equals
+/// 0.0, and every NaN equals every other NaN and sorts after all numbers.
+fn cmp_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).expect("neither operand is NaN"),
+    }
+}
+
+/// Exact order of an integer and a float. Converting the integer to f64 can
+/// round it, so a tie there is settled by comparing as integers.
+fn cmp_int_float(int: i128, float: f64) -> Ordering {
+    match (int as f64).partial_cmp(&float) {
+        Some(Ordering::Equal) => int.cmp(&(float as i128)),
+        Some(ordering) => ordering,
+        // Only NaN is unordered, and it sorts after every number
+        None => Ordering::Less,
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ValueKind {
     Numeric,
@@ -531,6 +784,7 @@ enum ValueKind {
     Timestamp,
     Boolean,
     String,
+    Interval,
     Null,
 }
 
@@ -538,7 +792,14 @@ fn validate_date_components(year: i32, month: u8, day: u8) -> Result<(), String>
     if !(1..=12).contains(&month) {
         return Err("Invalid month in date".to_string());
     }
-    let max_day = match month {
+    if day == 0 || day > days_in_month(year, month) {
+        return Err("Invalid day in date".to_string());
+    }
+    Ok(())
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
         1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
         4 | 6 | 9 | 11 => 30,
         2 => {
@@ -548,12 +809,41 @@ fn validate_date_components(year: i32, month: u8, day: u8) -> Result<(), String>
                 28
             }
         }
-        _ => unreachable!("month validated above"),
-    };
-    if day == 0 || day > max_day {
-        return Err("Invalid day in date".to_string());
+        _ => unreachable!("month validated by caller"),
     }
-    Ok(())
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = year as i64 - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of `days_from_civil`, or `None` outside years 0 through 9999
+fn civil_from_days(days: i64) -> Option<(i32, u8, u8)> {
+    let days = days.checked_add(719_468)?;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u8;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    if !(0..=9999).contains(&year) {
+        return None;
+    }
+    Some((year as i32, month, day))
 }
 
 fn is_leap_year(year: i32) -> bool {
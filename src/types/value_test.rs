@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
-    use crate::types::{Date, Decimal, Timestamp, Value};
+    use crate::types::{Date, Decimal, Interval, Timestamp, Value};
+    use std::cmp::Ordering;
 
     #[test]
     fn test_integer_creation() {
@@ -80,6 +81,153 @@ mod tests {
         assert_eq!(val.as_decimal(), Some(dec));
     }
 
+    #[test]
+    fn test_interval_parse() {
+        assert_eq!(
+            Interval::parse("7", "DAY").unwrap(),
+            Interval {
+                months: 0,
+                seconds: 7 * 86_400
+            }
+        );
+        assert_eq!(
+            Interval::parse("2", "years").unwrap(),
+            Interval {
+                months: 24,
+                seconds: 0
+            }
+        );
+        assert_eq!(Interval::parse("-90", "minute").unwrap().seconds, -5400);
+        assert!(Interval::parse("1.5", "DAY").is_err());
+        assert!(Interval::parse("1", "FORTNIGHT").is_err());
+        assert_eq!(
+            Interval::parse("26", "HOUR").unwrap().to_string(),
+            "1 day 02:00:00"
+        );
+        assert_eq!(
+            Interval::parse("14", "MONTH").unwrap().to_string(),
+            "1 year 2 mons"
+        );
+        assert_eq!(Interval::default().to_string(), "00:00:00");
+    }
+
+    #[test]
+    fn test_timestamp_add_fixed_interval() {
+        let ts = Timestamp::parse("2024-12-31 23:30:00").unwrap();
+        let hour = Interval::parse("1", "HOUR").unwrap();
+        assert_eq!(
+            ts.checked_add(&hour).unwrap().to_string(),
+            "2025-01-01 00:30:00"
+        );
+        assert_eq!(
+            ts.checked_add(&hour.checked_neg().unwrap())
+                .unwrap()
+                .to_string(),
+            "2024-12-31 22:30:00"
+        );
+
+        let leap = Timestamp::parse("2024-02-28 12:00:00").unwrap();
+        let day = Interval::parse("1", "DAY").unwrap();
+        assert_eq!(
+            leap.checked_add(&day).unwrap().to_string(),
+            "2024-02-29 12:00:00"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_add_calendar_interval() {
+        let ts = Timestamp::parse("2024-01-31 08:00:00").unwrap();
+        let month = Interval::parse("1", "MONTH").unwrap();
+        // The day is clamped to the end of the shorter month
+        assert_eq!(
+            ts.checked_add(&month).unwrap().to_string(),
+            "2024-02-29 08:00:00"
+        );
+        assert_eq!(
+            ts.checked_add(&month.checked_neg().unwrap())
+                .unwrap()
+                .to_string(),
+            "2023-12-31 08:00:00"
+        );
+
+        let leap_day = Timestamp::parse("2024-02-29 00:00:00").unwrap();
+        let year = Interval::parse("1", "YEAR").unwrap();
+        assert_eq!(
+            leap_day.checked_add(&year).unwrap().to_string(),
+            "2025-02-28 00:00:00"
+        );
+
+        let end = Timestamp::parse("9999-12-31 00:00:00").unwrap();
+        assert_eq!(end.checked_add(&Interval::parse("1", "DAY").unwrap()), None);
+    }
+
+    #[test]
+    fn test_date_add_interval() {
+        let date = Date::parse("2025-03-31").unwrap();
+        assert_eq!(
+            date.checked_add(&Interval::parse("-1", "MONTH").unwrap())
+                .unwrap()
+                .to_string(),
+            "2025-02-28"
+        );
+        assert_eq!(
+            date.checked_add(&Interval::parse("2", "WEEK").unwrap())
+                .unwrap()
+                .to_string(),
+            "2025-04-14"
+        );
+        assert_eq!(
+            date.checked_add(&Interval::parse("1", "HOUR").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decimal_checked_add() {
+        let a = Decimal::parse("0.10").unwrap();
+        let b = Decimal::parse("0.2").unwrap();
+        assert_eq!(
+            a.checked_add(&b),
+            Some(Decimal {
+                value: 30,
+                scale: 2
+            })
+        );
+        assert_eq!(
+            a.checked_add(&Decimal::from_i128(-1)).unwrap().to_string(),
+            "-0.90"
+        );
+
+        let max = Decimal {
+            value: i128::MAX,
+            scale: 0,
+        };
+        assert_eq!(max.checked_add(&Decimal::from_i128(1)), None);
+    }
+
+    #[test]
+    fn test_decimal_checked_div_int() {
+        let total = Decimal::parse("10.00").unwrap();
+        assert_eq!(total.checked_div_int(3, 6).unwrap().to_string(), "3.333333");
+        assert_eq!(
+            Decimal::parse("2.00")
+                .unwrap()
+                .checked_div_int(3, 6)
+                .unwrap()
+                .to_string(),
+            "0.666667"
+        );
+        assert_eq!(
+            Decimal::parse("-2.5")
+                .unwrap()
+                .checked_div_int(2, 0)
+                .unwrap()
+                .to_string(),
+            "-1"
+        );
+        assert_eq!(total.checked_div_int(0, 2), None);
+    }
+
     #[test]
     fn test_display() {
         assert_eq!(format!("{}", Value::Integer(42)), "42");
@@ -150,6 +298,80 @@ mod tests {
         assert!(f > a);
     }
 
+    #[test]
+    fn test_min_max_over_mixed_numeric_representations() {
+        // MIN/MAX fold a column with `<` and `>`, skipping NULLs
+        let column = vec![
+            Value::Unsigned(7),
+            Value::Integer(-3),
+            Value::Null,
+            Value::Unsigned(u64::MAX),
+            Value::Integer(5),
+            Value::Float(6.5),
+            Value::Integer(i64::MAX),
+        ];
+        let min = column.iter().filter(|v| !v.is_null()).min().unwrap();
+        let max = column.iter().filter(|v| !v.is_null()).max().unwrap();
+        assert_eq!(min, &Value::Integer(-3));
+        assert_eq!(max, &Value::Unsigned(u64::MAX));
+
+        // Integers too large for an exact f64 still order against floats
+        let big = 9_007_199_254_740_993;
+        let rounded = Value::Float(9_007_199_254_740_992.0);
+        assert!(Value::Integer(big) > rounded);
+        assert!(Value::Unsigned(big as u64) > rounded);
+        assert!(rounded < Value::Integer(big));
+        assert_ne!(Value::Integer(big), rounded);
+        assert_eq!(Value::Integer(big - 1), rounded);
+        assert_eq!(Value::Integer(0), Value::Float(-0.0));
+        assert!(Value::Float(f64::NAN) > Value::Unsigned(u64::MAX));
+    }
+
+    #[test]
+    fn test_float_total_order() {
+        let nan = Value::Float(f64::NAN);
+        assert_eq!(nan, Value::Float(-f64::NAN));
+        assert_eq!(nan.cmp(&Value::Float(-f64::NAN)), Ordering::Equal);
+        assert!(nan > Value::Float(f64::INFINITY));
+        assert!(Value::Float(-f64::NAN) > Value::Integer(i64::MAX));
+        assert!(Value::Integer(i64::MIN) < Value::Float(-f64::NAN));
+        assert!(nan > Value::Decimal(Decimal::parse("1.5").unwrap()));
+        assert_eq!(Value::Float(0.0), Value::Float(-0.0));
+        assert_eq!(Value::Float(-0.0).cmp(&Value::Float(0.0)), Ordering::Equal);
+
+        let mut values = vec![
+            Value::Float(f64::NAN),
+            Value::Float(f64::INFINITY),
+            Value::Float(0.0),
+            Value::Float(-f64::NAN),
+            Value::Float(f64::NEG_INFINITY),
+            Value::Float(-0.0),
+            Value::Float(-1.5),
+        ];
+        values.sort();
+        assert_eq!(values[0], Value::Float(f64::NEG_INFINITY));
+        assert_eq!(values[1], Value::Float(-1.5));
+        assert_eq!(values[2], Value::Float(0.0));
+        assert_eq!(values[3], Value::Float(0.0));
+        assert_eq!(values[4], Value::Float(f64::INFINITY));
+        assert!(values[5..].iter().all(|v| v.as_float().unwrap().is_nan()));
+
+        values.dedup();
+        assert_eq!(values.len(), 5);
+    }
+
+    #[test]
+    fn test_date_timestamp_comparison() {
+        let date = Value::Date(Date::parse("2025-01-02").unwrap());
+        let midnight = Value::Timestamp(Timestamp::parse("2025-01-02 00:00:00").unwrap());
+        let evening = Value::Timestamp(Timestamp::parse("2025-01-01 23:00:00").unwrap());
+
+        assert!(evening < date);
+        assert!(date < midnight);
+        assert!(midnight > date);
+        assert_ne!(date, midnight);
+    }
+
     #[test]
     fn test_string_comparison() {
         let a = Value::String("apple".to_string());
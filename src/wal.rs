@@ -101,6 +101,7 @@ enum ValueTag {
     Date = 6,
     Timestamp = 7,
     Decimal = 8,
+    Interval = 9,
 }
 
 impl ValueTag {
@@ -115,6 +116,7 @@ impl ValueTag {
             6 => Ok(ValueTag::Date),
             7 => Ok(ValueTag::Timestamp),
             8 => Ok(ValueTag::Decimal),
+            9 => Ok(ValueTag::Interval),
             _ => Err(WalError::InvalidValueTag(value)),
         }
     }
@@ -130,11 +132,24 @@ impl ValueTag {
             Value::Date(_) => ValueTag::Date,
             Value::Timestamp(_) => ValueTag::Timestamp,
             Value::Decimal(_) => ValueTag::Decimal,
+            Value::Interval(_) => ValueTag::Interval,
         }
     }
 }
 
 impl WalRecord {
+    /// Transaction the record belongs to
+    pub fn txn_id(&self) -> TxnId {
+        match self {
+            WalRecord::Begin { txn_id }
+            | WalRecord::Commit { txn_id }
+            | WalRecord::Rollback { txn_id }
+            | WalRecord::Insert { txn_id, .. }
+            | WalRecord::Update { txn_id, .. }
+            | WalRecord::Delete { txn_id, .. } => *txn_id,
+        }
+    }
+
     /// Serialize a WAL record into bytes (length prefixing happens at the file layer).
     pub fn serialize(&self) -> io::Result<Vec<u8>> {
         let mut buf = Vec::new();
@@ -245,34 +260,44 @@ impl WalRecord {
     }
 }
 
+/// Append-only log file, or a disabled log that keeps nothing
 pub struct WalFile {
-    path: PathBuf,
+    path: Option<PathBuf>,
 }
 
 impl WalFile {
     pub fn new(path: impl AsRef<Path>) -> Self {
         Self {
-            path: path.as_ref().to_path_buf(),
+            path: Some(path.as_ref().to_path_buf()),
         }
     }
 
+    /// A log that discards every record, for databases with nothing on disk
+    /// to recover
+    pub fn disabled() -> Self {
+        Self { path: None }
+    }
+
     pub fn append(&self, record: &WalRecord) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
         let data = record.serialize()?;
         let mut file = std::fs::OpenOptions::new()
             .create(true)
             .append(true)
-            .open(&self.path)?;
+            .open(path)?;
         codec::write_u32(&mut file, data.len() as u32)?;
         file.write_all(&data)?;
         file.flush()
     }
 
     pub fn read_all(&self) -> io::Result<Vec<WalRecord>> {
-        if !self.path.exists() {
+        let Some(path) = self.path.as_ref().filter(|path| path.exists()) else {
             return Ok(Vec::new());
-        }
+        };
 
-        let mut file = std::fs::File::open(&self.path)?;
+        let mut file = std::fs::File::open(path)?;
         let mut records = Vec::new();
 
         loop {
@@ -299,11 +324,11 @@ impl WalFile {
     }
 
     pub fn truncate(&self) -> io::Result<()> {
-        if !self.path.exists() {
+        let Some(path) = self.path.as_ref().filter(|path| path.exists()) else {
             return Ok(());
-        }
+        };
 
-        let file = std::fs::OpenOptions::new().write(true).open(&self.path)?;
+        let file = std::fs::OpenOptions::new().write(true).open(path)?;
         file.set_len(0)
     }
 }
@@ -375,6 +400,10 @@ fn write_value(buf: &mut Vec<u8>, value: &Value) -> io::Result<()> {
             codec::write_i128(buf, d.value)?;
             codec::write_u32(buf, d.scale)
         }
+        Value::Interval(interval) => {
+            codec::write_i64(buf, interval.months)?;
+            codec::write_i64(buf, interval.seconds)
+        }
     }
 }
 
@@ -414,6 +443,11 @@ fn read_value(cursor: &mut Cursor<&[u8]>) -> Result<Value, WalError> {
             let scale = codec::read_u32(cursor)?;
             Value::Decimal(crate::types::Decimal { value, scale })
         }
+        ValueTag::Interval => {
+            let months = codec::read_i64(cursor)?;
+            let seconds = codec::read_i64(cursor)?;
+            Value::Interval(crate::types::Interval { months, seconds })
+        }
     };
     Ok(value)
 }
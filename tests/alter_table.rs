@@ -1,8 +1,8 @@
 mod common;
 
 use common::TestDb;
-use db2::sql::ExecutionResult;
-use db2::types::Value;
+use db2::sql::{ExecutionResult, Executor, parse_sql};
+use db2::types::{Decimal, Value};
 
 #[test]
 fn test_alter_table_add_column() {
@@ -32,6 +32,72 @@ fn test_alter_table_add_column() {
     }
 }
 
+#[test]
+fn test_alter_table_add_not_null_column_to_empty_table() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER)");
+    db.execute_ok("ALTER TABLE users ADD COLUMN age INTEGER NOT NULL");
+
+    let err = db.execute_err("INSERT INTO users VALUES (1, NULL)");
+    assert!(err.to_string().contains("NOT NULL"), "{}", err);
+    db.execute_ok("INSERT INTO users VALUES (1, 30)");
+}
+
+#[test]
+fn test_alter_table_add_not_null_column_backfills_default() {
+    let mut db = TestDb::on_disk().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
+    db.execute_ok("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob')");
+
+    let err = db.execute_err("ALTER TABLE users ADD COLUMN age INTEGER NOT NULL");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("without a DEFAULT"), "{}", err);
+    let err = db.execute_err("ALTER TABLE users ADD COLUMN age INTEGER NOT NULL DEFAULT NULL");
+    assert!(err.to_string().contains("without a DEFAULT"), "{}", err);
+    db.execute_err("ALTER TABLE users ADD COLUMN age INTEGER NOT NULL DEFAULT 'old'");
+
+    db.execute_ok("ALTER TABLE users ADD COLUMN age INTEGER NOT NULL DEFAULT 18");
+    db.execute_ok("ALTER TABLE users ADD COLUMN city VARCHAR DEFAULT 'Paris'");
+    db.execute_ok("INSERT INTO users VALUES (3, 'Carol', 40, NULL)");
+
+    let expected = vec![
+        vec![
+            Value::Integer(1),
+            Value::Integer(18),
+            Value::String("Paris".to_string()),
+        ],
+        vec![
+            Value::Integer(2),
+            Value::Integer(18),
+            Value::String("Paris".to_string()),
+        ],
+        vec![Value::Integer(3), Value::Integer(40), Value::Null],
+    ];
+    match db.execute_ok("SELECT id, age, city FROM users ORDER BY id ASC") {
+        ExecutionResult::Select { rows, .. } => assert_eq!(rows, expected),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    // The NOT NULL constraint survives a restart
+    db.flush().unwrap();
+    let mut executor = Executor::new(db.path(), 10).unwrap();
+    let err = executor
+        .execute(parse_sql("UPDATE users SET age = NULL WHERE id = 1").unwrap())
+        .unwrap_err();
+    assert!(err.to_string().contains("NOT NULL"), "{}", err);
+}
+
+#[test]
+fn test_create_table_rejects_default() {
+    let mut db = TestDb::new().unwrap();
+
+    let err = db.execute_err("CREATE TABLE users (id INTEGER DEFAULT 0)");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("ADD COLUMN"), "{}", err);
+}
+
 #[test]
 fn test_alter_table_drop_column() {
     let mut db = TestDb::new().unwrap();
@@ -86,7 +152,7 @@ fn test_alter_table_rename_column() {
 
 #[test]
 fn test_alter_table_rename_table() {
-    let mut db = TestDb::new().unwrap();
+    let mut db = TestDb::on_disk().unwrap();
 
     db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
     db.execute_ok("CREATE INDEX idx_users_id ON users (id)");
@@ -127,3 +193,123 @@ fn test_alter_table_rename_table() {
     assert!(!old_path.exists());
     assert!(new_path.exists());
 }
+
+#[test]
+fn test_alter_column_type_converts_existing_rows() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE prices (id INTEGER, amount INTEGER)");
+    db.execute_ok("INSERT INTO prices VALUES (1, 10), (2, NULL), (3, -4)");
+    db.execute_ok("CREATE INDEX idx_id ON prices(id)");
+
+    let result = db.execute_ok("ALTER TABLE prices ALTER COLUMN amount TYPE DECIMAL");
+    assert!(matches!(result, ExecutionResult::AlterTable { .. }));
+    db.execute_ok("INSERT INTO prices VALUES (4, 2.5)");
+
+    match db.execute_ok("SELECT id, amount FROM prices ORDER BY id") {
+        ExecutionResult::Select { rows, .. } => assert_eq!(
+            rows,
+            vec![
+                vec![Value::Integer(1), Value::Decimal(Decimal::from_i128(10))],
+                vec![Value::Integer(2), Value::Null],
+                vec![Value::Integer(3), Value::Decimal(Decimal::from_i128(-4))],
+                vec![
+                    Value::Integer(4),
+                    Value::Decimal(Decimal::parse("2.5").unwrap())
+                ],
+            ]
+        ),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+    match db.execute_ok("SELECT SUM(amount) FROM prices") {
+        ExecutionResult::Select { rows, .. } => assert_eq!(
+            rows,
+            vec![vec![Value::Decimal(Decimal::parse("8.5").unwrap())]]
+        ),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    // Indexed columns are rebuilt with the converted keys
+    db.execute_ok("ALTER TABLE prices ALTER id TYPE UNSIGNED");
+    match db.execute_ok("SELECT id FROM prices WHERE id = 3") {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows, vec![vec![Value::Unsigned(3)]]);
+            assert!(plan.iter().any(|p| p.contains("Index scan")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+    let err = db.execute_err("ALTER TABLE prices ALTER id TYPE FLOAT");
+    assert!(err.to_string().contains("indexed by 'idx_id'"), "{}", err);
+
+    // A value that doesn't convert rejects the change and leaves the table as
+    // it was
+    let err = db.execute_err("ALTER TABLE prices ALTER amount TYPE VARCHAR");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("Cannot convert value"), "{}", err);
+    match db.execute_ok("SELECT amount FROM prices WHERE id = 1") {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows, vec![vec![Value::Decimal(Decimal::from_i128(10))]])
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let err = db.execute_err("ALTER TABLE prices ALTER missing TYPE INTEGER");
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_alter_column_type_converts_dead_versions_leniently() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE codes (id INTEGER, code INTEGER)");
+    db.execute_ok("INSERT INTO codes VALUES (1, 10), (2, -5), (3, -1)");
+
+    // Live values must convert
+    let err = db.execute_err("ALTER TABLE codes ALTER COLUMN code TYPE UNSIGNED");
+    assert!(err.to_string().contains("Cannot convert value"), "{}", err);
+
+    // Once replaced or deleted, the old versions no longer block the change
+    db.execute_ok("UPDATE codes SET code = 20 WHERE id = 2");
+    db.execute_ok("DELETE FROM codes WHERE id = 3");
+    db.execute_ok("ALTER TABLE codes ALTER COLUMN code TYPE UNSIGNED");
+
+    match db.execute_ok("SELECT id, code FROM codes ORDER BY id") {
+        ExecutionResult::Select { rows, .. } => assert_eq!(
+            rows,
+            vec![
+                vec![Value::Integer(1), Value::Unsigned(10)],
+                vec![Value::Integer(2), Value::Unsigned(20)],
+            ]
+        ),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_alter_column_type_keeps_foreign_key_types_matching() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE orgs (id INTEGER PRIMARY KEY, budget INTEGER)");
+    db.execute_ok("CREATE TABLE users (id INTEGER, org_id INTEGER REFERENCES orgs(id))");
+    db.execute_ok("INSERT INTO orgs VALUES (1, 100)");
+    db.execute_ok("INSERT INTO users VALUES (1, 1)");
+
+    for sql in [
+        "ALTER TABLE orgs ALTER COLUMN id TYPE DECIMAL",
+        "ALTER TABLE users ALTER COLUMN org_id TYPE UNSIGNED",
+    ] {
+        let err = db.execute_err(sql);
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{}", sql);
+        assert!(
+            err.to_string().contains("Foreign key type mismatch"),
+            "{}: {}",
+            sql,
+            err
+        );
+    }
+
+    // Columns outside the foreign key are free to change
+    db.execute_ok("ALTER TABLE orgs ALTER COLUMN budget TYPE DECIMAL");
+    db.execute_ok("INSERT INTO users VALUES (2, 1)");
+    db.execute_err("INSERT INTO users VALUES (3, 2)");
+}
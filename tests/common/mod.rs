@@ -6,16 +6,30 @@ use tempfile::TempDir;
 /// Test database wrapper for integration tests
 pub struct TestDb {
     executor: Executor,
+    /// Directory of an on-disk database
     #[allow(dead_code)]
-    temp_dir: TempDir,
+    temp_dir: Option<TempDir>,
 }
 
 impl TestDb {
-    /// Create a new test database with a temporary directory
+    /// Create a new in-memory test database
     pub fn new() -> io::Result<Self> {
+        Ok(Self {
+            executor: Executor::in_memory(),
+            temp_dir: None,
+        })
+    }
+
+    /// Create a new test database with a temporary directory, for tests that
+    /// look at the files or reopen the database
+    #[allow(dead_code)]
+    pub fn on_disk() -> io::Result<Self> {
         let temp_dir = TempDir::new()?;
         let executor = Executor::new(temp_dir.path(), 100)?;
-        Ok(Self { executor, temp_dir })
+        Ok(Self {
+            executor,
+            temp_dir: Some(temp_dir),
+        })
     }
 
     /// Execute a SQL statement and return the result
@@ -39,9 +53,12 @@ impl TestDb {
         ))
     }
 
-    /// Get the path to the database directory
+    /// Get the path to the database directory of an on-disk database
     pub fn path(&self) -> &Path {
-        self.temp_dir.path()
+        self.temp_dir
+            .as_ref()
+            .expect("only on-disk test databases have a path")
+            .path()
     }
 
     /// List all tables
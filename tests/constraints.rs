@@ -1,4 +1,4 @@
-use db2::sql::{ExecutionResult, Executor, parse_sql};
+use db2::sql::{ConstraintInfo, ExecutionResult, Executor, ForeignKeyInfo, parse_sql};
 use tempfile::TempDir;
 
 #[test]
@@ -69,6 +69,56 @@ fn test_foreign_key_constraints() {
     }
 }
 
+#[test]
+fn test_self_referencing_foreign_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+    let mut run = |sql: &str| executor.execute(parse_sql(sql).unwrap());
+
+    run("CREATE TABLE employees (id INTEGER PRIMARY KEY, name VARCHAR, manager_id INTEGER REFERENCES employees(id))").unwrap();
+    run("INSERT INTO employees VALUES (1, 'Ada', NULL)").unwrap();
+    run("INSERT INTO employees VALUES (2, 'Grace', 1), (3, 'Alan', 1)").unwrap();
+    run("INSERT INTO employees VALUES (4, 'Edsger', 2)").unwrap();
+
+    let err = run("INSERT INTO employees VALUES (5, 'Nobody', 42)").unwrap_err();
+    assert!(err.to_string().contains("Foreign key violation"));
+
+    let names = |result: ExecutionResult| match result {
+        ExecutionResult::Select { rows, .. } => rows
+            .into_iter()
+            .map(|row| row[0].to_string())
+            .collect::<Vec<_>>(),
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+    let reports_of_ada = run(
+        "SELECT name FROM employees WHERE manager_id = (SELECT id FROM employees WHERE name = 'Ada') ORDER BY name",
+    )
+    .unwrap();
+    assert_eq!(names(reports_of_ada), vec!["Alan", "Grace"]);
+    let second_level = run(
+        "SELECT name FROM employees WHERE manager_id IN (SELECT id FROM employees WHERE manager_id = 1)",
+    )
+    .unwrap();
+    assert_eq!(names(second_level), vec!["Edsger"]);
+
+    // A manager can't leave while someone still reports to them
+    let err = run("DELETE FROM employees WHERE id = 2").unwrap_err();
+    assert!(err.to_string().contains("Foreign key restrict"));
+    run("DELETE FROM employees WHERE id = 4").unwrap();
+    run("DELETE FROM employees WHERE id = 2").unwrap();
+
+    // The referenced column must still exist and match in type
+    let err =
+        run("CREATE TABLE nodes (id INTEGER, parent VARCHAR REFERENCES nodes(id))").unwrap_err();
+    assert!(err.to_string().contains("Foreign key type mismatch"));
+    let err =
+        run("CREATE TABLE nodes (id INTEGER, parent INTEGER REFERENCES nodes(uid))").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Referenced column 'nodes.uid' does not exist")
+    );
+}
+
 #[test]
 fn test_not_null_and_check_constraints() {
     let temp_dir = TempDir::new().unwrap();
@@ -92,3 +142,74 @@ fn test_not_null_and_check_constraints() {
         .execute(parse_sql("INSERT INTO products VALUES (3, 10)").unwrap())
         .unwrap();
 }
+
+#[test]
+fn test_indexed_foreign_key_checks_respect_visibility() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+    let mut run = |sql: &str| executor.execute(parse_sql(sql).unwrap());
+
+    run("CREATE TABLE orgs (id INTEGER PRIMARY KEY)").unwrap();
+    run("CREATE TABLE users (id INTEGER PRIMARY KEY, org_id INTEGER REFERENCES orgs(id))").unwrap();
+    run("CREATE UNIQUE INDEX idx_orgs ON orgs(id)").unwrap();
+    run("CREATE INDEX idx_users_org ON users(org_id)").unwrap();
+    run("INSERT INTO orgs VALUES (1), (2)").unwrap();
+    run("INSERT INTO users VALUES (10, 1)").unwrap();
+
+    // A parent deleted in this transaction can no longer be referenced,
+    // even though its index entry stays until vacuum
+    run("BEGIN").unwrap();
+    run("DELETE FROM orgs WHERE id = 2").unwrap();
+    let err = run("INSERT INTO users VALUES (11, 2)").unwrap_err();
+    assert!(err.to_string().contains("Foreign key violation"));
+    run("ROLLBACK").unwrap();
+    run("INSERT INTO users VALUES (11, 2)").unwrap();
+
+    // Likewise a deleted child no longer holds its parent in place
+    run("DELETE FROM users WHERE id = 10").unwrap();
+    run("DELETE FROM orgs WHERE id = 1").unwrap();
+    let err = run("DELETE FROM orgs WHERE id = 2").unwrap_err();
+    assert!(err.to_string().contains("Foreign key restrict"));
+}
+
+#[test]
+fn test_table_constraints_accessor() {
+    let temp_dir = TempDir::new().unwrap();
+    {
+        let mut executor = Executor::new(temp_dir.path(), 10).unwrap();
+        for sql in [
+            "CREATE TABLE orgs (id INTEGER PRIMARY KEY, name VARCHAR)",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, email VARCHAR UNIQUE, age INTEGER NOT NULL CHECK (age > 0), org_id INTEGER REFERENCES orgs(id))",
+        ] {
+            executor.execute(parse_sql(sql).unwrap()).unwrap();
+        }
+        executor.flush_all().unwrap();
+    }
+
+    let executor = Executor::new(temp_dir.path(), 10).unwrap();
+    let users = executor.table_constraints("users").expect("users exists");
+    assert_eq!(users.primary_key.as_deref(), Some("id"));
+    assert_eq!(users.unique, vec!["id".to_string(), "email".to_string()]);
+    assert_eq!(users.not_null, vec!["id".to_string(), "age".to_string()]);
+    assert_eq!(
+        users.foreign_keys,
+        vec![ForeignKeyInfo {
+            column: "org_id".to_string(),
+            ref_table: "orgs".to_string(),
+            ref_column: "id".to_string(),
+        }]
+    );
+    assert_eq!(users.checks.len(), 1);
+
+    let orgs = executor.table_constraints("orgs").expect("orgs exists");
+    assert_eq!(
+        orgs,
+        ConstraintInfo {
+            primary_key: Some("id".to_string()),
+            unique: vec!["id".to_string()],
+            not_null: vec!["id".to_string()],
+            ..ConstraintInfo::default()
+        }
+    );
+    assert!(executor.table_constraints("missing").is_none());
+}
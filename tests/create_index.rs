@@ -2,6 +2,7 @@ mod common;
 
 use common::TestDb;
 use db2::sql::{ExecutionResult, IndexType};
+use db2::types::Value;
 
 #[test]
 fn test_create_index_simple() {
@@ -123,13 +124,75 @@ fn test_create_index_duplicate_columns() {
     assert!(err.to_string().contains("already exists"));
 }
 
+#[test]
+fn test_create_index_if_not_exists_is_a_no_op() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, age INTEGER)");
+    db.execute_ok("INSERT INTO users VALUES (1, 30), (2, 40), (3, 30)");
+    let result = db.execute_ok("CREATE INDEX IF NOT EXISTS idx_age ON users USING HASH (age)");
+    assert!(matches!(result, ExecutionResult::CreateIndex { .. }));
+
+    // Same name, or same columns and type under another name
+    for sql in [
+        "CREATE INDEX IF NOT EXISTS idx_age ON users USING HASH (age)",
+        "CREATE INDEX IF NOT EXISTS idx_age ON users(id)",
+        "CREATE INDEX IF NOT EXISTS idx_age_2 ON users USING HASH (age)",
+    ] {
+        let result = db.execute_ok(sql);
+        match &result {
+            ExecutionResult::IndexExists { index_name } => assert_eq!(index_name, "idx_age"),
+            other => panic!("Expected IndexExists result, got: {:?}", other),
+        }
+        assert_eq!(
+            result.to_string(),
+            "Index 'idx_age' already exists, skipping"
+        );
+    }
+    assert_eq!(db.list_indexes().len(), 1);
+
+    // The existing index was not repopulated, so each row appears once
+    match db.execute_ok("SELECT id FROM users WHERE age = 30 ORDER BY id") {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows, vec![vec![Value::Integer(1)], vec![Value::Integer(3)]]);
+            assert!(plan.iter().any(|p| p.contains("Index scan")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    // A different index on the same columns is still a conflict
+    let err = db.execute_err("CREATE INDEX IF NOT EXISTS idx_age_btree ON users(age)");
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    let err = db.execute_err("CREATE INDEX idx_age ON users USING HASH (age)");
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    // The table must still exist
+    let err = db.execute_err("CREATE INDEX IF NOT EXISTS idx_age ON missing(age)");
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
 #[test]
 fn test_create_index_on_varchar_column() {
     let mut db = TestDb::new().unwrap();
 
     db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
-    let err = db.execute_err("CREATE INDEX idx ON users(name)");
-    assert!(err.to_string().contains("INTEGER"));
+    db.execute_ok("INSERT INTO users VALUES (1, 'Carol'), (2, 'Alice'), (3, 'Bob')");
+    db.execute_ok("CREATE INDEX idx ON users(name)");
+
+    match db.execute_ok("SELECT id FROM users WHERE name = 'Bob'") {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows, vec![vec![Value::Integer(3)]]);
+            assert!(plan.iter().any(|p| p.contains("Index scan")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    match db.execute_ok("SELECT id FROM users WHERE name >= 'B'") {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows.len(), 2);
+            assert!(plan.iter().any(|p| p.contains("Index scan")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
 }
 
 #[test]
@@ -359,3 +422,104 @@ fn test_unique_index_persistence() {
         assert_eq!(indexes[0].4, true); // is_unique should be true
     }
 }
+
+#[test]
+fn test_reindex_rebuilds_a_single_index() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, age INTEGER)");
+    db.execute_ok("CREATE UNIQUE INDEX idx_id ON users(id)");
+    db.execute_ok("CREATE INDEX idx_age ON users USING HASH (age)");
+    db.execute_ok("INSERT INTO users VALUES (1, 30), (2, 40), (3, 30)");
+    db.execute_ok("DELETE FROM users WHERE id = 2");
+
+    let result = db.execute_ok("REINDEX idx_age");
+    match &result {
+        ExecutionResult::Reindex { index_name } => assert_eq!(index_name, "idx_age"),
+        other => panic!("Expected Reindex result, got: {:?}", other),
+    }
+    assert_eq!(result.to_string(), "Index 'idx_age' rebuilt successfully");
+
+    match db.execute_ok("SELECT id FROM users WHERE age = 30 ORDER BY id") {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows, vec![vec![Value::Integer(1)], vec![Value::Integer(3)]]);
+            assert!(plan.iter().any(|p| p.contains("Index scan")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    // The rebuilt unique index still enforces its constraint
+    db.execute_ok("REINDEX idx_id");
+    db.execute_err("INSERT INTO users VALUES (3, 50)");
+    assert_eq!(db.list_indexes().len(), 2);
+
+    let err = db.execute_err("REINDEX idx_missing");
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_indexes_accept_null_keys() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE items (id INTEGER, a INTEGER, b INTEGER, code INTEGER)");
+    // Existing NULLs don't stop an index from being built
+    db.execute_ok("INSERT INTO items VALUES (1, NULL, NULL, NULL)");
+    db.execute_ok("CREATE INDEX idx_ab ON items(a, b)");
+    db.execute_ok("CREATE INDEX idx_b ON items USING HASH (b)");
+    db.execute_ok("CREATE UNIQUE INDEX idx_code ON items(code)");
+
+    // NULLs are distinct for uniqueness, and rows with NULL keys insert
+    db.execute_ok("INSERT INTO items VALUES (2, 5, 5, NULL), (3, NULL, 5, 7), (4, 6, NULL, 8)");
+
+    let ids = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { rows, plan, .. } => {
+            let ids: Vec<i64> = rows
+                .iter()
+                .map(|row| match row[0] {
+                    Value::Integer(id) => id,
+                    ref other => panic!("Unexpected id {:?}", other),
+                })
+                .collect();
+            (ids, plan.iter().any(|p| p.starts_with("Index scan")))
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    // Comparisons never match NULL, whether or not an index serves them
+    assert_eq!(
+        ids(&mut db, "SELECT id FROM items WHERE a = NULL"),
+        (vec![], true)
+    );
+    assert_eq!(
+        ids(&mut db, "SELECT id FROM items WHERE a < 10 ORDER BY id"),
+        (vec![2, 4], true)
+    );
+    assert_eq!(
+        ids(&mut db, "SELECT id FROM items WHERE a != 7 ORDER BY id"),
+        (vec![2, 4], true)
+    );
+    assert_eq!(
+        ids(&mut db, "SELECT id FROM items WHERE b = 5 ORDER BY id"),
+        (vec![2, 3], true)
+    );
+
+    // A leading-column lookup still finds rows whose later columns are NULL
+    assert_eq!(
+        ids(&mut db, "SELECT id FROM items WHERE a = 6 ORDER BY id"),
+        (vec![4], true)
+    );
+
+    // The NULL rows are still there for a null-safe comparison to find
+    assert_eq!(
+        ids(
+            &mut db,
+            "SELECT id FROM items WHERE a IS NOT DISTINCT FROM NULL ORDER BY id"
+        )
+        .0,
+        vec![1, 3]
+    );
+
+    db.execute_ok("REINDEX idx_code");
+    db.execute_ok("INSERT INTO items VALUES (5, NULL, NULL, NULL)");
+    db.execute_err("INSERT INTO items VALUES (6, 9, 9, 7)");
+}
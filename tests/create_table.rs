@@ -129,3 +129,17 @@ fn test_create_table_many_columns() {
     let tables = db.list_tables();
     assert_eq!(tables[0].1.column_count(), 6);
 }
+
+#[test]
+fn test_create_table_rejects_rows_larger_than_a_page() {
+    let mut db = TestDb::new().unwrap();
+
+    // Each DECIMAL takes 21 bytes, so 400 of them can never fit in a page
+    let columns: Vec<String> = (0..400).map(|i| format!("c{} DECIMAL", i)).collect();
+    let err = db.execute_err(&format!("CREATE TABLE wide ({})", columns.join(", ")));
+    assert!(err.to_string().contains("must fit in a page"));
+    assert!(db.list_tables().is_empty());
+
+    let columns: Vec<String> = (0..300).map(|i| format!("c{} DECIMAL", i)).collect();
+    db.execute_ok(&format!("CREATE TABLE wide ({})", columns.join(", ")));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::TestDb;
+use db2::sql::ExecutionResult;
+use db2::types::Value;
+
+#[test]
+fn test_describe_table() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, email VARCHAR UNIQUE, age INTEGER NOT NULL, bio VARCHAR)",
+    );
+
+    match db.execute_ok("DESCRIBE users") {
+        ExecutionResult::Select {
+            column_names, rows, ..
+        } => {
+            assert_eq!(
+                column_names,
+                vec![
+                    "name",
+                    "type",
+                    "nullable",
+                    "primary_key",
+                    "unique",
+                    "default"
+                ]
+            );
+            let flags: Vec<(Value, Value, Value, Value)> = rows
+                .iter()
+                .map(|r| (r[0].clone(), r[2].clone(), r[3].clone(), r[4].clone()))
+                .collect();
+            let s = |v: &str| Value::String(v.to_string());
+            let b = Value::Boolean;
+            assert_eq!(
+                flags,
+                vec![
+                    (s("id"), b(false), b(true), b(true)),
+                    (s("email"), b(true), b(false), b(true)),
+                    (s("age"), b(false), b(false), b(false)),
+                    (s("bio"), b(true), b(false), b(false)),
+                ]
+            );
+            assert_eq!(rows[0][1], s("INTEGER"));
+            assert_eq!(rows[1][1], s("VARCHAR"));
+            assert!(rows.iter().all(|r| r[5] == Value::Null));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_describe_synonyms() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
+
+    let expected = db.execute_ok("DESCRIBE users");
+    assert_eq!(db.execute_ok("DESC users"), expected);
+    assert_eq!(db.execute_ok("EXPLAIN COLUMNS FROM users"), expected);
+}
+
+#[test]
+fn test_describe_missing_table() {
+    let mut db = TestDb::new().unwrap();
+
+    let err = db.execute_err("DESCRIBE missing");
+    assert!(err.to_string().contains("does not exist"));
+}
@@ -188,3 +188,59 @@ fn test_drop_index_affects_query_plan() {
         other => panic!("Expected Select result, got: {:?}", other),
     }
 }
+
+#[test]
+fn test_drop_index_on_columns() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE orders (user_id INTEGER, order_id INTEGER)");
+    db.execute_ok("CREATE INDEX idx_user ON orders(user_id)");
+    db.execute_ok("CREATE INDEX idx_composite ON orders(user_id, order_id)");
+
+    let result = db.execute_ok("DROP INDEX ON orders(user_id, order_id)");
+    match result {
+        ExecutionResult::DropIndex { index_name } => assert_eq!(index_name, "idx_composite"),
+        other => panic!("Expected DropIndex result, got: {:?}", other),
+    }
+
+    let indexes = db.list_indexes();
+    assert_eq!(indexes.len(), 1);
+    assert_eq!(indexes[0].0, "idx_user");
+
+    let err = db.execute_err("DROP INDEX ON orders(order_id)");
+    assert!(err.to_string().contains("No index on orders(order_id)"));
+}
+
+#[test]
+fn test_drop_index_on_columns_single_match() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER)");
+    db.execute_ok("CREATE INDEX idx_a ON users(id)");
+    // A second index on the same columns is rejected, so a column list always
+    // identifies at most one index.
+    db.execute_err("CREATE INDEX idx_b ON users USING HASH (id)");
+
+    db.execute_ok("DROP INDEX ON users(id)");
+    assert_eq!(db.list_indexes().len(), 0);
+    db.execute_err("DROP INDEX ON users(id)");
+}
+
+#[test]
+fn test_drop_index_on_api_persists() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_path_buf();
+
+    {
+        let mut executor = db2::sql::Executor::new(&db_path, 100).unwrap();
+        let stmt = db2::sql::parse_sql("CREATE TABLE users (id INTEGER, email VARCHAR)").unwrap();
+        executor.execute(stmt).unwrap();
+        let stmt = db2::sql::parse_sql("CREATE INDEX idx_id ON users(id)").unwrap();
+        executor.execute(stmt).unwrap();
+        executor.drop_index_on("users", &["id"]).unwrap();
+        executor.flush_all().unwrap();
+    }
+
+    let executor = db2::sql::Executor::new(&db_path, 100).unwrap();
+    assert_eq!(executor.list_indexes().len(), 0);
+}
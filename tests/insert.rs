@@ -185,6 +185,121 @@ fn test_insert_wrong_column_count() {
     assert!(err.to_string().contains("does not match"));
 }
 
+#[test]
+fn test_insert_select_copies_rows() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE log (id INTEGER, created TIMESTAMP, msg VARCHAR)");
+    db.execute_ok("CREATE TABLE archive (id INTEGER, created TIMESTAMP, msg VARCHAR)");
+    db.execute_ok("CREATE INDEX idx_archive_id ON archive(id)");
+    db.execute_ok(
+        "INSERT INTO log VALUES \
+         (1, TIMESTAMP '2025-01-01 00:00:00', 'old'), \
+         (2, TIMESTAMP '2025-02-01 00:00:00', 'older'), \
+         (3, TIMESTAMP '2025-06-01 00:00:00', 'new')",
+    );
+
+    let result = db.execute_ok(
+        "INSERT INTO archive SELECT * FROM log WHERE created < TIMESTAMP '2025-03-01 00:00:00'",
+    );
+    match result {
+        ExecutionResult::Insert { row_ids } => assert_eq!(row_ids.len(), 2),
+        other => panic!("Expected Insert result, got: {:?}", other),
+    }
+
+    let result = db.execute_ok("SELECT id, msg FROM archive ORDER BY id");
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(
+                rows,
+                &vec![
+                    vec![Value::Integer(1), Value::String("old".to_string())],
+                    vec![Value::Integer(2), Value::String("older".to_string())],
+                ]
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let result = db.execute_ok("SELECT msg FROM archive WHERE id = 2");
+    match &result {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows, &vec![vec![Value::String("older".to_string())]]);
+            assert!(plan.iter().any(|p| p.contains("Index scan")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_insert_select_coerces_to_target_schema() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE readings (sensor INTEGER, value INTEGER)");
+    db.execute_ok("CREATE TABLE totals (sensor UNSIGNED, value FLOAT)");
+    db.execute_ok("INSERT INTO readings VALUES (1, 10), (2, NULL)");
+
+    db.execute_ok("INSERT INTO totals SELECT sensor, value FROM readings");
+    let result = db.execute_ok("SELECT * FROM totals ORDER BY sensor");
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(
+                rows,
+                &vec![
+                    vec![Value::Unsigned(1), Value::Float(10.0)],
+                    vec![Value::Unsigned(2), Value::Null],
+                ]
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_insert_select_validates_before_writing() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE source (id INTEGER, label VARCHAR)");
+    db.execute_ok("CREATE TABLE target (id INTEGER, label INTEGER)");
+    db.execute_ok("INSERT INTO source VALUES (1, NULL), (2, 'two')");
+
+    let err = db.execute_err("INSERT INTO target SELECT id FROM source");
+    assert!(err.to_string().contains("SELECT returns 1 columns"));
+
+    // The second row fails to convert, so the first must not be written
+    let err = db.execute_err("INSERT INTO target SELECT * FROM source");
+    assert!(err.to_string().contains("Column 'label'"));
+
+    let result = db.execute_ok("SELECT * FROM target");
+    match &result {
+        ExecutionResult::Select { rows, .. } => assert!(rows.is_empty()),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_insert_select_rolls_back_as_one_statement() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE source (id INTEGER)");
+    db.execute_ok("CREATE TABLE target (id INTEGER)");
+    db.execute_ok("INSERT INTO source VALUES (1), (2), (3)");
+
+    db.execute_ok("BEGIN");
+    db.execute_ok("INSERT INTO target SELECT * FROM source");
+    db.execute_ok("ROLLBACK");
+
+    // Copying a table into itself reads every source row before writing
+    db.execute_ok("INSERT INTO source SELECT * FROM source");
+
+    for (sql, expected) in [("SELECT * FROM target", 0), ("SELECT * FROM source", 6)] {
+        match db.execute_ok(sql) {
+            ExecutionResult::Select { rows, .. } => assert_eq!(rows.len(), expected),
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+    }
+}
+
 #[test]
 fn test_insert_updates_index() {
     let mut db = TestDb::new().unwrap();
@@ -79,3 +79,82 @@ fn test_query_features_aggregates() {
         other => panic!("Expected Select result, got: {:?}", other),
     }
 }
+
+#[test]
+fn test_query_features_aggregates_without_group_by_on_empty_table() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE numbers (id INTEGER)");
+
+    let result = db.execute_ok("SELECT COUNT(*), MAX(id) FROM numbers");
+    match &result {
+        ExecutionResult::Select {
+            column_names, rows, ..
+        } => {
+            assert_eq!(column_names, &vec!["COUNT(*)", "MAX(id)"]);
+            assert_eq!(rows, &vec![vec![Value::Integer(0), Value::Null]]);
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    db.execute_ok("INSERT INTO numbers VALUES (3), (7)");
+    let result = db.execute_ok("SELECT COUNT(*), MAX(id) FROM numbers");
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows, &vec![vec![Value::Integer(2), Value::Integer(7)]]);
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_query_features_min_max_skip_nulls() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE readings (sensor VARCHAR, val INTEGER)");
+    db.execute_ok(
+        "INSERT INTO readings VALUES ('a', NULL), ('a', -5), ('a', 12), ('b', NULL), ('b', NULL)",
+    );
+
+    let result = db.execute_ok(
+        "SELECT sensor, MIN(val), MAX(val) FROM readings GROUP BY sensor ORDER BY sensor",
+    );
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(
+                rows,
+                &vec![
+                    vec![
+                        Value::String("a".to_string()),
+                        Value::Integer(-5),
+                        Value::Integer(12)
+                    ],
+                    vec![Value::String("b".to_string()), Value::Null, Value::Null],
+                ]
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_query_features_rejects_mixed_aggregate_and_column() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE sales (id INTEGER, region VARCHAR)");
+    db.execute_ok("INSERT INTO sales VALUES (1, 'east'), (2, 'west')");
+
+    let expected = "Column 'id' must appear in GROUP BY or be used in an aggregate function";
+
+    let err = db.execute_err("SELECT id, COUNT(*) FROM sales");
+    assert_eq!(err.to_string(), expected);
+
+    // Same rule, and same message, when a GROUP BY is present
+    let err = db.execute_err("SELECT id, COUNT(*) FROM sales GROUP BY region");
+    assert_eq!(err.to_string(), expected);
+
+    // The check doesn't depend on there being rows to aggregate
+    db.execute_ok("DELETE FROM sales");
+    let err = db.execute_err("SELECT COUNT(*), id FROM sales");
+    assert_eq!(err.to_string(), expected);
+}
@@ -1,4 +1,4 @@
-use db2::sql::{ExecutionResult, Executor, parse_sql};
+use db2::sql::{ExecutionResult, Executor, InvalidStatement, parse_sql, parse_sql_statements};
 use db2::types::Value;
 use std::io;
 use tempfile::TempDir;
@@ -95,6 +95,45 @@ fn test_schema_drop_column_persists_after_reopen() {
     assert!(err.to_string().contains("Column"));
 }
 
+#[test]
+fn test_schema_alter_column_type_persists_after_reopen() {
+    let temp_dir = TempDir::new().unwrap();
+    {
+        let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+        execute_ok(
+            &mut executor,
+            "CREATE TABLE users (id INTEGER, score INTEGER)",
+        );
+        execute_ok(&mut executor, "INSERT INTO users VALUES (1, 7)");
+
+        // A dry run sees the new type without converting anything
+        let stmts = parse_sql_statements(
+            "ALTER TABLE users ALTER COLUMN score TYPE FLOAT; INSERT INTO users VALUES (2, 1.5)",
+        )
+        .unwrap();
+        executor.validate(&stmts).unwrap();
+
+        execute_ok(
+            &mut executor,
+            "ALTER TABLE users ALTER COLUMN score TYPE FLOAT",
+        );
+        executor.flush_all().unwrap();
+    }
+
+    let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+    execute_ok(&mut executor, "INSERT INTO users VALUES (2, 1.5)");
+    match execute_ok(&mut executor, "SELECT id, score FROM users ORDER BY id") {
+        ExecutionResult::Select { rows, .. } => assert_eq!(
+            rows,
+            vec![
+                vec![Value::Integer(1), Value::Float(7.0)],
+                vec![Value::Integer(2), Value::Float(1.5)],
+            ]
+        ),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
 #[test]
 fn test_schema_rename_column_persists_after_reopen() {
     let temp_dir = TempDir::new().unwrap();
@@ -164,3 +203,237 @@ fn test_schema_rename_table_persists_after_reopen() {
     assert!(!old_path.exists());
     assert!(new_path.exists());
 }
+
+#[test]
+fn test_validate_checks_a_migration_without_applying_it() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+    execute_ok(
+        &mut executor,
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name VARCHAR)",
+    );
+    execute_ok(&mut executor, "INSERT INTO users VALUES (1, 'Alice')");
+    executor.flush_all().unwrap();
+    let wal_len = std::fs::metadata(temp_dir.path().join("wal.log"))
+        .unwrap()
+        .len();
+
+    let migration = parse_sql_statements(
+        "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users(id));
+         INSERT INTO orders VALUES (1, 1);
+         ALTER TABLE users ADD COLUMN age INTEGER;
+         UPDATE users SET age = 30 WHERE id = 1;
+         CREATE INDEX idx_users_age ON users (age);
+         ALTER TABLE users RENAME COLUMN name TO full_name;
+         SELECT full_name, age FROM users WHERE age > 18;",
+    )
+    .unwrap();
+    executor.validate(&migration).unwrap();
+
+    let tables = executor.list_tables();
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].1.column_count(), 2);
+    assert!(executor.list_indexes().is_empty());
+    assert_eq!(
+        std::fs::metadata(temp_dir.path().join("wal.log"))
+            .unwrap()
+            .len(),
+        wal_len
+    );
+
+    for stmt in migration {
+        executor.execute(stmt).unwrap();
+    }
+}
+
+#[test]
+fn test_validate_reports_the_first_failing_statement() {
+    let temp_dir = TempDir::new().unwrap();
+    let executor = Executor::new(temp_dir.path(), 100).unwrap();
+
+    let cases = [
+        (
+            "CREATE TABLE t (a INTEGER NOT NULL);
+             ALTER TABLE t RENAME COLUMN a TO b;
+             SELECT a FROM t;",
+            2,
+            "Column 'a' not found",
+        ),
+        (
+            "CREATE TABLE t (a INTEGER NOT NULL);
+             INSERT INTO t VALUES (1);
+             INSERT INTO t VALUES (NULL);",
+            2,
+            "NOT NULL constraint violated",
+        ),
+        (
+            "CREATE TABLE t (a INTEGER);
+             DROP TABLE t;
+             INSERT INTO t VALUES (1);",
+            2,
+            "does not exist",
+        ),
+        (
+            "CREATE TABLE t (a BOOLEAN);
+             CREATE INDEX idx_a ON t (a);",
+            1,
+            "Only INTEGER, UNSIGNED or VARCHAR columns can be indexed",
+        ),
+        (
+            "CREATE TABLE t (a INTEGER NOT NULL, b INTEGER);
+             UPDATE t SET b = 1, a = NULL WHERE b = 2;",
+            1,
+            "NOT NULL constraint violated on a",
+        ),
+        (
+            "CREATE TABLE t (a INTEGER CHECK (a > 0), b INTEGER);
+             UPDATE t SET a = 5;
+             UPDATE t SET a = -1 WHERE b = 2;",
+            2,
+            "CHECK constraint violated",
+        ),
+        (
+            "CREATE TABLE s (x INTEGER);
+             CREATE TABLE t (a INTEGER, b DATE);
+             INSERT INTO t SELECT x, DATE '2024-01-01' FROM s;
+             ALTER TABLE t ALTER COLUMN b TYPE BOOLEAN;
+             INSERT INTO t SELECT x, DATE '2024-01-01' FROM s;",
+            4,
+            "Column 'b'",
+        ),
+        ("COMMIT;", 0, "No active transaction"),
+    ];
+
+    for (sql, index, message) in cases {
+        let err = executor
+            .validate(&parse_sql_statements(sql).unwrap())
+            .unwrap_err();
+        let invalid = err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<InvalidStatement>())
+            .expect("invalid statement payload");
+        assert_eq!(invalid.index, index, "{}", sql);
+        assert!(err.to_string().contains(message), "{}: {}", sql, err);
+    }
+    assert!(executor.list_tables().is_empty());
+}
+
+#[test]
+fn test_validate_matches_execution_for_constant_constraint_violations() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+    execute_ok(
+        &mut executor,
+        "CREATE TABLE t (a INTEGER NOT NULL CHECK (a > 0), b INTEGER)",
+    );
+    execute_ok(&mut executor, "INSERT INTO t VALUES (1, 1)");
+
+    for sql in [
+        "UPDATE t SET a = NULL",
+        "UPDATE t SET a = 0 WHERE b = 1",
+        "INSERT INTO t SELECT a, DATE '2024-01-01' FROM t",
+    ] {
+        let stmts = parse_sql_statements(sql).unwrap();
+        let validate_err = executor.validate(&stmts).unwrap_err();
+        let execute_err = executor.execute(stmts[0].clone()).unwrap_err();
+        assert_eq!(validate_err.kind(), execute_err.kind(), "{}", sql);
+        assert!(
+            validate_err.to_string().ends_with(&execute_err.to_string()),
+            "{}: {} vs {}",
+            sql,
+            validate_err,
+            execute_err
+        );
+    }
+}
+
+#[test]
+fn test_metadata_for_missing_table_file_is_preserved_until_purged() {
+    let temp_dir = TempDir::new().unwrap();
+    let table_file = temp_dir.path().join("orders.db");
+    let moved_file = temp_dir.path().join("orders.db.bak");
+    {
+        let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+        execute_ok(&mut executor, "CREATE TABLE users (id INTEGER PRIMARY KEY)");
+        execute_ok(
+            &mut executor,
+            "CREATE TABLE orders (id INTEGER UNIQUE, user_id INTEGER)",
+        );
+        execute_ok(
+            &mut executor,
+            "CREATE INDEX idx_orders_user ON orders(user_id)",
+        );
+        execute_ok(&mut executor, "INSERT INTO orders VALUES (1, 7)");
+        executor.flush_all().unwrap();
+    }
+    let index_names = |executor: &Executor| -> Vec<String> {
+        executor
+            .list_indexes()
+            .into_iter()
+            .map(|(name, ..)| name)
+            .collect()
+    };
+
+    // Rewriting the metadata while the table file is gone keeps its entries
+    std::fs::rename(&table_file, &moved_file).unwrap();
+    {
+        let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+        let orphans = executor.orphaned_metadata();
+        assert_eq!(orphans.len(), 2);
+        assert!(orphans.iter().all(|orphan| orphan.table == "orders"));
+        assert!(
+            orphans[0]
+                .to_string()
+                .starts_with("indexes.meta references missing table 'orders'")
+        );
+        execute_ok(&mut executor, "CREATE INDEX idx_users_id ON users(id)");
+    }
+    std::fs::rename(&moved_file, &table_file).unwrap();
+    {
+        let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+        assert!(executor.orphaned_metadata().is_empty());
+        assert!(index_names(&executor).contains(&"idx_orders_user".to_string()));
+        let err = execute_err(&mut executor, "INSERT INTO orders VALUES (1, 8)");
+        assert!(err.to_string().contains("Unique constraint violated"));
+    }
+
+    // Purging drops them for good
+    std::fs::rename(&table_file, &moved_file).unwrap();
+    {
+        let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+        let purged = executor.purge_orphaned_metadata().unwrap();
+        assert_eq!(purged.len(), 2);
+        assert!(executor.orphaned_metadata().is_empty());
+    }
+    std::fs::rename(&moved_file, &table_file).unwrap();
+    let executor = Executor::new(temp_dir.path(), 100).unwrap();
+    assert!(executor.orphaned_metadata().is_empty());
+    assert_eq!(index_names(&executor), vec!["idx_users_id".to_string()]);
+}
+
+#[test]
+fn test_recreated_table_does_not_inherit_orphaned_metadata() {
+    let temp_dir = TempDir::new().unwrap();
+    {
+        let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+        execute_ok(
+            &mut executor,
+            "CREATE TABLE orders (id INTEGER PRIMARY KEY)",
+        );
+        execute_ok(&mut executor, "CREATE INDEX idx_orders_id ON orders(id)");
+        executor.flush_all().unwrap();
+    }
+    std::fs::remove_file(temp_dir.path().join("orders.db")).unwrap();
+
+    {
+        let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+        assert_eq!(executor.orphaned_metadata().len(), 2);
+        execute_ok(&mut executor, "CREATE TABLE orders (id VARCHAR)");
+        assert!(executor.orphaned_metadata().is_empty());
+        executor.flush_all().unwrap();
+    }
+
+    let mut executor = Executor::new(temp_dir.path(), 100).unwrap();
+    assert!(executor.list_indexes().is_empty());
+    execute_ok(&mut executor, "INSERT INTO orders VALUES ('a'), ('a')");
+}
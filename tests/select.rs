@@ -255,6 +255,86 @@ fn test_select_limit_offset() {
     }
 }
 
+#[test]
+fn test_select_offset_without_limit() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE numbers (val INTEGER)");
+    db.execute_ok("INSERT INTO numbers VALUES (1), (2), (3), (4), (5)");
+
+    let result = db.execute_ok("SELECT val FROM numbers ORDER BY val ASC OFFSET 3");
+    match &result {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[0][0], Value::Integer(4));
+            assert_eq!(rows[1][0], Value::Integer(5));
+            assert!(!plan.iter().any(|p| p.contains("unstable ordering")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let result = db.execute_ok("SELECT val FROM numbers OFFSET 3");
+    match &result {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows.len(), 2);
+            assert!(plan.iter().any(|p| p.contains("unstable ordering")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_select_offset_zero_and_past_end() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE numbers (val INTEGER)");
+    db.execute_ok("INSERT INTO numbers VALUES (1), (2), (3)");
+
+    let result = db.execute_ok("SELECT val FROM numbers ORDER BY val ASC OFFSET 0");
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows.len(), 3);
+            assert_eq!(rows[0][0], Value::Integer(1));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let result = db.execute_ok("SELECT val FROM numbers ORDER BY val ASC LIMIT 2 OFFSET 10");
+    match &result {
+        ExecutionResult::Select { rows, .. } => assert!(rows.is_empty()),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_select_limit_offset_beyond_32_bits() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE numbers (val INTEGER)");
+    db.execute_ok("INSERT INTO numbers VALUES (1), (2), (3)");
+
+    let result = db.execute_ok("SELECT val FROM numbers ORDER BY val ASC OFFSET 4294967296");
+    match &result {
+        ExecutionResult::Select { rows, .. } => assert!(rows.is_empty()),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let result = db
+        .execute_ok("SELECT val FROM numbers ORDER BY val ASC LIMIT 18446744073709551615 OFFSET 1");
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(
+                rows,
+                &vec![vec![Value::Integer(2)], vec![Value::Integer(3)]]
+            )
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let err = db.execute_err("SELECT val FROM numbers LIMIT 18446744073709551616");
+    assert!(err.to_string().contains("LIMIT value too large"), "{}", err);
+}
+
 #[test]
 fn test_select_group_by_count() {
     let mut db = TestDb::new().unwrap();
@@ -337,6 +417,248 @@ fn test_select_in_subquery() {
     }
 }
 
+#[test]
+fn test_select_correlated_exists() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
+    db.execute_ok("CREATE TABLE orders (user_id INTEGER, amount INTEGER)");
+    db.execute_ok("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Cara')");
+    db.execute_ok("INSERT INTO orders VALUES (1, 10), (3, 5), (3, 50)");
+
+    let result = db.execute_ok(
+        "SELECT id FROM users WHERE EXISTS (SELECT 1 FROM orders WHERE orders.user_id = users.id AND amount > 20) ORDER BY id ASC",
+    );
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows.len(), 1);
+            assert_eq!(rows[0][0], Value::Integer(3));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let result = db.execute_ok(
+        "SELECT id, name FROM users WHERE NOT EXISTS (SELECT user_id FROM orders WHERE user_id = id) ORDER BY id ASC",
+    );
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows.len(), 1);
+            assert_eq!(rows[0][1], Value::String("Bob".to_string()));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_select_correlated_references_in_nested_expressions() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE o (id INTEGER, doc JSON)");
+    db.execute_ok("CREATE TABLE i (k INTEGER, tag VARCHAR)");
+    db.execute_ok("CREATE TABLE j (tag VARCHAR, id INTEGER)");
+    db.execute_ok(r#"INSERT INTO o VALUES (1, '{"k": 10}'), (2, '{"k": 20}'), (3, '{"k": 30}')"#);
+    db.execute_ok("INSERT INTO i VALUES (10, 'a'), (30, 'b')");
+    db.execute_ok("INSERT INTO j VALUES ('a', 1), ('b', 2)");
+
+    let ids = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { rows, .. } => rows
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<_>>(),
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    // Outer column inside a function call
+    assert_eq!(
+        ids(
+            &mut db,
+            "SELECT id FROM o WHERE EXISTS (SELECT 1 FROM i WHERE json_extract(o.doc, '$.k') = i.k) ORDER BY id ASC"
+        ),
+        vec![Value::Integer(1), Value::Integer(3)]
+    );
+
+    // Outer column two subqueries down, next to a column of the middle one
+    assert_eq!(
+        ids(
+            &mut db,
+            "SELECT id FROM o WHERE EXISTS (SELECT 1 FROM i WHERE i.k = json_extract(o.doc, '$.k') AND EXISTS (SELECT 1 FROM j WHERE j.tag = i.tag AND j.id = o.id)) ORDER BY id ASC"
+        ),
+        vec![Value::Integer(1)]
+    );
+
+    // Correlated IN subquery and a correlated computed select item
+    assert_eq!(
+        ids(
+            &mut db,
+            "SELECT id FROM o WHERE json_extract(doc, '$.k') IN (SELECT k FROM i WHERE tag = (SELECT tag FROM j WHERE j.id = o.id)) ORDER BY id ASC"
+        ),
+        vec![Value::Integer(1)]
+    );
+    assert_eq!(
+        ids(
+            &mut db,
+            "SELECT id FROM o WHERE (SELECT json_extract(o.doc, '$.k') FROM i WHERE k = 10) = 20"
+        ),
+        vec![Value::Integer(2)]
+    );
+
+    // A correlated column where only a column name fits is rejected
+    let err = db.execute_err("SELECT id FROM o WHERE EXISTS (SELECT k FROM i GROUP BY o.id)");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("Correlated column 'o.id'"));
+}
+
+#[test]
+fn test_select_correlated_scalar_subquery() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
+    db.execute_ok("CREATE TABLE orders (user_id INTEGER, amount INTEGER)");
+    db.execute_ok("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Cara')");
+    db.execute_ok("INSERT INTO orders VALUES (1, 10), (3, 5), (3, 50)");
+
+    let result = db.execute_ok(
+        "SELECT name, (SELECT COUNT(*) FROM orders WHERE orders.user_id = users.id), (SELECT MAX(amount) FROM orders WHERE user_id = id) FROM users ORDER BY name ASC",
+    );
+    match &result {
+        ExecutionResult::Select {
+            column_names, rows, ..
+        } => {
+            assert_eq!(column_names, &vec!["name", "(subquery)", "(subquery) 2"]);
+            assert_eq!(
+                rows,
+                &vec![
+                    vec![
+                        Value::String("Alice".to_string()),
+                        Value::Integer(1),
+                        Value::Integer(10)
+                    ],
+                    vec![
+                        Value::String("Bob".to_string()),
+                        Value::Integer(0),
+                        Value::Null
+                    ],
+                    vec![
+                        Value::String("Cara".to_string()),
+                        Value::Integer(2),
+                        Value::Integer(50)
+                    ],
+                ]
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    // Zero rows from the subquery yields NULL
+    let result = db.execute_ok(
+        "SELECT id, (SELECT amount FROM orders WHERE user_id = id AND amount > 20) FROM users ORDER BY id ASC",
+    );
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            let amounts: Vec<_> = rows.iter().map(|r| r[1].clone()).collect();
+            assert_eq!(amounts, vec![Value::Null, Value::Null, Value::Integer(50)]);
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    // Scalar subqueries also work as comparison operands
+    let result = db.execute_ok(
+        "SELECT name FROM users WHERE id = (SELECT MAX(user_id) FROM orders WHERE amount < 20)",
+    );
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows, &vec![vec![Value::String("Cara".to_string())]]);
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_select_scalar_subquery_must_be_scalar() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER)");
+    db.execute_ok("CREATE TABLE orders (user_id INTEGER, amount INTEGER)");
+    db.execute_ok("INSERT INTO users VALUES (3)");
+    db.execute_ok("INSERT INTO orders VALUES (3, 5), (3, 50)");
+
+    let err =
+        db.execute_err("SELECT id, (SELECT amount FROM orders WHERE user_id = id) FROM users");
+    assert!(err.to_string().contains("more than one row"));
+
+    let err = db.execute_err(
+        "SELECT id, (SELECT user_id, amount FROM orders WHERE user_id = id) FROM users",
+    );
+    assert!(err.to_string().contains("exactly one column"));
+}
+
+#[test]
+fn test_select_is_distinct_from_treats_null_as_a_value() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE pairs (id INTEGER, a INTEGER, b INTEGER)");
+    db.execute_ok("INSERT INTO pairs VALUES (1, 1, 1), (2, 1, NULL), (3, NULL, NULL), (4, 1, 2)");
+
+    let ids = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { rows, .. } => rows.into_iter().map(|r| r[0].clone()).collect(),
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    // Plain equality never matches NULL
+    let equal: Vec<Value> = ids(&mut db, "SELECT id FROM pairs WHERE a = b ORDER BY id");
+    assert_eq!(equal, vec![Value::Integer(1)]);
+
+    let same: Vec<Value> = ids(
+        &mut db,
+        "SELECT id FROM pairs WHERE a IS NOT DISTINCT FROM b ORDER BY id",
+    );
+    assert_eq!(same, vec![Value::Integer(1), Value::Integer(3)]);
+
+    let different: Vec<Value> = ids(
+        &mut db,
+        "SELECT id FROM pairs WHERE a IS DISTINCT FROM b ORDER BY id",
+    );
+    assert_eq!(different, vec![Value::Integer(2), Value::Integer(4)]);
+
+    let null_b: Vec<Value> = ids(
+        &mut db,
+        "SELECT id FROM pairs WHERE b IS NOT DISTINCT FROM NULL ORDER BY id",
+    );
+    assert_eq!(null_b, vec![Value::Integer(2), Value::Integer(3)]);
+
+    let not_null: Vec<Value> = ids(
+        &mut db,
+        "SELECT id FROM pairs WHERE 1 IS DISTINCT FROM NULL AND id = 4",
+    );
+    assert_eq!(not_null, vec![Value::Integer(4)]);
+}
+
+#[test]
+fn test_select_join_on_nullable_keys_with_is_not_distinct_from() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE staged (region VARCHAR, total INTEGER)");
+    db.execute_ok("CREATE TABLE current (region VARCHAR, total INTEGER)");
+    db.execute_ok("INSERT INTO staged VALUES ('east', 10), (NULL, 20)");
+    db.execute_ok("INSERT INTO current VALUES ('east', 1), (NULL, 2)");
+
+    let result = db.execute_ok(
+        "SELECT staged.total, current.total FROM staged JOIN current ON staged.region IS NOT DISTINCT FROM current.region ORDER BY staged.total",
+    );
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(
+                rows,
+                &vec![
+                    vec![Value::Integer(10), Value::Integer(1)],
+                    vec![Value::Integer(20), Value::Integer(2)],
+                ]
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
 #[test]
 fn test_select_where_no_matches() {
     let mut db = TestDb::new().unwrap();
@@ -410,6 +732,153 @@ fn test_select_index_range() {
     }
 }
 
+#[test]
+fn test_select_like_prefix_uses_string_index() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
+    db.execute_ok("CREATE INDEX idx_name ON users(name)");
+    db.execute_ok(
+        "INSERT INTO users VALUES (1, 'Alice'), (2, 'Albert'), (3, 'Am'), (4, 'Bob'), (5, 'Al')",
+    );
+
+    let names = |result: &ExecutionResult| match result {
+        ExecutionResult::Select { rows, .. } => {
+            let mut names: Vec<_> = rows.iter().map(|r| r[0].to_string()).collect();
+            names.sort();
+            names
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+    let plan = |result: &ExecutionResult| match result {
+        ExecutionResult::Select { plan, .. } => plan.clone(),
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    let result = db.execute_ok("SELECT name FROM users WHERE name LIKE 'Al%'");
+    assert_eq!(names(&result), vec!["Al", "Albert", "Alice"]);
+    assert!(plan(&result).iter().any(|p| p.contains("Index scan")));
+
+    let result = db.execute_ok("SELECT name FROM users WHERE name LIKE 'Alice'");
+    assert_eq!(names(&result), vec!["Alice"]);
+    assert!(plan(&result).iter().any(|p| p.contains("Index scan")));
+
+    let result = db.execute_ok("SELECT name FROM users WHERE name LIKE '%ice'");
+    assert_eq!(names(&result), vec!["Alice"]);
+    assert!(plan(&result).iter().any(|p| p.contains("Seq scan")));
+
+    let result = db.execute_ok("SELECT name FROM users WHERE name LIKE '_l%'");
+    assert_eq!(names(&result), vec!["Al", "Albert", "Alice"]);
+    assert!(plan(&result).iter().any(|p| p.contains("Seq scan")));
+}
+
+#[test]
+fn test_select_like_with_escape() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE promos (id INTEGER, label VARCHAR)");
+    db.execute_ok(
+        "INSERT INTO promos VALUES (1, '100% off'), (2, '1000 points'), (3, 'a_b'), (4, 'axb'), (5, NULL)",
+    );
+
+    let ids = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { rows, .. } => {
+            rows.into_iter().map(|r| r[0].clone()).collect::<Vec<_>>()
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    assert_eq!(
+        ids(
+            &mut db,
+            "SELECT id FROM promos WHERE label LIKE '100!%%' ESCAPE '!'"
+        ),
+        vec![Value::Integer(1)]
+    );
+    assert_eq!(
+        ids(&mut db, "SELECT id FROM promos WHERE label LIKE '100%'"),
+        vec![Value::Integer(1), Value::Integer(2)]
+    );
+    assert_eq!(
+        ids(&mut db, "SELECT id FROM promos WHERE label LIKE 'a\\_b'"),
+        vec![Value::Integer(3)]
+    );
+    assert_eq!(
+        ids(&mut db, "SELECT id FROM promos WHERE label LIKE 'a_b'"),
+        vec![Value::Integer(3), Value::Integer(4)]
+    );
+
+    let err = db.execute_err("SELECT id FROM promos WHERE label LIKE 'abc!' ESCAPE '!'");
+    assert!(err.to_string().contains("escape character"));
+    let err = db.execute_err("SELECT id FROM promos WHERE id LIKE '1%'");
+    assert!(err.to_string().contains("LIKE"));
+}
+
+#[test]
+fn test_select_index_range_both_bounds() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE items (price INTEGER)");
+    db.execute_ok("CREATE INDEX idx_price ON items(price)");
+    db.execute_ok("INSERT INTO items VALUES (50), (100), (150), (200), (250), (300), (350)");
+
+    let result = db.execute_ok("SELECT * FROM items WHERE price > 100 AND price < 300");
+    match &result {
+        ExecutionResult::Select { rows, plan, .. } => {
+            let prices: Vec<_> = rows.iter().map(|r| r[0].clone()).collect();
+            assert_eq!(
+                prices,
+                vec![
+                    Value::Integer(150),
+                    Value::Integer(200),
+                    Value::Integer(250)
+                ]
+            );
+            assert!(
+                plan.iter()
+                    .any(|p| p.contains("Index scan") && p.contains("price > 100 AND price < 300"))
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let result =
+        db.execute_ok("SELECT * FROM items WHERE price >= 100 AND price <= 300 AND price >= 200");
+    match &result {
+        ExecutionResult::Select { rows, .. } => assert_eq!(rows.len(), 3),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_select_index_range_contradictory_bounds() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE items (price INTEGER)");
+    db.execute_ok("CREATE INDEX idx_price ON items(price)");
+    db.execute_ok("INSERT INTO items VALUES (50), (100), (200), (300), (350)");
+
+    for sql in [
+        "SELECT * FROM items WHERE price > 300 AND price < 100",
+        "SELECT * FROM items WHERE price = 100 AND price = 200",
+        "SELECT * FROM items WHERE price > 9223372036854775807",
+    ] {
+        match &db.execute_ok(sql) {
+            ExecutionResult::Select { rows, plan, .. } => {
+                assert!(rows.is_empty(), "{} returned {:?}", sql, rows);
+                assert!(plan.iter().any(|p| p.contains("Index scan")));
+            }
+            other => panic!("Expected Select result, got: {:?}", other),
+        }
+    }
+
+    let result = db.execute_ok("DELETE FROM items WHERE price > 300 AND price < 100");
+    assert!(matches!(
+        result,
+        ExecutionResult::Delete { rows_deleted: 0 }
+    ));
+}
+
 #[test]
 fn test_hash_index_not_used_for_range_scan() {
     let mut db = TestDb::new().unwrap();
@@ -571,3 +1040,333 @@ fn test_select_specific_columns_after_join() {
         other => panic!("Expected Select result, got: {:?}", other),
     }
 }
+
+#[test]
+fn test_select_qualified_wildcard_in_join() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
+    db.execute_ok("CREATE TABLE orders (id INTEGER, user_id INTEGER, amount INTEGER)");
+
+    db.execute_ok("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob')");
+    db.execute_ok("INSERT INTO orders VALUES (10, 1, 100), (11, 2, 200), (12, 1, 150)");
+
+    let result = db.execute_ok(
+        "SELECT users.*, orders.id FROM users JOIN orders ON users.id = orders.user_id ORDER BY orders.id",
+    );
+    match &result {
+        ExecutionResult::Select {
+            column_names, rows, ..
+        } => {
+            assert_eq!(column_names, &["users.id", "users.name", "orders.id"]);
+            assert_eq!(
+                rows[0],
+                vec![
+                    Value::Integer(1),
+                    Value::String("Alice".to_string()),
+                    Value::Integer(10)
+                ]
+            );
+            assert_eq!(rows.len(), 3);
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    // Grouping by every column of the table makes table.* legal next to aggregates
+    let result = db.execute_ok(
+        "SELECT users.*, SUM(amount) FROM users JOIN orders ON users.id = orders.user_id GROUP BY users.id, users.name",
+    );
+    match &result {
+        ExecutionResult::Select {
+            column_names, rows, ..
+        } => {
+            assert_eq!(column_names, &["users.id", "users.name", "SUM(amount)"]);
+            assert_eq!(
+                rows,
+                &vec![
+                    vec![
+                        Value::Integer(1),
+                        Value::String("Alice".to_string()),
+                        Value::Integer(250)
+                    ],
+                    vec![
+                        Value::Integer(2),
+                        Value::String("Bob".to_string()),
+                        Value::Integer(200)
+                    ],
+                ]
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    let err = db.execute_err(
+        "SELECT users.*, SUM(amount) FROM users JOIN orders ON users.id = orders.user_id GROUP BY users.id",
+    );
+    assert!(
+        err.to_string()
+            .contains("Column 'name' must appear in GROUP BY")
+    );
+
+    let err =
+        db.execute_err("SELECT payments.* FROM users JOIN orders ON users.id = orders.user_id");
+    assert!(
+        err.to_string()
+            .contains("Table 'payments' is not in the FROM clause")
+    );
+}
+
+#[test]
+fn test_select_band_join() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE bands (label VARCHAR, lo INTEGER, hi INTEGER)");
+    db.execute_ok("CREATE TABLE readings (val INTEGER)");
+    db.execute_ok("CREATE INDEX idx_val ON readings (val)");
+
+    db.execute_ok("INSERT INTO bands VALUES ('low', 0, 9), ('high', 10, 19)");
+    db.execute_ok("INSERT INTO readings VALUES (3), (12), (15), (42)");
+
+    let result = db.execute_ok(
+        "SELECT label, val FROM bands JOIN readings ON bands.lo <= readings.val AND bands.hi >= readings.val ORDER BY val ASC",
+    );
+    match &result {
+        ExecutionResult::Select { rows, plan, .. } => {
+            assert_eq!(rows.len(), 3);
+            assert_eq!(
+                rows[0],
+                vec![Value::String("low".to_string()), Value::Integer(3)]
+            );
+            assert_eq!(
+                rows[1],
+                vec![Value::String("high".to_string()), Value::Integer(12)]
+            );
+            assert_eq!(
+                rows[2],
+                vec![Value::String("high".to_string()), Value::Integer(15)]
+            );
+            assert!(plan.iter().any(|p| p.contains("Nested loop join")));
+            assert!(!plan.iter().any(|p| p.contains("Use index")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_select_join_with_residual_condition() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR, min_amount INTEGER)");
+    db.execute_ok("CREATE TABLE orders (user_id INTEGER, amount INTEGER)");
+
+    db.execute_ok("INSERT INTO users VALUES (1, 'Alice', 100), (2, 'Bob', 10)");
+    db.execute_ok("INSERT INTO orders VALUES (1, 50), (1, 150), (2, 20)");
+
+    let result = db.execute_ok(
+        "SELECT name, amount FROM users JOIN orders ON users.id = orders.user_id AND orders.amount >= users.min_amount ORDER BY amount ASC",
+    );
+    match &result {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[0][0], Value::String("Bob".to_string()));
+            assert_eq!(rows[1][1], Value::Integer(150));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_order_by_ties_break_on_row_identity() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE items (grp INTEGER, rank INTEGER)");
+    db.execute_ok("INSERT INTO items VALUES (1, 4), (1, 2), (1, 3), (1, 1)");
+    db.execute_ok("CREATE INDEX idx_rank ON items(rank)");
+
+    let ranks = |result: ExecutionResult| match result {
+        ExecutionResult::Select { rows, .. } => rows
+            .into_iter()
+            .map(|row| row[1].clone())
+            .collect::<Vec<_>>(),
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+    let physical: Vec<Value> = [4, 2, 3, 1].into_iter().map(Value::Integer).collect();
+
+    // The index hands rows back in rank order, but ties on grp still come
+    // back in the same order as a sequential scan
+    let indexed = db.execute_ok("SELECT grp, rank FROM items WHERE rank >= 1 ORDER BY grp");
+    match &indexed {
+        ExecutionResult::Select { plan, .. } => {
+            assert!(plan.iter().any(|p| p.starts_with("Index scan")));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+    assert_eq!(ranks(indexed), physical);
+    assert_eq!(
+        ranks(db.execute_ok("SELECT grp, rank FROM items ORDER BY grp")),
+        physical
+    );
+
+    // Pages of the same ordering neither repeat nor skip rows
+    let mut paged =
+        ranks(db.execute_ok("SELECT grp, rank FROM items WHERE rank >= 1 ORDER BY grp LIMIT 2"));
+    paged.extend(ranks(db.execute_ok(
+        "SELECT grp, rank FROM items ORDER BY grp LIMIT 2 OFFSET 2",
+    )));
+    assert_eq!(paged, physical);
+}
+
+#[test]
+fn test_order_by_limit_matches_full_sort() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE items (id INTEGER, grp INTEGER, score FLOAT)");
+    let values: Vec<String> = (0..200)
+        .map(|i| (i * 37) % 200)
+        .map(|id| format!("({}, {}, {})", id, id % 7, (id % 5) as f64 / 2.0))
+        .collect();
+    db.execute_ok(&format!("INSERT INTO items VALUES {}", values.join(", ")));
+
+    let rows = |result: ExecutionResult| match result {
+        ExecutionResult::Select { rows, .. } => rows,
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    for order in [
+        "grp ASC",
+        "grp DESC",
+        "score DESC, grp ASC",
+        "grp ASC, score ASC",
+    ] {
+        let sql = format!("SELECT id, grp, score FROM items ORDER BY {}", order);
+        let full = rows(db.execute_ok(&sql));
+        assert_eq!(full.len(), 200);
+        for (limit, offset) in [
+            (0, 0),
+            (1, 0),
+            (5, 0),
+            (10, 3),
+            (30, 170),
+            (50, 190),
+            (300, 0),
+        ] {
+            let sql = format!("{} LIMIT {} OFFSET {}", sql, limit, offset);
+            let start = offset.min(200);
+            let end = (offset + limit).min(200);
+            assert_eq!(
+                rows(db.execute_ok(&sql)),
+                full[start..end].to_vec(),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    match db.execute_ok("SELECT id FROM items ORDER BY id LIMIT 5 OFFSET 2") {
+        ExecutionResult::Select { plan, rows, .. } => {
+            assert!(plan.contains(&"Top-k sort: keep 7 row(s)".to_string()));
+            let ids: Vec<Value> = (2..7).map(Value::Integer).collect();
+            assert_eq!(
+                rows.into_iter()
+                    .map(|row| row[0].clone())
+                    .collect::<Vec<_>>(),
+                ids
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_order_by_limit_holds_only_top_rows_while_scanning() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE items (id INTEGER, grp INTEGER)");
+    db.execute_ok("CREATE TABLE groups (grp INTEGER, name VARCHAR)");
+    let values: Vec<String> = (0..1000)
+        .map(|i| format!("({}, {})", (i * 37) % 1000, i % 4))
+        .collect();
+    db.execute_ok(&format!("INSERT INTO items VALUES {}", values.join(", ")));
+    db.execute_ok("INSERT INTO groups VALUES (0, 'a'), (1, 'b'), (2, 'c'), (3, 'd')");
+
+    let select = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { plan, rows, .. } => (plan, rows),
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    let (plan, rows) = select(
+        &mut db,
+        "SELECT id FROM items WHERE grp = 1 ORDER BY id DESC LIMIT 3 OFFSET 2",
+    );
+    assert!(
+        plan.contains(&"Top-k sort during scan: held at most 5 of 250 row(s)".to_string()),
+        "{:?}",
+        plan
+    );
+    let ids: Vec<Vec<Value>> = [989, 985, 981]
+        .into_iter()
+        .map(|id| vec![Value::Integer(id)])
+        .collect();
+    assert_eq!(rows, ids);
+
+    let (plan, rows) = select(
+        &mut db,
+        "SELECT items.id, groups.name FROM items JOIN groups ON items.grp = groups.grp ORDER BY items.id LIMIT 2",
+    );
+    assert!(
+        plan.contains(&"Top-k sort during scan: held at most 2 of 1000 row(s)".to_string()),
+        "{:?}",
+        plan
+    );
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::Integer(0), Value::String("a".to_string())],
+            vec![Value::Integer(1), Value::String("b".to_string())],
+        ]
+    );
+
+    // Rows merged by DISTINCT have to be collected before they are sorted
+    let (plan, _) = select(
+        &mut db,
+        "SELECT DISTINCT grp FROM items ORDER BY grp LIMIT 2",
+    );
+    assert!(
+        !plan.iter().any(|step| step.contains("during scan")),
+        "{:?}",
+        plan
+    );
+}
+
+#[test]
+fn test_where_bare_boolean_column() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, active BOOLEAN, name VARCHAR)");
+    db.execute_ok(
+        "INSERT INTO users VALUES (1, true, 'Alice'), (2, false, 'Bob'), (3, NULL, 'Carol'), (4, true, 'Dave')",
+    );
+
+    let select = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { rows, .. } => rows,
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+    let bare = select(&mut db, "SELECT id FROM users WHERE active");
+    let compared = select(&mut db, "SELECT id FROM users WHERE active = true");
+    assert_eq!(bare, compared);
+    assert_eq!(bare, vec![vec![Value::Integer(1)], vec![Value::Integer(4)]]);
+
+    assert_eq!(
+        select(&mut db, "SELECT id FROM users WHERE active AND id > 1"),
+        vec![vec![Value::Integer(4)]]
+    );
+
+    let err = db.execute_err("SELECT id FROM users WHERE name");
+    assert!(err.to_string().contains("boolean"), "{}", err);
+
+    db.execute_ok("DELETE FROM users WHERE active");
+    assert_eq!(
+        select(&mut db, "SELECT id FROM users"),
+        vec![vec![Value::Integer(2)], vec![Value::Integer(3)]]
+    );
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::TestDb;
+use db2::sql::ExecutionResult;
+use db2::types::Value;
+
+fn select_result(result: ExecutionResult) -> (Vec<String>, Vec<Vec<Value>>) {
+    match result {
+        ExecutionResult::Select {
+            column_names, rows, ..
+        } => (column_names, rows),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_show_tables() {
+    let mut db = TestDb::new().unwrap();
+
+    let (columns, rows) = select_result(db.execute_ok("SHOW TABLES"));
+    assert_eq!(columns, vec!["name", "columns"]);
+    assert!(rows.is_empty());
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, name VARCHAR)");
+    db.execute_ok("CREATE TABLE accounts (id INTEGER)");
+
+    let (_, rows) = select_result(db.execute_ok("SHOW TABLES"));
+    assert_eq!(
+        rows,
+        vec![
+            vec![
+                Value::String("accounts".to_string()),
+                Value::String("id INTEGER".to_string()),
+            ],
+            vec![
+                Value::String("users".to_string()),
+                Value::String("id INTEGER, name VARCHAR".to_string()),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_show_indexes() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER, age INTEGER)");
+    db.execute_ok("CREATE TABLE orders (user_id INTEGER, order_id INTEGER)");
+    db.execute_ok("CREATE UNIQUE INDEX idx_users_id ON users(id)");
+    db.execute_ok("CREATE INDEX idx_orders ON orders USING HASH (user_id, order_id)");
+
+    let (columns, rows) = select_result(db.execute_ok("SHOW INDEXES"));
+    assert_eq!(columns, vec!["name", "table", "columns", "type", "unique"]);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0][0], Value::String("idx_orders".to_string()));
+    assert_eq!(rows[0][2], Value::String("user_id, order_id".to_string()));
+    assert_eq!(rows[0][4], Value::Boolean(false));
+    assert_eq!(rows[1][0], Value::String("idx_users_id".to_string()));
+    assert_eq!(rows[1][4], Value::Boolean(true));
+
+    let (_, rows) = select_result(db.execute_ok("SHOW INDEXES FROM users"));
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0][1], Value::String("users".to_string()));
+
+    let err = db.execute_err("SHOW INDEXES FROM missing");
+    assert!(err.to_string().contains("does not exist"));
+}
+
+#[test]
+fn test_show_renders_as_table() {
+    let mut db = TestDb::new().unwrap();
+
+    db.execute_ok("CREATE TABLE users (id INTEGER)");
+    let output = db.execute_ok("SHOW TABLES").to_string();
+    assert!(output.starts_with("name | columns"));
+    assert!(output.contains("users | id INTEGER"));
+    assert!(output.ends_with("1 row(s) returned"));
+}
@@ -54,3 +54,448 @@ fn test_date_timestamp_decimal_types() {
         _ => panic!("Expected Select result"),
     }
 }
+
+#[test]
+fn test_decimal_sum_and_avg_are_exact() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE items (id INTEGER, price DECIMAL)");
+
+    let values = (0..100)
+        .map(|i| format!("({}, DECIMAL '0.10')", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    db.execute_ok(&format!("INSERT INTO items VALUES {}", values));
+    // Integers stored in a DECIMAL column have scale 0 and are summed exactly too
+    db.execute_ok("INSERT INTO items VALUES (100, 5), (101, DECIMAL '0.05')");
+
+    match db.execute_ok("SELECT SUM(price), AVG(price), SUM(id) FROM items") {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows.len(), 1);
+            assert_eq!(
+                rows[0][0],
+                Value::Decimal(Decimal::parse("15.05").expect("valid decimal"))
+            );
+            // 15.05 / 102, rounded to six fractional digits
+            assert_eq!(
+                rows[0][1],
+                Value::Decimal(Decimal::parse("0.147549").expect("valid decimal"))
+            );
+            // Integer-only aggregates are unchanged
+            assert_eq!(rows[0][2], Value::Float(5151.0));
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_decimal_sum_of_no_rows_is_null() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE items (price DECIMAL)");
+
+    match db.execute_ok("SELECT SUM(price), AVG(price) FROM items") {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows, vec![vec![Value::Null, Value::Null]]);
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_interval_arithmetic_on_timestamps_and_dates() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE events (id INTEGER, created TIMESTAMP, due DATE)");
+    db.execute_ok(
+        "INSERT INTO events VALUES \
+         (1, TIMESTAMP '2025-01-31 23:30:00', DATE '2025-01-31'), \
+         (2, TIMESTAMP '2025-03-01 08:00:00', DATE '2025-03-01')",
+    );
+
+    match db.execute_ok(
+        "SELECT id, created + INTERVAL '1' HOUR, due + INTERVAL '1' MONTH, due - INTERVAL '12' HOUR FROM events",
+    ) {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows.len(), 2);
+            assert_eq!(
+                rows[0][1],
+                Value::Timestamp(Timestamp::parse("2025-02-01 00:30:00").unwrap())
+            );
+            // Calendar months clamp to the end of the shorter month
+            assert_eq!(rows[0][2], Value::Date(Date::parse("2025-02-28").unwrap()));
+            // A sub-day interval turns a date into a timestamp
+            assert_eq!(
+                rows[1][3],
+                Value::Timestamp(Timestamp::parse("2025-02-28 12:00:00").unwrap())
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    match db.execute_ok(
+        "SELECT id FROM events WHERE created > TIMESTAMP '2025-03-08 00:00:00' - INTERVAL '7' DAY",
+    ) {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows, vec![vec![Value::Integer(2)]]);
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_interval_relative_to_current_timestamp() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE events (id INTEGER, created TIMESTAMP)");
+    db.execute_ok(
+        "INSERT INTO events VALUES (1, TIMESTAMP '2000-01-01 00:00:00'), (2, TIMESTAMP '9000-01-01 00:00:00')",
+    );
+
+    match db
+        .execute_ok("SELECT id FROM events WHERE created > CURRENT_TIMESTAMP - INTERVAL '7' DAY")
+    {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows, vec![vec![Value::Integer(2)]]);
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_interval_rejects_non_temporal_operand() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE events (id INTEGER, created TIMESTAMP)");
+    db.execute_ok("INSERT INTO events VALUES (1, TIMESTAMP '2025-01-01 00:00:00')");
+
+    let err = db.execute_err("SELECT id + INTERVAL '1' DAY FROM events");
+    assert!(err.to_string().contains("non-temporal"));
+
+    let err = db.execute_err("SELECT INTERVAL '1' DAY - created FROM events");
+    assert!(err.to_string().contains("Cannot subtract"));
+
+    let err = db.execute_err("SELECT created + INTERVAL '9000' YEAR FROM events");
+    assert!(err.to_string().contains("out of range"));
+}
+
+#[test]
+fn test_varchar_values_larger_than_a_page() {
+    let mut db = TestDb::on_disk().expect("test db");
+    db.execute_ok("CREATE TABLE docs (id INTEGER, body VARCHAR)");
+    let first = "a".repeat(20_000);
+    let second = "b".repeat(30_000);
+    db.execute_ok(&format!("INSERT INTO docs VALUES (1, '{}')", first));
+    db.execute_ok("INSERT INTO docs VALUES (2, 'short')");
+
+    let select_docs = |db: &mut TestDb| match db.execute_ok("SELECT id, body FROM docs ORDER BY id")
+    {
+        ExecutionResult::Select { rows, .. } => rows,
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+    assert_eq!(
+        select_docs(&mut db),
+        vec![
+            vec![Value::Integer(1), Value::String(first.clone())],
+            vec![Value::Integer(2), Value::String("short".to_string())],
+        ]
+    );
+
+    db.execute_ok("BEGIN");
+    db.execute_ok(&format!("UPDATE docs SET body = '{}' WHERE id = 1", second));
+    db.execute_ok("COMMIT");
+    db.execute_ok("ALTER TABLE docs ADD COLUMN n INTEGER");
+    assert_eq!(
+        select_docs(&mut db)[0],
+        vec![Value::Integer(1), Value::String(second.clone())]
+    );
+
+    db.execute_ok("DELETE FROM docs WHERE id = 2");
+    db.flush().expect("flush");
+    let mut executor = db2::sql::Executor::new(db.path(), 100).expect("reopen");
+    match executor
+        .execute(db2::sql::parse_sql("SELECT id, body, n FROM docs").unwrap())
+        .unwrap()
+    {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(
+                rows,
+                vec![vec![Value::Integer(1), Value::String(second), Value::Null]]
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_integer_arithmetic_rejects_overflow() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE nums (i INTEGER, u UNSIGNED, f FLOAT)");
+    db.execute_ok("INSERT INTO nums VALUES (9223372036854775807, 18446744073709551615, 1.5)");
+
+    let first_row = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { rows, .. } => rows[0].clone(),
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    // Results that fit keep an integer type
+    assert_eq!(
+        first_row(
+            &mut db,
+            "SELECT i - 1, u - 1, i + 0 - 9223372036854775807 FROM nums"
+        ),
+        vec![
+            Value::Integer(i64::MAX - 1),
+            Value::Unsigned(u64::MAX - 1),
+            Value::Integer(0),
+        ]
+    );
+    // A mix of INTEGER and UNSIGNED widens to UNSIGNED only when it must
+    assert_eq!(
+        first_row(&mut db, "SELECT u - i, i - u + u, f + i FROM nums"),
+        vec![
+            Value::Unsigned(u64::MAX - i64::MAX as u64),
+            Value::Integer(i64::MAX),
+            Value::Float(1.5 + i64::MAX as f64),
+        ]
+    );
+
+    for sql in [
+        "SELECT i + 1 FROM nums",
+        "SELECT i - i - i - 2 FROM nums",
+        "SELECT u + 1 FROM nums",
+        "SELECT u - u - u FROM nums",
+        "SELECT i - i - u FROM nums",
+    ] {
+        let err = db.execute_err(sql);
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{}", sql);
+        assert!(
+            err.to_string().contains("Integer overflow"),
+            "{}: {}",
+            sql,
+            err
+        );
+    }
+}
+
+#[test]
+fn test_division_and_modulo() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE nums (id INTEGER, i INTEGER, u UNSIGNED, f FLOAT)");
+    db.execute_ok("INSERT INTO nums VALUES (1, 5, 7, 5.0)");
+    db.execute_ok("INSERT INTO nums VALUES (2, -7, 0, 0.0)");
+    db.execute_ok("INSERT INTO nums VALUES (3, 0, 9, -1.5)");
+    db.execute_ok("INSERT INTO nums VALUES (4, -9223372036854775808, 1, 2.0)");
+
+    let first_row = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { rows, .. } => rows[0].clone(),
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    // Integer division truncates toward zero; a FLOAT operand divides exactly
+    assert_eq!(
+        first_row(
+            &mut db,
+            "SELECT i / 2, f / 2, i % 2, u / 2, u % 4, f % 2 FROM nums WHERE id = 1"
+        ),
+        vec![
+            Value::Integer(2),
+            Value::Float(2.5),
+            Value::Integer(1),
+            Value::Unsigned(3),
+            Value::Unsigned(3),
+            Value::Float(1.0),
+        ]
+    );
+    // The remainder takes the sign of the dividend
+    assert_eq!(
+        first_row(
+            &mut db,
+            "SELECT i / 2, i % 2, i % -2 FROM nums WHERE id = 2"
+        ),
+        vec![Value::Integer(-3), Value::Integer(-1), Value::Integer(-1)]
+    );
+    // `/` and `%` bind tighter than `+` and `-`
+    assert_eq!(
+        first_row(&mut db, "SELECT i + 7 / 2 - 3 % 2 FROM nums WHERE id = 1"),
+        vec![Value::Integer(7)]
+    );
+
+    match db.execute_ok("SELECT id FROM nums WHERE id % 2 = 0") {
+        ExecutionResult::Select { rows, .. } => {
+            assert_eq!(rows, vec![vec![Value::Integer(2)], vec![Value::Integer(4)]])
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    for sql in [
+        "SELECT i / 0 FROM nums WHERE id = 1",
+        "SELECT i % 0 FROM nums WHERE id = 1",
+        "SELECT i / u FROM nums WHERE id = 2",
+        "SELECT f / 0 FROM nums WHERE id = 1",
+        "SELECT i / f FROM nums WHERE id = 2",
+        "SELECT f % 0.0 FROM nums WHERE id = 1",
+    ] {
+        let err = db.execute_err(sql);
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{}", sql);
+        assert!(
+            err.to_string().contains("Division by zero"),
+            "{}: {}",
+            sql,
+            err
+        );
+    }
+
+    // The one quotient that doesn't fit in an INTEGER
+    let err = db.execute_err("SELECT i / -1 FROM nums WHERE id = 4");
+    assert!(err.to_string().contains("Integer overflow"), "{}", err);
+}
+
+#[test]
+fn test_out_of_range_floats_do_not_coerce_to_integers() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE nums (i INTEGER, u UNSIGNED)");
+
+    // 2^63 and 2^64 are the first whole floats past each type's range
+    for sql in [
+        "INSERT INTO nums VALUES (9223372036854775808.0, 0)",
+        "INSERT INTO nums VALUES (-9223372036854777856.0, 0)",
+        "INSERT INTO nums VALUES (0, 18446744073709551616.0)",
+        "INSERT INTO nums VALUES (0, -1.0)",
+    ] {
+        let err = db.execute_err(sql);
+        assert!(err.to_string().contains("out of range"), "{}: {}", sql, err);
+    }
+
+    db.execute_ok("INSERT INTO nums VALUES (-9223372036854775808.0, 18446744073709549568.0)");
+    match db.execute_ok("SELECT i, u FROM nums") {
+        ExecutionResult::Select { rows, .. } => assert_eq!(
+            rows,
+            vec![vec![
+                Value::Integer(i64::MIN),
+                Value::Unsigned(18446744073709549568)
+            ]]
+        ),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_signed_zeros_and_infinities_group_and_sort() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE nums (f FLOAT)");
+    db.execute_ok("INSERT INTO nums VALUES (1e400), (0.0), (-1e400), (-0.0), (1.5), (0.0)");
+
+    match db.execute_ok("SELECT DISTINCT f FROM nums ORDER BY f ASC") {
+        ExecutionResult::Select { rows, .. } => assert_eq!(
+            rows,
+            vec![
+                vec![Value::Float(f64::NEG_INFINITY)],
+                vec![Value::Float(0.0)],
+                vec![Value::Float(1.5)],
+                vec![Value::Float(f64::INFINITY)],
+            ]
+        ),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+
+    match db.execute_ok("SELECT f, COUNT(*) FROM nums GROUP BY f ORDER BY f DESC") {
+        ExecutionResult::Select { rows, .. } => {
+            let counts: Vec<Value> = rows.iter().map(|row| row[1].clone()).collect();
+            assert_eq!(
+                counts,
+                vec![
+                    Value::Integer(1),
+                    Value::Integer(1),
+                    Value::Integer(3),
+                    Value::Integer(1)
+                ]
+            );
+        }
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_nan_is_rejected_on_insert() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE nums (f FLOAT)");
+    db.execute_ok("CREATE TABLE sums (f FLOAT)");
+    db.execute_ok("INSERT INTO nums VALUES (1e400), (-1e400)");
+
+    // inf + -inf is NaN
+    let err = db.execute_err("INSERT INTO sums SELECT SUM(f) FROM nums");
+    assert!(err.to_string().contains("NaN"), "{}", err);
+    match db.execute_ok("SELECT COUNT(*) FROM sums") {
+        ExecutionResult::Select { rows, .. } => assert_eq!(rows, vec![vec![Value::Integer(0)]]),
+        other => panic!("Expected Select result, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_json_column_validates_and_extracts_paths() {
+    let mut db = TestDb::new().expect("test db");
+    db.execute_ok("CREATE TABLE docs (id INTEGER, body JSON)");
+    db.execute_ok(
+        r#"INSERT INTO docs VALUES
+            (1, '{"name": "Ada", "age": 36, "admin": true, "tags": ["x", "y"]}'),
+            (2, '{"name": "Bob", "age": 29, "admin": false, "manager": {"name": "Ada"}}'),
+            (3, NULL)"#,
+    );
+
+    let err = db.execute_err(r#"INSERT INTO docs VALUES (4, '{"name": "Eve",}')"#);
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("Invalid JSON"));
+    let err = db.execute_err("UPDATE docs SET body = 'not json' WHERE id = 1");
+    assert!(err.to_string().contains("Invalid JSON"));
+
+    let rows = |db: &mut TestDb, sql: &str| match db.execute_ok(sql) {
+        ExecutionResult::Select { rows, .. } => rows,
+        other => panic!("Expected Select result, got: {:?}", other),
+    };
+
+    assert_eq!(
+        rows(
+            &mut db,
+            "SELECT id, json_extract(body, '$.name'), json_extract(body, '$.tags[1]') FROM docs"
+        ),
+        vec![
+            vec![
+                Value::Integer(1),
+                Value::String("Ada".to_string()),
+                Value::String("y".to_string()),
+            ],
+            vec![
+                Value::Integer(2),
+                Value::String("Bob".to_string()),
+                Value::Null,
+            ],
+            vec![Value::Integer(3), Value::Null, Value::Null],
+        ]
+    );
+
+    // Extracted scalars compare like any other value
+    assert_eq!(
+        rows(
+            &mut db,
+            "SELECT id FROM docs WHERE json_extract(body, '$.age') > 30"
+        ),
+        vec![vec![Value::Integer(1)]]
+    );
+    assert_eq!(
+        rows(
+            &mut db,
+            "SELECT id FROM docs WHERE json_extract(body, '$.admin')"
+        ),
+        vec![vec![Value::Integer(1)]]
+    );
+    assert_eq!(
+        rows(
+            &mut db,
+            "SELECT id FROM docs WHERE JSON_EXTRACT(body, '$.manager.name') = 'Ada'"
+        ),
+        vec![vec![Value::Integer(2)]]
+    );
+
+    let err = db.execute_err("SELECT id FROM docs WHERE json_extract(body, 'name') = 'Ada'");
+    assert!(err.to_string().contains("Invalid JSON path"));
+    let err = db.execute_err("SELECT json_extract(id, '$.a') FROM docs");
+    assert!(err.to_string().contains("requires a JSON document"));
+    let err = db.execute_err("CREATE INDEX idx_body ON docs(body)");
+    assert!(err.to_string().contains("can be indexed"));
+}